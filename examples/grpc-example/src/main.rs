@@ -6,16 +6,18 @@
 //! Run with: cargo run --example grpc-example
 //!
 //! Then test with:
-//! curl -X POST http://localhost:3002/user.UserService/GetUser \
+//! curl -X POST http://localhost:3002/user/UserService/GetUser \
 //!   -H "Content-Type: application/json" \
 //!   -d '{"id": 1}'
 //!
-//! curl -X POST http://localhost:3002/user.UserService/CreateUser \
+//! curl -X POST http://localhost:3002/user/UserService/CreateUser \
 //!   -H "Content-Type: application/json" \
 //!   -d '{"name": "Alice", "email": "alice@example.com"}'
 
 use std::sync::{Arc, Mutex};
-use ultraapi::grpc::{service, GrpcHandler, GrpcMethod, GrpcRequest, GrpcResponse, GrpcTranscoder};
+use ultraapi::grpc::{
+    service, GrpcExt, GrpcHandler, GrpcMethod, GrpcRequest, GrpcResponse, GrpcTranscoder,
+};
 use ultraapi::prelude::*;
 
 /// User model
@@ -163,23 +165,20 @@ async fn main() {
     // Build the gRPC transcoder
     let transcoder = build_grpc_transcoder(store);
 
-    // Get the gRPC router
-    let grpc_router = transcoder.into_router();
+    // Mount the gRPC services into the app (shares middleware, state and /openapi.json)
+    let app = UltraApiApp::new()
+        .title("gRPC Transcoding Example")
+        .version("0.1.0")
+        .grpc(transcoder);
 
-    // Use axum to serve the gRPC routes
     let addr = "0.0.0.0:3002";
-    let listener = tokio::net::TcpListener::bind(addr)
-        .await
-        .expect("Failed to bind to address");
-
     println!("🚀 gRPC Transcoding Example Server");
     println!("📖 Available endpoints:");
-    println!("   POST /user.UserService/GetUser");
-    println!("   POST /user.UserService/CreateUser");
+    println!("   POST /user/UserService/GetUser");
+    println!("   POST /user/UserService/CreateUser");
     println!("   GET  /grpc.health.v1.Health/Check");
+    println!("   GET  /openapi.json");
     println!("🌐 Server running at http://{}", addr);
 
-    axum::serve(listener, grpc_router)
-        .await
-        .expect("Server error");
+    app.serve(addr).await;
 }
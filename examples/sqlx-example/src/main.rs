@@ -333,7 +333,7 @@ mod tests {
 
         assert_eq!(row.get::<String, _>("name"), "Test Item");
         assert_eq!(row.get::<String, _>("description"), "Test description");
-        assert_eq!(row.get::<bool, _>("completed"), false);
+        assert!(!row.get::<bool, _>("completed"));
 
         // Update the item
         sqlx::query("UPDATE items SET completed = true WHERE id = ?")
@@ -348,7 +348,7 @@ mod tests {
             .await
             .expect("Failed to fetch updated item");
 
-        assert_eq!(row.get::<bool, _>("completed"), true);
+        assert!(row.get::<bool, _>("completed"));
 
         // Delete the item
         sqlx::query("DELETE FROM items WHERE id = ?")
@@ -127,6 +127,30 @@ fn is_multipart_type(ty: &Type) -> bool {
     false
 }
 
+fn is_multipart_form_type(ty: &Type) -> bool {
+    if let Type::Path(tp) = ty {
+        if let Some(seg) = tp.path.segments.last() {
+            return seg.ident == "MultipartForm";
+        }
+    }
+    false
+}
+
+/// `UploadFile`, `Option<UploadFile>`, `Vec<UploadFile>` or `Option<Vec<UploadFile>>`
+fn is_upload_file_type(ty: &Type) -> bool {
+    if let Type::Path(tp) = ty {
+        if let Some(seg) = tp.path.segments.last() {
+            if seg.ident == "UploadFile" {
+                return true;
+            }
+            if seg.ident == "Option" || seg.ident == "Vec" {
+                return extract_inner_type(seg).is_some_and(is_upload_file_type);
+            }
+        }
+    }
+    false
+}
+
 fn is_session_type(ty: &Type) -> bool {
     if let Type::Path(tp) = ty {
         if let Some(seg) = tp.path.segments.last() {
@@ -136,6 +160,15 @@ fn is_session_type(ty: &Type) -> bool {
     false
 }
 
+fn is_last_event_id_type(ty: &Type) -> bool {
+    if let Type::Path(tp) = ty {
+        if let Some(seg) = tp.path.segments.last() {
+            return seg.ident == "LastEventId";
+        }
+    }
+    false
+}
+
 fn is_background_tasks_type(ty: &Type) -> bool {
     if let Type::Path(tp) = ty {
         if let Some(seg) = tp.path.segments.last() {
@@ -302,6 +335,475 @@ fn is_scalar_query_type(ty: &Type) -> bool {
     false
 }
 
+fn is_vec_type(ty: &Type) -> bool {
+    get_vec_inner_type_name(ty).is_some()
+}
+
+/// Types that never carry an `#[api_model]` validator, so nested validation can skip them.
+fn is_leaf_validation_type(ty: &Type) -> bool {
+    if is_primitive_type(ty) {
+        return true;
+    }
+    matches!(
+        get_type_name(ty).as_str(),
+        "str"
+            | "char"
+            | "isize"
+            | "usize"
+            | "Cow"
+            | "Value"
+            | "Uuid"
+            | "DateTime"
+            | "NaiveDate"
+            | "NaiveDateTime"
+            | "NaiveTime"
+            | "Duration"
+            | "SystemTime"
+            | "PathBuf"
+            | "IpAddr"
+            | "Ipv4Addr"
+            | "Ipv6Addr"
+            | "Bytes"
+    )
+}
+
+fn generic_type_args(ty: &Type) -> Vec<&Type> {
+    if let Type::Path(tp) = ty {
+        if let Some(seg) = tp.path.segments.last() {
+            if let syn::PathArguments::AngleBracketed(args) = &seg.arguments {
+                return args
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        syn::GenericArgument::Type(t) => Some(t),
+                        _ => None,
+                    })
+                    .collect();
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// Build the statement pushing a structured validation error for a model field.
+fn validation_error_push(
+    field: impl quote::ToTokens,
+    input: TokenStream2,
+    error_type: &str,
+    msg: TokenStream2,
+    ctx: Option<TokenStream2>,
+) -> TokenStream2 {
+    let ctx = ctx.map(|ctx| quote! { .with_ctx(ultraapi::serde_json::json!(#ctx)) });
+    quote! {
+        errors.push(
+            ultraapi::ValidationErrorDetail::new(#error_type, #msg)
+                .at(#field)
+                .with_input(#input)
+                #ctx
+        );
+    }
+}
+
+/// Build code that validates a nested value (`access` is a reference expression) and
+/// appends its errors to `errors`, re-rooted under `path` (an expression evaluating to
+/// the parent's `Vec<ValidationLocItem>`).
+///
+/// Descends through `Option`, `Box`, sequences (`Vec`, `VecDeque`, sets) and maps,
+/// producing locations such as `items[3].address.zip`. Returns `None` when the
+/// type cannot contain an `#[api_model]` value.
+fn nested_validation_tokens(
+    access: TokenStream2,
+    ty: &Type,
+    path: TokenStream2,
+) -> Option<TokenStream2> {
+    let Type::Path(_) = ty else {
+        return None;
+    };
+    if is_leaf_validation_type(ty) {
+        return None;
+    }
+
+    let type_name = get_type_name(ty);
+    let args = generic_type_args(ty);
+    match (type_name.as_str(), args.as_slice()) {
+        ("Option", [inner]) => {
+            let check = nested_validation_tokens(quote!(item), inner, path)?;
+            Some(quote! {
+                if let Some(item) = #access {
+                    #check
+                }
+            })
+        }
+        ("Box" | "Arc" | "Rc", [inner]) => {
+            nested_validation_tokens(quote!(&**#access), inner, path)
+        }
+        ("Vec" | "VecDeque" | "HashSet" | "BTreeSet" | "IndexSet", [inner]) => {
+            let check = nested_validation_tokens(quote!(item), inner, quote!(item_path))?;
+            Some(quote! {
+                for (index, item) in (#access).iter().enumerate() {
+                    let mut item_path: Vec<ultraapi::ValidationLocItem> = (#path).to_vec();
+                    item_path.push(ultraapi::ValidationLocItem::from(index));
+                    #check
+                }
+            })
+        }
+        ("HashMap" | "BTreeMap" | "IndexMap", [_, value]) => {
+            let check = nested_validation_tokens(quote!(item), value, quote!(item_path))?;
+            Some(quote! {
+                for (key, item) in (#access).iter() {
+                    let mut item_path: Vec<ultraapi::ValidationLocItem> = (#path).to_vec();
+                    item_path.push(ultraapi::ValidationLocItem::from(key.to_string()));
+                    #check
+                }
+            })
+        }
+        _ => Some(quote! {
+            if let Err(nested) = ultraapi::ValidatedWrapper::validate_detailed(#access) {
+                errors.extend(nested.into_iter().map(|e| e.nested_under(&#path)));
+            }
+        }),
+    }
+}
+
+/// Parse a numeric `#[validate(...)]` argument such as `2`, `0.5` or `-10`.
+fn parse_validate_number(input: syn::parse::ParseStream) -> syn::Result<f64> {
+    let expr: syn::Expr = input.parse()?;
+    validate_number_from_expr(&expr)
+}
+
+fn validate_number_from_expr(expr: &syn::Expr) -> syn::Result<f64> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit),
+            ..
+        }) => lit.base10_parse::<f64>(),
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Float(lit),
+            ..
+        }) => lit.base10_parse::<f64>(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => validate_number_from_expr(expr).map(|v| -v),
+        other => Err(syn::Error::new_spanned(other, "expected a numeric literal")),
+    }
+}
+
+/// Convert a literal `#[validate(...)]` argument into `serde_json::json!` tokens
+/// plus a human readable rendering for error messages.
+fn validate_literal_to_json(expr: &syn::Expr) -> syn::Result<(TokenStream2, String)> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(s),
+            ..
+        }) => {
+            let value = s.value();
+            Ok((quote! { ultraapi::serde_json::json!(#value) }, value))
+        }
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Bool(b),
+            ..
+        }) => {
+            let value = b.value;
+            Ok((
+                quote! { ultraapi::serde_json::json!(#value) },
+                value.to_string(),
+            ))
+        }
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit),
+            ..
+        }) => {
+            let value: i64 = lit.base10_parse()?;
+            Ok((
+                quote! { ultraapi::serde_json::json!(#value) },
+                value.to_string(),
+            ))
+        }
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Float(lit),
+            ..
+        }) => {
+            let value: f64 = lit.base10_parse()?;
+            Ok((
+                quote! { ultraapi::serde_json::json!(#value) },
+                value.to_string(),
+            ))
+        }
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr: inner,
+            ..
+        }) => match inner.as_ref() {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(lit),
+                ..
+            }) => {
+                let value = -lit.base10_parse::<i64>()?;
+                Ok((
+                    quote! { ultraapi::serde_json::json!(#value) },
+                    value.to_string(),
+                ))
+            }
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Float(lit),
+                ..
+            }) => {
+                let value = -lit.base10_parse::<f64>()?;
+                Ok((
+                    quote! { ultraapi::serde_json::json!(#value) },
+                    value.to_string(),
+                ))
+            }
+            other => Err(syn::Error::new_spanned(other, "expected a numeric literal")),
+        },
+        other => Err(syn::Error::new_spanned(
+            other,
+            "expected a string, number or bool literal",
+        )),
+    }
+}
+
+/// `#[validate(...)]` constraints declared on a path/query/header/cookie handler argument.
+#[derive(Default)]
+struct ParamValidation {
+    /// Checks against `value` (a reference to the extracted value) that push into `errors`.
+    checks: Vec<TokenStream2>,
+    /// Assignments applied to the parameter's `openapi::DynParameter` (bound as `param`).
+    schema: Vec<TokenStream2>,
+    /// `required`: a missing cookie is reported as a `missing` error.
+    required: bool,
+}
+
+/// Parse `#[validate(...)]` on a handler argument.
+///
+/// `loc` is the error location (parameter name). Header and cookie values are raw
+/// strings, so only string keywords are accepted for those sources.
+fn parse_param_validation(
+    attrs: &[syn::Attribute],
+    loc: TokenStream2,
+    source: &str,
+) -> syn::Result<ParamValidation> {
+    let string_only = source == "header" || source == "cookie";
+    let mut validation = ParamValidation::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("validate")) {
+        attr.parse_nested_meta(|meta| {
+            let numeric = ["minimum", "maximum", "exclusive_minimum", "exclusive_maximum", "multiple_of"]
+                .iter()
+                .any(|k| meta.path.is_ident(k));
+            if numeric && string_only {
+                return Err(meta.error(format!(
+                    "{} parameters only support string constraints (min_length, max_length, pattern, one_of)",
+                    source
+                )));
+            }
+
+            if meta.path.is_ident("required") {
+                if source != "cookie" {
+                    return Err(meta.error("`required` is only supported on cookie parameters"));
+                }
+                validation.required = true;
+            } else if numeric {
+                let expr: syn::Expr = meta.value()?.parse()?;
+                let bound = validate_number_from_expr(&expr)?;
+                let (bound_json, display) = validate_literal_to_json(&expr)?;
+                let (op, error_type, msg, ctx_key, schema_field) = if meta.path.is_ident("minimum") {
+                    (quote!(<), "greater_than_equal", "must be at least", "ge", quote!(minimum))
+                } else if meta.path.is_ident("maximum") {
+                    (quote!(>), "less_than_equal", "must be at most", "le", quote!(maximum))
+                } else if meta.path.is_ident("exclusive_minimum") {
+                    (quote!(<=), "greater_than", "must be greater than", "gt", quote!(exclusive_minimum))
+                } else if meta.path.is_ident("exclusive_maximum") {
+                    (quote!(>=), "less_than", "must be less than", "lt", quote!(exclusive_maximum))
+                } else {
+                    if bound <= 0.0 {
+                        return Err(meta.error("multiple_of must be greater than 0"));
+                    }
+                    (quote!(), "multiple_of", "must be a multiple of", "multiple_of", quote!(multiple_of))
+                };
+                let msg = format!("{} {}", msg, display);
+                let push = validation_error_push(
+                    &loc,
+                    quote!(value),
+                    error_type,
+                    quote!(#msg),
+                    Some(quote!({ #ctx_key: #bound_json })),
+                );
+                validation.checks.push(if error_type == "multiple_of" {
+                    quote! {
+                        {
+                            let quotient = (*value as f64) / #bound;
+                            if (quotient - quotient.round()).abs() > 1e-9 {
+                                #push
+                            }
+                        }
+                    }
+                } else {
+                    quote! {
+                        if (*value as f64) #op #bound {
+                            #push
+                        }
+                    }
+                });
+                validation.schema.push(quote! { param.#schema_field = Some(#bound); });
+            } else if meta.path.is_ident("min_length") || meta.path.is_ident("max_length") {
+                let lit: syn::LitInt = meta.value()?.parse()?;
+                let n: usize = lit.base10_parse()?;
+                let n_u32 = n as u32;
+                let (op, error_type, msg, ctx, schema_field) = if meta.path.is_ident("min_length") {
+                    (
+                        quote!(<),
+                        "string_too_short",
+                        format!("must be at least {} characters", n),
+                        quote!({ "min_length": #n }),
+                        quote!(min_length),
+                    )
+                } else {
+                    (
+                        quote!(>),
+                        "string_too_long",
+                        format!("must be at most {} characters", n),
+                        quote!({ "max_length": #n }),
+                        quote!(max_length),
+                    )
+                };
+                let push = validation_error_push(&loc, quote!(value), error_type, quote!(#msg), Some(ctx));
+                validation.checks.push(quote! {
+                    if value.len() #op #n {
+                        #push
+                    }
+                });
+                validation.schema.push(quote! { param.#schema_field = Some(#n_u32); });
+            } else if meta.path.is_ident("pattern") {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                let pat = lit.value();
+                let msg = format!("must match pattern {}", pat);
+                let push = validation_error_push(
+                    &loc,
+                    quote!(value),
+                    "string_pattern_mismatch",
+                    quote!(#msg),
+                    Some(quote!({ "pattern": #pat })),
+                );
+                validation.checks.push(quote! {
+                    {
+                        static RE: std::sync::OnceLock<ultraapi::regex::Regex> = std::sync::OnceLock::new();
+                        let re = RE.get_or_init(|| ultraapi::regex::Regex::new(#pat).expect("Invalid regex"));
+                        if !re.is_match(value) {
+                            #push
+                        }
+                    }
+                });
+                validation.schema.push(quote! { param.pattern = Some(#pat.to_string()); });
+            } else if meta.path.is_ident("one_of") {
+                let array: syn::ExprArray = meta.value()?.parse()?;
+                let mut allowed = Vec::new();
+                let mut displays = Vec::new();
+                for elem in &array.elems {
+                    let (tokens, display) = validate_literal_to_json(elem)?;
+                    allowed.push(tokens);
+                    displays.push(display);
+                }
+                if allowed.is_empty() {
+                    return Err(meta.error("one_of requires at least one value"));
+                }
+                let msg = format!("must be one of {}", displays.join(", "));
+                let push = validation_error_push(
+                    &loc,
+                    quote!(value),
+                    "literal_error",
+                    quote!(#msg),
+                    Some(quote!({ "expected": allowed })),
+                );
+                validation.checks.push(quote! {
+                    {
+                        let allowed: Vec<ultraapi::serde_json::Value> = vec![#(#allowed),*];
+                        let value_json = ultraapi::serde_json::to_value(value)
+                            .unwrap_or(ultraapi::serde_json::Value::Null);
+                        if !allowed.contains(&value_json) {
+                            #push
+                        }
+                    }
+                });
+                validation.schema.push(quote! { param.enum_values = Some(vec![#(#allowed),*]); });
+            } else {
+                return Err(meta.error(
+                    "unsupported parameter constraint; expected one of minimum, maximum, \
+                     exclusive_minimum, exclusive_maximum, multiple_of, min_length, max_length, \
+                     pattern, one_of, required",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(validation)
+}
+
+/// Wrap parameter checks so that any failure returns a 422 rooted at `source`.
+fn param_validation_block(source: &str, body: TokenStream2) -> TokenStream2 {
+    let source = format_ident!("{}", source);
+    quote! {
+        {
+            let mut errors: Vec<ultraapi::ValidationErrorDetail> = Vec::new();
+            #body
+            if !errors.is_empty() {
+                return Err(ultraapi::ApiError::validation_errors(ultraapi::ValidationSource::#source, errors));
+            }
+        }
+    }
+}
+
+/// Push an `openapi::DynParameter` (bound as `param` while `schema` patches are applied).
+fn dyn_parameter_push(
+    name: TokenStream2,
+    location: &str,
+    required: TokenStream2,
+    schema_type: &str,
+    style: &str,
+    explode: bool,
+    schema: &[TokenStream2],
+) -> TokenStream2 {
+    quote! {
+        {
+            #[allow(unused_mut)]
+            let mut param = ultraapi::openapi::DynParameter {
+                name: #name,
+                location: #location.to_string(),
+                required: #required,
+                schema_type: #schema_type.to_string(),
+                description: None,
+                style: Some(#style.to_string()),
+                explode: Some(#explode),
+                example: None,
+                examples: None,
+                minimum: None,
+                maximum: None,
+                min_length: None,
+                max_length: None,
+                pattern: None,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+                multiple_of: None,
+                enum_values: None,
+            };
+            #(#schema)*
+            params.push(param);
+        }
+    }
+}
+
+/// OpenAPI scalar type for a path parameter's Rust type.
+fn openapi_scalar_type(ty: &Type) -> &'static str {
+    match get_type_name(ty).as_str() {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64" | "u128" => "integer",
+        "f32" | "f64" => "number",
+        "String" => "string",
+        "bool" => "boolean",
+        _ => "string",
+    }
+}
+
 /// Extract doc comment string from attributes
 fn extract_doc_comment(attrs: &[syn::Attribute]) -> String {
     let mut lines = Vec::new();
@@ -546,12 +1048,79 @@ fn parse_response_model_args(tokens: TokenStream2) -> syn::Result<ParsedResponse
     Ok(parsed)
 }
 
+/// Extraction code for one `#[dependencies(...)]` entry. OAuth2 bearer dependencies
+/// are extracted from the request and add their security scheme to the route.
+fn route_dependency_extraction(dep_ty: &Type, security_schemes: &mut Vec<String>) -> TokenStream2 {
+    let resolved_ty = resolve_dependency_type(dep_ty);
+
+    if is_oauth2_password_bearer_type(dep_ty) || is_oauth2_password_bearer_type(&resolved_ty) {
+        if !security_schemes.iter().any(|s| s == "oauth2Password") {
+            security_schemes.push("oauth2Password".to_string());
+        }
+        return quote! {
+            let _ultraapi_route_dependency: ultraapi::middleware::OAuth2PasswordBearer =
+                ultraapi::middleware::OAuth2PasswordBearer::from_request_parts(&mut parts, &state).await
+                    .map_err(|e| e)?;
+        };
+    }
+
+    if is_optional_oauth2_password_bearer_type(dep_ty)
+        || is_optional_oauth2_password_bearer_type(&resolved_ty)
+    {
+        if !security_schemes.iter().any(|s| s == "oauth2Password") {
+            security_schemes.push("oauth2Password".to_string());
+        }
+        return quote! {
+            let _ultraapi_route_dependency: ultraapi::middleware::OptionalOAuth2PasswordBearer =
+                ultraapi::middleware::OptionalOAuth2PasswordBearer::from_request_parts(&mut parts, &state).await
+                    .map_err(|e| e)?;
+        };
+    }
+
+    if is_oauth2_auth_code_bearer_type(dep_ty) || is_oauth2_auth_code_bearer_type(&resolved_ty) {
+        if !security_schemes.iter().any(|s| s == "oauth2AuthCode") {
+            security_schemes.push("oauth2AuthCode".to_string());
+        }
+        return quote! {
+            let _ultraapi_route_dependency: ultraapi::middleware::OAuth2AuthorizationCodeBearer =
+                ultraapi::middleware::OAuth2AuthorizationCodeBearer::from_request_parts(&mut parts, &state).await
+                    .map_err(|e| e)?;
+        };
+    }
+
+    if is_optional_oauth2_auth_code_bearer_type(dep_ty)
+        || is_optional_oauth2_auth_code_bearer_type(&resolved_ty)
+    {
+        if !security_schemes.iter().any(|s| s == "oauth2AuthCode") {
+            security_schemes.push("oauth2AuthCode".to_string());
+        }
+        return quote! {
+            let _ultraapi_route_dependency: ultraapi::middleware::OptionalOAuth2AuthorizationCodeBearer =
+                ultraapi::middleware::OptionalOAuth2AuthorizationCodeBearer::from_request_parts(&mut parts, &state).await
+                    .map_err(|e| e)?;
+        };
+    }
+
+    quote! {
+        let _ultraapi_route_dependency =
+            ultraapi::resolve_route_dependency::<#resolved_ty>(&state, &dep_scope, &depends_cache).await?;
+    }
+}
+
 fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> TokenStream {
     let path = parse_macro_input!(attr as LitStr).value();
     let input_fn = parse_macro_input!(item as ItemFn);
     let fn_name = &input_fn.sig.ident;
     let fn_vis = &input_fn.vis;
-    let fn_sig = &input_fn.sig;
+    // Argument-level #[validate(...)] is consumed here and must not reach rustc
+    let mut fn_sig = input_fn.sig.clone();
+    for arg in fn_sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = arg {
+            pat_type
+                .attrs
+                .retain(|a| !a.path().is_ident("validate") && !a.path().is_ident("upload"));
+        }
+    }
     let fn_block = &input_fn.block;
 
     // Parse custom attributes: #[status(N)], #[tag("x")], #[security("x")],
@@ -580,6 +1149,9 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
     let mut deprecated: bool = false;
     let mut external_docs_url: Option<String> = None;
     let mut external_docs_description: Option<String> = None;
+    // Request limits
+    let mut body_limit: Option<u64> = None;
+    let mut timeout_ms: Option<u64> = None;
     let description = extract_doc_comment(&input_fn.attrs);
 
     let mut clean_attrs: Vec<&syn::Attribute> = Vec::new();
@@ -698,6 +1270,34 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
                     return err.to_compile_error().into();
                 }
             }
+        } else if attr.path().is_ident("body_limit") {
+            // Parse body_limit("10MB")
+            let parsed = attr.parse_args::<LitStr>().and_then(|lit| {
+                parse_byte_size(&lit.value()).ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        &lit,
+                        "invalid size, expected e.g. \"512KB\" or \"10MB\"",
+                    )
+                })
+            });
+            match parsed {
+                Ok(bytes) => body_limit = Some(bytes),
+                Err(err) => return err.to_compile_error().into(),
+            }
+        } else if attr.path().is_ident("timeout") {
+            // Parse timeout("5s")
+            let parsed = attr.parse_args::<LitStr>().and_then(|lit| {
+                parse_duration_ms(&lit.value()).ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        &lit,
+                        "invalid duration, expected e.g. \"500ms\" or \"5s\"",
+                    )
+                })
+            });
+            match parsed {
+                Ok(ms) => timeout_ms = Some(ms),
+                Err(err) => return err.to_compile_error().into(),
+            }
         } else if attr.path().is_ident("callback") {
             // Parse #[callback(name = "...", expression = "...", route = ROUTE_REF)]
             // This attribute is handled separately - it generates inventory::submit! for CallbackInfo
@@ -798,6 +1398,8 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
     let mut has_body = false;
     let mut has_form_body = false;
     let mut has_multipart_body = false;
+    let mut upload_params: Vec<(&syn::Ident, &Type, &Vec<syn::Attribute>)> = Vec::new();
+    let upload_form_name = format_ident!("__ultraapi_upload_form_{}", fn_name);
     let mut has_request_extractor = false;
     let mut has_generator_deps = false;
     let mut has_depends_params = false;
@@ -807,10 +1409,26 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
     let mut query_extraction = quote! {};
     let mut scalar_query_params: Vec<(&syn::Ident, &Type)> = Vec::new();
     let mut openapi_dynamic_params: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut path_param_validations: Vec<TokenStream2> = Vec::new();
+    let mut query_param_validations: Vec<TokenStream2> = Vec::new();
 
     for arg in &input_fn.sig.inputs {
-        if let FnArg::Typed(PatType { pat, ty, .. }) = arg {
+        if let FnArg::Typed(PatType { pat, ty, attrs, .. }) = arg {
             let param_name = quote!(#pat).to_string();
+            let has_validate_attr = attrs.iter().any(|a| a.path().is_ident("validate"));
+            if has_validate_attr
+                && !(is_header_type(ty)
+                    || is_cookie_type(ty)
+                    || path_params.contains(&param_name)
+                    || is_scalar_query_type(ty))
+            {
+                return syn::Error::new_spanned(
+                    arg,
+                    "#[validate(...)] on handler arguments is only supported for path, scalar query, header and cookie parameters",
+                )
+                .to_compile_error()
+                .into();
+            }
             if is_dep_type(ty) {
                 if let Type::Path(tp) = ty.as_ref() {
                     if let Some(seg) = tp.path.segments.last() {
@@ -896,8 +1514,8 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
                                     ultraapi::axum::extract::Query::from_request_parts(&mut parts, &state).await
                                     .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid query parameters: {}", e)))?;
                                 // Validate the query params using ValidatedWrapper (handles both api_model and non-api_model types)
-                                if let Err(e) = ultraapi::ValidatedWrapper::validate(&#pat.0) {
-                                    return Err(ultraapi::ApiError::validation_error(e));
+                                if let Err(e) = ultraapi::ValidatedWrapper::validate_detailed(&#pat.0) {
+                                    return Err(ultraapi::ApiError::validation_errors(ultraapi::ValidationSource::Query, e));
                                 }
                             };
                             call_args.push(quote!(#pat));
@@ -907,39 +1525,49 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
             } else if is_header_type(ty) {
                 // TypedHeader<T> and Option<TypedHeader<T>> extractor
                 if let Some(inner) = typed_header_inner_type(ty) {
+                    let header_name =
+                        quote!(<#inner as ultraapi::axum_extra::headers::Header>::name().as_str());
+                    let validation =
+                        match parse_param_validation(attrs, header_name.clone(), "header") {
+                            Ok(validation) => validation,
+                            Err(err) => return err.to_compile_error().into(),
+                        };
                     if option_inner_type(ty).is_some() {
                         dep_extractions.push(quote! {
                             let #pat: Option<ultraapi::axum_extra::extract::TypedHeader<#inner>> =
                                 <ultraapi::axum_extra::extract::TypedHeader<#inner> as ultraapi::axum::extract::OptionalFromRequestParts<ultraapi::AppState>>::from_request_parts(&mut parts, &state).await
-                                .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid header: {}", e)))?;
+                                .map_err(ultraapi::ApiError::from_typed_header_rejection)?;
                         });
                     } else {
                         dep_extractions.push(quote! {
                             let #pat: ultraapi::axum_extra::extract::TypedHeader<#inner> =
                                 ultraapi::axum_extra::extract::TypedHeader::from_request_parts(&mut parts, &state).await
-                                .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid header: {}", e)))?;
+                                .map_err(ultraapi::ApiError::from_typed_header_rejection)?;
                         });
                     }
+                    // Constraints are checked against the raw header value
+                    if !validation.checks.is_empty() {
+                        let checks = &validation.checks;
+                        dep_extractions.push(param_validation_block(
+                            "Header",
+                            quote! {
+                                if let Some(value) = parts.headers.get(#header_name).and_then(|v| v.to_str().ok()) {
+                                    #(#checks)*
+                                }
+                            },
+                        ));
+                    }
 
                     let header_required = option_inner_type(ty).is_none();
-                    openapi_dynamic_params.push(quote! {
-                        params.push(ultraapi::openapi::DynParameter {
-                            name: <#inner as ultraapi::axum_extra::headers::Header>::name().as_str().to_string(),
-                            location: "header".to_string(),
-                            required: #header_required,
-                            schema_type: "string".to_string(),
-                            description: None,
-                            style: Some("simple".to_string()),
-                            explode: Some(false),
-                            example: None,
-                            examples: None,
-                            minimum: None,
-                            maximum: None,
-                            min_length: None,
-                            max_length: None,
-                            pattern: None,
-                        });
-                    });
+                    openapi_dynamic_params.push(dyn_parameter_push(
+                        quote!(#header_name.to_string()),
+                        "header",
+                        quote!(#header_required),
+                        "string",
+                        "simple",
+                        false,
+                        &validation.schema,
+                    ));
 
                     call_args.push(quote!(#pat));
                 }
@@ -969,26 +1597,59 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
 
                         let cookie_name =
                             pat_ident_name(pat).unwrap_or_else(|| "cookie".to_string());
+                        let validation =
+                            match parse_param_validation(attrs, quote!(#cookie_name), "cookie") {
+                                Ok(validation) => validation,
+                                Err(err) => return err.to_compile_error().into(),
+                            };
+                        // Constraints apply to the cookie named after the argument
+                        let missing = if validation.required {
+                            validation_error_push(
+                                &cookie_name,
+                                quote!(&ultraapi::serde_json::Value::Null),
+                                "missing",
+                                quote!("Field required"),
+                                None,
+                            )
+                        } else {
+                            quote! {}
+                        };
+                        if !validation.checks.is_empty() {
+                            let checks = &validation.checks;
+                            dep_extractions.push(param_validation_block(
+                                "Cookie",
+                                quote! {
+                                    if let Some(cookie) = #pat.get(#cookie_name) {
+                                        let value = cookie.value();
+                                        #(#checks)*
+                                    } else {
+                                        #missing
+                                    }
+                                },
+                            ));
+                        } else if validation.required {
+                            dep_extractions.push(param_validation_block(
+                                "Cookie",
+                                quote! {
+                                    if #pat.get(#cookie_name).is_none() {
+                                        #missing
+                                    }
+                                },
+                            ));
+                        }
+
                         let cookie_type_name = cookie_type.to_string();
-                        let cookie_required = cookie_type_name != "CookieJar";
-                        openapi_dynamic_params.push(quote! {
-                            params.push(ultraapi::openapi::DynParameter {
-                                name: #cookie_name.to_string(),
-                                location: "cookie".to_string(),
-                                required: #cookie_required,
-                                schema_type: "string".to_string(),
-                                description: None,
-                                style: Some("form".to_string()),
-                                explode: Some(true),
-                                example: None,
-                                examples: None,
-                                minimum: None,
-                                maximum: None,
-                                min_length: None,
-                                max_length: None,
-                                pattern: None,
-                            });
-                        });
+                        let cookie_required =
+                            cookie_type_name != "CookieJar" || validation.required;
+                        openapi_dynamic_params.push(dyn_parameter_push(
+                            quote!(#cookie_name.to_string()),
+                            "cookie",
+                            quote!(#cookie_required),
+                            "string",
+                            "form",
+                            true,
+                            &validation.schema,
+                        ));
 
                         call_args.push(quote!(#pat));
                     }
@@ -1005,14 +1666,54 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
                                 let #pat: ultraapi::axum::extract::Form<#inner> =
                                     ultraapi::axum::extract::Form::from_request(req, &state).await
                                     .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid form data: {}", e)))?;
-                                if let Err(e) = ultraapi::ValidatedWrapper::validate(&#pat.0) {
-                                    return Err(ultraapi::ApiError::validation_error(e));
+                                if let Err(e) = ultraapi::ValidatedWrapper::validate_detailed(&#pat.0) {
+                                    return Err(ultraapi::ApiError::validation_errors(ultraapi::ValidationSource::Body, e));
                                 }
                             });
                             call_args.push(quote!(#pat));
                         }
                     }
                 }
+            } else if is_multipart_form_type(ty) {
+                // MultipartForm<T> extractor for #[form_model] structs
+                has_body = true;
+                has_multipart_body = true;
+                if let Type::Path(tp) = ty.as_ref() {
+                    if let Some(seg) = tp.path.segments.last() {
+                        if let Some(inner) = extract_inner_type(seg) {
+                            body_type = Some(inner);
+                            dep_extractions.push(quote! {
+                                let #pat: ultraapi::upload::MultipartForm<#inner> =
+                                    ultraapi::upload::MultipartForm::from_request(req, &state).await?;
+                            });
+                            call_args.push(quote!(#pat));
+                        }
+                    }
+                }
+            } else if is_upload_file_type(ty) {
+                // UploadFile parameters become the fields of a generated #[form_model]
+                has_body = true;
+                has_multipart_body = true;
+                let syn::Pat::Ident(pat_ident) = pat.as_ref() else {
+                    return syn::Error::new_spanned(
+                        pat,
+                        "UploadFile parameters must use identifier patterns",
+                    )
+                    .to_compile_error()
+                    .into();
+                };
+                let ident = &pat_ident.ident;
+                if upload_params.is_empty() {
+                    dep_extractions.push(quote! {
+                        let ultraapi::upload::MultipartForm(__ultraapi_upload_form): ultraapi::upload::MultipartForm<#upload_form_name> =
+                            ultraapi::upload::MultipartForm::from_request(req, &state).await?;
+                    });
+                }
+                dep_extractions.push(quote! {
+                    let #pat = __ultraapi_upload_form.#ident;
+                });
+                upload_params.push((ident, ty.as_ref(), attrs));
+                call_args.push(quote!(#ident));
             } else if is_multipart_type(ty) {
                 // Multipart extractor for file uploads
                 has_body = true;
@@ -1073,12 +1774,70 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
                 call_args.push(quote!(#pat));
             } else if path_params.contains(&param_name) {
                 if let syn::Pat::Ident(pi) = pat.as_ref() {
-                    path_param_types.push((&pi.ident, ty));
+                    let ident = &pi.ident;
+                    let validation =
+                        match parse_param_validation(attrs, quote!(#param_name), "path") {
+                            Ok(validation) => validation,
+                            Err(err) => return err.to_compile_error().into(),
+                        };
+                    if !validation.checks.is_empty() {
+                        // Constrained path params are documented dynamically so the
+                        // constraints can be attached to their schema
+                        let checks = &validation.checks;
+                        path_param_validations.push(param_validation_block(
+                            "Path",
+                            quote! {
+                                let value = &#ident;
+                                #(#checks)*
+                            },
+                        ));
+                        openapi_dynamic_params.push(dyn_parameter_push(
+                            quote!(#param_name.to_string()),
+                            "path",
+                            quote!(true),
+                            openapi_scalar_type(ty),
+                            "simple",
+                            false,
+                            &validation.schema,
+                        ));
+                    }
+                    path_param_types.push((ident, ty));
                     call_args.push(quote!(#pat));
                 }
             } else if is_scalar_query_type(ty) {
                 if let syn::Pat::Ident(pi) = pat.as_ref() {
-                    scalar_query_params.push((&pi.ident, ty));
+                    let ident = &pi.ident;
+                    let validation =
+                        match parse_param_validation(attrs, quote!(#param_name), "query") {
+                            Ok(validation) => validation,
+                            Err(err) => return err.to_compile_error().into(),
+                        };
+                    if !validation.checks.is_empty() {
+                        let checks = &validation.checks;
+                        let binding = if option_inner_type(ty).is_some() {
+                            quote! {
+                                if let Some(value) = #ident.as_ref() {
+                                    #(#checks)*
+                                }
+                            }
+                        } else {
+                            quote! {
+                                let value = &#ident;
+                                #(#checks)*
+                            }
+                        };
+                        query_param_validations.push(param_validation_block("Query", binding));
+                        let schema = &validation.schema;
+                        openapi_dynamic_params.push(quote! {
+                            if let Some(param) = params
+                                .iter_mut()
+                                .find(|p| p.location == "query" && p.name == #param_name)
+                            {
+                                #(#schema)*
+                            }
+                        });
+                    }
+                    scalar_query_params.push((ident, ty));
                     call_args.push(quote!(#pat));
                 } else {
                     return syn::Error::new_spanned(
@@ -1114,66 +1873,9 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
     let mut route_dependency_type_names: Vec<String> = Vec::new();
 
     for dep_ty in &route_dependencies {
-        let resolved_ty = resolve_dependency_type(dep_ty);
-        route_dependency_type_names.push(get_type_name(&resolved_ty));
-
-        if is_oauth2_password_bearer_type(dep_ty) || is_oauth2_password_bearer_type(&resolved_ty) {
-            route_dependency_extractions.push(quote! {
-                let _ultraapi_route_dependency: ultraapi::middleware::OAuth2PasswordBearer =
-                    ultraapi::middleware::OAuth2PasswordBearer::from_request_parts(&mut parts, &state).await
-                        .map_err(|e| e)?;
-            });
-            if !security_schemes.iter().any(|s| s == "oauth2Password") {
-                security_schemes.push("oauth2Password".to_string());
-            }
-            continue;
-        }
-
-        if is_optional_oauth2_password_bearer_type(dep_ty)
-            || is_optional_oauth2_password_bearer_type(&resolved_ty)
-        {
-            route_dependency_extractions.push(quote! {
-                let _ultraapi_route_dependency: ultraapi::middleware::OptionalOAuth2PasswordBearer =
-                    ultraapi::middleware::OptionalOAuth2PasswordBearer::from_request_parts(&mut parts, &state).await
-                        .map_err(|e| e)?;
-            });
-            if !security_schemes.iter().any(|s| s == "oauth2Password") {
-                security_schemes.push("oauth2Password".to_string());
-            }
-            continue;
-        }
-
-        if is_oauth2_auth_code_bearer_type(dep_ty) || is_oauth2_auth_code_bearer_type(&resolved_ty)
-        {
-            route_dependency_extractions.push(quote! {
-                let _ultraapi_route_dependency: ultraapi::middleware::OAuth2AuthorizationCodeBearer =
-                    ultraapi::middleware::OAuth2AuthorizationCodeBearer::from_request_parts(&mut parts, &state).await
-                        .map_err(|e| e)?;
-            });
-            if !security_schemes.iter().any(|s| s == "oauth2AuthCode") {
-                security_schemes.push("oauth2AuthCode".to_string());
-            }
-            continue;
-        }
-
-        if is_optional_oauth2_auth_code_bearer_type(dep_ty)
-            || is_optional_oauth2_auth_code_bearer_type(&resolved_ty)
-        {
-            route_dependency_extractions.push(quote! {
-                let _ultraapi_route_dependency: ultraapi::middleware::OptionalOAuth2AuthorizationCodeBearer =
-                    ultraapi::middleware::OptionalOAuth2AuthorizationCodeBearer::from_request_parts(&mut parts, &state).await
-                        .map_err(|e| e)?;
-            });
-            if !security_schemes.iter().any(|s| s == "oauth2AuthCode") {
-                security_schemes.push("oauth2AuthCode".to_string());
-            }
-            continue;
-        }
-
-        route_dependency_extractions.push(quote! {
-            let _ultraapi_route_dependency =
-                ultraapi::resolve_route_dependency::<#resolved_ty>(&state, &dep_scope, &depends_cache).await?;
-        });
+        route_dependency_type_names.push(get_type_name(&resolve_dependency_type(dep_ty)));
+        route_dependency_extractions
+            .push(route_dependency_extraction(dep_ty, &mut security_schemes));
     }
 
     if !route_dependency_extractions.is_empty() {
@@ -1184,6 +1886,20 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
     route_dependency_type_names.sort();
     route_dependency_type_names.dedup();
 
+    let has_raw_multipart = input_fn
+        .sig
+        .inputs
+        .iter()
+        .any(|arg| matches!(arg, FnArg::Typed(PatType { ty, .. }) if is_multipart_type(ty)));
+    if !upload_params.is_empty() && (body_type.is_some() || has_form_body || has_raw_multipart) {
+        return syn::Error::new_spanned(
+            &input_fn.sig,
+            "UploadFile parameters cannot be combined with body/Form/Multipart/MultipartForm extractors",
+        )
+        .to_compile_error()
+        .into();
+    }
+
     if has_request_extractor && (has_body || has_form_body || has_multipart_body) {
         return syn::Error::new_spanned(
             &input_fn.sig,
@@ -1206,11 +1922,28 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
         .and_then(|t| get_result_ok_type(t))
         .or(return_type);
 
-    let return_type_name = effective_return_type
+    // JsonLines<S> / JsonArrayStream<S>: the schema and response_model apply to each item
+    let json_stream = effective_return_type.and_then(json_stream_return);
+    let json_stream_item = match &json_stream {
+        Some((_, Some(item))) => Some(item),
+        Some((_, None)) => {
+            return syn::Error::new_spanned(
+                effective_return_type,
+                "cannot determine the item type of the stream; use `impl Stream<Item = T>`, `BoxStream<'_, T>` or `Pin<Box<dyn Stream<Item = T> + Send>>`",
+            )
+            .to_compile_error()
+            .into();
+        }
+        None => None,
+    };
+
+    let return_type_name = json_stream_item
+        .or(effective_return_type)
         .map(get_type_name)
         .unwrap_or_else(|| "()".to_string());
     let body_type_name_for_field_set = body_type.map(get_type_name);
-    let should_capture_request_field_set = has_response_model
+    let should_capture_request_field_set = json_stream.is_none()
+        && has_response_model
         && exclude_unset
         && has_body
         && body_type_name_for_field_set
@@ -1219,12 +1952,18 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
             .unwrap_or(false);
 
     // Detect Vec<T> return type for array schema (check effective type, i.e. inside Result if applicable)
-    let is_vec_response = effective_return_type
-        .map(|t| get_vec_inner_type_name(t).is_some())
-        .unwrap_or(false);
-    let vec_inner_type_name = effective_return_type
-        .and_then(get_vec_inner_type_name)
-        .unwrap_or_default();
+    let is_json_array_stream = matches!(json_stream, Some((JsonStreamKind::Array, _)));
+    let is_vec_response = is_json_array_stream
+        || effective_return_type
+            .map(|t| get_vec_inner_type_name(t).is_some())
+            .unwrap_or(false);
+    let vec_inner_type_name = if is_json_array_stream {
+        return_type_name.clone()
+    } else {
+        effective_return_type
+            .and_then(get_vec_inner_type_name)
+            .unwrap_or_default()
+    };
 
     let path_extraction = if !path_param_types.is_empty() {
         let names: Vec<_> = path_param_types.iter().map(|(n, _)| *n).collect();
@@ -1232,16 +1971,20 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
         if path_param_types.len() == 1 {
             let n = names[0];
             let t = types[0];
+            let name = n.to_string();
             quote! {
                 let ultraapi::axum::extract::Path(#n): ultraapi::axum::extract::Path<#t> =
                     ultraapi::axum::extract::Path::from_request_parts(&mut parts, &state).await
-                    .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid path param: {}", e)))?;
+                    .map_err(|e| ultraapi::ApiError::from_path_rejection(e, &[#name]))?;
+                #(#path_param_validations)*
             }
         } else {
+            let name_strs: Vec<String> = names.iter().map(|n| n.to_string()).collect();
             quote! {
                 let ultraapi::axum::extract::Path((#(#names),*)): ultraapi::axum::extract::Path<(#(#types),*)> =
                     ultraapi::axum::extract::Path::from_request_parts(&mut parts, &state).await
-                    .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid path params: {}", e)))?;
+                    .map_err(|e| ultraapi::ApiError::from_path_rejection(e, &[#(#name_strs),*]))?;
+                #(#path_param_validations)*
             }
         }
     } else {
@@ -1249,6 +1992,28 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
     };
 
     let scalar_query_struct_name = format_ident!("__ultraapi_scalar_query_{}", fn_name);
+    let upload_form_def = if upload_params.is_empty() {
+        quote! {}
+    } else {
+        let schema_name = format!("Body_{}", fn_name);
+        let upload_fields: Vec<_> = upload_params
+            .iter()
+            .map(|(ident, ty, attrs)| {
+                let upload_attrs = attrs.iter().filter(|a| a.path().is_ident("upload"));
+                quote! { #(#upload_attrs)* #ident: #ty }
+            })
+            .collect();
+
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_camel_case_types)]
+            #[ultraapi::prelude::form_model(name = #schema_name)]
+            struct #upload_form_name {
+                #(#upload_fields,)*
+            }
+        }
+    };
+
     let scalar_query_struct_def = if scalar_query_params.is_empty() {
         quote! {}
     } else {
@@ -1280,6 +2045,7 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
                 ultraapi::axum::extract::Query::from_request_parts(&mut parts, &state).await
                 .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid query parameters: {}", e)))?;
             #(#scalar_query_bindings)*
+            #(#query_param_validations)*
         }
     };
 
@@ -1320,20 +2086,20 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
             .unwrap();
         if should_capture_request_field_set {
             quote! {
-                let ultraapi::axum::Json(__ultraapi_raw_body): ultraapi::axum::Json<ultraapi::serde_json::Value> =
-                    ultraapi::axum::Json::from_request(req, &state).await
-                    .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid body: {}", e)))?;
+                let __ultraapi_body_bytes = ultraapi::deserialize::read_json_body(req, &state).await?;
+                let __ultraapi_raw_body: ultraapi::serde_json::Value = ultraapi::deserialize::from_json_slice(&__ultraapi_body_bytes)
+                    .map_err(|e| ultraapi::ApiError::validation_errors(ultraapi::ValidationSource::Body, e))?;
                 __ultraapi_response_field_set = Some(ultraapi::collect_present_field_paths(&__ultraapi_raw_body));
-                let #bpat: #bty = ultraapi::serde_json::from_value(__ultraapi_raw_body)
-                    .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid body: {}", e)))?;
-                #bpat.validate().map_err(|e| ultraapi::ApiError::validation_error(e))?;
+                let #bpat: #bty = ultraapi::deserialize::from_json_value(__ultraapi_raw_body)
+                    .map_err(|e| ultraapi::ApiError::validation_errors(ultraapi::ValidationSource::Body, e))?;
+                #bpat.validate_detailed().map_err(|e| ultraapi::ApiError::validation_errors(ultraapi::ValidationSource::Body, e))?;
             }
         } else {
             quote! {
-                let ultraapi::axum::Json(#bpat): ultraapi::axum::Json<#bty> =
-                    ultraapi::axum::Json::from_request(req, &state).await
-                    .map_err(|e| ultraapi::ApiError::bad_request(format!("Invalid body: {}", e)))?;
-                #bpat.validate().map_err(|e| ultraapi::ApiError::validation_error(e))?;
+                let __ultraapi_body_bytes = ultraapi::deserialize::read_json_body(req, &state).await?;
+                let #bpat: #bty = ultraapi::deserialize::from_json_slice(&__ultraapi_body_bytes)
+                    .map_err(|e| ultraapi::ApiError::validation_errors(ultraapi::ValidationSource::Body, e))?;
+                #bpat.validate_detailed().map_err(|e| ultraapi::ApiError::validation_errors(ultraapi::ValidationSource::Body, e))?;
             }
         }
     } else if has_request_extractor {
@@ -1407,6 +2173,33 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
 
     // Generate response based on response_class
     let response_expr = match response_class.as_deref() {
+        // JsonLines / JsonArrayStream serialize their items while streaming
+        _ if json_stream.is_some() => {
+            let call = if is_result_return {
+                quote! { #fn_name(#(#call_args),*).await? }
+            } else {
+                quote! { #fn_name(#(#call_args),*).await }
+            };
+            let item_shaping = if has_response_model {
+                quote! {
+                    let result = result.response_model(
+                        #route_info_name.response_model_options.clone(),
+                        Some(#return_type_name),
+                        #by_alias,
+                    );
+                }
+            } else {
+                quote! {}
+            };
+            quote! {
+                let result = #call;
+                #item_shaping
+                Ok((
+                    ultraapi::axum::http::StatusCode::from_u16(#status_lit).unwrap(),
+                    result,
+                ).into_response())
+            }
+        }
         // HTML response
         Some("html") => {
             if is_result_return {
@@ -1592,24 +2385,28 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
         }
     };
 
+    let constrained_path_params: Vec<String> = input_fn
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(PatType { pat, attrs, .. })
+                if attrs.iter().any(|a| a.path().is_ident("validate")) =>
+            {
+                Some(quote!(#pat).to_string())
+            }
+            _ => None,
+        })
+        .collect();
     let path_param_schemas: Vec<_> = path_params
         .iter()
+        .filter(|p| !constrained_path_params.contains(p))
         .map(|p| {
             // Find the type of this path param
             let openapi_type = path_param_types
                 .iter()
                 .find(|(name, _)| name.to_string() == *p)
-                .map(|(_, ty)| {
-                    let type_name = get_type_name(ty);
-                    match type_name.as_str() {
-                        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64"
-                        | "u128" => "integer",
-                        "f32" | "f64" => "number",
-                        "String" => "string",
-                        "bool" => "boolean",
-                        _ => "string",
-                    }
-                })
+                .map(|(_, ty)| openapi_scalar_type(ty))
                 .unwrap_or("string");
             quote! {
                 ultraapi::openapi::Parameter {
@@ -1627,7 +2424,9 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
         })
         .collect();
 
-    let body_type_name = if has_multipart_body {
+    let body_type_name = if !upload_params.is_empty() {
+        format!("Body_{}", fn_name)
+    } else if has_multipart_body && body_type.is_none() {
         "Multipart".to_string()
     } else {
         body_type.map(get_type_name).unwrap_or_default()
@@ -1681,6 +2480,9 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
 
     // Generate response_class based on attribute
     let response_class_expr = match response_class.as_deref() {
+        _ if matches!(json_stream, Some((JsonStreamKind::Lines, _))) => {
+            quote! { ultraapi::ResponseClass::NdJson }
+        }
         Some("html") => quote! { ultraapi::ResponseClass::Html },
         Some("text") => quote! { ultraapi::ResponseClass::Text },
         Some("binary") => quote! { ultraapi::ResponseClass::Binary },
@@ -1745,6 +2547,15 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
         None => quote! { None },
     };
 
+    let body_limit_expr = match body_limit {
+        Some(bytes) => quote! { Some(#bytes) },
+        None => quote! { None },
+    };
+    let timeout_expr = match timeout_ms {
+        Some(ms) => quote! { Some(std::time::Duration::from_millis(#ms)) },
+        None => quote! { None },
+    };
+
     // Generate per-request scope/cache setup for Depends resolution.
     let scope_creation = if has_depends_params {
         quote! {
@@ -1790,6 +2601,7 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
         #fn_vis #fn_sig #fn_block
 
         #scalar_query_struct_def
+        #upload_form_def
 
         #[doc(hidden)]
         async fn #wrapper_name(
@@ -1845,6 +2657,11 @@ fn route_macro_impl(method: &str, attr: TokenStream, item: TokenStream) -> Token
             deprecated: #deprecated,
             external_docs_url: #external_docs_url_expr,
             external_docs_description: #external_docs_description_expr,
+            incoming_message: None,
+            outgoing_message: None,
+            sse_events: &[],
+            body_limit: #body_limit_expr,
+            timeout: #timeout_expr,
             register_fn: |app: ultraapi::axum::Router<ultraapi::AppState>| {
                 app.route(#axum_path, ultraapi::axum::routing::#method_ident(#wrapper_name))
             },
@@ -1922,6 +2739,19 @@ pub fn trace(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     // stream events ...
 /// }
 /// ```
+///
+/// # Typed events and keep-alive
+///
+/// Handlers returning `impl Stream<Item = SseEvent<T>>` send `T` as JSON `data:`, and `T` is
+/// documented as the event payload in OpenAPI and AsyncAPI. `events` lists the event names;
+/// `keep_alive_secs` / `keep_alive_text` send comment frames on idle streams.
+///
+/// ```text
+/// #[sse("/orders", events = ["created", "shipped"], keep_alive_secs = 15)]
+/// async fn orders() -> impl Stream<Item = SseEvent<Order>> {
+///     // ...
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn sse(attr: TokenStream, item: TokenStream) -> TokenStream {
     sse_macro_impl(attr, item)
@@ -1931,7 +2761,8 @@ pub fn sse(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///
 /// Note: WebSocket upgrade routes are intentionally excluded from OpenAPI paths
 /// (aligned with FastAPI behavior), because OpenAPI does not model WS upgrades
-/// as regular HTTP operations.
+/// as regular HTTP operations. They are described in the AsyncAPI document
+/// (`/asyncapi.json`) instead.
 ///
 /// # Example
 /// ```text
@@ -1954,22 +2785,158 @@ pub fn sse(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     }
 /// }
 /// ```
+///
+/// # Typed messages
+///
+/// Taking a `TypedSocket<In, Out>` argument lets the macro perform the upgrade and
+/// (de)serialize JSON frames. `incoming = ...` / `outgoing = ...` name the message types
+/// for the AsyncAPI document; they default to the `TypedSocket` generics.
+///
+/// ```text
+/// #[ws("/chat", incoming = ClientMsg, outgoing = ServerMsg)]
+/// async fn chat(mut socket: TypedSocket<ClientMsg, ServerMsg>) {
+///     while let Some(message) = socket.recv().await {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// # Dependencies and options
+///
+/// `Dep<T>`, `State<T>`, `Depends<T>`, `#[dependencies(...)]` and `#[security(...)]` work as
+/// on HTTP routes and are checked during the handshake. Bearer tokens are also read from
+/// `Sec-WebSocket-Protocol: bearer, <token>` and `?access_token=<token>`.
+///
+/// Yield dependencies are cleaned up when a `TypedSocket` handler returns.
+///
+/// A handler taking a `WebSocketUpgrade` upgrades the connection itself, so the macro never
+/// sees the socket:
+///
+/// - its yield dependencies are cleaned up right after the handshake response, not when the
+///   connection closes
+/// - it gets no keepalive; `ping_interval_secs` / `idle_timeout_secs` are a compile error
+///
+/// ```text
+/// #[ws(
+///     "/chat",
+///     allowed_origins = ["https://app.example.com"],
+///     max_message_size = 65536,
+///     max_frame_size = 16384,
+///     ping_interval_secs = 30,   // TypedSocket only
+///     idle_timeout_secs = 90,    // TypedSocket only
+/// )]
+/// ```
 #[proc_macro_attribute]
 pub fn ws(attr: TokenStream, item: TokenStream) -> TokenStream {
     ws_macro_impl(attr, item)
 }
 
+/// `#[ws("/path", incoming = ClientMsg, outgoing = ServerMsg, ping_interval_secs = 30, ...)]`
+struct WsArgs {
+    path: LitStr,
+    incoming: Option<Type>,
+    outgoing: Option<Type>,
+    ping_interval_secs: Option<LitInt>,
+    idle_timeout_secs: Option<LitInt>,
+    max_frame_size: Option<LitInt>,
+    max_message_size: Option<LitInt>,
+    allowed_origins: Vec<LitStr>,
+}
+
+impl syn::parse::Parse for WsArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Self {
+            path: input.parse()?,
+            incoming: None,
+            outgoing: None,
+            ping_interval_secs: None,
+            idle_timeout_secs: None,
+            max_frame_size: None,
+            max_message_size: None,
+            allowed_origins: Vec::new(),
+        };
+        while input.peek(syn::Token![,]) {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            match key.to_string().as_str() {
+                "incoming" => args.incoming = Some(input.parse()?),
+                "outgoing" => args.outgoing = Some(input.parse()?),
+                "ping_interval_secs" => args.ping_interval_secs = Some(input.parse()?),
+                "idle_timeout_secs" => args.idle_timeout_secs = Some(input.parse()?),
+                "max_frame_size" => args.max_frame_size = Some(input.parse()?),
+                "max_message_size" => args.max_message_size = Some(input.parse()?),
+                "allowed_origins" => {
+                    let content;
+                    syn::bracketed!(content in input);
+                    let origins =
+                        syn::punctuated::Punctuated::<LitStr, syn::Token![,]>::parse_terminated(
+                            &content,
+                        )?;
+                    args.allowed_origins.extend(origins);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected one of `incoming`, `outgoing`, `ping_interval_secs`, \
+                         `idle_timeout_secs`, `max_frame_size`, `max_message_size`, `allowed_origins`",
+                    ))
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+fn is_websocket_upgrade_type(ty: &Type) -> bool {
+    if let Type::Path(tp) = ty {
+        if let Some(seg) = tp.path.segments.last() {
+            return seg.ident == "WebSocketUpgrade";
+        }
+    }
+    false
+}
+
+fn is_typed_socket_type(ty: &Type) -> bool {
+    if let Type::Path(tp) = ty {
+        if let Some(seg) = tp.path.segments.last() {
+            return seg.ident == "TypedSocket";
+        }
+    }
+    false
+}
+
+fn message_schema_expr(ty: Option<&Type>) -> TokenStream2 {
+    match ty {
+        Some(ty) => {
+            let name = get_type_name(ty);
+            quote! {
+                Some(ultraapi::asyncapi::MessageSchema {
+                    name: #name,
+                    schema_fn: ultraapi::asyncapi::message_schema::<#ty>,
+                })
+            }
+        }
+        None => quote! { None },
+    }
+}
+
 fn ws_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let path = parse_macro_input!(attr as LitStr).value();
+    let ws_args = parse_macro_input!(attr as WsArgs);
+    let path = ws_args.path.value();
     let input_fn = parse_macro_input!(item as ItemFn);
     let fn_name = &input_fn.sig.ident;
     let fn_vis = &input_fn.vis;
     let fn_sig = &input_fn.sig;
     let fn_block = &input_fn.block;
 
-    // Parse custom attributes
+    // Parse custom attributes: #[tag("x")], #[security("x")], #[dependencies(...)], doc comments
     let mut tags: Vec<String> = Vec::new();
     let mut security_schemes: Vec<String> = Vec::new();
+    let mut route_dependencies: Vec<Type> = Vec::new();
     let description = extract_doc_comment(&input_fn.attrs);
 
     let mut clean_attrs: Vec<&syn::Attribute> = Vec::new();
@@ -1988,6 +2955,21 @@ fn ws_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                     security_schemes.push(lit.value());
                 }
             }
+        } else if attr.path().is_ident("dependencies") {
+            if let syn::Meta::List(list) = &attr.meta {
+                let parsed = match parse_dependencies_attr(list.tokens.clone()) {
+                    Ok(parsed) => parsed,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+                route_dependencies.extend(parsed);
+            } else {
+                return syn::Error::new_spanned(
+                    attr,
+                    "dependencies attribute must be used as #[dependencies(...)]",
+                )
+                .to_compile_error()
+                .into();
+            }
         } else {
             clean_attrs.push(attr);
         }
@@ -2001,10 +2983,17 @@ fn ws_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Extract dependencies from function arguments
     let mut dep_extractions = Vec::new();
     let mut call_args = Vec::new();
+    // Typed mode: a `TypedSocket<In, Out>` argument makes the wrapper perform the upgrade
+    let mut typed_socket: Option<&Type> = None;
 
     for arg in &input_fn.sig.inputs {
         if let FnArg::Typed(PatType { pat, ty, .. }) = arg {
-            if is_dep_type(ty) {
+            if is_typed_socket_type(ty) {
+                typed_socket = Some(ty);
+                call_args.push(quote!(__typed_socket));
+            } else if is_websocket_upgrade_type(ty) {
+                call_args.push(quote!(ws));
+            } else if is_dep_type(ty) {
                 if let Type::Path(tp) = ty.as_ref() {
                     if let Some(seg) = tp.path.segments.last() {
                         if let Some(inner) = extract_inner_type(seg) {
@@ -2027,7 +3016,8 @@ fn ws_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                     }
                 }
             } else if is_depends_type(ty) {
-                // FastAPI-style Depends<T> for WebSocket
+                // FastAPI-style Depends<T> - same resolution as HTTP routes, including
+                // generator (yield-based) dependencies cleaned up when the connection ends
                 if let Type::Path(tp) = ty.as_ref() {
                     if let Some(seg) = tp.path.segments.last() {
                         if let Some(inner) = extract_inner_type(seg) {
@@ -2035,16 +3025,30 @@ fn ws_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                                 let #pat: ultraapi::Depends<#inner> = if let Some(cached) = depends_cache.get::<#inner>() {
                                     ultraapi::Depends(cached)
                                 } else if let Some(resolver) = state.get_depends_resolver() {
-                                    match resolver.resolve_with_cache::<#inner>(&state, &depends_cache).await {
-                                        Ok(dep) => ultraapi::Depends(dep),
-                                        Err(e) => return Err(ultraapi::ApiError::internal(e.to_string()).into_response()),
+                                    if resolver.is_generator::<#inner>() {
+                                        match resolver.resolve_generator::<#inner>(&state, &dep_scope).await {
+                                            Ok(dep) => {
+                                                let dep_typed: std::sync::Arc<#inner> = dep.downcast()
+                                                    .map_err(|_| ultraapi::ApiError::internal(
+                                                        format!("Type mismatch for generator: {}", std::any::type_name::<#inner>())
+                                                    ))?;
+                                                depends_cache.insert(dep_typed.clone());
+                                                ultraapi::Depends(dep_typed)
+                                            }
+                                            Err(e) => return Err(ultraapi::ApiError::internal(e.to_string())),
+                                        }
+                                    } else {
+                                        match resolver.resolve_with_cache::<#inner>(&state, &depends_cache).await {
+                                            Ok(dep) => ultraapi::Depends(dep),
+                                            Err(e) => return Err(ultraapi::ApiError::internal(e.to_string())),
+                                        }
                                     }
                                 } else {
                                     let dep = state
                                         .get::<#inner>()
                                         .ok_or_else(|| ultraapi::ApiError::internal(
                                             format!("Dependency not registered: {}", std::any::type_name::<#inner>())
-                                        ).into_response())?;
+                                        ))?;
                                     depends_cache.insert(dep.clone());
                                     ultraapi::Depends(dep)
                                 };
@@ -2054,30 +3058,149 @@ fn ws_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                     }
                 }
             } else {
-                // Other arguments (like WebSocketUpgrade) are passed as-is
+                // Other arguments are passed as-is
                 call_args.push(quote!(#pat));
             }
         }
     }
 
+    let mut route_dependency_extractions = Vec::new();
+    let mut route_dependency_type_names: Vec<String> = Vec::new();
+    for dep_ty in &route_dependencies {
+        route_dependency_type_names.push(get_type_name(&resolve_dependency_type(dep_ty)));
+        route_dependency_extractions
+            .push(route_dependency_extraction(dep_ty, &mut security_schemes));
+    }
+    route_dependency_type_names.sort();
+    route_dependency_type_names.dedup();
+
     let fn_name_str = fn_name.to_string();
 
+    // Message types come from the attribute, or else from the TypedSocket generics
+    let socket_generics = typed_socket.map(generic_type_args).unwrap_or_default();
+    let incoming_ty = ws_args
+        .incoming
+        .as_ref()
+        .or(socket_generics.first().copied());
+    let outgoing_ty = ws_args
+        .outgoing
+        .as_ref()
+        .or(socket_generics.get(1).copied());
+    let incoming_message_expr = message_schema_expr(incoming_ty);
+    let outgoing_message_expr = message_schema_expr(outgoing_ty);
+
+    // Handshake options applied to the WebSocketUpgrade before the handler sees it
+    let max_frame_size = ws_args
+        .max_frame_size
+        .as_ref()
+        .map(|size| quote!(.max_frame_size(#size)));
+    let max_message_size = ws_args
+        .max_message_size
+        .as_ref()
+        .map(|size| quote!(.max_message_size(#size)));
+    let origin_check = if ws_args.allowed_origins.is_empty() {
+        quote! {}
+    } else {
+        let origins = &ws_args.allowed_origins;
+        quote! {
+            // Browsers always send Origin; reject cross-site handshakes not listed
+            if let Some(origin) = parts.headers.get(ultraapi::axum::http::header::ORIGIN) {
+                let allowed: &[&str] = &[#(#origins),*];
+                if !origin.to_str().is_ok_and(|origin| allowed.contains(&origin)) {
+                    return ultraapi::ApiError::forbidden("Origin not allowed").into_response();
+                }
+            }
+        }
+    };
+
+    let handler_call = if let Some(socket_ty) = typed_socket {
+        let ping_interval = ws_args
+            .ping_interval_secs
+            .as_ref()
+            .map(|secs| quote!(.ping_interval(std::time::Duration::from_secs(#secs))));
+        let idle_timeout = ws_args
+            .idle_timeout_secs
+            .as_ref()
+            .map(|secs| quote!(.idle_timeout(std::time::Duration::from_secs(#secs))));
+        quote! {
+            let socket_scope = dep_scope.clone();
+            ws.on_upgrade(move |socket| async move {
+                let __typed_socket = <#socket_ty>::new(socket) #ping_interval #idle_timeout;
+                #fn_name(#(#call_args),*).await;
+                // Yield dependencies live as long as the connection
+                socket_scope.run_function_cleanup().await;
+                socket_scope.run_request_cleanup().await;
+            })
+            .into_response()
+        }
+    } else {
+        if let Some(option) = ws_args
+            .ping_interval_secs
+            .as_ref()
+            .or(ws_args.idle_timeout_secs.as_ref())
+        {
+            return syn::Error::new(
+                option.span(),
+                "ping_interval_secs / idle_timeout_secs require a TypedSocket argument",
+            )
+            .to_compile_error()
+            .into();
+        }
+        quote! {
+            // Call the user's handler, passing the WebSocketUpgrade and any extracted deps.
+            // The connection is upgraded in a task the handler spawns, so yield dependencies
+            // can only be scoped to the handshake here
+            let response = #fn_name(#(#call_args),*).await.into_response();
+            dep_scope.run_function_cleanup().await;
+            let request_scope = dep_scope.clone();
+            tokio::spawn(async move {
+                request_scope.run_request_cleanup().await;
+            });
+            response
+        }
+    };
+
     let output = quote! {
         #(#clean_attrs)*
         #fn_vis #fn_sig #fn_block
 
         #[doc(hidden)]
         async fn #wrapper_name(
-            ws: ultraapi::axum::extract::ws::WebSocketUpgrade,
             ultraapi::axum::extract::State(state): ultraapi::axum::extract::State<ultraapi::AppState>,
+            mut parts: ultraapi::axum::http::request::Parts,
         ) -> ultraapi::axum::response::Response {
+            use ultraapi::axum::extract::FromRequestParts;
             use ultraapi::axum::response::IntoResponse;
 
-            let depends_cache = ultraapi::RequestDependsCache::new();
-            #(#dep_extractions)*
+            let ws = match ultraapi::axum::extract::ws::WebSocketUpgrade::from_request_parts(&mut parts, &state).await {
+                Ok(ws) => ws,
+                Err(rejection) => return rejection.into_response(),
+            };
+            #origin_check
+            // Select the subprotocol a bearer token was announced with, as browsers require
+            let ws = ws
+                #max_frame_size
+                #max_message_size
+                .protocols([ultraapi::middleware::WEBSOCKET_BEARER_PROTOCOL]);
 
-            // Call the user's handler, passing the WebSocketUpgrade and any extracted deps
-            #fn_name(#(#call_args),*).await.into_response()
+            let dep_scope = std::sync::Arc::new(ultraapi::DependencyScope::new());
+            let depends_cache = ultraapi::RequestDependsCache::new();
+            let result: Result<ultraapi::axum::response::Response, ultraapi::ApiError> = async {
+                #(#route_dependency_extractions)*
+                #(#dep_extractions)*
+                Ok({ #handler_call })
+            }
+            .await;
+
+            match result {
+                Ok(response) => response,
+                Err(error) => {
+                    // The handshake is rejected: release what the dependencies acquired
+                    dep_scope.run_function_cleanup().await;
+                    dep_scope.run_request_cleanup().await;
+                    error.into_response()
+                }
+            }
         }
 
         #[doc(hidden)]
@@ -2101,7 +3224,7 @@ fn ws_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
             description: #description,
             tags: &[#(#tags),*],
             security: &[#(#security_schemes),*],
-            dependencies: &[],
+            dependencies: &[#(#route_dependency_type_names),*],
             query_params_fn: None,
             has_query_params: false,
             response_model_options: ultraapi::ResponseModelOptions {
@@ -2118,6 +3241,11 @@ fn ws_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
             deprecated: false,
             external_docs_url: None,
             external_docs_description: None,
+            incoming_message: #incoming_message_expr,
+            outgoing_message: #outgoing_message_expr,
+            sse_events: &[],
+            body_limit: None,
+            timeout: None,
             register_fn: |app: ultraapi::axum::Router<ultraapi::AppState>| {
                 app.route(#axum_path, ultraapi::axum::routing::get(#wrapper_name))
             },
@@ -2135,8 +3263,165 @@ fn ws_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     output.into()
 }
 
+/// `#[sse("/path", events = ["a", "b"], keep_alive_secs = 15, keep_alive_text = "ping")]`
+struct SseArgs {
+    path: LitStr,
+    events: Vec<LitStr>,
+    keep_alive_secs: Option<LitInt>,
+    keep_alive_text: Option<LitStr>,
+}
+
+impl syn::parse::Parse for SseArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Self {
+            path: input.parse()?,
+            events: Vec::new(),
+            keep_alive_secs: None,
+            keep_alive_text: None,
+        };
+        while input.peek(syn::Token![,]) {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            match key.to_string().as_str() {
+                "events" => {
+                    let content;
+                    syn::bracketed!(content in input);
+                    let events =
+                        syn::punctuated::Punctuated::<LitStr, syn::Token![,]>::parse_terminated(
+                            &content,
+                        )?;
+                    args.events.extend(events);
+                }
+                "keep_alive_secs" => args.keep_alive_secs = Some(input.parse()?),
+                "keep_alive_text" => args.keep_alive_text = Some(input.parse()?),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected one of `events`, `keep_alive_secs`, `keep_alive_text`",
+                    ))
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum JsonStreamKind {
+    Lines,
+    Array,
+}
+
+/// `JsonLines<S>` / `JsonArrayStream<S>` return types with the item type of `S`
+fn json_stream_return(ty: &Type) -> Option<(JsonStreamKind, Option<Type>)> {
+    let Type::Path(tp) = ty else {
+        return None;
+    };
+    let seg = tp.path.segments.last()?;
+    let kind = if seg.ident == "JsonLines" {
+        JsonStreamKind::Lines
+    } else if seg.ident == "JsonArrayStream" {
+        JsonStreamKind::Array
+    } else {
+        return None;
+    };
+    Some((kind, extract_inner_type(seg).and_then(stream_item_type)))
+}
+
+/// `T` of `impl Stream<Item = T>`, `Pin<Box<dyn Stream<Item = T>>>` or `BoxStream<'_, T>`
+fn stream_item_type(ty: &Type) -> Option<Type> {
+    let bounds = match ty {
+        Type::ImplTrait(impl_trait) => &impl_trait.bounds,
+        Type::TraitObject(trait_object) => &trait_object.bounds,
+        Type::Paren(paren) => return stream_item_type(&paren.elem),
+        Type::Path(tp) => {
+            let seg = tp.path.segments.last()?;
+            if seg.ident == "Pin" || seg.ident == "Box" {
+                return extract_inner_type(seg).and_then(stream_item_type);
+            }
+            if seg.ident != "BoxStream" && seg.ident != "LocalBoxStream" {
+                return None;
+            }
+            let syn::PathArguments::AngleBracketed(args) = &seg.arguments else {
+                return None;
+            };
+            return args.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Type(item) => Some(item.clone()),
+                _ => None,
+            });
+        }
+        _ => return None,
+    };
+    for bound in bounds {
+        let syn::TypeParamBound::Trait(trait_bound) = bound else {
+            continue;
+        };
+        let Some(seg) = trait_bound.path.segments.last() else {
+            continue;
+        };
+        if seg.ident != "Stream" {
+            continue;
+        }
+        let syn::PathArguments::AngleBracketed(args) = &seg.arguments else {
+            continue;
+        };
+        for arg in &args.args {
+            if let syn::GenericArgument::AssocType(assoc) = arg {
+                if assoc.ident == "Item" {
+                    return Some(assoc.ty.clone());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// `T` of a handler returning `impl Stream<Item = SseEvent<T>>`
+fn sse_event_item_type(output: &syn::ReturnType) -> Option<Type> {
+    let syn::ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    let Type::ImplTrait(impl_trait) = ty.as_ref() else {
+        return None;
+    };
+    for bound in &impl_trait.bounds {
+        let syn::TypeParamBound::Trait(trait_bound) = bound else {
+            continue;
+        };
+        let Some(seg) = trait_bound.path.segments.last() else {
+            continue;
+        };
+        if seg.ident != "Stream" {
+            continue;
+        }
+        let syn::PathArguments::AngleBracketed(args) = &seg.arguments else {
+            continue;
+        };
+        for arg in &args.args {
+            if let syn::GenericArgument::AssocType(assoc) = arg {
+                if assoc.ident != "Item" {
+                    continue;
+                }
+                if let Type::Path(tp) = &assoc.ty {
+                    if let Some(item_seg) = tp.path.segments.last() {
+                        if item_seg.ident == "SseEvent" {
+                            return extract_inner_type(item_seg).cloned();
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
 fn sse_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let path = parse_macro_input!(attr as LitStr).value();
+    let sse_args = parse_macro_input!(attr as SseArgs);
+    let path = sse_args.path.value();
     let input_fn = parse_macro_input!(item as ItemFn);
     let fn_name = &input_fn.sig.ident;
     let fn_vis = &input_fn.vis;
@@ -2196,6 +3481,7 @@ fn sse_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     let mut dep_extractions = Vec::new();
     let mut call_args = Vec::new();
     let mut path_param_types: Vec<(&syn::Ident, &Type)> = Vec::new();
+    let mut has_last_event_id = false;
 
     for arg in &input_fn.sig.inputs {
         if let FnArg::Typed(PatType { pat, ty, .. }) = arg {
@@ -2249,6 +3535,16 @@ fn sse_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                         }
                     }
                 }
+            } else if is_last_event_id_type(ty) {
+                // Last-Event-ID header of a reconnecting EventSource
+                dep_extractions.push(quote! {
+                    let #pat: ultraapi::sse::LastEventId = match ultraapi::sse::LastEventId::from_request_parts(&mut parts, &state).await {
+                        Ok(id) => id,
+                        Err(never) => match never {},
+                    };
+                });
+                call_args.push(quote!(#pat));
+                has_last_event_id = true;
             } else if path_params.contains(&param_name) {
                 if let syn::Pat::Ident(pi) = pat.as_ref() {
                     path_param_types.push((&pi.ident, ty));
@@ -2317,6 +3613,59 @@ fn sse_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
         })
         .collect();
 
+    // Typed events: serialize each SseEvent<T> and record T for the docs
+    let event_item_type = sse_event_item_type(&input_fn.sig.output);
+    if event_item_type.is_none() && !sse_args.events.is_empty() {
+        return syn::Error::new(
+            sse_args.path.span(),
+            "`events` requires a handler returning `impl Stream<Item = SseEvent<T>>`",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let stream_mapping = if event_item_type.is_some() {
+        quote! {
+            let stream = ultraapi::tokio_stream::StreamExt::map(
+                stream,
+                ultraapi::sse::SseEvent::into_event,
+            );
+        }
+    } else {
+        quote! {}
+    };
+    let outgoing_message_expr = message_schema_expr(event_item_type.as_ref());
+    let sse_events = &sse_args.events;
+
+    let keep_alive = match (&sse_args.keep_alive_secs, &sse_args.keep_alive_text) {
+        (None, None) => quote! {},
+        (secs, text) => {
+            let interval = secs.as_ref().map(|secs| {
+                quote! { .interval(std::time::Duration::from_secs(#secs)) }
+            });
+            let text = text.as_ref().map(|text| quote! { .text(#text) });
+            quote! {
+                .keep_alive(ultraapi::axum::response::sse::KeepAlive::new() #interval #text)
+            }
+        }
+    };
+
+    let mut path_param_schemas = path_param_schemas;
+    if has_last_event_id {
+        path_param_schemas.push(quote! {
+            ultraapi::openapi::Parameter {
+                name: "Last-Event-ID",
+                location: "header",
+                required: false,
+                schema: ultraapi::openapi::SchemaObject::new_type("string"),
+                description: Some("ID of the last event received before reconnecting"),
+                style: None,
+                explode: None,
+                example: None,
+                examples: None,
+            }
+        });
+    }
+
     let return_type_name = "Sse".to_string();
     let fn_name_str = fn_name.to_string();
     let status_lit = proc_macro2::Literal::u16_unsuffixed(success_status);
@@ -2341,7 +3690,8 @@ fn sse_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
             #(#dep_extractions)*
 
             let stream = #fn_name(#(#call_args),*).await;
-            let sse = Sse::new(stream);
+            #stream_mapping
+            let sse = Sse::new(stream)#keep_alive;
             Ok(sse.into_response())
         }
 
@@ -2383,6 +3733,11 @@ fn sse_macro_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
             deprecated: false,
             external_docs_url: None,
             external_docs_description: None,
+            incoming_message: None,
+            outgoing_message: #outgoing_message_expr,
+            sse_events: &[#(#sse_events),*],
+            body_limit: None,
+            timeout: None,
             register_fn: |app: ultraapi::axum::Router<ultraapi::AppState>| {
                 app.route(#axum_path, ultraapi::axum::routing::get(#wrapper_name))
             },
@@ -2461,9 +3816,46 @@ fn api_model_enum(input: ItemEnum) -> TokenStream {
         quote! { Some(#description.to_string()) }
     };
 
+    // Enums with data-carrying variants (e.g. tagged WebSocket messages) are documented
+    // as a oneOf union derived from schemars; unit-only enums stay string enums.
+    let has_data_variants = variants
+        .iter()
+        .any(|v| !matches!(v.fields, syn::Fields::Unit));
+    let (schema_expr, nested_expr) = if has_data_variants {
+        (
+            quote! {
+                let root = ultraapi::schemars::schema_for!(#name);
+                let mut schema = ultraapi::openapi::schema_from_schemars_union(#name_str, &root).schema;
+                schema.description = #desc_expr;
+                schema
+            },
+            quote! {
+                let root = ultraapi::schemars::schema_for!(#name);
+                ultraapi::openapi::schema_from_schemars_union(#name_str, &root).nested
+            },
+        )
+    } else {
+        (
+            quote! {
+                ultraapi::openapi::Schema {
+                    type_name: "string".to_string(),
+                    properties: std::collections::HashMap::new(),
+                    required: vec![],
+                    description: #desc_expr,
+                    enum_values: Some(vec![#(#variant_names.to_string()),*]),
+                    example: None,
+                    one_of: None,
+                    discriminator: None,
+                }
+            },
+            quote! { std::collections::HashMap::new() },
+        )
+    };
+
+    // The derive goes first so container attributes such as #[serde(tag = "type")] follow it
     let output = quote! {
-        #(#attrs)*
         #[derive(ultraapi::serde::Serialize, ultraapi::serde::Deserialize, ultraapi::schemars::JsonSchema)]
+        #(#attrs)*
         #[serde(crate = "ultraapi::serde")]
         #[schemars(crate = "ultraapi::schemars")]
         #vis enum #name {
@@ -2483,9 +3875,12 @@ fn api_model_enum(input: ItemEnum) -> TokenStream {
                 type_name: stringify!(#name),
                 validate_fn: |any: &dyn std::any::Any| {
                     if let Some(val) = any.downcast_ref::<#name>() {
-                        <#name as ultraapi::Validate>::validate(val)
+                        <#name as ultraapi::Validate>::validate_detailed(val)
                     } else {
-                        Err(vec!["Internal validation error: type mismatch".to_string()])
+                        Err(vec![ultraapi::ValidationErrorDetail::new(
+                            "value_error",
+                            "Internal validation error: type mismatch",
+                        )])
                     }
                 },
             }
@@ -2497,21 +3892,14 @@ fn api_model_enum(input: ItemEnum) -> TokenStream {
                 schema_fn: || {
                     static CACHE: std::sync::OnceLock<ultraapi::openapi::Schema> = std::sync::OnceLock::new();
                     CACHE.get_or_init(|| {
-                        ultraapi::openapi::Schema {
-                            type_name: "string".to_string(),
-                            properties: std::collections::HashMap::new(),
-                            required: vec![],
-                            description: #desc_expr,
-                            enum_values: Some(vec![#(#variant_names.to_string()),*]),
-                            example: None,
-                            one_of: None,
-                            discriminator: None,
-                        }
+                        #schema_expr
                     }).clone()
                 },
                 nested_fn: || {
                     static CACHE: std::sync::OnceLock<std::collections::HashMap<String, ultraapi::openapi::Schema>> = std::sync::OnceLock::new();
-                    CACHE.get_or_init(|| std::collections::HashMap::new()).clone()
+                    CACHE.get_or_init(|| {
+                        #nested_expr
+                    }).clone()
                 },
             }
         }
@@ -2559,6 +3947,7 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
         let mut field_deprecated = false;
         let mut has_custom_rename = false; // from #[alias(...)]
         let mut field_default_expr: Option<TokenStream2> = None;
+        let mut is_flatten = false;
 
         // Collect passthrough serde items (not managed by us: default, flatten, with, etc.)
         let mut passthrough_serde_items: Vec<proc_macro2::TokenStream> = Vec::new();
@@ -2611,6 +4000,10 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                                 syn::Meta::Path(p) if p.is_ident("skip_deserializing") => {
                                     skip_deserializing = true;
                                 }
+                                syn::Meta::Path(p) if p.is_ident("flatten") => {
+                                    is_flatten = true;
+                                    passthrough_serde_items.push(quote! { flatten });
+                                }
                                 syn::Meta::Path(p) if p.is_ident("default") => {
                                     let field_ty = &field.ty;
                                     field_default_expr = Some(
@@ -2748,16 +4141,25 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                 }
             });
         }
+        let field_is_vec = is_vec_type(&field.ty);
         for attr in &field.attrs {
             if attr.path().is_ident("validate") {
-                let _ = attr.parse_nested_meta(|meta| {
+                let parsed = attr.parse_nested_meta(|meta| {
+                    let field_loc = schema_field_name_str.as_str();
                     if meta.path.is_ident("min_length") {
                         let value = meta.value()?;
                         let lit: syn::LitInt = value.parse()?;
                         let min: usize = lit.base10_parse()?;
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "string_too_short",
+                            quote!(format!("must be at least {} characters", #min)),
+                            Some(quote!({ "min_length": #min })),
+                        );
                         validation_checks.push(quote! {
                             if self.#field_name.len() < #min {
-                                errors.push(format!("{}: must be at least {} characters", #schema_field_name_str, #min));
+                                #push
                             }
                         });
                         schema_patches.push(quote! {
@@ -2769,9 +4171,16 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                         let value = meta.value()?;
                         let lit: syn::LitInt = value.parse()?;
                         let max: usize = lit.base10_parse()?;
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "string_too_long",
+                            quote!(format!("must be at most {} characters", #max)),
+                            Some(quote!({ "max_length": #max })),
+                        );
                         validation_checks.push(quote! {
                             if self.#field_name.len() > #max {
-                                errors.push(format!("{}: must be at most {} characters", #schema_field_name_str, #max));
+                                #push
                             }
                         });
                         schema_patches.push(quote! {
@@ -2780,6 +4189,13 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                             }
                         });
                     } else if meta.path.is_ident("email") {
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "value_error",
+                            quote!("must be a valid email address"),
+                            None,
+                        );
                         validation_checks.push(quote! {
                             {
                                 let email = &self.#field_name;
@@ -2797,7 +4213,7 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                                         }
                                     };
                                 if !valid {
-                                    errors.push(format!("{}: must be a valid email address", #schema_field_name_str));
+                                    #push
                                 }
                             }
                         });
@@ -2811,9 +4227,16 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                         let lit: syn::LitInt = value.parse()?;
                         let min: i64 = lit.base10_parse()?;
                         let min_f64 = min as f64;
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "greater_than_equal",
+                            quote!(format!("must be at least {}", #min)),
+                            Some(quote!({ "ge": #min })),
+                        );
                         validation_checks.push(quote! {
                             if (self.#field_name as f64) < #min_f64 {
-                                errors.push(format!("{}: must be at least {}", #schema_field_name_str, #min));
+                                #push
                             }
                         });
                         schema_patches.push(quote! {
@@ -2826,9 +4249,16 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                         let lit: syn::LitInt = value.parse()?;
                         let max: i64 = lit.base10_parse()?;
                         let max_f64 = max as f64;
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "less_than_equal",
+                            quote!(format!("must be at most {}", #max)),
+                            Some(quote!({ "le": #max })),
+                        );
                         validation_checks.push(quote! {
                             if (self.#field_name as f64) > #max_f64 {
-                                errors.push(format!("{}: must be at most {}", #schema_field_name_str, #max));
+                                #push
                             }
                         });
                         schema_patches.push(quote! {
@@ -2840,12 +4270,19 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                         let value = meta.value()?;
                         let lit: syn::LitStr = value.parse()?;
                         let pat = lit.value();
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "string_pattern_mismatch",
+                            quote!(format!("must match pattern {}", #pat)),
+                            Some(quote!({ "pattern": #pat })),
+                        );
                         validation_checks.push(quote! {
                             {
                                 static RE: std::sync::OnceLock<ultraapi::regex::Regex> = std::sync::OnceLock::new();
                                 let re = RE.get_or_init(|| ultraapi::regex::Regex::new(#pat).expect("Invalid regex"));
                                 if !re.is_match(&self.#field_name) {
-                                    errors.push(format!("{}: must match pattern {}", #schema_field_name_str, #pat));
+                                    #push
                                 }
                             }
                         });
@@ -2858,9 +4295,20 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                         let value = meta.value()?;
                         let lit: syn::LitInt = value.parse()?;
                         let min: usize = lit.base10_parse()?;
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "too_short",
+                            quote!(format!("must have at least {} items", #min)),
+                            Some(quote!({
+                                "field_type": "List",
+                                "min_length": #min,
+                                "actual_length": self.#field_name.len()
+                            })),
+                        );
                         validation_checks.push(quote! {
                             if self.#field_name.len() < #min {
-                                errors.push(format!("{}: must have at least {} items", #schema_field_name_str, #min));
+                                #push
                             }
                         });
                         schema_patches.push(quote! {
@@ -2868,9 +4316,385 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                                 prop.min_items = Some(#min);
                             }
                         });
+                    } else if meta.path.is_ident("max_items") {
+                        let value = meta.value()?;
+                        let lit: syn::LitInt = value.parse()?;
+                        let max: usize = lit.base10_parse()?;
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "too_long",
+                            quote!(format!("must have at most {} items", #max)),
+                            Some(quote!({
+                                "field_type": "List",
+                                "max_length": #max,
+                                "actual_length": self.#field_name.len()
+                            })),
+                        );
+                        validation_checks.push(quote! {
+                            if self.#field_name.len() > #max {
+                                #push
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.max_items = Some(#max);
+                            }
+                        });
+                    } else if meta.path.is_ident("unique_items") {
+                        // Uniqueness is judged on the JSON representation, as in JSON Schema
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "unique_items",
+                            quote!("must contain unique items"),
+                            None,
+                        );
+                        validation_checks.push(quote! {
+                            {
+                                let mut seen: Vec<ultraapi::serde_json::Value> = Vec::new();
+                                for item in self.#field_name.iter() {
+                                    let value = ultraapi::serde_json::to_value(item)
+                                        .unwrap_or(ultraapi::serde_json::Value::Null);
+                                    if seen.contains(&value) {
+                                        #push
+                                        break;
+                                    }
+                                    seen.push(value);
+                                }
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.unique_items = Some(true);
+                            }
+                        });
+                    } else if meta.path.is_ident("multiple_of") {
+                        let factor = parse_validate_number(meta.value()?)?;
+                        if factor <= 0.0 {
+                            return Err(meta.error("multiple_of must be greater than 0"));
+                        }
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "multiple_of",
+                            quote!(format!("must be a multiple of {}", #factor)),
+                            Some(quote!({ "multiple_of": #factor })),
+                        );
+                        validation_checks.push(quote! {
+                            {
+                                let quotient = (self.#field_name as f64) / #factor;
+                                if (quotient - quotient.round()).abs() > 1e-9 {
+                                    #push
+                                }
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.multiple_of = Some(#factor);
+                            }
+                        });
+                    } else if meta.path.is_ident("exclusive_minimum") {
+                        let min = parse_validate_number(meta.value()?)?;
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "greater_than",
+                            quote!(format!("must be greater than {}", #min)),
+                            Some(quote!({ "gt": #min })),
+                        );
+                        validation_checks.push(quote! {
+                            if (self.#field_name as f64) <= #min {
+                                #push
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.exclusive_minimum = Some(#min);
+                            }
+                        });
+                    } else if meta.path.is_ident("exclusive_maximum") {
+                        let max = parse_validate_number(meta.value()?)?;
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "less_than",
+                            quote!(format!("must be less than {}", #max)),
+                            Some(quote!({ "lt": #max })),
+                        );
+                        validation_checks.push(quote! {
+                            if (self.#field_name as f64) >= #max {
+                                #push
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.exclusive_maximum = Some(#max);
+                            }
+                        });
+                    } else if meta.path.is_ident("url") {
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "url_parsing",
+                            quote!("must be a valid URL"),
+                            None,
+                        );
+                        validation_checks.push(quote! {
+                            {
+                                static RE: std::sync::OnceLock<ultraapi::regex::Regex> = std::sync::OnceLock::new();
+                                let re = RE.get_or_init(|| {
+                                    ultraapi::regex::Regex::new(r"^[A-Za-z][A-Za-z0-9+.\-]*://[^\s/?#@]+(@[^\s/?#]+)?(:[0-9]+)?([/?#]\S*)?$")
+                                        .expect("Invalid regex")
+                                });
+                                if !re.is_match(&self.#field_name) {
+                                    #push
+                                }
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.format = Some("uri".to_string());
+                            }
+                        });
+                    } else if meta.path.is_ident("uuid") {
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "uuid_parsing",
+                            quote!("must be a valid UUID"),
+                            None,
+                        );
+                        validation_checks.push(quote! {
+                            {
+                                static RE: std::sync::OnceLock<ultraapi::regex::Regex> = std::sync::OnceLock::new();
+                                let re = RE.get_or_init(|| {
+                                    ultraapi::regex::Regex::new(
+                                        r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+                                    )
+                                    .expect("Invalid regex")
+                                });
+                                if !re.is_match(&self.#field_name) {
+                                    #push
+                                }
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.format = Some("uuid".to_string());
+                            }
+                        });
+                    } else if meta.path.is_ident("ip")
+                        || meta.path.is_ident("ipv4")
+                        || meta.path.is_ident("ipv6")
+                    {
+                        // `ip` accepts either family and uses pydantic's IPvAnyAddress format name
+                        let (addr_ty, format, label, error_type) = if meta.path.is_ident("ipv4") {
+                            (quote!(std::net::Ipv4Addr), "ipv4", "IPv4 address", "ip_v4_address")
+                        } else if meta.path.is_ident("ipv6") {
+                            (quote!(std::net::Ipv6Addr), "ipv6", "IPv6 address", "ip_v6_address")
+                        } else {
+                            (quote!(std::net::IpAddr), "ipvanyaddress", "IP address", "ip_any_address")
+                        };
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            error_type,
+                            quote!(format!("must be a valid {}", #label)),
+                            None,
+                        );
+                        validation_checks.push(quote! {
+                            if self.#field_name.parse::<#addr_ty>().is_err() {
+                                #push
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.format = Some(#format.to_string());
+                            }
+                        });
+                    } else if meta.path.is_ident("one_of") {
+                        let value = meta.value()?;
+                        let array: syn::ExprArray = value.parse()?;
+                        let mut allowed = Vec::new();
+                        let mut displays = Vec::new();
+                        for elem in &array.elems {
+                            let (tokens, display) = validate_literal_to_json(elem)?;
+                            allowed.push(tokens);
+                            displays.push(display);
+                        }
+                        if allowed.is_empty() {
+                            return Err(meta.error("one_of requires at least one value"));
+                        }
+                        let allowed_display = displays.join(", ");
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "literal_error",
+                            quote!(format!("must be one of {}", #allowed_display)),
+                            Some(quote!({ "expected": allowed })),
+                        );
+                        validation_checks.push(quote! {
+                            {
+                                let allowed: Vec<ultraapi::serde_json::Value> = vec![#(#allowed),*];
+                                let value = ultraapi::serde_json::to_value(&self.#field_name)
+                                    .unwrap_or(ultraapi::serde_json::Value::Null);
+                                if !allowed.contains(&value) {
+                                    #push
+                                }
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.enum_values = Some(vec![#(#allowed),*]);
+                            }
+                        });
+                    } else if meta.path.is_ident("length") {
+                        // length(min = N, max = M, equal = K): minLength/maxLength for
+                        // strings, minItems/maxItems for Vec fields
+                        let mut min: Option<usize> = None;
+                        let mut max: Option<usize> = None;
+                        meta.parse_nested_meta(|nested| {
+                            let lit: syn::LitInt = nested.value()?.parse()?;
+                            let n: usize = lit.base10_parse()?;
+                            if nested.path.is_ident("equal") {
+                                min = Some(n);
+                                max = Some(n);
+                            } else if nested.path.is_ident("min") {
+                                min = Some(n);
+                            } else if nested.path.is_ident("max") {
+                                max = Some(n);
+                            } else {
+                                return Err(nested.error("expected `min`, `max` or `equal`"));
+                            }
+                            Ok(())
+                        })?;
+                        let (unit, short_type, long_type) = if field_is_vec {
+                            ("items", "too_short", "too_long")
+                        } else {
+                            ("characters", "string_too_short", "string_too_long")
+                        };
+                        let too_short = |lo: usize, msg: TokenStream2| {
+                            let ctx = if field_is_vec {
+                                quote!({
+                                    "field_type": "List",
+                                    "min_length": #lo,
+                                    "actual_length": self.#field_name.len()
+                                })
+                            } else {
+                                quote!({ "min_length": #lo })
+                            };
+                            validation_error_push(
+                                field_loc,
+                                quote!(&self.#field_name),
+                                short_type,
+                                msg,
+                                Some(ctx),
+                            )
+                        };
+                        let too_long = |hi: usize, msg: TokenStream2| {
+                            let ctx = if field_is_vec {
+                                quote!({
+                                    "field_type": "List",
+                                    "max_length": #hi,
+                                    "actual_length": self.#field_name.len()
+                                })
+                            } else {
+                                quote!({ "max_length": #hi })
+                            };
+                            validation_error_push(
+                                field_loc,
+                                quote!(&self.#field_name),
+                                long_type,
+                                msg,
+                                Some(ctx),
+                            )
+                        };
+                        match (min, max) {
+                            (Some(lo), Some(hi)) if lo == hi => {
+                                let exact_msg =
+                                    quote!(format!("must have exactly {} {}", #lo, #unit));
+                                let short_push = too_short(lo, exact_msg.clone());
+                                let long_push = too_long(hi, exact_msg);
+                                validation_checks.push(quote! {
+                                    if self.#field_name.len() < #lo {
+                                        #short_push
+                                    } else if self.#field_name.len() > #hi {
+                                        #long_push
+                                    }
+                                });
+                            }
+                            _ => {
+                                if let Some(lo) = min {
+                                    let push = too_short(
+                                        lo,
+                                        quote!(format!("must have at least {} {}", #lo, #unit)),
+                                    );
+                                    validation_checks.push(quote! {
+                                        if self.#field_name.len() < #lo {
+                                            #push
+                                        }
+                                    });
+                                }
+                                if let Some(hi) = max {
+                                    let push = too_long(
+                                        hi,
+                                        quote!(format!("must have at most {} {}", #hi, #unit)),
+                                    );
+                                    validation_checks.push(quote! {
+                                        if self.#field_name.len() > #hi {
+                                            #push
+                                        }
+                                    });
+                                }
+                            }
+                        }
+                        let (min_field, max_field) = if field_is_vec {
+                            (quote!(min_items), quote!(max_items))
+                        } else {
+                            (quote!(min_length), quote!(max_length))
+                        };
+                        let min_assign = min.map(|lo| quote! { prop.#min_field = Some(#lo); });
+                        let max_assign = max.map(|hi| quote! { prop.#max_field = Some(#hi); });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                #min_assign
+                                #max_assign
+                            }
+                        });
+                    } else if meta.path.is_ident("contains") {
+                        let value = meta.value()?;
+                        let expr: syn::Expr = value.parse()?;
+                        let (needle, display) = validate_literal_to_json(&expr)?;
+                        let push = validation_error_push(
+                            field_loc,
+                            quote!(&self.#field_name),
+                            "contains",
+                            quote!(format!("must contain {}", #display)),
+                            Some(quote!({ "contains": needle })),
+                        );
+                        validation_checks.push(quote! {
+                            {
+                                let needle = #needle;
+                                let found = self.#field_name.iter().any(|item| {
+                                    ultraapi::serde_json::to_value(item).ok().as_ref() == Some(&needle)
+                                });
+                                if !found {
+                                    #push
+                                }
+                            }
+                        });
+                        schema_patches.push(quote! {
+                            if let Some(prop) = props.get_mut(#schema_field_name_str) {
+                                prop.contains = Some(#needle);
+                            }
+                        });
                     }
                     Ok(())
                 });
+                if let Err(err) = parsed {
+                    return err.to_compile_error().into();
+                }
             } else if attr.path().is_ident("schema") {
                 let _ = attr.parse_nested_meta(|meta| {
                     if meta.path.is_ident("example") {
@@ -2899,6 +4723,21 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
             }
         }
 
+        // Recurse into nested api_model values (directly, or inside Option/Vec/maps).
+        // Flattened fields share the parent's location, so they get no prefix.
+        if !skip_field && !skip_deserializing {
+            let nested_path = if is_flatten {
+                quote!(([] as [ultraapi::ValidationLocItem; 0]))
+            } else {
+                quote!([ultraapi::ValidationLocItem::from(#schema_field_name_str)])
+            };
+            if let Some(check) =
+                nested_validation_tokens(quote!(&self.#field_name), &field.ty, nested_path)
+            {
+                validation_checks.push(check);
+            }
+        }
+
         if field_deprecated {
             schema_patches.push(quote! {
                 if let Some(prop) = props.get_mut(#schema_field_name_str) {
@@ -2933,7 +4772,11 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
     let custom_validation_check = if let Some(custom_fn) = custom_validation_fn {
         quote! {
             if let Err(custom_errors) = #custom_fn(self) {
-                errors.extend(custom_errors);
+                errors.extend(
+                    custom_errors
+                        .into_iter()
+                        .map(ultraapi::ValidationErrorDetail::from_message),
+                );
             }
         }
     } else {
@@ -3039,7 +4882,12 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
 
         impl ultraapi::Validate for #name {
             fn validate(&self) -> Result<(), Vec<String>> {
-                let mut errors = Vec::new();
+                <Self as ultraapi::Validate>::validate_detailed(self)
+                    .map_err(|errors| errors.iter().map(|e| e.to_message()).collect())
+            }
+
+            fn validate_detailed(&self) -> Result<(), Vec<ultraapi::ValidationErrorDetail>> {
+                let mut errors: Vec<ultraapi::ValidationErrorDetail> = Vec::new();
                 #(#validation_checks)*
                 #custom_validation_check
                 if errors.is_empty() { Ok(()) } else { Err(errors) }
@@ -3055,9 +4903,12 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                 type_name: stringify!(#name),
                 validate_fn: |any: &dyn std::any::Any| {
                     if let Some(val) = any.downcast_ref::<#name>() {
-                        <#name as ultraapi::Validate>::validate(val)
+                        <#name as ultraapi::Validate>::validate_detailed(val)
                     } else {
-                        Err(vec!["Internal validation error: type mismatch".to_string()])
+                        Err(vec![ultraapi::ValidationErrorDetail::new(
+                            "value_error",
+                            "Internal validation error: type mismatch",
+                        )])
                     }
                 },
             }
@@ -3093,6 +4944,13 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
                                 if patch.maximum.is_some() { prop.maximum = patch.maximum; }
                                 if patch.pattern.is_some() { prop.pattern = patch.pattern.clone(); }
                                 if patch.min_items.is_some() { prop.min_items = patch.min_items; }
+                                if patch.max_items.is_some() { prop.max_items = patch.max_items; }
+                                if patch.unique_items.is_some() { prop.unique_items = patch.unique_items.unwrap_or(false); }
+                                if patch.multiple_of.is_some() { prop.multiple_of = patch.multiple_of; }
+                                if patch.exclusive_minimum.is_some() { prop.exclusive_minimum = patch.exclusive_minimum; }
+                                if patch.exclusive_maximum.is_some() { prop.exclusive_maximum = patch.exclusive_maximum; }
+                                if patch.enum_values.is_some() { prop.enum_values = patch.enum_values.clone(); }
+                                if patch.contains.is_some() { prop.contains = patch.contains.clone(); }
                                 if patch.description.is_some() { prop.description = patch.description.clone(); }
                                 if patch.example.is_some() { prop.example = patch.example.clone(); }
                                 if patch.read_only.is_some() { prop.read_only = patch.read_only.unwrap_or(false); }
@@ -3120,3 +4978,227 @@ fn api_model_struct(input: ItemStruct, custom_validation_fn: Option<Path>) -> To
 
     output.into()
 }
+
+/// Parse a byte size such as `"512"`, `"10KB"`, `"5MB"` or `"1GB"` (units are 1024-based)
+fn parse_byte_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" | "KIB" => 1024,
+        "MB" | "MIB" => 1024 * 1024,
+        "GB" | "GIB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
+
+/// Parse a duration such as `"500ms"`, `"5s"`, `"2m"` or `"1h"` into milliseconds
+fn parse_duration_ms(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim() {
+        "ms" => 1,
+        "s" => 1000,
+        "m" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
+
+/// Binds multipart/form-data fields to a struct.
+///
+/// `UploadFile`, `Vec<UploadFile>`, text values (`String`, numbers, `bool`), `Vec` of them
+/// and `Option` of any of these are supported. Receive it with `MultipartForm<T>`.
+/// `#[upload(max_size = "5MB", content_types = ["image/png", "image/*"])]` limits a file field.
+///
+/// ```ignore
+/// #[form_model]
+/// struct Profile {
+///     name: String,
+///     #[upload(max_size = "2MB", content_types = ["image/*"])]
+///     avatar: UploadFile,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn form_model(attr: TokenStream, item: TokenStream) -> TokenStream {
+    // `name = "..."` sets the schema name (used for the forms generated from UploadFile params)
+    let mut schema_name: Option<String> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            schema_name = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else {
+            Err(meta.error("unsupported form_model attribute, expected `name`"))
+        }
+    });
+    if let Err(err) = parser.parse(attr) {
+        return err.to_compile_error().into();
+    }
+
+    let input = parse_macro_input!(item as ItemStruct);
+    match form_model_impl(input, schema_name) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn form_model_impl(
+    mut input: ItemStruct,
+    schema_name: Option<String>,
+) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "form_model does not support generic structs",
+        ));
+    }
+    let syn::Fields::Named(fields) = &mut input.fields else {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "form_model requires a struct with named fields",
+        ));
+    };
+
+    let mut form_fields = Vec::new();
+    let mut conversions = Vec::new();
+    let mut field_idents = Vec::new();
+    let mut binary_fields = Vec::new();
+    for field in fields.named.iter_mut() {
+        let ident = field.ident.clone().expect("named field");
+        let name = ident.to_string().trim_start_matches("r#").to_string();
+        let ty = &field.ty;
+
+        let mut max_size: Option<u64> = None;
+        let mut content_types: Vec<String> = Vec::new();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("upload")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("max_size") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    max_size = Some(parse_byte_size(&lit.value()).ok_or_else(|| {
+                        syn::Error::new_spanned(
+                            &lit,
+                            "invalid size, expected e.g. \"512KB\" or \"5MB\"",
+                        )
+                    })?);
+                    Ok(())
+                } else if meta.path.is_ident("content_types") {
+                    let list: syn::ExprArray = meta.value()?.parse()?;
+                    for elem in list.elems {
+                        match elem {
+                            syn::Expr::Lit(syn::ExprLit {
+                                lit: syn::Lit::Str(s),
+                                ..
+                            }) => content_types.push(s.value()),
+                            other => {
+                                return Err(syn::Error::new_spanned(
+                                    other,
+                                    "content_types must be string literals",
+                                ))
+                            }
+                        }
+                    }
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "unsupported upload attribute, expected `max_size` or `content_types`",
+                    ))
+                }
+            })?;
+        }
+        field.attrs.retain(|a| !a.path().is_ident("upload"));
+
+        let max_size_expr = match max_size {
+            Some(size) => quote! { Some(#size) },
+            None => quote! { None },
+        };
+        form_fields.push(quote! {
+            ultraapi::upload::FormField {
+                name: #name,
+                max_size: #max_size_expr,
+                content_types: &[#(#content_types),*],
+            }
+        });
+        conversions.push(quote! {
+            let #ident = match <#ty as ultraapi::upload::FromFormField>::from_form_field(#name, form) {
+                Ok(value) => Some(value),
+                Err(error) => {
+                    errors.push(error);
+                    None
+                }
+            };
+        });
+        if is_upload_file_type(ty) {
+            binary_fields.push(name.clone());
+        }
+        field_idents.push(ident);
+    }
+
+    let name = &input.ident;
+    let name_str = schema_name.unwrap_or_else(|| name.to_string());
+    let description = extract_doc_comment(&input.attrs);
+    let desc_expr = if description.is_empty() {
+        quote! { None }
+    } else {
+        quote! { Some(#description.to_string()) }
+    };
+
+    Ok(quote! {
+        #[derive(ultraapi::schemars::JsonSchema)]
+        #[schemars(crate = "ultraapi::schemars")]
+        #input
+
+        impl ultraapi::upload::FormModel for #name {
+            fn form_fields() -> &'static [ultraapi::upload::FormField] {
+                &[#(#form_fields),*]
+            }
+
+            fn from_form_data(
+                form: &mut ultraapi::upload::FormData,
+            ) -> Result<Self, Vec<ultraapi::ValidationErrorDetail>> {
+                let mut errors = Vec::new();
+                #(#conversions)*
+                if !errors.is_empty() {
+                    return Err(errors);
+                }
+                Ok(Self {
+                    #(#field_idents: #field_idents.expect("converted without errors"),)*
+                })
+            }
+        }
+
+        ultraapi::inventory::submit! {
+            ultraapi::SchemaInfo {
+                name: #name_str,
+                schema_fn: || {
+                    static CACHE: std::sync::OnceLock<ultraapi::openapi::Schema> = std::sync::OnceLock::new();
+                    CACHE.get_or_init(|| {
+                        let base = ultraapi::schemars::schema_for!(#name);
+                        let mut schema = ultraapi::openapi::schema_from_schemars(#name_str, &base);
+                        schema.description = #desc_expr;
+                        // File fields (and the items of file lists) are `format: binary`
+                        for field in [#(#binary_fields),*] {
+                            if let Some(prop) = schema.properties.get_mut(field) {
+                                match prop.items.as_deref_mut() {
+                                    Some(items) => items.format = Some("binary".to_string()),
+                                    None => prop.format = Some("binary".to_string()),
+                                }
+                            }
+                        }
+                        schema
+                    }).clone()
+                },
+                nested_fn: std::collections::HashMap::new,
+            }
+        }
+    })
+}
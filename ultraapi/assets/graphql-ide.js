@@ -0,0 +1,162 @@
+(function () {
+  "use strict";
+  var config = document.getElementById("graphql-ide").dataset;
+  var storage = window.localStorage;
+  var socket = null;
+
+  document.body.insertAdjacentHTML(
+    "beforeend",
+    '<header><h1>GraphQL</h1><span class="status" id="status"></span>' +
+      '<button id="schema-toggle">Schema</button>' +
+      '<button id="stop" disabled>Stop</button>' +
+      '<button class="primary" id="run" title="Ctrl+Enter">Run</button></header>' +
+      "<main>" +
+      '<section><label>Query</label><textarea id="query" spellcheck="false"></textarea>' +
+      '<label>Variables</label><textarea class="small" id="variables" spellcheck="false"></textarea>' +
+      '<label>Headers</label><textarea class="small" id="headers" spellcheck="false"></textarea></section>' +
+      '<section><label>Response</label><pre id="result"></pre></section>' +
+      '<section id="schema"><label>Schema</label><pre id="sdl"></pre></section>' +
+      "</main>"
+  );
+
+  var $ = function (id) {
+    return document.getElementById(id);
+  };
+  var fields = ["query", "variables", "headers"];
+  var defaults = { query: "{\n  __typename\n}\n", variables: "{}", headers: "{}" };
+  fields.forEach(function (name) {
+    $(name).value = storage.getItem("graphql-ide:" + name) || defaults[name];
+    $(name).addEventListener("input", function () {
+      storage.setItem("graphql-ide:" + name, $(name).value);
+    });
+  });
+
+  function parseJson(name) {
+    var text = $(name).value.trim();
+    return text ? JSON.parse(text) : {};
+  }
+
+  function show(value) {
+    $("result").textContent =
+      typeof value === "string" ? value : JSON.stringify(value, null, 2);
+  }
+
+  function setStatus(text) {
+    $("status").textContent = text;
+  }
+
+  function isSubscription(query) {
+    var source = query.replace(/#[^\n]*/g, "");
+    var match = source.match(/^\s*(query|mutation|subscription)\b/);
+    return !!match && match[1] === "subscription";
+  }
+
+  function stop() {
+    if (socket) {
+      socket.close();
+      socket = null;
+    }
+    $("stop").disabled = true;
+  }
+
+  function subscribe(payload, headers) {
+    var scheme = location.protocol === "https:" ? "wss://" : "ws://";
+    var events = [];
+    socket = new WebSocket(scheme + location.host + config.subscriptionUrl, "graphql-transport-ws");
+    $("stop").disabled = false;
+    setStatus("connecting");
+    socket.onopen = function () {
+      socket.send(JSON.stringify({ type: "connection_init", payload: headers }));
+    };
+    socket.onmessage = function (event) {
+      var message = JSON.parse(event.data);
+      if (message.type === "connection_ack") {
+        setStatus("subscribed");
+        socket.send(JSON.stringify({ id: "1", type: "subscribe", payload: payload }));
+      } else if (message.type === "next" || message.type === "error") {
+        events.unshift(message.payload);
+        show(events);
+      } else if (message.type === "complete") {
+        setStatus("complete");
+        stop();
+      } else if (message.type === "ping") {
+        socket.send(JSON.stringify({ type: "pong" }));
+      }
+    };
+    socket.onclose = function (event) {
+      if (socket) {
+        setStatus("closed" + (event.reason ? ": " + event.reason : ""));
+      }
+      stop();
+    };
+  }
+
+  function run() {
+    var payload, headers;
+    try {
+      payload = { query: $("query").value, variables: parseJson("variables") };
+      headers = parseJson("headers");
+    } catch (error) {
+      show("Invalid JSON: " + error.message);
+      return;
+    }
+    stop();
+    if (isSubscription(payload.query)) {
+      subscribe(payload, headers);
+      return;
+    }
+    setStatus("running");
+    var started = Date.now();
+    fetch(config.url, {
+      method: "POST",
+      headers: Object.assign({ "content-type": "application/json", accept: "application/json" }, headers),
+      body: JSON.stringify(payload),
+      credentials: "same-origin",
+    })
+      .then(function (response) {
+        setStatus(response.status + " in " + (Date.now() - started) + " ms");
+        return response.text();
+      })
+      .then(function (text) {
+        try {
+          show(JSON.parse(text));
+        } catch (error) {
+          show(text);
+        }
+      })
+      .catch(function (error) {
+        setStatus("failed");
+        show(String(error));
+      });
+  }
+
+  function loadSchema() {
+    if (!config.sdlUrl) {
+      $("sdl").textContent = "Schema export is disabled.";
+      return;
+    }
+    fetch(config.sdlUrl, { headers: parseJson("headers"), credentials: "same-origin" })
+      .then(function (response) {
+        return response.text();
+      })
+      .then(function (text) {
+        $("sdl").textContent = text;
+      });
+  }
+
+  $("run").addEventListener("click", run);
+  $("stop").addEventListener("click", stop);
+  $("schema-toggle").addEventListener("click", function () {
+    var panel = $("schema");
+    panel.classList.toggle("open");
+    if (panel.classList.contains("open")) {
+      loadSchema();
+    }
+  });
+  document.addEventListener("keydown", function (event) {
+    if ((event.ctrlKey || event.metaKey) && event.key === "Enter") {
+      event.preventDefault();
+      run();
+    }
+  });
+})();
@@ -0,0 +1,171 @@
+//! AsyncAPI document generation for `#[ws]` and `#[sse]` routes
+//!
+//! OpenAPI は WebSocket / SSE のメッセージを表現できないため、これらのルートは
+//! AsyncAPI 3.0 ドキュメント (デフォルト `/asyncapi.json`) に出力します。
+//!
+//! - ルートごとに 1 チャンネル (`address` はルートのパス、パスパラメータは `parameters`)
+//! - クライアントからのメッセージ (`incoming`) は `receive` オペレーション、
+//!   サーバーからのメッセージ (`outgoing`) は `send` オペレーション
+//! - メッセージのペイロードは schemars の JSON Schema を `components/schemas` に登録
+
+use schemars::gen::SchemaSettings;
+use schemars::schema::RootSchema;
+use serde_json::{json, Map, Value};
+
+use crate::openapi;
+
+/// Message type of a `#[ws]` / `#[sse]` route, recorded for the AsyncAPI document.
+#[derive(Clone, Copy)]
+pub struct MessageSchema {
+    pub name: &'static str,
+    pub schema_fn: fn() -> RootSchema,
+}
+
+/// JSON Schema of a message type with references into `#/components/schemas/`.
+pub fn message_schema<T: schemars::JsonSchema>() -> RootSchema {
+    SchemaSettings::draft07()
+        .with(|settings| {
+            settings.definitions_path = "#/components/schemas/".to_string();
+            settings.meta_schema = None;
+        })
+        .into_generator()
+        .into_root_schema_for::<T>()
+}
+
+/// A `#[ws]` / `#[sse]` route as it appears in the document.
+pub struct Channel<'a> {
+    /// Full path of the route (router prefixes applied)
+    pub address: String,
+    pub tags: Vec<String>,
+    pub route: &'a crate::RouteInfo,
+}
+
+/// Build an AsyncAPI 3.0 document describing the given channels.
+pub fn document(info: &openapi::Info, channels: &[Channel<'_>]) -> Value {
+    let mut channels_obj = Map::new();
+    let mut operations = Map::new();
+    let mut messages = Map::new();
+    let mut schemas = Map::new();
+
+    for channel in channels {
+        let route = channel.route;
+        let channel_id = route.handler_name;
+        let channel_ref = format!("#/channels/{}", channel_id);
+
+        let mut channel_obj = json!({ "address": channel.address });
+        if !route.description.is_empty() {
+            channel_obj["description"] = Value::String(route.description.to_string());
+        }
+
+        let parameters: Map<String, Value> = channel
+            .address
+            .split('/')
+            .filter(|segment| segment.starts_with('{') && segment.ends_with('}'))
+            .map(|segment| (segment[1..segment.len() - 1].to_string(), json!({})))
+            .collect();
+        if !parameters.is_empty() {
+            channel_obj["parameters"] = Value::Object(parameters);
+        }
+
+        if route.is_websocket {
+            channel_obj["bindings"] =
+                json!({ "ws": { "method": "GET", "bindingVersion": "0.1.0" } });
+        }
+
+        let mut channel_messages = Map::new();
+        for message in [route.incoming_message, route.outgoing_message]
+            .into_iter()
+            .flatten()
+        {
+            register_message(message, &mut messages, &mut schemas);
+            channel_messages.insert(
+                message.name.to_string(),
+                json!({ "$ref": format!("#/components/messages/{}", message.name) }),
+            );
+        }
+        if !channel_messages.is_empty() {
+            channel_obj["messages"] = Value::Object(channel_messages);
+        }
+        channels_obj.insert(channel_id.to_string(), channel_obj);
+
+        // Operations are described from the application's point of view:
+        // it receives client messages and sends server messages.
+        let mut actions = Vec::new();
+        if route.is_websocket {
+            actions.push(("receive", route.incoming_message));
+        }
+        actions.push(("send", route.outgoing_message));
+
+        for (action, message) in actions {
+            let mut operation = json!({
+                "action": action,
+                "channel": { "$ref": channel_ref },
+            });
+            if let Some(message) = message {
+                operation["messages"] = json!([{
+                    "$ref": format!("{}/messages/{}", channel_ref, message.name)
+                }]);
+            }
+            if !channel.tags.is_empty() {
+                operation["tags"] = channel
+                    .tags
+                    .iter()
+                    .map(|tag| json!({ "name": tag }))
+                    .collect();
+            }
+            operations.insert(format!("{}_{}", channel_id, action), operation);
+        }
+    }
+
+    let mut info_obj = json!({ "title": info.title, "version": info.version });
+    if let Some(description) = &info.description {
+        info_obj["description"] = Value::String(description.clone());
+    }
+
+    let mut components = Map::new();
+    if !messages.is_empty() {
+        components.insert("messages".to_string(), Value::Object(messages));
+    }
+    if !schemas.is_empty() {
+        components.insert("schemas".to_string(), Value::Object(schemas));
+    }
+
+    json!({
+        "asyncapi": "3.0.0",
+        "info": info_obj,
+        "defaultContentType": "application/json",
+        "channels": channels_obj,
+        "operations": operations,
+        "components": components,
+    })
+}
+
+fn register_message(
+    message: MessageSchema,
+    messages: &mut Map<String, Value>,
+    schemas: &mut Map<String, Value>,
+) {
+    if messages.contains_key(message.name) {
+        return;
+    }
+
+    let root = (message.schema_fn)();
+    for (name, schema) in &root.definitions {
+        schemas
+            .entry(name.clone())
+            .or_insert_with(|| serde_json::to_value(schema).unwrap_or(Value::Null));
+    }
+    schemas.insert(
+        message.name.to_string(),
+        serde_json::to_value(&root.schema).unwrap_or(Value::Null),
+    );
+
+    messages.insert(
+        message.name.to_string(),
+        json!({
+            "name": message.name,
+            "contentType": "application/json",
+            "payload": { "$ref": format!("#/components/schemas/{}", message.name) },
+        }),
+    );
+}
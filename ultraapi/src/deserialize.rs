@@ -0,0 +1,365 @@
+//! Request body deserialization with FastAPI-style error reporting
+//!
+//! serde は最初のエラーで停止し、`missing field ...` のような文字列しか返しません。
+//! このモジュールは JSON ボディのデシリアライズ失敗を `ValidationErrorDetail` に変換します。
+//!
+//! - ボディはバイト列から直接デシリアライズし、失敗した場合だけ JSON ツリーを構築して報告に使用
+//! - `serde_path_to_error` でエラー位置 (`items[1].price`) を取得
+//! - 登録済みの `#[api_model]` スキーマ (`SchemaInfo`) でペイロード全体を走査し、
+//!   `missing` / `type_error` / `enum` を可能な限りまとめて報告
+//! - スキーマが無い型 (または走査で検出できないエラー) は serde のエラー 1 件にフォールバック
+//! - JSON として読めないボディは `json_invalid` (422) として報告
+
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::{header, HeaderMap};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::openapi::{Property, Schema};
+use crate::{ApiError, AppState, SchemaInfo, ValidationErrorDetail, ValidationLocItem};
+
+/// Read a JSON request body, checking `Content-Type` like axum's `Json` extractor.
+pub async fn read_json_body(req: Request, state: &AppState) -> Result<Bytes, ApiError> {
+    if !has_json_content_type(req.headers()) {
+        return Err(ApiError::bad_request(
+            "Invalid body: Expected request with `Content-Type: application/json`".to_string(),
+        ));
+    }
+    Bytes::from_request(req, state)
+        .await
+        .map_err(|rejection| ApiError::bad_request(format!("Invalid body: {}", rejection)))
+}
+
+/// `application/json` or any `application/*+json` type
+fn has_json_content_type(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence == "application/json"
+        || (essence.starts_with("application/") && essence.ends_with("+json"))
+}
+
+/// Deserialize a JSON body straight from its bytes.
+///
+/// Malformed JSON is reported like pydantic does: a `json_invalid` entry at `["body"]` with the
+/// parser error in `ctx`. Only a body that fails is parsed into a tree for the schema walk.
+pub fn from_json_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Vec<ValidationErrorDetail>> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let error = match serde_path_to_error::deserialize::<_, T>(&mut deserializer) {
+        Ok(parsed) => {
+            return match deserializer.end() {
+                Ok(()) => Ok(parsed),
+                Err(trailing) => Err(vec![json_invalid(&trailing)]),
+            }
+        }
+        Err(error) => error,
+    };
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(value) => Err(collect_errors::<T>(&error, &value)),
+        Err(syntax) => Err(vec![json_invalid(&syntax)]),
+    }
+}
+
+/// Deserialize a JSON body into `T`, reporting failures as body-relative validation errors.
+pub fn from_json_value<T: DeserializeOwned>(value: Value) -> Result<T, Vec<ValidationErrorDetail>> {
+    // Deserialize from a borrow so the tree is still there for error reporting
+    serde_path_to_error::deserialize::<_, T>(&value)
+        .map_err(|error| collect_errors::<T>(&error, &value))
+}
+
+fn json_invalid(error: &serde_json::Error) -> ValidationErrorDetail {
+    ValidationErrorDetail::new("json_invalid", "JSON decode error")
+        .with_ctx(serde_json::json!({ "error": error.to_string() }))
+}
+
+/// Errors of the schema walk, plus serde's own error unless the walk already reported that
+/// location
+fn collect_errors<T>(
+    error: &serde_path_to_error::Error<serde_json::Error>,
+    value: &Value,
+) -> Vec<ValidationErrorDetail> {
+    let mut collected = SchemaWalker::for_type::<T>()
+        .map(|walker| walker.check_root(value))
+        .unwrap_or_default();
+    let serde_detail = serde_error_detail(error, value);
+    if !collected
+        .iter()
+        .any(|e| e.field_path() == serde_detail.field_path())
+    {
+        collected.push(serde_detail);
+    }
+    collected
+}
+
+/// Convert a single serde error (with its path) into a validation error.
+fn serde_error_detail(
+    error: &serde_path_to_error::Error<serde_json::Error>,
+    original: &Value,
+) -> ValidationErrorDetail {
+    let mut loc: Vec<ValidationLocItem> = Vec::new();
+    for segment in error.path().iter() {
+        match segment {
+            serde_path_to_error::Segment::Seq { index } => loc.push((*index).into()),
+            serde_path_to_error::Segment::Map { key } => loc.push(key.as_str().into()),
+            serde_path_to_error::Segment::Enum { variant } => loc.push(variant.as_str().into()),
+            serde_path_to_error::Segment::Unknown => {}
+        }
+    }
+
+    let message = error.inner().to_string();
+    let message = match message.find(" at line ") {
+        Some(pos) => message[..pos].to_string(),
+        None => message,
+    };
+
+    let detail = if let Some(field) = backticked(&message, "missing field ") {
+        loc.push(field.into());
+        ValidationErrorDetail::new("missing", "Field required")
+    } else if let Some(field) = backticked(&message, "unknown field ") {
+        loc.push(field.into());
+        ValidationErrorDetail::new("extra_forbidden", "Extra inputs are not permitted")
+    } else if message.starts_with("unknown variant ") {
+        let expected = expected_variants(&message);
+        ValidationErrorDetail::new("enum", format!("Input should be {}", expected))
+            .with_ctx(serde_json::json!({ "expected": expected }))
+    } else if let Some(expected) = message
+        .strip_prefix("invalid type: ")
+        .and_then(|rest| rest.split_once(", expected "))
+        .map(|(_, expected)| expected)
+    {
+        ValidationErrorDetail::new("type_error", type_error_message(expected))
+    } else {
+        ValidationErrorDetail::new("value_error", message)
+    };
+
+    let mut detail = detail.nested_under(&loc);
+    if detail.error_type != "missing" {
+        if let Some(input) = value_at(original, &detail.loc) {
+            detail = detail.with_input(input);
+        }
+    }
+    detail
+}
+
+/// `missing field `name`` -> `name`
+fn backticked<'a>(message: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = message.strip_prefix(prefix)?.strip_prefix('`')?;
+    rest.split('`').next()
+}
+
+/// `unknown variant `x`, expected one of `a`, `b`` -> `'a' or 'b'`
+fn expected_variants(message: &str) -> String {
+    let expected = message.split(", expected ").nth(1).unwrap_or_default();
+    let variants: Vec<String> = expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(|v| format!("'{}'", v))
+        .collect();
+    join_alternatives(&variants)
+}
+
+fn join_alternatives(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [init @ .., last] => format!("{} or {}", init.join(", "), last),
+    }
+}
+
+/// Human readable message for a serde `expected ...` clause or a JSON Schema type.
+fn type_error_message(expected: &str) -> String {
+    let kind = match expected {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64" | "u128" | "isize"
+        | "usize" | "integer" => "a valid integer",
+        "f32" | "f64" | "number" => "a valid number",
+        "a string" | "string" => "a valid string",
+        "a boolean" | "boolean" => "a valid boolean",
+        "a sequence" | "array" => "a valid list",
+        "a map" | "object" => "a valid dictionary",
+        other if other.starts_with("struct ") || other.starts_with("a map") => "a valid dictionary",
+        other => return format!("Input should be {}", other),
+    };
+    format!("Input should be {}", kind)
+}
+
+fn value_at<'a>(root: &'a Value, loc: &[ValidationLocItem]) -> Option<&'a Value> {
+    let mut current = root;
+    for item in loc {
+        current = match item {
+            ValidationLocItem::Str(key) => current.get(key.as_str())?,
+            ValidationLocItem::Int(index) => current.get(usize::try_from(*index).ok()?)?,
+        };
+    }
+    Some(current)
+}
+
+/// Walks a JSON payload against the `#[api_model]` schemas registered in inventory.
+struct SchemaWalker {
+    root: Schema,
+    definitions: HashMap<String, Schema>,
+}
+
+impl SchemaWalker {
+    fn for_type<T>() -> Option<Self> {
+        let full_type_name = std::any::type_name::<T>();
+        let simple_name = full_type_name.rsplit("::").next().unwrap_or(full_type_name);
+        let info = inventory::iter::<SchemaInfo>
+            .into_iter()
+            .find(|info| info.name == simple_name)?;
+        Some(Self {
+            root: (info.schema_fn)(),
+            definitions: (info.nested_fn)(),
+        })
+    }
+
+    fn check_root(&self, value: &Value) -> Vec<ValidationErrorDetail> {
+        let mut errors = Vec::new();
+        self.check_schema(&self.root, value, &mut Vec::new(), &mut errors, 0);
+        errors
+    }
+
+    fn resolve(&self, ref_path: &str) -> Option<Schema> {
+        let name = ref_path.trim_start_matches("#/components/schemas/");
+        if let Some(schema) = self.definitions.get(name) {
+            return Some(schema.clone());
+        }
+        inventory::iter::<SchemaInfo>
+            .into_iter()
+            .find(|info| info.name == name)
+            .map(|info| (info.schema_fn)())
+    }
+
+    fn check_schema(
+        &self,
+        schema: &Schema,
+        value: &Value,
+        loc: &mut Vec<ValidationLocItem>,
+        errors: &mut Vec<ValidationErrorDetail>,
+        depth: usize,
+    ) {
+        // Guard against self-referencing models
+        if depth > 32 {
+            return;
+        }
+
+        if let Some(variants) = &schema.enum_values {
+            let matches = value
+                .as_str()
+                .map(|s| variants.iter().any(|v| v == s))
+                .unwrap_or(false);
+            if !matches {
+                let quoted: Vec<String> = variants.iter().map(|v| format!("'{}'", v)).collect();
+                let expected = join_alternatives(&quoted);
+                errors.push(
+                    ValidationErrorDetail::new("enum", format!("Input should be {}", expected))
+                        .nested_under(loc)
+                        .with_input(value)
+                        .with_ctx(serde_json::json!({ "expected": expected })),
+                );
+            }
+            return;
+        }
+
+        // Unions and other non-object schemas are left to serde
+        if schema.one_of.is_some() || schema.type_name != "object" {
+            return;
+        }
+
+        let Some(object) = value.as_object() else {
+            errors.push(
+                ValidationErrorDetail::new("type_error", type_error_message("object"))
+                    .nested_under(loc)
+                    .with_input(value),
+            );
+            return;
+        };
+
+        for field in &schema.required {
+            if !object.contains_key(field) {
+                loc.push(field.as_str().into());
+                errors.push(
+                    ValidationErrorDetail::new("missing", "Field required").nested_under(loc),
+                );
+                loc.pop();
+            }
+        }
+
+        // Properties are a HashMap; sort for a stable error order
+        let mut fields: Vec<(&String, &Property)> = schema.properties.iter().collect();
+        fields.sort_by_key(|(name, _)| *name);
+        for (name, property) in fields {
+            if let Some(field_value) = object.get(name) {
+                loc.push(name.as_str().into());
+                self.check_property(property, field_value, loc, errors, depth);
+                loc.pop();
+            }
+        }
+    }
+
+    fn check_property(
+        &self,
+        property: &Property,
+        value: &Value,
+        loc: &mut Vec<ValidationLocItem>,
+        errors: &mut Vec<ValidationErrorDetail>,
+        depth: usize,
+    ) {
+        if value.is_null() && property.nullable {
+            return;
+        }
+
+        if let Some(ref_path) = &property.ref_path {
+            if let Some(schema) = self.resolve(ref_path) {
+                self.check_schema(&schema, value, loc, errors, depth + 1);
+            }
+            return;
+        }
+
+        let type_matches = match property.type_name.as_str() {
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            // Unknown / free-form types are accepted as-is
+            _ => true,
+        };
+        if !type_matches {
+            errors.push(
+                ValidationErrorDetail::new("type_error", type_error_message(&property.type_name))
+                    .nested_under(loc)
+                    .with_input(value),
+            );
+            return;
+        }
+
+        if let (Some(items), Some(array)) = (&property.items, value.as_array()) {
+            for (index, item) in array.iter().enumerate() {
+                loc.push(index.into());
+                self.check_property(items, item, loc, errors, depth);
+                loc.pop();
+            }
+        }
+
+        if let (Some(values), Some(map)) = (&property.additional_properties, value.as_object()) {
+            for (key, item) in map {
+                loc.push(key.as_str().into());
+                self.check_property(values, item, loc, errors, depth);
+                loc.pop();
+            }
+        }
+    }
+}
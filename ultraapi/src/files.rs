@@ -0,0 +1,510 @@
+//! Disk-backed file responses with Range requests and conditional GET
+//!
+//! [`DiskFileResponse`] はファイルをメモリに読み込まず、パスからストリーミングで返します。
+//! `UltraApiApp` のルーターはリクエストヘッダーを見て次のように応答します
+//! (`static_files` で配信するファイルも同じ処理です)。
+//!
+//! - `Accept-Ranges: bytes`、`ETag` (サイズと更新時刻から生成)、`Last-Modified` を付与
+//! - `If-None-Match` / `If-Modified-Since` が一致すれば 304 Not Modified
+//! - `Range` が 1 つなら 206 + `Content-Range`、複数なら 206 `multipart/byteranges`
+//! - 範囲がファイル外なら 416 (`Content-Range: bytes */<size>`)。`If-Range` が一致しないときは全体を返す
+//!
+//! ```ignore
+//! #[get("/videos/{name}")]
+//! #[response_class("file")]
+//! async fn video(name: String) -> DiskFileResponse {
+//!     DiskFileResponse::new(format!("./videos/{}", name)).with_content_type("video/mp4")
+//! }
+//! ```
+
+use std::convert::Infallible;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::{Body, Bytes};
+use axum::extract::Request;
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::ApiError;
+
+/// Requests with more ranges than this get the whole file
+const MAX_RANGES: usize = 16;
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A file streamed from disk.
+///
+/// Range requests, `ETag` / `Last-Modified` validators and 304 responses are handled
+/// by the router built by `UltraApiApp`; on a plain axum router the whole file is sent.
+#[derive(Clone, Debug)]
+pub struct DiskFileResponse {
+    path: PathBuf,
+    filename: Option<String>,
+    content_type: Option<String>,
+}
+
+impl DiskFileResponse {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            filename: None,
+            content_type: None,
+        }
+    }
+
+    /// Send `Content-Disposition: attachment; filename="..."`
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Set the Content-Type (guessed from the extension by default)
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get_filename(&self) -> Option<&String> {
+        self.filename.as_ref()
+    }
+
+    pub fn get_content_type(&self) -> String {
+        self.content_type.clone().unwrap_or_else(|| {
+            mime_guess::from_path(&self.path)
+                .first_or_octet_stream()
+                .to_string()
+        })
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Ok(value) = HeaderValue::from_str(&self.get_content_type()) {
+            headers.insert(header::CONTENT_TYPE, value);
+        }
+        if let Some(filename) = &self.filename {
+            let disposition = format!("attachment; filename=\"{}\"", filename.replace('"', ""));
+            if let Ok(value) = HeaderValue::from_str(&disposition) {
+                headers.insert(header::CONTENT_DISPOSITION, value);
+            }
+        }
+        headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        headers
+    }
+}
+
+impl IntoResponse for DiskFileResponse {
+    fn into_response(self) -> Response {
+        // The whole file; file_response_middleware replaces it using the request headers
+        let mut response = Response::new(Body::from_stream(file_stream(
+            self.path.clone(),
+            0,
+            u64::MAX,
+        )));
+        *response.headers_mut() = self.headers();
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Request headers that decide how a file is served
+#[derive(Default)]
+struct Preconditions {
+    range: Option<String>,
+    if_range: Option<String>,
+    if_none_match: Option<String>,
+    if_modified_since: Option<String>,
+}
+
+impl Preconditions {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let get = |name: header::HeaderName| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            range: get(header::RANGE),
+            if_range: get(header::IF_RANGE),
+            if_none_match: get(header::IF_NONE_MATCH),
+            if_modified_since: get(header::IF_MODIFIED_SINCE),
+        }
+    }
+}
+
+/// Serve [`DiskFileResponse`]s returned by handlers according to the request headers
+pub(crate) async fn file_response_middleware(req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let preconditions = Preconditions::from_headers(req.headers());
+
+    let response = next.run(req).await;
+    let Some(file) = response.extensions().get::<DiskFileResponse>().cloned() else {
+        return response;
+    };
+
+    let (parts, _) = response.into_parts();
+    let mut served = serve_file(&file, &preconditions, &method).await;
+    if served.status() == StatusCode::OK {
+        // Keep a status set by the route (e.g. #[status(...)])
+        *served.status_mut() = parts.status;
+    }
+    // Headers added by the handler (cookies, cache control, ...) are kept
+    let present: Vec<_> = served.headers().keys().cloned().collect();
+    for (name, value) in parts.headers.iter() {
+        if !present.contains(name) {
+            served.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+    served
+}
+
+/// `nest_service` target for `UltraApiApp::static_files`
+pub(crate) fn static_files_service(
+    dir: impl Into<PathBuf>,
+) -> impl tower::Service<
+    Request,
+    Response = Response,
+    Error = Infallible,
+    Future = impl std::future::Future<Output = Result<Response, Infallible>> + Send,
+> + Clone
+       + Send
+       + Sync
+       + 'static {
+    let root = Arc::new(dir.into());
+    tower::service_fn(move |req: Request| {
+        let root = root.clone();
+        async move { Ok(serve_static(&root, req).await) }
+    })
+}
+
+async fn serve_static(root: &Path, req: Request) -> Response {
+    let method = req.method().clone();
+    if method != Method::GET && method != Method::HEAD {
+        return ApiError::with_status(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed")
+            .with_header(header::ALLOW, HeaderValue::from_static("GET, HEAD"))
+            .into_response();
+    }
+
+    let Some(mut path) = resolve_static_path(root, req.uri().path()) else {
+        return ApiError::not_found("File not found".to_string()).into_response();
+    };
+    if req.uri().path().ends_with('/') || tokio::fs::metadata(&path).await.is_ok_and(|m| m.is_dir())
+    {
+        path.push("index.html");
+    }
+
+    let preconditions = Preconditions::from_headers(req.headers());
+    serve_file(&DiskFileResponse::new(path), &preconditions, &method).await
+}
+
+/// Join a request path onto `root`, refusing anything that could leave it
+fn resolve_static_path(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    for segment in request_path.split('/') {
+        let segment = percent_encoding::percent_decode_str(segment)
+            .decode_utf8()
+            .ok()?;
+        match segment.as_ref() {
+            "" | "." => continue,
+            ".." => return None,
+            s if s.contains(['/', '\\', ':', '\0']) => return None,
+            s => path.push(s),
+        }
+    }
+    Some(path)
+}
+
+async fn serve_file(
+    file: &DiskFileResponse,
+    preconditions: &Preconditions,
+    method: &Method,
+) -> Response {
+    let metadata = match tokio::fs::metadata(&file.path).await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        Ok(_) => return ApiError::not_found("File not found".to_string()).into_response(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return ApiError::not_found("File not found".to_string()).into_response()
+        }
+        Err(e) => return ApiError::internal(format!("Failed to read file: {}", e)).into_response(),
+    };
+
+    let len = metadata.len();
+    let modified = metadata.modified().ok();
+    let etag = entity_tag(len, modified);
+
+    let mut headers = file.headers();
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Some(modified) = modified {
+        if let Ok(value) = HeaderValue::from_str(&httpdate::fmt_http_date(modified)) {
+            headers.insert(header::LAST_MODIFIED, value);
+        }
+    }
+
+    let is_get = *method == Method::GET;
+    if (is_get || *method == Method::HEAD) && not_modified(preconditions, &etag, modified) {
+        headers.remove(header::CONTENT_TYPE);
+        return build(StatusCode::NOT_MODIFIED, headers, Body::empty());
+    }
+
+    let ranges = match (&preconditions.range, is_get) {
+        (Some(range), true) if if_range_matches(preconditions, &etag, modified) => {
+            parse_ranges(range, len)
+        }
+        _ => RangeRequest::Full,
+    };
+    let body_for = |stream: BoxStream<'static, std::io::Result<Bytes>>| {
+        if *method == Method::HEAD {
+            Body::empty()
+        } else {
+            Body::from_stream(stream)
+        }
+    };
+
+    match ranges {
+        RangeRequest::Full => {
+            headers.insert(header::CONTENT_LENGTH, HeaderValue::from(len));
+            let body = body_for(file_stream(file.path.clone(), 0, len));
+            build(StatusCode::OK, headers, body)
+        }
+        RangeRequest::Unsatisfiable => {
+            let mut error =
+                ApiError::with_status(StatusCode::RANGE_NOT_SATISFIABLE, "Range not satisfiable");
+            if let Ok(value) = HeaderValue::from_str(&format!("bytes */{}", len)) {
+                error = error.with_header(header::CONTENT_RANGE, value);
+            }
+            error.into_response()
+        }
+        RangeRequest::Partial(ranges) if ranges.len() == 1 => {
+            let (start, end) = ranges[0];
+            let content_range = format!("bytes {}-{}/{}", start, end, len);
+            if let Ok(value) = HeaderValue::from_str(&content_range) {
+                headers.insert(header::CONTENT_RANGE, value);
+            }
+            headers.insert(header::CONTENT_LENGTH, HeaderValue::from(end - start + 1));
+            let body = body_for(file_stream(file.path.clone(), start, end - start + 1));
+            build(StatusCode::PARTIAL_CONTENT, headers, body)
+        }
+        RangeRequest::Partial(ranges) => {
+            let boundary = multipart_boundary();
+            let content_type = file.get_content_type();
+            let mut parts: Vec<BoxStream<'static, std::io::Result<Bytes>>> = Vec::new();
+            let mut content_length = 0u64;
+
+            for (index, (start, end)) in ranges.iter().copied().enumerate() {
+                let part_header = format!(
+                    "{}--{}\r\nContent-Type: {}\r\nContent-Range: bytes {}-{}/{}\r\n\r\n",
+                    if index == 0 { "" } else { "\r\n" },
+                    boundary,
+                    content_type,
+                    start,
+                    end,
+                    len
+                );
+                content_length += part_header.len() as u64 + (end - start + 1);
+                parts.push(stream::once(async move { Ok(Bytes::from(part_header)) }).boxed());
+                parts.push(file_stream(file.path.clone(), start, end - start + 1));
+            }
+            let closing = format!("\r\n--{}--\r\n", boundary);
+            content_length += closing.len() as u64;
+            parts.push(stream::once(async move { Ok(Bytes::from(closing)) }).boxed());
+
+            let multipart_type = format!("multipart/byteranges; boundary={}", boundary);
+            if let Ok(value) = HeaderValue::from_str(&multipart_type) {
+                headers.insert(header::CONTENT_TYPE, value);
+            }
+            headers.insert(header::CONTENT_LENGTH, HeaderValue::from(content_length));
+            let body = body_for(stream::iter(parts).flatten().boxed());
+            build(StatusCode::PARTIAL_CONTENT, headers, body)
+        }
+    }
+}
+
+fn build(status: StatusCode, headers: HeaderMap, body: Body) -> Response {
+    let mut response = Response::new(body);
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    response
+}
+
+/// Strong validator from the size and modification time, like most file servers
+fn entity_tag(len: u64, modified: Option<SystemTime>) -> String {
+    let modified = modified
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+    format!(
+        "\"{:x}-{:x}-{:x}\"",
+        modified.as_secs(),
+        modified.subsec_nanos(),
+        len
+    )
+}
+
+fn not_modified(preconditions: &Preconditions, etag: &str, modified: Option<SystemTime>) -> bool {
+    // If-None-Match takes precedence over If-Modified-Since
+    if let Some(if_none_match) = &preconditions.if_none_match {
+        let weak = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+        return if_none_match
+            .split(',')
+            .any(|tag| tag.trim() == "*" || weak(tag) == weak(etag));
+    }
+    match (&preconditions.if_modified_since, modified) {
+        (Some(since), Some(modified)) => match httpdate::parse_http_date(since) {
+            Ok(since) => seconds(modified) <= seconds(since),
+            Err(_) => false,
+        },
+        _ => false,
+    }
+}
+
+/// `If-Range` keeps the Range only while the representation is unchanged
+fn if_range_matches(
+    preconditions: &Preconditions,
+    etag: &str,
+    modified: Option<SystemTime>,
+) -> bool {
+    let Some(if_range) = preconditions.if_range.as_deref().map(str::trim) else {
+        return true;
+    };
+    if if_range.starts_with('"') || if_range.starts_with("W/") {
+        // Strong comparison: weak tags never match
+        return if_range == etag;
+    }
+    match (httpdate::parse_http_date(if_range), modified) {
+        (Ok(date), Some(modified)) => seconds(date) == seconds(modified),
+        _ => false,
+    }
+}
+
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum RangeRequest {
+    /// No usable Range header: send the whole file
+    Full,
+    Unsatisfiable,
+    /// Inclusive byte ranges, sorted and coalesced
+    Partial(Vec<(u64, u64)>),
+}
+
+fn parse_ranges(header: &str, len: u64) -> RangeRequest {
+    let Some((unit, specs)) = header.split_once('=') else {
+        return RangeRequest::Full;
+    };
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return RangeRequest::Full;
+    }
+
+    let mut ranges = Vec::new();
+    let mut count = 0;
+    for spec in specs.split(',') {
+        count += 1;
+        let Some((first, last)) = spec.trim().split_once('-') else {
+            return RangeRequest::Full;
+        };
+        let (first, last) = (first.trim(), last.trim());
+        let range = if first.is_empty() {
+            // Suffix range: the last N bytes
+            let Ok(suffix) = last.parse::<u64>() else {
+                return RangeRequest::Full;
+            };
+            (suffix > 0 && len > 0).then(|| (len.saturating_sub(suffix), len - 1))
+        } else {
+            let Ok(start) = first.parse::<u64>() else {
+                return RangeRequest::Full;
+            };
+            let end = if last.is_empty() {
+                u64::MAX
+            } else {
+                match last.parse::<u64>() {
+                    Ok(end) if end >= start => end,
+                    _ => return RangeRequest::Full,
+                }
+            };
+            (start < len).then(|| (start, end.min(len - 1)))
+        };
+        ranges.extend(range);
+    }
+
+    if count > MAX_RANGES {
+        return RangeRequest::Full;
+    }
+    if ranges.is_empty() {
+        return RangeRequest::Unsatisfiable;
+    }
+
+    ranges.sort_unstable();
+    let mut coalesced: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match coalesced.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => coalesced.push((start, end)),
+        }
+    }
+    RangeRequest::Partial(coalesced)
+}
+
+fn multipart_boundary() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or_default();
+    format!(
+        "ultraapi-{:08x}{:08x}",
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// `len` bytes of the file starting at `start`, read in chunks
+fn file_stream(path: PathBuf, start: u64, len: u64) -> BoxStream<'static, std::io::Result<Bytes>> {
+    stream::once(async move {
+        let mut file = tokio::fs::File::open(&path).await?;
+        if start > 0 {
+            file.seek(SeekFrom::Start(start)).await?;
+        }
+        Ok::<_, std::io::Error>(file)
+    })
+    .map_ok(move |file| {
+        stream::try_unfold((file, len), |(mut file, remaining)| async move {
+            if remaining == 0 {
+                return Ok(None);
+            }
+            let mut buf = vec![0u8; remaining.min(CHUNK_SIZE as u64) as usize];
+            let n = file.read(&mut buf).await?;
+            if n == 0 {
+                // End of file: an open-ended read is done, a range read was cut short
+                return if remaining == u64::MAX {
+                    Ok(None)
+                } else {
+                    Err(std::io::ErrorKind::UnexpectedEof.into())
+                };
+            }
+            buf.truncate(n);
+            let remaining = if remaining == u64::MAX {
+                remaining
+            } else {
+                remaining - n as u64
+            };
+            Ok(Some((Bytes::from(buf), (file, remaining))))
+        })
+    })
+    .try_flatten()
+    .boxed()
+}
@@ -28,12 +28,99 @@
 //!     graphiql("/graphql", None).await
 //! }
 //! ```
+//!
+//! # Mounting on UltraApiApp
+//!
+//! `UltraApiApp::graphql` は POST / GET での実行と GraphiQL (ブラウザからの GET) を登録します。
+//! ルートはアプリのミドルウェア (CORS・認証・レート制限・セッション) を共有し、リゾルバーの
+//! `Context` には `AppState`・`Session`・認証済みの `Credentials` が入ります。`Dep<T>` で登録した
+//! 依存性は [`GraphQLContextExt::dep`] で取得できます。
+//!
+//! ```ignore
+//! #[Object]
+//! impl Query {
+//!     async fn me(&self, ctx: &Context<'_>) -> async_graphql::Result<String> {
+//!         let db = ctx.dep::<Database>()?;
+//!         let credentials = ctx.credentials().ok_or("unauthenticated")?;
+//!         db.user_name(&credentials.value).await
+//!     }
+//! }
+//!
+//! let app = UltraApiApp::new()
+//!     .dep(Database::connect())
+//!     .bearer_auth()
+//!     .graphql("/graphql", GraphQLEndpoint::new(schema).security("bearerAuth"));
+//! ```
+//!
+//! # Subscriptions
+//!
+//! サブスクリプションは WebSocket (`{path}/ws`、`subscription_path` で変更可) で提供され、
+//! `graphql-transport-ws` と旧来の `graphql-ws` の両サブプロトコルに対応します。
+//! ブラウザはハンドシェイクにヘッダーを付けられないため、WebSocket ルートは認証レイヤーを
+//! 通らず、`connection_init` の payload をヘッダーとして (`{"Authorization": "Bearer ..."}`
+//! または `{"headers": {...}}`) ハンドシェイクのヘッダーに重ね、アプリの `AuthValidator` で
+//! 検証します。保護されたエンドポイントでは検証に失敗すると接続を閉じ、成功すると
+//! `Credentials` がサブスクリプションの `Context` に入ります。GraphiQL は自動的にこの
+//! エンドポイントを使います。
+//!
+//! # Safeguards
+//!
+//! 公開エンドポイント向けに `GraphQLEndpoint` で実行前の制限を設定できます。HTTP と
+//! サブスクリプションの両方に適用されます。
+//!
+//! - `max_depth` / `max_complexity`: フィールドのネスト深さと選択フィールド数 (フラグメント展開後)
+//! - `max_batch_size`: バッチのオペレーション数 (超過は 400)
+//! - `introspection(false)`: `__schema` / `__type` と SDL エクスポート (`{path}/schema.graphql`) を無効化
+//! - `persisted_queries`: Automatic Persisted Queries。`InMemoryPersistedQueryStore` か独自の
+//!   [`PersistedQueryStore`] 実装を指定します
+//! - `ide_mode(GraphQLIdeMode::Embedded)`: CDN を使わない組み込みの簡易コンソール (オフライン環境向け)。
+//!   GraphiQL そのものをオフラインで使う場合は、GraphiQL と React のバンドルを npm CDN と同じ
+//!   配置 (`graphiql@3/...`, `react@18/umd/...`) で静的ファイルとして配信し、
+//!   `GraphQLIdeMode::Cdn("/static/npm".into())` のようにそのパスを指定します
+//!
+//! ```ignore
+//! let endpoint = GraphQLEndpoint::new(schema)
+//!     .max_depth(10)
+//!     .max_complexity(200)
+//!     .max_batch_size(5)
+//!     .introspection(cfg!(debug_assertions))
+//!     .persisted_queries(InMemoryPersistedQueryStore::new(10_000))
+//!     .ide_mode(GraphQLIdeMode::Embedded);
+//! ```
 
 #[cfg(feature = "graphql")]
 mod graphql_impl {
-    use async_graphql::Schema;
-    use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
-    use axum::response::{Html, IntoResponse};
+    use async_graphql::http::ALL_WEBSOCKET_PROTOCOLS;
+    use async_graphql::parser::types::{
+        DocumentOperations, ExecutableDocument, FragmentDefinition, OperationDefinition,
+        OperationType, Selection, SelectionSet,
+    };
+    use async_graphql::parser::Positioned;
+    use async_graphql::{
+        BatchRequest, BatchResponse, Data, ErrorExtensions, Executor, Name, ObjectType, Pos,
+        Schema, ServerError, SubscriptionType,
+    };
+    use async_graphql_axum::rejection::GraphQLRejection;
+    use async_graphql_axum::{
+        GraphQLBatchRequest, GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket,
+    };
+    use axum::body::Body;
+    use axum::extract::ws::{WebSocket, WebSocketUpgrade};
+    use axum::extract::{FromRequest, FromRequestParts, Request, State};
+    use axum::http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri};
+    use axum::response::{Html, IntoResponse, Response};
+    use axum::routing::{get, on, MethodFilter};
+    use axum::Router;
+    use futures_util::future::BoxFuture;
+    use futures_util::stream::{self, BoxStream, StreamExt};
+    use parking_lot::Mutex;
+    use sha2::{Digest, Sha256};
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Arc;
+
+    use crate::middleware::{AuthLayer, Credentials};
+    use crate::session::Session;
+    use crate::{AppState, Dep};
 
     /// GraphQL endpoint handler
     ///
@@ -102,14 +189,18 @@ mod graphql_impl {
     ///
     /// ```ignore
     /// async fn graphiql() -> impl IntoResponse {
-    ///     graphiql("/graphql", None).await
+    ///     graphiql("/graphql", Some("/graphql/ws")).await
     /// }
     /// ```
     pub async fn graphiql(
         graphql_endpoint: &str,
-        _subscription_endpoint: Option<&str>,
+        subscription_endpoint: Option<&str>,
     ) -> impl IntoResponse {
-        let html = generate_graphiql_html(graphql_endpoint);
+        let html = generate_graphiql_html(
+            graphql_endpoint,
+            subscription_endpoint,
+            DEFAULT_GRAPHIQL_CDN,
+        );
         Html(html)
     }
 
@@ -127,40 +218,886 @@ mod graphql_impl {
     /// ```
     pub async fn playground(
         graphql_endpoint: &str,
-        _subscription_endpoint: Option<&str>,
+        subscription_endpoint: Option<&str>,
     ) -> impl IntoResponse {
-        let html = generate_playground_html(graphql_endpoint);
+        let html = generate_playground_html(graphql_endpoint, subscription_endpoint);
         Html(html)
     }
 
+    /// npm CDN GraphiQL and React are loaded from by default
+    pub const DEFAULT_GRAPHIQL_CDN: &str = "https://cdn.jsdelivr.net/npm";
+
+    /// GraphQL IDE serving mode
+    #[derive(Debug, Clone)]
+    pub enum GraphQLIdeMode {
+        /// Load GraphiQL 3 and React 18 from an npm CDN root laid out like jsdelivr or unpkg
+        /// (`{cdn}/graphiql@3/graphiql.min.js`, `{cdn}/react@18/umd/...`)
+        Cdn(String),
+        /// Use the embedded console (works offline; a minimal IDE, not GraphiQL)
+        Embedded,
+    }
+
+    impl Default for GraphQLIdeMode {
+        fn default() -> Self {
+            Self::Cdn(DEFAULT_GRAPHIQL_CDN.to_string())
+        }
+    }
+
+    /// A GraphQL schema mounted with `UltraApiApp::graphql`
+    ///
+    /// A `Schema` converts into an endpoint that also exports its SDL at
+    /// `{path}/schema.graphql`; other async-graphql executors use [`Self::from_executor`].
+    #[derive(Clone)]
+    pub struct GraphQLEndpoint {
+        executor: Arc<dyn ErasedExecutor>,
+        sdl: Option<Arc<str>>,
+        security: Vec<String>,
+        ide: bool,
+        ide_mode: GraphQLIdeMode,
+        subscription_path: Option<String>,
+        safeguards: Safeguards,
+    }
+
+    impl GraphQLEndpoint {
+        /// Serve a schema (e.g. `Schema::build(..).finish()`)
+        pub fn new<Q, M, S>(schema: Schema<Q, M, S>) -> Self
+        where
+            Q: ObjectType + 'static,
+            M: ObjectType + 'static,
+            S: SubscriptionType + 'static,
+        {
+            let sdl = schema.sdl();
+            let mut endpoint = Self::from_executor(schema);
+            endpoint.sdl = Some(sdl.into());
+            endpoint
+        }
+
+        /// Serve any async-graphql executor (no SDL export)
+        pub fn from_executor<E: Executor>(executor: E) -> Self {
+            Self {
+                executor: Arc::new(executor),
+                sdl: None,
+                security: Vec::new(),
+                ide: true,
+                ide_mode: GraphQLIdeMode::default(),
+                subscription_path: None,
+                safeguards: Safeguards::default(),
+            }
+        }
+
+        /// Require a security scheme (e.g. "bearerAuth") for queries and the IDE
+        pub fn security(mut self, scheme: &str) -> Self {
+            self.security.push(scheme.to_string());
+            self
+        }
+
+        /// Serve GraphiQL to browsers on `GET` (default: true)
+        pub fn ide(mut self, enabled: bool) -> Self {
+            self.ide = enabled;
+            self
+        }
+
+        /// Where the IDE loads its assets from (default: GraphiQL from jsdelivr)
+        pub fn ide_mode(mut self, mode: GraphQLIdeMode) -> Self {
+            self.ide_mode = mode;
+            self
+        }
+
+        /// Serve subscriptions at `path` instead of `{graphql path}/ws`
+        pub fn subscription_path(mut self, path: &str) -> Self {
+            self.subscription_path = Some(path.to_string());
+            self
+        }
+
+        /// Reject operations nested deeper than `depth` fields
+        pub fn max_depth(mut self, depth: usize) -> Self {
+            self.safeguards.max_depth = Some(depth);
+            self
+        }
+
+        /// Reject operations selecting more than `complexity` fields (fragments expanded)
+        pub fn max_complexity(mut self, complexity: usize) -> Self {
+            self.safeguards.max_complexity = Some(complexity);
+            self
+        }
+
+        /// Reject batches of more than `size` operations (`1` disables batching)
+        pub fn max_batch_size(mut self, size: usize) -> Self {
+            self.safeguards.max_batch_size = Some(size);
+            self
+        }
+
+        /// Allow `__schema` / `__type` queries and the SDL export (default: true)
+        pub fn introspection(mut self, enabled: bool) -> Self {
+            self.safeguards.introspection = enabled;
+            self
+        }
+
+        /// Enable Automatic Persisted Queries backed by `store`
+        pub fn persisted_queries(mut self, store: impl PersistedQueryStore + 'static) -> Self {
+            self.safeguards.persisted_queries = Some(Arc::new(store));
+            self
+        }
+
+        /// Security schemes required by this endpoint
+        pub fn security_schemes(&self) -> &[String] {
+            &self.security
+        }
+
+        /// WebSocket path for subscriptions of the endpoint mounted at `path`
+        pub(crate) fn subscription_endpoint(&self, path: &str) -> String {
+            self.subscription_path
+                .clone()
+                .unwrap_or_else(|| format!("{}/ws", path.trim_end_matches('/')))
+        }
+
+        /// Path of the SDL export for the endpoint mounted at `path`
+        pub(crate) fn sdl_endpoint(&self, path: &str) -> Option<String> {
+            (self.sdl.is_some() && self.safeguards.introspection)
+                .then(|| format!("{}/schema.graphql", path.trim_end_matches('/')))
+        }
+
+        /// GET (queries and the IDE) and POST routes at `path`, plus the SDL export
+        pub(crate) fn route(self, path: &str) -> Router<AppState> {
+            let mut router = Router::new();
+            if let (Some(sdl_path), Some(sdl)) = (self.sdl_endpoint(path), self.sdl.clone()) {
+                router = router.route(
+                    &sdl_path,
+                    get(move || {
+                        let sdl = sdl.clone();
+                        async move {
+                            (
+                                [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+                                sdl.to_string(),
+                            )
+                        }
+                    }),
+                );
+            }
+
+            let endpoint = Arc::new(self);
+            let graphql_path = path.to_string();
+            router.route(
+                path,
+                on(
+                    MethodFilter::GET.or(MethodFilter::POST),
+                    move |State(state): State<AppState>, request: Request| {
+                        let endpoint = endpoint.clone();
+                        let path = graphql_path.clone();
+                        async move { endpoint.handle(&path, state, request).await }
+                    },
+                ),
+            )
+        }
+
+        /// WebSocket subscriptions, authenticated by `auth` during `connection_init`
+        pub(crate) fn subscription_route(
+            self,
+            path: &str,
+            auth: Option<AuthLayer>,
+        ) -> Router<AppState> {
+            let ws_path = self.subscription_endpoint(path);
+            let endpoint = Arc::new(self);
+            let auth = auth.map(Arc::new);
+            Router::new().route(
+                &ws_path,
+                get(move |State(state): State<AppState>, request: Request| {
+                    let endpoint = endpoint.clone();
+                    let auth = auth.clone();
+                    async move { endpoint.handle_websocket(state, auth, request).await }
+                }),
+            )
+        }
+
+        async fn handle(&self, path: &str, state: AppState, request: Request) -> Response {
+            if request.method() == Method::GET && self.ide && wants_ide(&request) {
+                let ws_path = self.subscription_endpoint(path);
+                let html = match &self.ide_mode {
+                    GraphQLIdeMode::Cdn(cdn) => generate_graphiql_html(path, Some(&ws_path), cdn),
+                    GraphQLIdeMode::Embedded => generate_embedded_ide_html(
+                        path,
+                        &ws_path,
+                        self.sdl_endpoint(path).as_deref(),
+                    ),
+                };
+                return Html(html).into_response();
+            }
+
+            let (mut parts, body) = request.into_parts();
+            let session = Session::from_request_parts(&mut parts, &()).await.ok();
+            let credentials = parts.extensions.get::<Credentials>().cloned();
+            let is_get = parts.method == Method::GET;
+            let batch = match GraphQLBatchRequest::<GraphQLRejection>::from_request(
+                Request::from_parts(parts, body),
+                &(),
+            )
+            .await
+            {
+                Ok(batch) => batch.into_inner(),
+                Err(rejection) => return rejection.into_response(),
+            };
+
+            let (requests, single) = match batch {
+                BatchRequest::Single(request) => (vec![request], true),
+                BatchRequest::Batch(requests) => {
+                    if let Some(max) = self.safeguards.max_batch_size {
+                        if requests.len() > max {
+                            return batch_too_large(requests.len(), max);
+                        }
+                    }
+                    (requests, false)
+                }
+            };
+
+            // Rejected operations keep their slot so the batch response stays in order
+            let mut slots = Vec::with_capacity(requests.len());
+            let mut runnable = Vec::new();
+            for request in requests {
+                match self.safeguards.prepare(request).await {
+                    Ok(request) => {
+                        // GET must not change state (GraphQL over HTTP)
+                        if is_get && operation_type(&request) == Some(OperationType::Mutation) {
+                            return method_not_allowed();
+                        }
+                        let mut request = request.data(state.clone());
+                        if let Some(session) = &session {
+                            request = request.data(session.clone());
+                        }
+                        if let Some(credentials) = &credentials {
+                            request = request.data(credentials.clone());
+                        }
+                        runnable.push(request);
+                        slots.push(None);
+                    }
+                    Err(response) => slots.push(Some(response)),
+                }
+            }
+
+            let mut executed = match runnable.len() {
+                0 => Vec::new(),
+                _ => match self
+                    .executor
+                    .execute_batch(BatchRequest::Batch(runnable))
+                    .await
+                {
+                    BatchResponse::Batch(responses) => responses,
+                    BatchResponse::Single(response) => vec![response],
+                },
+            }
+            .into_iter();
+            let mut responses = slots
+                .into_iter()
+                .map(|slot| slot.or_else(|| executed.next()).unwrap_or_default())
+                .collect::<Vec<_>>();
+
+            let response = match (single, responses.pop()) {
+                (true, Some(response)) => BatchResponse::Single(response),
+                (_, last) => {
+                    responses.extend(last);
+                    BatchResponse::Batch(responses)
+                }
+            };
+            GraphQLResponse::from(response).into_response()
+        }
+
+        async fn handle_websocket(
+            &self,
+            state: AppState,
+            auth: Option<Arc<AuthLayer>>,
+            request: Request,
+        ) -> Response {
+            let (mut parts, _body) = request.into_parts();
+            let protocol = match GraphQLProtocol::from_request_parts(&mut parts, &()).await {
+                Ok(protocol) => protocol,
+                Err(rejection) => return rejection.into_response(),
+            };
+            let upgrade = match WebSocketUpgrade::from_request_parts(&mut parts, &()).await {
+                Ok(upgrade) => upgrade,
+                Err(rejection) => return rejection.into_response(),
+            };
+
+            let mut data = Data::default();
+            data.insert(state);
+            if let Ok(session) = Session::from_request_parts(&mut parts, &()).await {
+                data.insert(session);
+            }
+
+            // Only protected endpoints authenticate, like the HTTP routes
+            let security = self.security.clone();
+            let on_init: ConnectionInit = match auth {
+                Some(auth) if !security.is_empty() => {
+                    let (uri, headers) = (parts.uri, parts.headers);
+                    Box::new(move |payload| {
+                        Box::pin(async move {
+                            authenticate_connection(&auth, &security, uri, headers, &payload)
+                        })
+                    })
+                }
+                _ => Box::new(|_| Box::pin(async { Ok(Data::default()) })),
+            };
+
+            let executor = self.executor.clone();
+            let safeguards = Arc::new(self.safeguards.clone());
+            upgrade
+                .protocols(ALL_WEBSOCKET_PROTOCOLS)
+                .on_upgrade(move |socket| {
+                    executor.serve_websocket(socket, protocol, data, on_init, safeguards)
+                })
+                .into_response()
+        }
+    }
+
+    impl<Q, M, S> From<Schema<Q, M, S>> for GraphQLEndpoint
+    where
+        Q: ObjectType + 'static,
+        M: ObjectType + 'static,
+        S: SubscriptionType + 'static,
+    {
+        fn from(schema: Schema<Q, M, S>) -> Self {
+            Self::new(schema)
+        }
+    }
+
+    impl std::fmt::Debug for GraphQLEndpoint {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("GraphQLEndpoint")
+                .field("security", &self.security)
+                .field("ide", &self.ide)
+                .field("ide_mode", &self.ide_mode)
+                .field("safeguards", &self.safeguards)
+                .finish_non_exhaustive()
+        }
+    }
+
+    /// Storage for Automatic Persisted Queries (SHA-256 hex digest -> query)
+    ///
+    /// Implement this for a shared store (e.g. Redis) when running several instances.
+    #[async_trait::async_trait]
+    pub trait PersistedQueryStore: Send + Sync {
+        /// Look up a query by the hex SHA-256 digest of its text
+        async fn get(&self, hash: &str) -> Option<String>;
+
+        /// Register a query under the hex SHA-256 digest of its text
+        async fn set(&self, hash: &str, query: &str);
+    }
+
+    /// Bounded in-memory [`PersistedQueryStore`]; the oldest queries are evicted first
+    pub struct InMemoryPersistedQueryStore {
+        capacity: usize,
+        entries: Mutex<(HashMap<String, String>, VecDeque<String>)>,
+    }
+
+    impl InMemoryPersistedQueryStore {
+        /// Keep at most `capacity` queries
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                capacity,
+                entries: Mutex::new((HashMap::new(), VecDeque::new())),
+            }
+        }
+    }
+
+    impl Default for InMemoryPersistedQueryStore {
+        fn default() -> Self {
+            Self::new(1024)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl PersistedQueryStore for InMemoryPersistedQueryStore {
+        async fn get(&self, hash: &str) -> Option<String> {
+            self.entries.lock().0.get(hash).cloned()
+        }
+
+        async fn set(&self, hash: &str, query: &str) {
+            let mut entries = self.entries.lock();
+            let (queries, order) = &mut *entries;
+            if queries
+                .insert(hash.to_string(), query.to_string())
+                .is_none()
+            {
+                order.push_back(hash.to_string());
+            }
+            while order.len() > self.capacity {
+                if let Some(oldest) = order.pop_front() {
+                    queries.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    /// Per-operation limits applied before execution
+    #[derive(Clone)]
+    struct Safeguards {
+        max_depth: Option<usize>,
+        max_complexity: Option<usize>,
+        max_batch_size: Option<usize>,
+        introspection: bool,
+        persisted_queries: Option<Arc<dyn PersistedQueryStore>>,
+    }
+
+    impl Default for Safeguards {
+        fn default() -> Self {
+            Self {
+                max_depth: None,
+                max_complexity: None,
+                max_batch_size: None,
+                introspection: true,
+                persisted_queries: None,
+            }
+        }
+    }
+
+    impl std::fmt::Debug for Safeguards {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Safeguards")
+                .field("max_depth", &self.max_depth)
+                .field("max_complexity", &self.max_complexity)
+                .field("max_batch_size", &self.max_batch_size)
+                .field("introspection", &self.introspection)
+                .field("persisted_queries", &self.persisted_queries.is_some())
+                .finish()
+        }
+    }
+
+    impl Safeguards {
+        /// Resolve a persisted query and check the limits, or answer with errors
+        async fn prepare(
+            &self,
+            mut request: async_graphql::Request,
+        ) -> Result<async_graphql::Request, async_graphql::Response> {
+            if let Some(hash) = persisted_query_hash(&request) {
+                let Some(store) = &self.persisted_queries else {
+                    return Err(persisted_query_error(
+                        "PersistedQueryNotSupported",
+                        "PERSISTED_QUERY_NOT_SUPPORTED",
+                    ));
+                };
+                if request.query.is_empty() {
+                    match store.get(&hash).await {
+                        Some(query) => request.query = query,
+                        None => {
+                            return Err(persisted_query_error(
+                                "PersistedQueryNotFound",
+                                "PERSISTED_QUERY_NOT_FOUND",
+                            ))
+                        }
+                    }
+                } else if hex_sha256(&request.query) == hash {
+                    store.set(&hash, &request.query).await;
+                } else {
+                    return Err(error_response("provided sha does not match query"));
+                }
+            }
+
+            if !self.introspection {
+                request = request.disable_introspection();
+            }
+
+            if self.max_depth.is_some() || self.max_complexity.is_some() {
+                // Unparsable documents are reported by the executor
+                if let Ok(document) = async_graphql::parser::parse_query(&request.query) {
+                    if let Some(operation) =
+                        selected_operation(&document, request.operation_name.as_deref())
+                    {
+                        let (depth, complexity) = measure(
+                            &operation.selection_set.node,
+                            &document.fragments,
+                            &mut HashMap::new(),
+                            &mut Vec::new(),
+                        );
+                        if self.max_depth.is_some_and(|max| depth > max) {
+                            return Err(error_response("Query is nested too deep."));
+                        }
+                        if self.max_complexity.is_some_and(|max| complexity > max) {
+                            return Err(error_response("Query is too complex."));
+                        }
+                    }
+                }
+            }
+            Ok(request)
+        }
+    }
+
+    /// `sha256Hash` of an Apollo `persistedQuery` extension (version 1)
+    fn persisted_query_hash(request: &async_graphql::Request) -> Option<String> {
+        let extension = request
+            .extensions
+            .get("persistedQuery")?
+            .clone()
+            .into_json()
+            .ok()?;
+        (extension.get("version")?.as_i64()? == 1)
+            .then(|| extension.get("sha256Hash")?.as_str().map(str::to_lowercase))
+            .flatten()
+    }
+
+    fn hex_sha256(query: &str) -> String {
+        Sha256::digest(query.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Field depth and number of fields selected below `set`.
+    ///
+    /// Each fragment is measured once and cached, so repeated spreads cost no more than
+    /// a lookup (otherwise nested spreads grow exponentially before any limit applies).
+    fn measure(
+        set: &SelectionSet,
+        fragments: &HashMap<Name, Positioned<FragmentDefinition>>,
+        cache: &mut HashMap<Name, (usize, usize)>,
+        visiting: &mut Vec<Name>,
+    ) -> (usize, usize) {
+        let (mut max_depth, mut complexity) = (0usize, 0usize);
+        for selection in &set.items {
+            let (nested_depth, nested_complexity) = match &selection.node {
+                Selection::Field(field) => {
+                    let (d, c) =
+                        measure(&field.node.selection_set.node, fragments, cache, visiting);
+                    (d + 1, c.saturating_add(1))
+                }
+                Selection::InlineFragment(fragment) => measure(
+                    &fragment.node.selection_set.node,
+                    fragments,
+                    cache,
+                    visiting,
+                ),
+                Selection::FragmentSpread(spread) => {
+                    let name = &spread.node.fragment_name.node;
+                    // Cyclic spreads are rejected by validation
+                    match (cache.get(name), fragments.get(name)) {
+                        (Some(measured), _) => *measured,
+                        (None, Some(fragment)) if !visiting.contains(name) => {
+                            visiting.push(name.clone());
+                            let measured = measure(
+                                &fragment.node.selection_set.node,
+                                fragments,
+                                cache,
+                                visiting,
+                            );
+                            visiting.pop();
+                            cache.insert(name.clone(), measured);
+                            measured
+                        }
+                        _ => (0, 0),
+                    }
+                }
+            };
+            max_depth = max_depth.max(nested_depth);
+            complexity = complexity.saturating_add(nested_complexity);
+        }
+        (max_depth, complexity)
+    }
+
+    fn error_response(message: &str) -> async_graphql::Response {
+        async_graphql::Response::from_errors(vec![ServerError::new(message, None)])
+    }
+
+    fn persisted_query_error(message: &str, code: &'static str) -> async_graphql::Response {
+        let error = async_graphql::Error::new(message)
+            .extend_with(|_, extensions| extensions.set("code", code));
+        async_graphql::Response::from_errors(vec![error.into_server_error(Pos::default())])
+    }
+
+    fn batch_too_large(size: usize, max: usize) -> Response {
+        let body = serde_json::json!({
+            "errors": [{
+                "message": format!("batch of {} operations exceeds the limit of {}", size, max)
+            }]
+        });
+        (StatusCode::BAD_REQUEST, axum::Json(body)).into_response()
+    }
+
+    /// An executor that applies [`Safeguards`] (used for subscriptions)
+    #[derive(Clone)]
+    struct Guarded<E> {
+        executor: E,
+        safeguards: Arc<Safeguards>,
+    }
+
+    impl<E: Executor> Executor for Guarded<E> {
+        async fn execute(&self, request: async_graphql::Request) -> async_graphql::Response {
+            match self.safeguards.prepare(request).await {
+                Ok(request) => self.executor.execute(request).await,
+                Err(response) => response,
+            }
+        }
+
+        fn execute_stream(
+            &self,
+            request: async_graphql::Request,
+            session_data: Option<Arc<Data>>,
+        ) -> BoxStream<'static, async_graphql::Response> {
+            let executor = self.executor.clone();
+            let safeguards = self.safeguards.clone();
+            stream::once(async move { safeguards.prepare(request).await })
+                .flat_map(move |prepared| match prepared {
+                    Ok(request) => executor.execute_stream(request, session_data.clone()),
+                    Err(response) => stream::once(async move { response }).boxed(),
+                })
+                .boxed()
+        }
+    }
+
+    /// `connection_init` callback: payload -> data for the connection
+    type ConnectionInit = Box<
+        dyn FnOnce(serde_json::Value) -> BoxFuture<'static, async_graphql::Result<Data>> + Send,
+    >;
+
+    /// Object-safe view of an async-graphql `Executor`
+    trait ErasedExecutor: Send + Sync {
+        fn execute_batch(&self, request: BatchRequest) -> BoxFuture<'static, BatchResponse>;
+
+        fn serve_websocket(
+            &self,
+            socket: WebSocket,
+            protocol: GraphQLProtocol,
+            data: Data,
+            on_init: ConnectionInit,
+            safeguards: Arc<Safeguards>,
+        ) -> BoxFuture<'static, ()>;
+    }
+
+    impl<E: Executor> ErasedExecutor for E {
+        fn execute_batch(&self, request: BatchRequest) -> BoxFuture<'static, BatchResponse> {
+            let executor = self.clone();
+            Box::pin(async move { Executor::execute_batch(&executor, request).await })
+        }
+
+        fn serve_websocket(
+            &self,
+            socket: WebSocket,
+            protocol: GraphQLProtocol,
+            data: Data,
+            on_init: ConnectionInit,
+            safeguards: Arc<Safeguards>,
+        ) -> BoxFuture<'static, ()> {
+            let executor = Guarded {
+                executor: self.clone(),
+                safeguards,
+            };
+            Box::pin(
+                GraphQLWebSocket::new(socket, executor, protocol)
+                    .with_data(data)
+                    .on_connection_init(on_init)
+                    .serve(),
+            )
+        }
+    }
+
+    /// Validate the handshake headers overlaid with the `connection_init` payload
+    fn authenticate_connection(
+        auth: &AuthLayer,
+        security: &[String],
+        uri: Uri,
+        mut headers: HeaderMap,
+        payload: &serde_json::Value,
+    ) -> async_graphql::Result<Data> {
+        headers.extend(payload_headers(payload));
+        let mut request = axum::http::Request::new(Body::empty());
+        *request.uri_mut() = uri;
+        *request.headers_mut() = headers;
+
+        match auth.authenticate(&request, Some(security)) {
+            Ok(Some(credentials)) => {
+                let mut data = Data::default();
+                data.insert(credentials);
+                Ok(data)
+            }
+            Ok(None) => Err("Missing authentication credentials".into()),
+            Err(error) => Err(async_graphql::Error::new(error.message)),
+        }
+    }
+
+    /// Header-like entries of a `connection_init` payload: top-level strings and an
+    /// optional `headers` object
+    fn payload_headers(payload: &serde_json::Value) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let Some(object) = payload.as_object() else {
+            return headers;
+        };
+        let nested = object.get("headers").and_then(|value| value.as_object());
+        for (name, value) in object.iter().chain(nested.into_iter().flatten()) {
+            let Some(value) = value.as_str() else {
+                continue;
+            };
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        headers
+    }
+
+    /// A browser navigation without a query
+    fn wants_ide(request: &Request) -> bool {
+        let accepts_html = request
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.contains("text/html"));
+        let has_query = request
+            .uri()
+            .query()
+            .is_some_and(|query| query.split('&').any(|pair| pair.starts_with("query=")));
+        accepts_html && !has_query
+    }
+
+    /// Type of the operation that will run, when the document parses
+    fn operation_type(request: &async_graphql::Request) -> Option<OperationType> {
+        let document = async_graphql::parser::parse_query(&request.query).ok()?;
+        selected_operation(&document, request.operation_name.as_deref()).map(|op| op.ty)
+    }
+
+    /// The operation `operation_name` selects in `document`
+    fn selected_operation<'a>(
+        document: &'a ExecutableDocument,
+        operation_name: Option<&str>,
+    ) -> Option<&'a OperationDefinition> {
+        let operation = match (&document.operations, operation_name) {
+            (DocumentOperations::Single(operation), _) => operation,
+            (DocumentOperations::Multiple(operations), Some(name)) => operations
+                .iter()
+                .find(|(n, _)| n.as_str() == name)
+                .map(|(_, operation)| operation)?,
+            (DocumentOperations::Multiple(operations), None) if operations.len() == 1 => {
+                operations.values().next()?
+            }
+            _ => return None,
+        };
+        Some(&operation.node)
+    }
+
+    fn method_not_allowed() -> Response {
+        let body = serde_json::json!({
+            "errors": [{ "message": "mutations are only allowed with POST" }]
+        });
+        let mut response = (StatusCode::METHOD_NOT_ALLOWED, axum::Json(body)).into_response();
+        response
+            .headers_mut()
+            .insert(header::ALLOW, HeaderValue::from_static("POST"));
+        response
+    }
+
+    /// UltraAPI values in the async-graphql `Context` of an endpoint mounted with
+    /// `UltraApiApp::graphql`
+    pub trait GraphQLContextExt {
+        /// The application state
+        fn app_state(&self) -> Option<&AppState>;
+
+        /// A dependency registered with `UltraApiApp::dep`
+        fn dep<T: 'static + Send + Sync>(&self) -> async_graphql::Result<Dep<T>>;
+
+        /// The request session (with `UltraApiApp::session_cookies`)
+        fn session(&self) -> Option<&Session>;
+
+        /// Credentials validated by the auth middleware (protected endpoints)
+        fn credentials(&self) -> Option<&Credentials>;
+    }
+
+    impl GraphQLContextExt for async_graphql::Context<'_> {
+        fn app_state(&self) -> Option<&AppState> {
+            self.data_opt::<AppState>()
+        }
+
+        fn dep<T: 'static + Send + Sync>(&self) -> async_graphql::Result<Dep<T>> {
+            self.app_state()
+                .and_then(|state| Dep::from_app_state(state).ok())
+                .ok_or_else(|| {
+                    async_graphql::Error::new(format!(
+                        "Dependency not registered: {}",
+                        std::any::type_name::<T>()
+                    ))
+                })
+        }
+
+        fn session(&self) -> Option<&Session> {
+            self.data_opt::<Session>()
+        }
+
+        fn credentials(&self) -> Option<&Credentials> {
+            self.data_opt::<Credentials>()
+        }
+    }
+
+    /// JavaScript expression for an absolute `ws(s)://` URL of `path` on the current host
+    fn websocket_url_js(path: Option<&str>) -> String {
+        match path {
+            Some(path) => format!(
+                "(location.protocol === 'https:' ? 'wss://' : 'ws://') + location.host + '{}'",
+                path
+            ),
+            None => "undefined".to_string(),
+        }
+    }
+
     /// Generate GraphiQL HTML
-    fn generate_graphiql_html(endpoint: &str) -> String {
+    fn generate_graphiql_html(
+        endpoint: &str,
+        subscription_endpoint: Option<&str>,
+        cdn: &str,
+    ) -> String {
         format!(
             r#"<!DOCTYPE html>
 <html>
 <head>
-  <link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/graphiql/graphiql.min.css" />
-  <script crossorigin src="https://cdn.jsdelivr.net/npm/graphiql/graphiql.min.js"></script>
+  <link rel="stylesheet" href="{cdn}/graphiql@3/graphiql.min.css" />
+  <script crossorigin src="{cdn}/react@18/umd/react.production.min.js"></script>
+  <script crossorigin src="{cdn}/react-dom@18/umd/react-dom.production.min.js"></script>
+  <script crossorigin src="{cdn}/graphiql@3/graphiql.min.js"></script>
 </head>
 <body style="margin: 0;">
   <div id="graphiql" style="height: 100vh;"></div>
   <script>
     const fetcher = GraphiQL.createFetcher({{
-      url: '{}',
+      url: '{endpoint}',
+      subscriptionUrl: {subscription_url},
     }});
-    ReactDOM.render(
+    ReactDOM.createRoot(document.getElementById('graphiql')).render(
       React.createElement(GraphiQL, {{ fetcher }}),
-      document.getElementById('graphiql'),
     );
   </script>
 </body>
 </html>"#,
-            endpoint
+            cdn = cdn.trim_end_matches('/'),
+            endpoint = endpoint,
+            subscription_url = websocket_url_js(subscription_endpoint)
+        )
+    }
+
+    /// Generate the embedded (offline) IDE HTML
+    fn generate_embedded_ide_html(
+        endpoint: &str,
+        subscription_endpoint: &str,
+        sdl_endpoint: Option<&str>,
+    ) -> String {
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>GraphQL IDE</title>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <style>{css}</style>
+</head>
+<body>
+  <script id="graphql-ide" data-url="{endpoint}" data-subscription-url="{subscription_endpoint}" data-sdl-url="{sdl_endpoint}"></script>
+  <script>{js}</script>
+</body>
+</html>"#,
+            css = include_str!("../assets/graphql-ide.css"),
+            js = include_str!("../assets/graphql-ide.js"),
+            endpoint = endpoint,
+            subscription_endpoint = subscription_endpoint,
+            sdl_endpoint = sdl_endpoint.unwrap_or_default(),
         )
     }
 
     /// Generate GraphQL Playground HTML
-    fn generate_playground_html(endpoint: &str) -> String {
+    fn generate_playground_html(endpoint: &str, subscription_endpoint: Option<&str>) -> String {
         format!(
             r#"<!DOCTYPE html>
 <html>
@@ -173,11 +1110,13 @@ mod graphql_impl {
   <script>
     const playground = Graphplayground.createPlayground(document.getElementById('playground'), {{
       endpoint: '{}',
+      subscriptionEndpoint: {},
     }});
   </script>
 </body>
 </html>"#,
-            endpoint
+            endpoint,
+            websocket_url_js(subscription_endpoint)
         )
     }
 }
@@ -25,19 +25,134 @@
 //! let transcoder = GrpcTranscoder::new().register_service(user_service);
 //! let _router = transcoder.into_router();
 //! ```
+//!
+//! ## Mounting into UltraApiApp
+//!
+//! `GrpcExt::grpc` と `grpc_service!` で登録したサービスは `UltraApiApp` のルーターに
+//! マージされ、通常のルートと同じ `AppState`・ミドルウェア (CORS / 認証 / レート制限)・
+//! lifespan を共有します。各メソッドは `/openapi.json` に POST オペレーションとして出力されます。
+//!
+//! ```
+//! use ultraapi::grpc::{service, GrpcExt, GrpcTranscoder};
+//! use ultraapi::UltraApiApp;
+//!
+//! let transcoder = GrpcTranscoder::new().register_service(
+//!     service("UserService")
+//!         .package("user")
+//!         .method_unary("GetUser", "/user.UserService/GetUser")
+//!         .security("bearer")
+//!         .build(),
+//! );
+//! let _app = UltraApiApp::new().grpc(transcoder);
+//! ```
+//!
+//! ## Descriptor-driven transcoding
+//!
+//! `protoc --include_imports --descriptor_set_out` で生成した `FileDescriptorSet` から
+//! サービスを構築すると、`google.api.http` アノテーションに従ったルート
+//! (`GET /v1/users/{id}` など) が生成され、パス変数・クエリ・ボディがリクエストメッセージに
+//! バインドされます。JSON は proto3 JSON マッピングで protobuf と相互変換され、
+//! ハンドラーは prost のメッセージ型を直接受け取れます。
+//!
+//! ```ignore
+//! let pool = DescriptorPool::decode(include_bytes!("../user.bin"))?;
+//! let users = GrpcService::from_descriptor(&pool, "user.v1.UserService")?
+//!     .with_typed_handler("GetUser", |req: GetUserRequest, _ctx| async move {
+//!         Ok(User { id: req.id, ..Default::default() })
+//!     });
+//! ```
+//!
+//! JSON ゲートウェイと gRPC-Web が読み込むリクエストボディは `GrpcService::max_body_size`
+//! (既定 2 MiB) までで、超えると `RESOURCE_EXHAUSTED` (HTTP 413) を返します。
+//!
+//! ## Streaming methods
+//!
+//! サーバーストリーミングのメソッドには `GrpcService::with_streaming_handler` でメッセージの
+//! ストリームを返すハンドラーを登録します。ゲートウェイは `application/x-ndjson`
+//! (`{"result": ...}` を 1 行ずつ) で、`Accept: text/event-stream` のときは SSE で返します。
+//! クライアントストリーミングのメソッドは NDJSON のリクエストボディを
+//! `GrpcRequest::messages` として受け取ります。
+//!
+//! ```ignore
+//! let feed = GrpcService::from_descriptor(&pool, "feed.v1.Feed")?
+//!     .with_typed_streaming_handler("Watch", |req: WatchRequest, _ctx| async move {
+//!         Ok(tokio_stream::iter((0..req.count).map(|i| Ok(Tick { seq: i }))))
+//!     });
+//! ```
+//!
+//! ## Native gRPC
+//!
+//! `GrpcExt::grpc_service` で tonic のサービスを REST と同じポートに載せられます。
+//! `content-type: application/grpc` のリクエストだけがサービスに渡され、依存性
+//! (`GrpcRequestExt::dep` / `depends`)・認証ミドルウェア・lifespan は REST と共通です。
+//!
+//! ```ignore
+//! let app = UltraApiApp::new()
+//!     .dep(Database::connect())
+//!     .bearer_auth()
+//!     .grpc_service(NativeGrpcService::new(GreeterServer::new(MyGreeter)).security("bearerAuth"));
+//! ```
+//!
+//! ## gRPC-Web
+//!
+//! 登録したサービスはブラウザの grpc-web クライアントからも `POST /{package}.{Service}/{Method}`
+//! で呼び出せます (バイナリ / base64 テキスト、トレーラーはボディ内)。protobuf の変換には
+//! ディスクリプタが必要で、ディスクリプタのないサービスは `application/grpc-web+json` を使います。
+//! アプリに CORS を設定していない場合は、プリフライトにもゲートウェイが応答します。
+//!
+//! ## Health checking and reflection
+//!
+//! gRPC サービスを持つアプリには `grpc.health.v1.Health` (Check / Watch) と
+//! `grpc.reflection.v1` / `v1alpha` が自動で追加されます。稼働状態は [`HealthReporter`]
+//! で更新でき、リフレクションは登録済みのディスクリプタ (`from_descriptor` /
+//! `GrpcExt::grpc_descriptors`) から応答します。
 
 use axum::{
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{get, post},
+    extract::{FromRequestParts, Query, RawPathParams, Request, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, on, options, post, MethodFilter},
     Json, Router,
 };
+use bytes::{Bytes, BytesMut};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use crate::openapi::{self, DynParameter, Property, Schema};
+use crate::AppState;
+
+mod descriptor;
+mod health;
+mod http_rule;
+mod json;
+pub(crate) mod native;
+mod reflection;
+mod status;
+mod streaming;
+mod web;
+mod wire;
+
+pub use descriptor::{
+    DescriptorError, DescriptorPool, EnumDescriptor, FieldDescriptor, FieldKind, MessageDescriptor,
+    MethodDescriptor, ServiceDescriptor,
+};
+pub use health::{HealthReporter, ServingStatus};
+pub use http_rule::HttpRule;
+pub use json::JsonMappingError;
+pub use native::{GrpcRequestExt, NativeGrpcService};
+pub use status::GrpcCode;
+pub use streaming::{GrpcMessageStream, GrpcStream, GrpcStreamingHandler};
+
+use http_rule::PathTemplate;
+pub(crate) use reflection::ReflectionIndex;
+
+/// Default `GrpcService::max_body_size`, the same as axum's `DefaultBodyLimit`
+pub const DEFAULT_MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
 /// A gRPC method descriptor
 #[derive(Clone)]
 pub struct GrpcMethod {
@@ -51,6 +166,12 @@ pub struct GrpcMethod {
     pub response_type: String,
     /// Whether this is a server streaming method
     pub streaming: bool,
+    /// Whether this is a client streaming method (NDJSON request body)
+    pub client_streaming: bool,
+    /// `google.api.http` bindings; empty means `POST /{package}/{service}/{method}`
+    pub http_rules: Vec<HttpRule>,
+    /// Protobuf descriptor (set for services built with `GrpcService::from_descriptor`)
+    pub descriptor: Option<MethodDescriptor>,
 }
 
 /// A registered gRPC service
@@ -64,9 +185,17 @@ pub struct GrpcService {
     pub package: Option<String>,
     /// Methods in this service
     pub methods: Vec<GrpcMethod>,
+    /// Security schemes required to call this service (OpenAPI + auth middleware)
+    pub security: Vec<String>,
+    /// Largest request body the JSON gateway and gRPC-Web buffer, in bytes
+    pub max_body_size: usize,
     /// Runtime handlers for methods (not included in Clone, use Arc manually)
     #[doc(hidden)]
     pub handlers: Arc<std::sync::RwLock<std::collections::HashMap<String, GrpcHandler>>>,
+    /// Runtime handlers for server-streaming methods
+    #[doc(hidden)]
+    pub streaming_handlers:
+        Arc<std::sync::RwLock<std::collections::HashMap<String, GrpcStreamingHandler>>>,
 }
 
 impl GrpcService {
@@ -77,7 +206,10 @@ impl GrpcService {
             full_path: format!("/{}", name),
             package: None,
             methods: Vec::new(),
+            security: Vec::new(),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
             handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            streaming_handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
         }
     }
 
@@ -87,6 +219,107 @@ impl GrpcService {
         self
     }
 
+    /// Require a security scheme (e.g. "bearer") for every method of this service
+    pub fn security(mut self, scheme: &str) -> Self {
+        self.security.push(scheme.to_string());
+        self
+    }
+
+    /// Limit request bodies read by the JSON gateway and gRPC-Web (default: 2 MiB)
+    ///
+    /// Larger bodies are answered with `RESOURCE_EXHAUSTED` (HTTP 413 on the gateway).
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = bytes;
+        self
+    }
+
+    /// HTTP path of a transcoded method: `/{package}/{service}/{method}`
+    pub fn http_path(&self, method: &GrpcMethod) -> String {
+        if let Some(ref pkg) = self.package {
+            format!("/{}/{}/{}", pkg, self.name, method.name)
+        } else {
+            format!("/{}/{}", self.name, method.name)
+        }
+    }
+
+    /// HTTP bindings of a method: its `google.api.http` rules, or the default
+    /// `POST /{package}/{service}/{method}` with the whole message as body.
+    pub fn http_rules(&self, method: &GrpcMethod) -> Vec<HttpRule> {
+        if method.http_rules.is_empty() {
+            vec![HttpRule::post(&self.http_path(method)).body("*")]
+        } else {
+            method.http_rules.clone()
+        }
+    }
+
+    /// `(HTTP method, axum route path)` of every binding and gRPC-Web endpoint, for
+    /// the auth middleware
+    pub(crate) fn route_patterns(&self) -> Vec<(String, String)> {
+        let mut patterns: Vec<(String, String)> = self
+            .methods
+            .iter()
+            .flat_map(|method| self.http_rules(method))
+            .filter_map(|rule| {
+                let (_, template) = rule.compile().ok()?;
+                Some((rule.method, template.axum_path()))
+            })
+            .collect();
+        for method in &self.methods {
+            let pattern = ("POST".to_string(), method.full_path.clone());
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+        patterns
+    }
+
+    /// Build a service from a descriptor pool (`package.Service`), including the
+    /// `google.api.http` rules and message descriptors of every method.
+    pub fn from_descriptor(
+        pool: &DescriptorPool,
+        service_name: &str,
+    ) -> Result<Self, DescriptorError> {
+        let descriptor = pool.service(service_name).ok_or_else(|| DescriptorError {
+            message: format!("service {} not found", service_name),
+        })?;
+
+        let mut service = Self::new(&descriptor.name);
+        service.full_path = format!("/{}", descriptor.full_name);
+        service.package = descriptor.package.clone();
+        for method in descriptor.methods {
+            for rule in &method.http_rules {
+                rule.validate().map_err(|e| DescriptorError {
+                    message: format!(
+                        "invalid HTTP rule {} {} for {}: {}",
+                        rule.method, rule.path, method.full_path, e
+                    ),
+                })?;
+            }
+            service.methods.push(GrpcMethod {
+                name: method.name.clone(),
+                full_path: method.full_path.clone(),
+                request_type: method.input_type.clone(),
+                response_type: method.output_type.clone(),
+                streaming: method.server_streaming,
+                client_streaming: method.client_streaming,
+                http_rules: method.http_rules.clone(),
+                descriptor: Some(method),
+            });
+        }
+        Ok(service)
+    }
+
+    /// Register a handler taking and returning prost messages (see `typed_handler`)
+    pub fn with_typed_handler<Req, Resp, F, Fut>(self, method_name: &str, handler: F) -> Self
+    where
+        Req: prost::Message + Default + 'static,
+        Resp: prost::Message + 'static,
+        F: Fn(Req, GrpcRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Resp, GrpcError>> + Send + Sync + 'static,
+    {
+        self.with_handler(method_name, typed_handler(handler))
+    }
+
     /// Register a handler for a method (not cloneable, use before cloning)
     pub fn with_handler(self, method_name: &str, handler: GrpcHandler) -> Self {
         if let Ok(mut handlers) = self.handlers.write() {
@@ -101,6 +334,39 @@ impl GrpcService {
         handlers.get(method_name).cloned()
     }
 
+    /// Register a handler for a server-streaming method
+    ///
+    /// The gateway sends the messages as NDJSON, or as SSE for `Accept: text/event-stream`.
+    pub fn with_streaming_handler(self, method_name: &str, handler: GrpcStreamingHandler) -> Self {
+        if let Ok(mut handlers) = self.streaming_handlers.write() {
+            handlers.insert(method_name.to_string(), handler);
+        }
+        self
+    }
+
+    /// Register a server-streaming handler taking a prost request and yielding prost
+    /// messages (see `typed_streaming_handler`)
+    pub fn with_typed_streaming_handler<Req, Resp, F, Fut, S>(
+        self,
+        method_name: &str,
+        handler: F,
+    ) -> Self
+    where
+        Req: prost::Message + Default + 'static,
+        Resp: prost::Message + 'static,
+        F: Fn(Req, GrpcRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<S, GrpcError>> + Send + Sync + 'static,
+        S: futures_util::Stream<Item = Result<Resp, GrpcError>> + Send + 'static,
+    {
+        self.with_streaming_handler(method_name, typed_streaming_handler(handler))
+    }
+
+    /// Get a streaming handler by method name
+    pub fn get_streaming_handler(&self, method_name: &str) -> Option<GrpcStreamingHandler> {
+        let handlers = self.streaming_handlers.read().ok()?;
+        handlers.get(method_name).cloned()
+    }
+
     /// Set a handler for a method (consumes self, returns new instance)
     #[doc(hidden)]
     pub fn set_handler(self, method_name: &str, handler: GrpcHandler) -> Self {
@@ -117,7 +383,6 @@ pub type GrpcHandler = std::sync::Arc<
 >;
 
 /// A gRPC request after transcoding from HTTP/JSON
-#[derive(Debug)]
 pub struct GrpcRequest {
     /// The JSON body deserialized
     pub body: serde_json::Value,
@@ -127,6 +392,126 @@ pub struct GrpcRequest {
     pub query_params: HashMap<String, String>,
     /// The full method path
     pub method_path: String,
+    /// Request headers as gRPC metadata
+    pub metadata: GrpcMetadata,
+    /// Application state shared with the HTTP routes (dependencies)
+    pub state: AppState,
+    /// Method descriptor, when the service was built from a descriptor set
+    pub descriptor: Option<MethodDescriptor>,
+    /// Request messages of a client-streaming method (`body` then only holds the
+    /// fields bound from the path and query)
+    pub messages: Option<GrpcMessageStream>,
+}
+
+impl GrpcRequest {
+    /// Get a dependency registered on the app (`UltraApiApp::dep`)
+    pub fn dep<T: 'static + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.state.get::<T>()
+    }
+
+    /// Decode the bound request (body + path + query) into a prost message
+    /// using the proto3 JSON mapping of the method descriptor.
+    pub fn decode<T: prost::Message + Default>(&self) -> Result<T, GrpcError> {
+        self.decode_message(&self.body)
+    }
+
+    /// Decode one request message (e.g. an item of `messages`) into a prost message
+    pub fn decode_message<T: prost::Message + Default>(
+        &self,
+        message: &serde_json::Value,
+    ) -> Result<T, GrpcError> {
+        let descriptor = self
+            .descriptor
+            .as_ref()
+            .ok_or_else(|| GrpcError::internal("method has no protobuf descriptor"))?;
+        let bytes = descriptor
+            .encode_request(message)
+            .map_err(|e| GrpcError::invalid_argument(&e.to_string()))?;
+        T::decode(bytes.as_slice()).map_err(|e| GrpcError::invalid_argument(&e.to_string()))
+    }
+}
+
+/// Wrap a handler that takes and returns prost messages.
+///
+/// The request JSON is converted with the method descriptor (invalid input becomes
+/// `INVALID_ARGUMENT`), and the response message is rendered as proto3 JSON.
+pub fn typed_handler<Req, Resp, F, Fut>(handler: F) -> GrpcHandler
+where
+    Req: prost::Message + Default + 'static,
+    Resp: prost::Message + 'static,
+    F: Fn(Req, GrpcRequest) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Resp, GrpcError>> + Send + Sync + 'static,
+{
+    let handler = Arc::new(handler);
+    Arc::new(move |request: GrpcRequest| {
+        let handler = handler.clone();
+        Box::pin(async move {
+            let message = match request.decode::<Req>() {
+                Ok(message) => message,
+                Err(error) => return GrpcResponse::from(error),
+            };
+            let descriptor = request.descriptor.clone();
+            let response = match handler(message, request).await {
+                Ok(response) => response,
+                Err(error) => return GrpcResponse::from(error),
+            };
+            let body = descriptor
+                .expect("decode succeeded, so the descriptor is present")
+                .decode_response(&response.encode_to_vec());
+            match body {
+                Ok(body) => GrpcResponse {
+                    body,
+                    status_code: 0,
+                },
+                Err(e) => GrpcResponse::from(GrpcError::internal(&e.to_string())),
+            }
+        })
+    })
+}
+
+/// Wrap a server-streaming handler that takes a prost request and yields prost messages.
+///
+/// Each message is rendered as proto3 JSON with the method descriptor.
+pub fn typed_streaming_handler<Req, Resp, F, Fut, S>(handler: F) -> GrpcStreamingHandler
+where
+    Req: prost::Message + Default + 'static,
+    Resp: prost::Message + 'static,
+    F: Fn(Req, GrpcRequest) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<S, GrpcError>> + Send + Sync + 'static,
+    S: futures_util::Stream<Item = Result<Resp, GrpcError>> + Send + 'static,
+{
+    let handler = Arc::new(handler);
+    Arc::new(move |request: GrpcRequest| {
+        let handler = handler.clone();
+        Box::pin(async move {
+            let message = request.decode::<Req>()?;
+            let descriptor = request
+                .descriptor
+                .clone()
+                .expect("decode succeeded, so the descriptor is present");
+            let messages = handler(message, request).await?.map(move |item| {
+                item.and_then(|response| {
+                    descriptor
+                        .decode_response(&response.encode_to_vec())
+                        .map_err(|e| GrpcError::internal(&e.to_string()))
+                })
+            });
+            Ok(Box::pin(messages) as GrpcStream)
+        })
+    })
+}
+
+impl std::fmt::Debug for GrpcRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GrpcRequest")
+            .field("body", &self.body)
+            .field("path_params", &self.path_params)
+            .field("query_params", &self.query_params)
+            .field("method_path", &self.method_path)
+            .field("metadata", &self.metadata)
+            .field("messages", &self.messages)
+            .finish_non_exhaustive()
+    }
 }
 
 /// A gRPC response to be transcoded back to HTTP/JSON
@@ -138,6 +523,15 @@ pub struct GrpcResponse {
     pub status_code: i32,
 }
 
+impl From<GrpcError> for GrpcResponse {
+    fn from(error: GrpcError) -> Self {
+        Self {
+            status_code: error.code,
+            body: serde_json::to_value(&error).unwrap_or_default(),
+        }
+    }
+}
+
 impl GrpcMethod {
     /// Create a new unary (non-streaming) gRPC method
     pub fn unary(name: &str, full_path: &str) -> Self {
@@ -147,6 +541,9 @@ impl GrpcMethod {
             request_type: format!("{}Request", name),
             response_type: format!("{}Response", name),
             streaming: false,
+            client_streaming: false,
+            http_rules: Vec::new(),
+            descriptor: None,
         }
     }
 
@@ -158,8 +555,39 @@ impl GrpcMethod {
             request_type: format!("{}Request", name),
             response_type: format!("{}Response", name),
             streaming: true,
+            client_streaming: false,
+            http_rules: Vec::new(),
+            descriptor: None,
+        }
+    }
+
+    /// Create a new client streaming gRPC method
+    pub fn client_streaming(name: &str, full_path: &str) -> Self {
+        Self {
+            client_streaming: true,
+            ..Self::unary(name, full_path)
+        }
+    }
+
+    /// Create a new bidirectional streaming gRPC method
+    pub fn bidi_streaming(name: &str, full_path: &str) -> Self {
+        Self {
+            client_streaming: true,
+            ..Self::server_streaming(name, full_path)
         }
     }
+
+    /// Add an HTTP binding (`google.api.http` rule)
+    ///
+    /// A rule with an unsupported method or an invalid path template is skipped with a
+    /// warning; use [`HttpRule::validate`] to check rules built at runtime.
+    pub fn http(mut self, rule: HttpRule) -> Self {
+        match rule.validate() {
+            Ok(()) => self.http_rules.push(rule),
+            Err(e) => warn_invalid_rule(&self.full_path, &rule, &e),
+        }
+        self
+    }
 }
 
 /// gRPC metadata (headers)
@@ -169,8 +597,29 @@ pub struct GrpcMetadata {
     pub headers: HashMap<String, String>,
 }
 
+impl GrpcMetadata {
+    /// Build metadata from HTTP headers (names are lowercased, non-UTF-8 values skipped)
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let headers = headers
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|v| (name.as_str().to_string(), v.to_string()))
+            })
+            .collect();
+        Self { headers }
+    }
+
+    /// Get a metadata value by (lowercase) key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.headers.get(key).map(String::as_str)
+    }
+}
+
 /// gRPC error response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct GrpcError {
     pub code: i32,
     pub message: String,
@@ -179,39 +628,209 @@ pub struct GrpcError {
 }
 
 impl GrpcError {
-    pub fn ok(message: &str) -> Self {
+    /// Create an error with a canonical status code
+    pub fn new(code: GrpcCode, message: &str) -> Self {
         Self {
-            code: 0,
+            code: code.as_i32(),
             message: message.to_string(),
             details: vec![],
         }
     }
 
+    pub fn ok(message: &str) -> Self {
+        Self::new(GrpcCode::Ok, message)
+    }
+
+    pub fn cancelled(message: &str) -> Self {
+        Self::new(GrpcCode::Cancelled, message)
+    }
+
+    pub fn unknown(message: &str) -> Self {
+        Self::new(GrpcCode::Unknown, message)
+    }
+
+    pub fn invalid_argument(message: &str) -> Self {
+        Self::new(GrpcCode::InvalidArgument, message)
+    }
+
+    pub fn deadline_exceeded(message: &str) -> Self {
+        Self::new(GrpcCode::DeadlineExceeded, message)
+    }
+
     pub fn not_found(message: &str) -> Self {
-        Self {
-            code: 5, // NOT_FOUND
-            message: message.to_string(),
-            details: vec![],
-        }
+        Self::new(GrpcCode::NotFound, message)
+    }
+
+    pub fn already_exists(message: &str) -> Self {
+        Self::new(GrpcCode::AlreadyExists, message)
+    }
+
+    pub fn permission_denied(message: &str) -> Self {
+        Self::new(GrpcCode::PermissionDenied, message)
+    }
+
+    pub fn resource_exhausted(message: &str) -> Self {
+        Self::new(GrpcCode::ResourceExhausted, message)
+    }
+
+    pub fn failed_precondition(message: &str) -> Self {
+        Self::new(GrpcCode::FailedPrecondition, message)
+    }
+
+    pub fn aborted(message: &str) -> Self {
+        Self::new(GrpcCode::Aborted, message)
+    }
+
+    pub fn out_of_range(message: &str) -> Self {
+        Self::new(GrpcCode::OutOfRange, message)
+    }
+
+    pub fn unimplemented(message: &str) -> Self {
+        Self::new(GrpcCode::Unimplemented, message)
     }
 
     pub fn internal(message: &str) -> Self {
-        Self {
-            code: 13, // INTERNAL
-            message: message.to_string(),
-            details: vec![],
+        Self::new(GrpcCode::Internal, message)
+    }
+
+    pub fn unavailable(message: &str) -> Self {
+        Self::new(GrpcCode::Unavailable, message)
+    }
+
+    pub fn data_loss(message: &str) -> Self {
+        Self::new(GrpcCode::DataLoss, message)
+    }
+
+    pub fn unauthenticated(message: &str) -> Self {
+        Self::new(GrpcCode::Unauthenticated, message)
+    }
+
+    /// The status code (unknown values are `Unknown`)
+    pub fn grpc_code(&self) -> GrpcCode {
+        GrpcCode::from_i32(self.code)
+    }
+
+    /// HTTP status of the transcoded response
+    pub fn http_status(&self) -> StatusCode {
+        self.grpc_code().http_status()
+    }
+
+    /// Add a `google.rpc.BadRequest` field violation
+    pub fn with_field_violation(mut self, field: &str, description: &str) -> Self {
+        let violation = serde_json::json!({ "field": field, "description": description });
+        match self
+            .details
+            .iter_mut()
+            .find(|detail| detail["@type"] == status::BAD_REQUEST_TYPE)
+        {
+            Some(detail) => {
+                if let Some(violations) = detail["fieldViolations"].as_array_mut() {
+                    violations.push(violation);
+                }
+            }
+            None => self.details.push(status::bad_request_detail(vec![(
+                field.to_string(),
+                description.to_string(),
+            )])),
         }
+        self
     }
 
-    pub fn invalid_argument(message: &str) -> Self {
+    /// Add a `google.rpc.RetryInfo` detail (also sent as `Retry-After` over HTTP)
+    pub fn with_retry_delay(mut self, delay: std::time::Duration) -> Self {
+        self.details
+            .retain(|detail| detail["@type"] != status::RETRY_INFO_TYPE);
+        self.details.push(status::retry_info_detail(delay));
+        self
+    }
+
+    /// Add a `google.rpc.ErrorInfo` detail
+    pub fn with_error_info(mut self, reason: &str, domain: &str) -> Self {
+        self.details.push(serde_json::json!({
+            "@type": status::ERROR_INFO_TYPE,
+            "reason": reason,
+            "domain": domain,
+        }));
+        self
+    }
+
+    /// Add any JSON detail (`{"@type": "...", ...}`)
+    pub fn with_detail(mut self, detail: serde_json::Value) -> Self {
+        self.details.push(detail);
+        self
+    }
+
+    /// Retry delay from a `RetryInfo` detail
+    pub fn retry_delay(&self) -> Option<std::time::Duration> {
+        status::retry_delay(&self.details)
+    }
+}
+
+impl std::fmt::Display for GrpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.grpc_code(), self.message)
+    }
+}
+
+impl std::error::Error for GrpcError {}
+
+impl IntoResponse for GrpcError {
+    fn into_response(self) -> Response {
+        let status = self.http_status();
+        let retry_after = self.retry_delay();
+        let mut response = (status, Json(self)).into_response();
+        if let Some(delay) = retry_after {
+            // Retry-After only has second precision
+            let seconds = delay.as_secs() + u64::from(delay.subsec_nanos() > 0);
+            response.headers_mut().insert(
+                axum::http::header::RETRY_AFTER,
+                axum::http::HeaderValue::from(seconds),
+            );
+        }
+        response
+    }
+}
+
+impl From<crate::ApiError> for GrpcError {
+    fn from(error: crate::ApiError) -> Self {
+        let (code, message, details) = status::api_error_parts(&error);
         Self {
-            code: 3, // INVALID_ARGUMENT
-            message: message.to_string(),
-            details: vec![],
+            code: code.as_i32(),
+            message,
+            details,
         }
     }
 }
 
+impl From<crate::HttpException> for GrpcError {
+    fn from(error: crate::HttpException) -> Self {
+        Self::from(crate::ApiError::from(error))
+    }
+}
+
+impl From<crate::ApiError> for tonic::Status {
+    fn from(error: crate::ApiError) -> Self {
+        GrpcError::from(error).into()
+    }
+}
+
+impl From<crate::HttpException> for tonic::Status {
+    fn from(error: crate::HttpException) -> Self {
+        GrpcError::from(error).into()
+    }
+}
+
+impl From<GrpcError> for tonic::Status {
+    fn from(error: GrpcError) -> Self {
+        let details = status::encode_status(error.code, &error.message, &error.details);
+        tonic::Status::with_details(
+            tonic::Code::from_i32(error.code),
+            error.message,
+            details.into(),
+        )
+    }
+}
+
 /// Configuration for gRPC transcoding
 #[derive(Clone)]
 pub struct GrpcTranscoder {
@@ -239,47 +858,678 @@ impl GrpcTranscoder {
         self.services.get(name)
     }
 
+    /// Iterate over the registered services
+    pub fn services(&self) -> impl Iterator<Item = &GrpcService> {
+        self.services.values()
+    }
+
+    /// Merge the services of another transcoder (services with the same name are replaced)
+    pub fn merge(mut self, other: GrpcTranscoder) -> Self {
+        let services = Arc::make_mut(&mut self.services);
+        for (name, service) in other.services.iter() {
+            services.insert(name.clone(), service.clone());
+        }
+        self
+    }
+
+    /// Add services registered with `grpc_service!` that are not registered explicitly.
+    pub fn with_registered_services(mut self) -> Self {
+        let services = Arc::make_mut(&mut self.services);
+        for info in inventory::iter::<&'static GrpcServiceInfo> {
+            services
+                .entry(info.name.to_string())
+                .or_insert_with(|| (info.service_fn)());
+        }
+        self
+    }
+
     /// Build the HTTP routes for all registered gRPC services
     pub fn into_router(self) -> Router {
-        let mut router = Router::new();
+        let index = Arc::new(ReflectionIndex::new(&self.descriptor_pools(), []));
+        self.add_routes(Router::new(), index, true)
+            .with_state(AppState::new())
+    }
 
-        // Add gRPC health check endpoint
-        router = router.route("/grpc.health.v1.Health/Check", get(grpc_health_check));
+    /// Descriptor pools of the services built with `GrpcService::from_descriptor`
+    pub(crate) fn descriptor_pools(&self) -> Vec<DescriptorPool> {
+        self.services
+            .values()
+            .flat_map(|service| &service.methods)
+            .filter_map(|method| method.descriptor.as_ref())
+            .map(|descriptor| descriptor.pool().clone())
+            .collect()
+    }
 
-        // Add service descriptor endpoint (for reflection)
+    /// Add health, reflection, transcoding and gRPC-Web routes to an app router sharing
+    /// `AppState`. `web_cors` answers CORS for gRPC-Web when the app has no CORS layer.
+    pub(crate) fn add_routes(
+        &self,
+        mut router: Router<AppState>,
+        index: Arc<ReflectionIndex>,
+        web_cors: bool,
+    ) -> Router<AppState> {
+        // Health and reflection for HTTP/JSON clients (gRPC clients are served by
+        // the native services)
+        router = router.route(
+            "/grpc.health.v1.Health/Check",
+            get(health::health_check_json),
+        );
         router = router.route(
             "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo",
-            post(grpc_reflection),
+            post(move |request: Request| reflection::reflection_json(index.clone(), request)),
         );
 
-        // Add transcoding routes for each service
-        for (_name, service) in self.services.iter() {
+        // gRPC-Web calls are sent to the gRPC method path
+        let mut web_bindings: HashMap<String, Arc<web::WebBinding>> = self
+            .services
+            .values()
+            .flat_map(|service| {
+                service.methods.iter().map(move |method| {
+                    let binding = web::WebBinding::new(service, method, web_cors);
+                    (method.full_path.clone(), Arc::new(binding))
+                })
+            })
+            .collect();
+        let web_paths: Vec<String> = web_bindings.keys().cloned().collect();
+
+        // Add transcoding routes for each HTTP binding of each method. Templates that only
+        // differ in the `:verb` after a capture share an axum route, so bindings are grouped
+        // by method and axum path and the verb picks one per request.
+        let mut groups: Vec<(String, String, MethodFilter, Vec<Arc<HttpBinding>>)> = Vec::new();
+        for service in self.services.values() {
             for method in &service.methods {
-                let service = service.clone();
-                let method = method.clone();
-
-                // Create HTTP path: /{package}/{service}/{method}
-                let http_path = if let Some(ref pkg) = service.package {
-                    format!("/{}/{}/{}", pkg, service.name, method.name)
-                } else {
-                    format!("/{}/{}", service.name, method.name)
-                };
-
+                for rule in service.http_rules(method) {
+                    // Rules pushed to `http_rules` directly are not validated by `http()`
+                    let (filter, template) = match rule.compile() {
+                        Ok(compiled) => compiled,
+                        Err(e) => {
+                            warn_invalid_rule(&method.full_path, &rule, &e);
+                            continue;
+                        }
+                    };
+                    let mut binding = HttpBinding::new(service, method, rule, template);
+                    // e.g. `POST /Vault/Open` for a service without a package
+                    if binding.rule.method == "POST" {
+                        binding.web = web_bindings.remove(&binding.template.axum_path());
+                    }
+                    let http_method = binding.rule.method.to_ascii_uppercase();
+                    let path = binding.template.axum_path();
+                    let binding = Arc::new(binding);
+                    match groups
+                        .iter_mut()
+                        .find(|(m, p, _, _)| *m == http_method && *p == path)
+                    {
+                        Some((_, _, _, bindings)) => bindings.push(binding),
+                        None => groups.push((http_method, path, filter, vec![binding])),
+                    }
+                }
+            }
+        }
+        for (_, path, filter, bindings) in groups {
+            let bindings = Arc::new(bindings);
+            router = router.route(
+                &path,
+                on(
+                    filter,
+                    move |State(state): State<AppState>, request: Request| {
+                        let bindings = bindings.clone();
+                        async move {
+                            match HttpBinding::select(&bindings, request.uri().path()) {
+                                Some(binding) => binding.handle(state, request).await,
+                                None => GrpcError::not_found(&format!(
+                                    "no route for {}",
+                                    request.uri().path()
+                                ))
+                                .into_response(),
+                            }
+                        }
+                    },
+                ),
+            );
+        }
+        for (path, binding) in web_bindings {
+            router = router.route(
+                &path,
+                post(move |State(state): State<AppState>, request: Request| {
+                    let binding = binding.clone();
+                    async move { binding.handle(state, request).await }
+                }),
+            );
+        }
+        if web_cors {
+            for path in web_paths {
                 router = router.route(
-                    &http_path,
-                    post(
-                        move |axum::extract::Json(body): axum::extract::Json<serde_json::Value>| {
-                            let service = service.clone();
-                            let method = method.clone();
-                            async move { handle_grpc_request(service, method, body).await }
-                        },
-                    ),
+                    &path,
+                    options(|headers: HeaderMap| async move { web::preflight(&headers) }),
                 );
             }
         }
 
         router
     }
+
+    /// Add one operation per HTTP binding (tagged by service) to the OpenAPI spec.
+    ///
+    /// Descriptor-backed messages get component schemas generated from their fields.
+    /// Other message types reference a registered `#[api_model]` schema of the same
+    /// name, or get a free-form object component.
+    pub(crate) fn extend_openapi(
+        &self,
+        paths: &mut HashMap<String, openapi::PathItem>,
+        schemas: &mut HashMap<String, Schema>,
+    ) {
+        let mut services: Vec<&GrpcService> = self.services.values().collect();
+        services.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for service in services {
+            let security: Vec<HashMap<String, Vec<String>>> = service
+                .security
+                .iter()
+                .map(|scheme| HashMap::from([(scheme.clone(), Vec::new())]))
+                .collect();
+
+            for method in &service.methods {
+                let request_schema = message_component(method, &method.request_type, schemas);
+                let response_schema = message_component(method, &method.response_type, schemas);
+                schemas
+                    .entry("GrpcError".to_string())
+                    .or_insert_with(grpc_error_schema);
+
+                for (index, rule) in service.http_rules(method).into_iter().enumerate() {
+                    let Ok((_, template)) = rule.compile() else {
+                        continue;
+                    };
+                    // Client-streaming requests are one message per NDJSON line
+                    let request_content_type = if method.client_streaming {
+                        "application/x-ndjson"
+                    } else {
+                        "application/json"
+                    };
+                    let request_body = rule.body.as_deref().map(|body| openapi::RequestBody {
+                        required: true,
+                        content_type: request_content_type.to_string(),
+                        schema_ref: component_ref(
+                            &field_component(method, &method.request_type, body, schemas)
+                                .unwrap_or_else(|| request_schema.clone()),
+                        ),
+                    });
+                    let response_schema = rule
+                        .response_body
+                        .as_deref()
+                        .and_then(|field| {
+                            field_component(method, &method.response_type, field, schemas)
+                        })
+                        .unwrap_or_else(|| response_schema.clone());
+
+                    let mut responses = HashMap::new();
+                    let success = if method.streaming {
+                        let name = format!("StreamResultOf{}", response_schema);
+                        schemas
+                            .entry(name.clone())
+                            .or_insert_with(|| stream_result_schema(&response_schema));
+                        openapi::ResponseDef {
+                            description: "Stream of messages (NDJSON or SSE)".to_string(),
+                            schema_ref: Some(serde_json::json!({ "$ref": component_ref(&name) })),
+                            content_type: Some("application/x-ndjson".to_string()),
+                            headers: HashMap::new(),
+                        }
+                    } else {
+                        openapi::ResponseDef {
+                            description: "Successful Response".to_string(),
+                            schema_ref: Some(
+                                serde_json::json!({ "$ref": component_ref(&response_schema) }),
+                            ),
+                            content_type: Some("application/json".to_string()),
+                            headers: HashMap::new(),
+                        }
+                    };
+                    responses.insert("200".to_string(), success);
+                    responses.insert(
+                        "default".to_string(),
+                        openapi::ResponseDef {
+                            description: "gRPC Error".to_string(),
+                            schema_ref: Some(serde_json::json!({
+                                "$ref": "#/components/schemas/GrpcError"
+                            })),
+                            content_type: Some("application/json".to_string()),
+                            headers: HashMap::new(),
+                        },
+                    );
+
+                    // Additional bindings get a numeric suffix, like grpc-gateway
+                    let operation_id = if index == 0 {
+                        format!("{}_{}", service.name, method.name)
+                    } else {
+                        format!("{}_{}{}", service.name, method.name, index + 1)
+                    };
+                    let operation = openapi::Operation {
+                        summary: Some(method.name.clone()),
+                        description: Some(format!("gRPC method `{}`", method.full_path)),
+                        operation_id: Some(operation_id),
+                        tags: vec![service.name.clone()],
+                        parameters: Vec::new(),
+                        request_body,
+                        responses,
+                        security: security.clone(),
+                        callbacks: HashMap::new(),
+                        deprecated: false,
+                        external_docs: None,
+                    };
+
+                    paths
+                        .entry(template.openapi_path())
+                        .or_default()
+                        .insert(rule.method.to_lowercase(), operation);
+                }
+            }
+        }
+    }
+
+    /// Add path and query parameters of the HTTP bindings to the serialized spec.
+    ///
+    /// `openapi::Parameter` only holds static strings, so parameters derived from
+    /// descriptors are merged as `DynParameter`s after serialization.
+    pub(crate) fn extend_openapi_parameters(&self, spec: &mut serde_json::Value) {
+        for service in self.services.values() {
+            for method in &service.methods {
+                for rule in service.http_rules(method) {
+                    let Ok((_, template)) = rule.compile() else {
+                        continue;
+                    };
+                    let parameters = binding_parameters(method, &rule, &template);
+                    if parameters.is_empty() {
+                        continue;
+                    }
+                    let operation =
+                        &mut spec["paths"][template.openapi_path()][rule.method.to_lowercase()];
+                    if !operation.is_object() {
+                        continue;
+                    }
+                    operation["parameters"] = serde_json::to_value(parameters).unwrap_or_default();
+                }
+            }
+        }
+    }
+}
+
+fn component_ref(name: &str) -> String {
+    format!("#/components/schemas/{}", name)
+}
+
+/// Ensure a component schema exists for a method's message type and return its name.
+fn message_component(
+    method: &GrpcMethod,
+    type_name: &str,
+    schemas: &mut HashMap<String, Schema>,
+) -> String {
+    if let Some(descriptor) = &method.descriptor {
+        if descriptor.pool().message(type_name).is_some() {
+            descriptor_schema(descriptor.pool(), type_name, schemas);
+            return type_name.to_string();
+        }
+    }
+    let name = message_schema_name(type_name);
+    schemas
+        .entry(name.clone())
+        .or_insert_with(|| message_placeholder_schema(&name));
+    name
+}
+
+/// Component name for a message-typed field (`body: "book"`), if it has one.
+fn field_component(
+    method: &GrpcMethod,
+    type_name: &str,
+    field: &str,
+    schemas: &mut HashMap<String, Schema>,
+) -> Option<String> {
+    if field == "*" {
+        return None;
+    }
+    let pool = method.descriptor.as_ref()?.pool();
+    let field = pool.message(type_name)?.field(field)?;
+    if field.kind != FieldKind::Message || field.repeated {
+        return None;
+    }
+    let field_type = field.type_name.clone()?;
+    pool.message(&field_type)?;
+    descriptor_schema(pool, &field_type, schemas);
+    Some(field_type)
+}
+
+/// `my_crate::GetUserRequest` -> `GetUserRequest`
+fn message_schema_name(type_name: &str) -> String {
+    type_name
+        .rsplit("::")
+        .next()
+        .unwrap_or(type_name)
+        .to_string()
+}
+
+fn message_placeholder_schema(name: &str) -> Schema {
+    Schema {
+        type_name: "object".to_string(),
+        properties: HashMap::new(),
+        required: Vec::new(),
+        description: Some(format!("gRPC message `{}` (JSON mapping)", name)),
+        enum_values: None,
+        example: None,
+        one_of: None,
+        discriminator: None,
+    }
+}
+
+/// Component schemas for a descriptor message and the messages it references
+fn descriptor_schema(
+    pool: &DescriptorPool,
+    type_name: &str,
+    schemas: &mut HashMap<String, Schema>,
+) {
+    if schemas.contains_key(type_name) {
+        return;
+    }
+    let Some(message) = pool.message(type_name) else {
+        return;
+    };
+    // Insert first so self-referencing messages terminate
+    schemas.insert(type_name.to_string(), message_placeholder_schema(type_name));
+
+    let mut properties = HashMap::new();
+    for field in &message.fields {
+        properties.insert(
+            field.json_name.clone(),
+            field_property(pool, field, schemas),
+        );
+    }
+    schemas.insert(
+        type_name.to_string(),
+        Schema {
+            type_name: "object".to_string(),
+            properties,
+            required: Vec::new(),
+            description: None,
+            enum_values: None,
+            example: None,
+            one_of: None,
+            discriminator: None,
+        },
+    );
+}
+
+fn field_property(
+    pool: &DescriptorPool,
+    field: &FieldDescriptor,
+    schemas: &mut HashMap<String, Schema>,
+) -> Property {
+    let type_name = field.type_name.as_deref().unwrap_or_default();
+
+    if let Some(entry) = pool.message(type_name).filter(|m| m.map_entry) {
+        let mut property = blank_property("object");
+        if let Some(value_field) = entry.field_by_number(2) {
+            property.additional_properties =
+                Some(Box::new(field_property(pool, value_field, schemas)));
+        }
+        return property;
+    }
+
+    let item = match field.kind {
+        FieldKind::Message => match well_known_json_type(type_name) {
+            Some((json_type, format)) => {
+                let mut property = blank_property(json_type);
+                property.format = format.map(str::to_string);
+                property
+            }
+            None => {
+                descriptor_schema(pool, type_name, schemas);
+                let mut property = blank_property("object");
+                property.ref_path = Some(component_ref(type_name));
+                property
+            }
+        },
+        FieldKind::Enum => {
+            let mut property = blank_property("string");
+            property.enum_values = pool.enum_type(type_name).map(|e| {
+                e.values
+                    .iter()
+                    .map(|(name, _)| serde_json::Value::String(name.clone()))
+                    .collect()
+            });
+            property
+        }
+        kind => {
+            let (json_type, format) = scalar_json_type(kind);
+            let mut property = blank_property(json_type);
+            property.format = format.map(str::to_string);
+            property
+        }
+    };
+
+    if field.repeated {
+        let mut property = blank_property("array");
+        property.items = Some(Box::new(item));
+        property
+    } else {
+        item
+    }
+}
+
+/// proto3 JSON representation of scalar types
+fn scalar_json_type(kind: FieldKind) -> (&'static str, Option<&'static str>) {
+    match kind {
+        FieldKind::Int32 | FieldKind::Sint32 | FieldKind::Sfixed32 => ("integer", Some("int32")),
+        FieldKind::Uint32 | FieldKind::Fixed32 => ("integer", Some("uint32")),
+        FieldKind::Int64 | FieldKind::Sint64 | FieldKind::Sfixed64 => ("string", Some("int64")),
+        FieldKind::Uint64 | FieldKind::Fixed64 => ("string", Some("uint64")),
+        FieldKind::Float => ("number", Some("float")),
+        FieldKind::Double => ("number", Some("double")),
+        FieldKind::Bool => ("boolean", None),
+        FieldKind::Bytes => ("string", Some("byte")),
+        FieldKind::String | FieldKind::Enum => ("string", None),
+        FieldKind::Message => ("object", None),
+    }
+}
+
+fn well_known_json_type(type_name: &str) -> Option<(&'static str, Option<&'static str>)> {
+    Some(match type_name {
+        "google.protobuf.Timestamp" => ("string", Some("date-time")),
+        "google.protobuf.Duration" | "google.protobuf.FieldMask" => ("string", None),
+        "google.protobuf.Struct" | "google.protobuf.Empty" => ("object", None),
+        "google.protobuf.ListValue" => ("array", None),
+        "google.protobuf.Value" => ("object", None),
+        "google.protobuf.DoubleValue" | "google.protobuf.FloatValue" => ("number", None),
+        "google.protobuf.Int32Value" | "google.protobuf.UInt32Value" => ("integer", None),
+        "google.protobuf.Int64Value" | "google.protobuf.UInt64Value" => ("string", Some("int64")),
+        "google.protobuf.BoolValue" => ("boolean", None),
+        "google.protobuf.StringValue" => ("string", None),
+        "google.protobuf.BytesValue" => ("string", Some("byte")),
+        _ => return None,
+    })
+}
+
+/// Path parameters (and, without a whole-message body, query parameters) of a binding
+fn binding_parameters(
+    method: &GrpcMethod,
+    rule: &HttpRule,
+    template: &PathTemplate,
+) -> Vec<DynParameter> {
+    let pool = method.descriptor.as_ref().map(|d| d.pool());
+    let lookup = |path: &str| -> Option<FieldDescriptor> {
+        let pool = pool?;
+        let mut type_name = method.request_type.clone();
+        let mut found = None;
+        for segment in path.split('.') {
+            let field = pool.message(&type_name)?.field(segment)?.clone();
+            type_name = field.type_name.clone().unwrap_or_default();
+            found = Some(field);
+        }
+        found
+    };
+
+    let mut parameters = Vec::new();
+    let path_fields: Vec<&str> = template.field_paths().collect();
+    for field_path in &path_fields {
+        let field = lookup(field_path);
+        parameters.push(dyn_parameter(
+            field_path,
+            "path",
+            true,
+            field.as_ref(),
+            pool,
+        ));
+    }
+
+    if rule.body.as_deref() == Some("*") {
+        return parameters;
+    }
+    let (Some(pool), Some(message)) = (pool, pool.and_then(|p| p.message(&method.request_type)))
+    else {
+        return parameters;
+    };
+    for field in &message.fields {
+        let bound_in_path = path_fields
+            .iter()
+            .any(|p| p.split('.').next() == Some(field.name.as_str()));
+        let is_body = rule.body.as_deref() == Some(field.name.as_str());
+        let is_message = field.kind == FieldKind::Message
+            && well_known_json_type(field.type_name.as_deref().unwrap_or_default()).is_none();
+        if bound_in_path || is_body || is_message {
+            continue;
+        }
+        parameters.push(dyn_parameter(
+            &field.json_name,
+            "query",
+            false,
+            Some(field),
+            Some(pool),
+        ));
+    }
+    parameters
+}
+
+fn dyn_parameter(
+    name: &str,
+    location: &str,
+    required: bool,
+    field: Option<&FieldDescriptor>,
+    pool: Option<&DescriptorPool>,
+) -> DynParameter {
+    let schema_type = match field {
+        Some(f) if f.repeated => "array",
+        Some(f) if f.kind == FieldKind::Message => f
+            .type_name
+            .as_deref()
+            .and_then(well_known_json_type)
+            .map(|(t, _)| t)
+            .unwrap_or("object"),
+        Some(f) => scalar_json_type(f.kind).0,
+        None => "string",
+    };
+    let enum_values = field.filter(|f| f.kind == FieldKind::Enum).and_then(|f| {
+        let values = pool?.enum_type(f.type_name.as_deref()?)?;
+        Some(
+            values
+                .values
+                .iter()
+                .map(|(name, _)| serde_json::Value::String(name.clone()))
+                .collect(),
+        )
+    });
+    DynParameter {
+        name: name.to_string(),
+        location: location.to_string(),
+        required,
+        schema_type: schema_type.to_string(),
+        description: None,
+        style: None,
+        explode: None,
+        example: None,
+        examples: None,
+        minimum: None,
+        maximum: None,
+        min_length: None,
+        max_length: None,
+        pattern: None,
+        exclusive_minimum: None,
+        exclusive_maximum: None,
+        multiple_of: None,
+        enum_values,
+    }
+}
+
+fn grpc_error_schema() -> Schema {
+    let mut properties = HashMap::new();
+    properties.insert(
+        "code".to_string(),
+        described_property("integer", "gRPC status code"),
+    );
+    properties.insert(
+        "message".to_string(),
+        described_property("string", "Error message"),
+    );
+    properties.insert(
+        "details".to_string(),
+        described_property("array", "Error details"),
+    );
+    Schema {
+        type_name: "object".to_string(),
+        properties,
+        required: vec!["code".to_string(), "message".to_string()],
+        description: Some("gRPC error transcoded to JSON".to_string()),
+        enum_values: None,
+        example: None,
+        one_of: None,
+        discriminator: None,
+    }
+}
+
+/// One NDJSON line of a server-streaming response: `{"result": ...}` or `{"error": ...}`
+fn stream_result_schema(message_schema: &str) -> Schema {
+    let mut result = blank_property("object");
+    result.ref_path = Some(component_ref(message_schema));
+    let mut error = blank_property("object");
+    error.ref_path = Some(component_ref("GrpcError"));
+
+    let mut properties = HashMap::new();
+    properties.insert("result".to_string(), result);
+    properties.insert("error".to_string(), error);
+    Schema {
+        type_name: "object".to_string(),
+        properties,
+        required: Vec::new(),
+        description: Some(format!("Stream result of {}", message_schema)),
+        enum_values: None,
+        example: None,
+        one_of: None,
+        discriminator: None,
+    }
+}
+
+fn described_property(type_name: &str, description: &str) -> Property {
+    let mut property = blank_property(type_name);
+    property.description = Some(description.to_string());
+    property
+}
+
+fn blank_property(type_name: &str) -> Property {
+    Property {
+        type_name: type_name.to_string(),
+        format: None,
+        min_length: None,
+        max_length: None,
+        minimum: None,
+        maximum: None,
+        pattern: None,
+        min_items: None,
+        description: None,
+        ref_path: None,
+        items: None,
+        nullable: false,
+        example: None,
+        additional_properties: None,
+        read_only: false,
+        write_only: false,
+        deprecated: false,
+        ..Default::default()
+    }
 }
 
 impl Default for GrpcTranscoder {
@@ -288,71 +1538,335 @@ impl Default for GrpcTranscoder {
     }
 }
 
-/// Handle a gRPC transcoding request
-async fn handle_grpc_request(
+/// One HTTP route of a transcoded method
+struct HttpBinding {
     service: GrpcService,
     method: GrpcMethod,
-    body: serde_json::Value,
-) -> impl IntoResponse {
-    // Find the handler for this method
-    if let Some(handler) = service.get_handler(&method.name) {
+    rule: HttpRule,
+    template: PathTemplate,
+    /// gRPC-Web endpoint sharing this route (the gRPC path is also the HTTP path)
+    web: Option<Arc<web::WebBinding>>,
+}
+
+impl HttpBinding {
+    fn new(
+        service: &GrpcService,
+        method: &GrpcMethod,
+        rule: HttpRule,
+        template: PathTemplate,
+    ) -> Self {
+        Self {
+            service: service.clone(),
+            method: method.clone(),
+            rule,
+            template,
+            web: None,
+        }
+    }
+
+    /// The binding whose `:verb` ends the last path segment, else the one without a verb
+    fn select<'a>(bindings: &'a [Arc<HttpBinding>], path: &str) -> Option<&'a HttpBinding> {
+        let last = path.rsplit('/').next().unwrap_or_default();
+        bindings
+            .iter()
+            .find(|binding| {
+                binding
+                    .template
+                    .verb()
+                    .is_some_and(|verb| last.strip_suffix(verb).is_some_and(|s| s.ends_with(':')))
+            })
+            .or_else(|| {
+                bindings
+                    .iter()
+                    .find(|binding| binding.template.verb().is_none())
+            })
+            .map(|binding| binding.as_ref())
+    }
+
+    /// Bind path variables, query parameters and the body into the request message
+    async fn handle(&self, state: AppState, request: Request) -> Response {
+        if let Some(web) = &self.web {
+            if web::WebFormat::from_headers(request.headers()).is_some() {
+                return web.handle(state, request).await;
+            }
+        }
+        let (mut parts, body) = request.into_parts();
+        let params: HashMap<String, String> =
+            match RawPathParams::from_request_parts(&mut parts, &()).await {
+                Ok(params) => params
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+                Err(_) => HashMap::new(),
+            };
+        let Some(path_values) = self.template.bind(&params) else {
+            return GrpcError::not_found(&format!("no route for {}", parts.uri.path()))
+                .into_response();
+        };
+        let query: Vec<(String, String)> = Query::try_from_uri(&parts.uri)
+            .map(|Query(query)| query)
+            .unwrap_or_default();
+
+        // Client-streaming bodies are NDJSON messages; the path and query still bind
+        // into `GrpcRequest::body`
+        let (mut message, messages) = if self.method.client_streaming {
+            (
+                serde_json::Map::new(),
+                Some(GrpcMessageStream::from_body(
+                    body,
+                    self.service.max_body_size,
+                )),
+            )
+        } else {
+            match self.read_body(body).await {
+                Ok(message) => (message, None),
+                Err(response) => return response,
+            }
+        };
+
+        for (field_path, value) in &path_values {
+            if let Err(e) = self.bind_value(&mut message, field_path, value) {
+                return invalid_argument(&e);
+            }
+        }
+        // With `body: "*"` every field comes from the body
+        if self.rule.body.as_deref() != Some("*") || self.method.client_streaming {
+            for (name, value) in &query {
+                if let Err(e) = self.bind_value(&mut message, name, value) {
+                    return invalid_argument(&e);
+                }
+            }
+        }
+
         let request = GrpcRequest {
-            body,
-            path_params: HashMap::new(),
-            query_params: HashMap::new(),
-            method_path: method.full_path.clone(),
+            body: serde_json::Value::Object(message),
+            path_params: path_values.into_iter().collect(),
+            query_params: query.into_iter().collect(),
+            method_path: self.method.full_path.clone(),
+            metadata: GrpcMetadata::from_headers(&parts.headers),
+            state,
+            descriptor: self.method.descriptor.clone(),
+            messages,
         };
+        let response_body = self.rule.response_body.as_deref();
+        if self.method.streaming {
+            if let Some(handler) = self.service.get_streaming_handler(&self.method.name) {
+                return match handler(request).await {
+                    Ok(stream) => streaming::stream_response(
+                        stream,
+                        response_body,
+                        streaming::wants_sse(&parts.headers),
+                    ),
+                    Err(error) => error.into_response(),
+                };
+            }
+        }
+        handle_grpc_request(&self.service, &self.method, request, response_body).await
+    }
 
-        let response = handler(request).await;
+    /// Parse the JSON body into the request message according to the rule's `body`
+    async fn read_body(
+        &self,
+        body: axum::body::Body,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, Response> {
+        let bytes = read_limited_body(body, self.service.max_body_size)
+            .await
+            .map_err(body_error_response)?;
+        let body_json = match self.rule.body.as_deref() {
+            Some(_) if !bytes.is_empty() => Some(
+                serde_json::from_slice(&bytes)
+                    .map_err(|e| invalid_argument(&format!("invalid JSON body: {}", e)))?,
+            ),
+            _ => None,
+        };
+        match (self.rule.body.as_deref(), body_json) {
+            (Some("*"), Some(serde_json::Value::Object(map))) => Ok(map),
+            (Some("*"), Some(_)) => Err(invalid_argument("request body must be a JSON object")),
+            (Some(field), Some(value)) => {
+                let mut map = serde_json::Map::new();
+                map.insert(field.to_string(), value);
+                Ok(map)
+            }
+            _ => Ok(serde_json::Map::new()),
+        }
+    }
 
-        if response.status_code == 0 {
-            (StatusCode::OK, Json(response.body)).into_response()
-        } else {
-            let error = GrpcError {
-                code: response.status_code,
-                message: "Error".to_string(),
-                details: vec![response.body],
-            };
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+    fn bind_value(
+        &self,
+        message: &mut serde_json::Map<String, serde_json::Value>,
+        field_path: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        match &self.method.descriptor {
+            Some(descriptor) => json::bind_url_value(
+                descriptor.pool(),
+                &descriptor.input_type,
+                message,
+                field_path,
+                value,
+            )
+            .map_err(|e| e.to_string()),
+            None => {
+                bind_untyped(message, field_path, value);
+                Ok(())
+            }
         }
-    } else {
-        let error = GrpcError::not_found(&format!("Method {} not found", method.name));
-        (StatusCode::NOT_FOUND, Json(error)).into_response()
     }
 }
 
-/// gRPC health check handler
-async fn grpc_health_check() -> impl IntoResponse {
-    #[derive(Serialize)]
-    struct HealthResponse {
-        status: String,
+/// Without a descriptor URL values are bound as strings (repeated keys become arrays)
+fn bind_untyped(
+    message: &mut serde_json::Map<String, serde_json::Value>,
+    field_path: &str,
+    value: &str,
+) {
+    let (head, rest) = match field_path.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (field_path, None),
+    };
+    match rest {
+        Some(rest) => {
+            let entry = message
+                .entry(head.to_string())
+                .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+            if let serde_json::Value::Object(inner) = entry {
+                bind_untyped(inner, rest, value);
+            }
+        }
+        None => {
+            let value = serde_json::Value::String(value.to_string());
+            match message.get_mut(head) {
+                Some(serde_json::Value::Array(items)) => items.push(value),
+                Some(existing) => {
+                    let first = existing.take();
+                    *existing = serde_json::Value::Array(vec![first, value]);
+                }
+                None => {
+                    message.insert(head.to_string(), value);
+                }
+            }
+        }
+    }
+}
+
+fn warn_invalid_rule(method_path: &str, rule: &HttpRule, error: &str) {
+    eprintln!(
+        "Warning: skipping HTTP rule {} {} for {}: {}",
+        rule.method, rule.path, method_path, error
+    );
+}
+
+fn invalid_argument(message: &str) -> Response {
+    GrpcError::invalid_argument(message).into_response()
+}
+
+/// Buffer a request body, failing with `RESOURCE_EXHAUSTED` past `limit` bytes
+pub(crate) async fn read_limited_body(
+    body: axum::body::Body,
+    limit: usize,
+) -> Result<Bytes, GrpcError> {
+    let mut chunks = body.into_data_stream();
+    let mut buffer = BytesMut::new();
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk.map_err(|e| GrpcError::invalid_argument(&e.to_string()))?;
+        if buffer.len() + chunk.len() > limit {
+            return Err(GrpcError::resource_exhausted(&format!(
+                "request body exceeds the limit of {} bytes",
+                limit
+            )));
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+    Ok(buffer.freeze())
+}
+
+/// JSON response for a `read_limited_body` error; an oversized body is 413
+pub(crate) fn body_error_response(error: GrpcError) -> Response {
+    let too_large = error.code == GrpcCode::ResourceExhausted.as_i32();
+    let mut response = error.into_response();
+    if too_large {
+        *response.status_mut() = StatusCode::PAYLOAD_TOO_LARGE;
     }
+    response
+}
 
-    (
-        StatusCode::OK,
-        Json(HealthResponse {
-            status: "SERVING".to_string(),
-        }),
-    )
+/// Dispatch a transcoded request to the method handler
+async fn handle_grpc_request(
+    service: &GrpcService,
+    method: &GrpcMethod,
+    request: GrpcRequest,
+    response_body: Option<&str>,
+) -> Response {
+    match call_unary(service, method, request).await {
+        Ok(body) => {
+            let body = match response_body {
+                Some(field) => select_response_field(body, field),
+                None => body,
+            };
+            (StatusCode::OK, Json(body)).into_response()
+        }
+        Err(error) => error.into_response(),
+    }
 }
 
-/// gRPC server reflection handler
-async fn grpc_reflection() -> impl IntoResponse {
-    #[derive(Serialize)]
-    struct FileDescriptorResponse {
-        #[serde(rename = "fileDescriptorProto")]
-        proto: String,
+/// Run the unary handler of a method
+async fn call_unary(
+    service: &GrpcService,
+    method: &GrpcMethod,
+    request: GrpcRequest,
+) -> Result<serde_json::Value, GrpcError> {
+    let Some(handler) = service.get_handler(&method.name) else {
+        return Err(GrpcError::not_found(&format!(
+            "Method {} not found",
+            method.name
+        )));
+    };
+
+    let response = handler(request).await;
+    if response.status_code == 0 {
+        return Ok(response.body);
     }
 
-    // Return empty list of services (can be extended for full reflection)
-    (
-        StatusCode::OK,
-        Json(vec![FileDescriptorResponse {
-            proto: "".to_string(),
-        }]),
+    // Bodies built from `GrpcError` keep their message and details; anything else
+    // becomes the single detail of a generic error
+    Err(
+        match serde_json::from_value::<GrpcErrorBody>(response.body.clone()) {
+            Ok(body) => GrpcError {
+                code: response.status_code,
+                message: body.message,
+                details: body.details,
+            },
+            Err(_) => GrpcError {
+                code: response.status_code,
+                message: GrpcCode::from_i32(response.status_code)
+                    .as_str()
+                    .to_string(),
+                details: vec![response.body],
+            },
+        },
     )
 }
 
+#[derive(Deserialize)]
+struct GrpcErrorBody {
+    #[allow(dead_code)]
+    code: i32,
+    message: String,
+    #[serde(default)]
+    details: Vec<serde_json::Value>,
+}
+
+/// `response_body` may name the proto field or its JSON name
+fn select_response_field(mut body: serde_json::Value, field: &str) -> serde_json::Value {
+    let Some(object) = body.as_object_mut() else {
+        return body;
+    };
+    object
+        .remove(&descriptor::to_lower_camel(field))
+        .or_else(|| object.remove(field))
+        .unwrap_or(serde_json::Value::Null)
+}
+
 /// Internal state for gRPC services
 #[derive(Clone)]
 pub struct GrpcState {
@@ -372,18 +1886,62 @@ impl GrpcState {
 /// Extension trait to add gRPC support to UltraApiApp
 pub trait GrpcExt {
     /// Add gRPC transcoding support to the application
-    fn grpc(self, _transcoder: GrpcTranscoder) -> Self;
+    ///
+    /// Can be called multiple times; services are merged by name.
+    fn grpc(self, transcoder: GrpcTranscoder) -> Self;
+
+    /// Serve a native gRPC (tonic) service on the same port as the REST routes
+    ///
+    /// Requests with `content-type: application/grpc` for the service are dispatched
+    /// to it; they share the app's dependencies, auth middleware and lifespan.
+    fn grpc_service(self, service: impl Into<NativeGrpcService>) -> Self;
+
+    /// Serve the files of a descriptor set through server reflection
+    ///
+    /// Use it for native services (e.g. the `FILE_DESCRIPTOR_SET` generated by
+    /// `tonic-build`); services built with `GrpcService::from_descriptor` are
+    /// included automatically.
+    fn grpc_descriptors(self, pool: DescriptorPool) -> Self;
 }
 
 impl GrpcExt for crate::UltraApiApp {
-    #[allow(unused_mut)]
-    fn grpc(self, _transcoder: GrpcTranscoder) -> Self {
-        // Note: The actual merging happens when user calls into_router()
-        // and merges the gRPC router with the main router
+    fn grpc(mut self, transcoder: GrpcTranscoder) -> Self {
+        self.grpc_transcoder = Some(match self.grpc_transcoder.take() {
+            Some(existing) => existing.merge(transcoder),
+            None => transcoder,
+        });
+        self
+    }
+
+    fn grpc_service(mut self, service: impl Into<NativeGrpcService>) -> Self {
+        self.native_grpc_services.push(service.into());
+        self
+    }
+
+    fn grpc_descriptors(mut self, pool: DescriptorPool) -> Self {
+        self.grpc_descriptor_pools.push(pool);
         self
     }
 }
 
+/// `grpc.health.v1.Health` and server reflection (v1 and v1alpha), mounted with
+/// the application's native services
+pub(crate) fn builtin_services(
+    health: &HealthReporter,
+    index: &Arc<ReflectionIndex>,
+) -> Vec<NativeGrpcService> {
+    use reflection::ReflectionServer;
+
+    vec![
+        NativeGrpcService::new(health::HealthServer::new(health.clone())),
+        NativeGrpcService::named(ReflectionServer::V1, ReflectionServer::new(index.clone())),
+        NativeGrpcService::named(
+            ReflectionServer::V1ALPHA,
+            ReflectionServer::new(index.clone()),
+        ),
+    ]
+}
+
 /// Inventory for registered gRPC services
 #[allow(unused_doc_comments)]
 inventory::collect!(&'static GrpcServiceInfo);
@@ -435,6 +1993,7 @@ pub struct GrpcMethodBuilder {
     request_type: String,
     response_type: String,
     streaming: bool,
+    client_streaming: bool,
 }
 
 impl GrpcMethodBuilder {
@@ -445,6 +2004,7 @@ impl GrpcMethodBuilder {
             request_type: format!("{}Request", name),
             response_type: format!("{}Response", name),
             streaming: false,
+            client_streaming: false,
         }
     }
 
@@ -468,6 +2028,11 @@ impl GrpcMethodBuilder {
         self
     }
 
+    pub fn client_streaming(mut self) -> Self {
+        self.client_streaming = true;
+        self
+    }
+
     pub fn build(self) -> GrpcMethod {
         GrpcMethod {
             name: self.name,
@@ -475,6 +2040,9 @@ impl GrpcMethodBuilder {
             request_type: self.request_type,
             response_type: self.response_type,
             streaming: self.streaming,
+            client_streaming: self.client_streaming,
+            http_rules: Vec::new(),
+            descriptor: None,
         }
     }
 }
@@ -490,6 +2058,7 @@ pub struct ServiceBuilder {
     name: String,
     package: Option<String>,
     methods: Vec<GrpcMethod>,
+    security: Vec<String>,
 }
 
 impl ServiceBuilder {
@@ -498,6 +2067,7 @@ impl ServiceBuilder {
             name: name.to_string(),
             package: None,
             methods: Vec::new(),
+            security: Vec::new(),
         }
     }
 
@@ -516,6 +2086,22 @@ impl ServiceBuilder {
         self
     }
 
+    pub fn method_server_streaming(mut self, name: &str, path: &str) -> Self {
+        self.methods.push(GrpcMethod::server_streaming(name, path));
+        self
+    }
+
+    pub fn method_client_streaming(mut self, name: &str, path: &str) -> Self {
+        self.methods.push(GrpcMethod::client_streaming(name, path));
+        self
+    }
+
+    /// Require a security scheme (e.g. "bearer") for every method of this service
+    pub fn security(mut self, scheme: &str) -> Self {
+        self.security.push(scheme.to_string());
+        self
+    }
+
     /// Add a handler for a method (returns GrpcService, not ServiceBuilder)
     pub fn with_handler(self, method_name: &str, handler: GrpcHandler) -> GrpcService {
         let full_path = if let Some(ref pkg) = self.package {
@@ -529,7 +2115,10 @@ impl ServiceBuilder {
             full_path,
             package: self.package,
             methods: self.methods,
+            security: self.security,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
             handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            streaming_handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
         };
 
         if let Ok(mut handlers) = service.handlers.write() {
@@ -539,6 +2128,15 @@ impl ServiceBuilder {
         service
     }
 
+    /// Add a streaming handler for a method (returns GrpcService, not ServiceBuilder)
+    pub fn with_streaming_handler(
+        self,
+        method_name: &str,
+        handler: GrpcStreamingHandler,
+    ) -> GrpcService {
+        self.build().with_streaming_handler(method_name, handler)
+    }
+
     pub fn build(self) -> GrpcService {
         let full_path = if let Some(ref pkg) = self.package {
             format!("/{}.{}", pkg, self.name)
@@ -551,7 +2149,10 @@ impl ServiceBuilder {
             full_path,
             package: self.package,
             methods: self.methods,
+            security: self.security,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
             handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            streaming_handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
         }
     }
 }
@@ -0,0 +1,606 @@
+//! Protobuf descriptors for descriptor-driven transcoding
+//!
+//! `protoc --include_imports --descriptor_set_out=api.bin` で生成した `FileDescriptorSet` を
+//! 読み込み、メッセージ・enum・サービス定義と `google.api.http` アノテーションを取り出します。
+//!
+//! ```ignore
+//! let pool = DescriptorPool::decode(include_bytes!("../api.bin"))?;
+//! let users = GrpcService::from_descriptor(&pool, "user.v1.UserService")?;
+//! ```
+
+use prost::Message as _;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{DescriptorProto, FileDescriptorProto, FileDescriptorSet};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::http_rule::HttpRule;
+use super::json::{self, JsonMappingError};
+use super::wire::{self, WireValue};
+
+/// Field number of the `google.api.http` extension on `MethodOptions`
+const HTTP_RULE_EXTENSION: u32 = 72295728;
+
+/// Error raised while loading descriptors
+#[derive(Debug, Clone)]
+pub struct DescriptorError {
+    pub message: String,
+}
+
+impl DescriptorError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for DescriptorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid descriptor set: {}", self.message)
+    }
+}
+
+impl std::error::Error for DescriptorError {}
+
+/// Protobuf field type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Double,
+    Float,
+    Int64,
+    Uint64,
+    Int32,
+    Fixed64,
+    Fixed32,
+    Bool,
+    String,
+    Message,
+    Bytes,
+    Uint32,
+    Enum,
+    Sfixed32,
+    Sfixed64,
+    Sint32,
+    Sint64,
+}
+
+impl FieldKind {
+    fn from_proto(ty: Type) -> Option<Self> {
+        Some(match ty {
+            Type::Double => Self::Double,
+            Type::Float => Self::Float,
+            Type::Int64 => Self::Int64,
+            Type::Uint64 => Self::Uint64,
+            Type::Int32 => Self::Int32,
+            Type::Fixed64 => Self::Fixed64,
+            Type::Fixed32 => Self::Fixed32,
+            Type::Bool => Self::Bool,
+            Type::String => Self::String,
+            Type::Message => Self::Message,
+            Type::Bytes => Self::Bytes,
+            Type::Uint32 => Self::Uint32,
+            Type::Enum => Self::Enum,
+            Type::Sfixed32 => Self::Sfixed32,
+            Type::Sfixed64 => Self::Sfixed64,
+            Type::Sint32 => Self::Sint32,
+            Type::Sint64 => Self::Sint64,
+            // proto2 groups are not supported by proto3 JSON
+            Type::Group => return None,
+        })
+    }
+
+    /// Scalars that are packed in proto3 repeated fields
+    pub(crate) fn is_packable(self) -> bool {
+        !matches!(self, Self::String | Self::Bytes | Self::Message)
+    }
+}
+
+/// A message field
+#[derive(Debug, Clone)]
+pub struct FieldDescriptor {
+    /// Field name as written in the .proto file (`user_id`)
+    pub name: String,
+    /// proto3 JSON name (`userId`)
+    pub json_name: String,
+    pub number: u32,
+    pub kind: FieldKind,
+    pub repeated: bool,
+    /// Fully-qualified message/enum type name (without leading dot)
+    pub type_name: Option<String>,
+}
+
+/// A message type
+#[derive(Debug, Clone)]
+pub struct MessageDescriptor {
+    /// Fully-qualified name (`user.v1.User`)
+    pub full_name: String,
+    pub fields: Vec<FieldDescriptor>,
+    /// Synthetic `map<K, V>` entry message
+    pub map_entry: bool,
+}
+
+impl MessageDescriptor {
+    /// Find a field by proto name or JSON name
+    pub fn field(&self, name: &str) -> Option<&FieldDescriptor> {
+        self.fields
+            .iter()
+            .find(|f| f.json_name == name || f.name == name)
+    }
+
+    pub fn field_by_number(&self, number: u32) -> Option<&FieldDescriptor> {
+        self.fields.iter().find(|f| f.number == number)
+    }
+}
+
+/// An enum type
+#[derive(Debug, Clone)]
+pub struct EnumDescriptor {
+    pub full_name: String,
+    pub values: Vec<(String, i32)>,
+}
+
+/// A service method
+#[derive(Debug, Clone)]
+pub struct MethodDescriptor {
+    pub name: String,
+    /// gRPC path (`/user.v1.UserService/GetUser`)
+    pub full_path: String,
+    pub input_type: String,
+    pub output_type: String,
+    pub client_streaming: bool,
+    pub server_streaming: bool,
+    /// `google.api.http` bindings (primary rule first, then additional bindings)
+    pub http_rules: Vec<HttpRule>,
+    pool: DescriptorPool,
+}
+
+impl MethodDescriptor {
+    /// The pool this method was loaded from
+    pub fn pool(&self) -> &DescriptorPool {
+        &self.pool
+    }
+
+    /// proto3 JSON request -> protobuf bytes
+    pub fn encode_request(&self, value: &Value) -> Result<Vec<u8>, JsonMappingError> {
+        self.pool.encode_json(&self.input_type, value)
+    }
+
+    /// protobuf request bytes -> proto3 JSON
+    pub fn decode_request(&self, bytes: &[u8]) -> Result<Value, JsonMappingError> {
+        self.pool.decode_to_json(&self.input_type, bytes)
+    }
+
+    /// proto3 JSON response -> protobuf bytes
+    pub fn encode_response(&self, value: &Value) -> Result<Vec<u8>, JsonMappingError> {
+        self.pool.encode_json(&self.output_type, value)
+    }
+
+    /// protobuf response bytes -> proto3 JSON
+    pub fn decode_response(&self, bytes: &[u8]) -> Result<Value, JsonMappingError> {
+        self.pool.decode_to_json(&self.output_type, bytes)
+    }
+}
+
+/// A service definition
+#[derive(Debug, Clone)]
+pub struct ServiceDescriptor {
+    /// Fully-qualified name (`user.v1.UserService`)
+    pub full_name: String,
+    pub name: String,
+    pub package: Option<String>,
+    pub methods: Vec<MethodDescriptor>,
+}
+
+/// A file of the descriptor set, kept serialized for server reflection
+#[derive(Debug, Clone)]
+pub(crate) struct DescriptorFile {
+    /// File name (`user/v1/user.proto`)
+    pub(crate) name: String,
+    /// Serialized `FileDescriptorProto`, including extension options
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) dependencies: Vec<String>,
+    /// Fully-qualified messages, enums, services and methods defined in the file
+    pub(crate) symbols: Vec<String>,
+}
+
+impl DescriptorFile {
+    pub(crate) fn new(bytes: Vec<u8>) -> Result<Self, DescriptorError> {
+        let file = FileDescriptorProto::decode(bytes.as_slice())
+            .map_err(|e| DescriptorError::new(e.to_string()))?;
+        let prefix = file.package().to_string();
+
+        let mut symbols = Vec::new();
+        for message in &file.message_type {
+            message_symbols(&prefix, message, &mut symbols);
+        }
+        for enum_type in &file.enum_type {
+            symbols.push(qualify(&prefix, enum_type.name()));
+        }
+        for service in &file.service {
+            let full_name = qualify(&prefix, service.name());
+            for method in &service.method {
+                symbols.push(qualify(&full_name, method.name()));
+            }
+            symbols.push(full_name);
+        }
+
+        Ok(Self {
+            name: file.name().to_string(),
+            bytes,
+            dependencies: file.dependency,
+            symbols,
+        })
+    }
+}
+
+fn message_symbols(prefix: &str, message: &DescriptorProto, symbols: &mut Vec<String>) {
+    let full_name = qualify(prefix, message.name());
+    for nested in &message.nested_type {
+        message_symbols(&full_name, nested, symbols);
+    }
+    for enum_type in &message.enum_type {
+        symbols.push(qualify(&full_name, enum_type.name()));
+    }
+    symbols.push(full_name);
+}
+
+#[derive(Debug, Default)]
+struct PoolInner {
+    messages: HashMap<String, MessageDescriptor>,
+    enums: HashMap<String, EnumDescriptor>,
+    services: Vec<(String, Option<String>, String)>,
+    methods: HashMap<String, Vec<RawMethod>>,
+    files: Vec<DescriptorFile>,
+}
+
+#[derive(Debug, Clone)]
+struct RawMethod {
+    name: String,
+    input_type: String,
+    output_type: String,
+    client_streaming: bool,
+    server_streaming: bool,
+    http_rules: Vec<HttpRule>,
+}
+
+/// Messages, enums and services loaded from a `FileDescriptorSet`
+#[derive(Clone, Default)]
+pub struct DescriptorPool {
+    inner: Arc<PoolInner>,
+}
+
+impl std::fmt::Debug for DescriptorPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DescriptorPool")
+            .field("messages", &self.inner.messages.len())
+            .field("services", &self.inner.services.len())
+            .finish()
+    }
+}
+
+impl DescriptorPool {
+    /// Decode a serialized `FileDescriptorSet`
+    pub fn decode(bytes: &[u8]) -> Result<Self, DescriptorError> {
+        let set =
+            FileDescriptorSet::decode(bytes).map_err(|e| DescriptorError::new(e.to_string()))?;
+        let http_rules = read_http_rules(bytes).map_err(DescriptorError::new)?;
+
+        let mut inner = PoolInner::default();
+        // prost_types drops extension options, so reflection serves the raw files
+        for (number, value) in wire::fields(bytes).map_err(DescriptorError::new)? {
+            if let (1, WireValue::Bytes(file)) = (number, value) {
+                inner.files.push(DescriptorFile::new(file.to_vec())?);
+            }
+        }
+        for file in &set.file {
+            let package = file.package.clone().filter(|p| !p.is_empty());
+            let prefix = package.clone().unwrap_or_default();
+
+            for message in &file.message_type {
+                add_message(&mut inner, &prefix, message)?;
+            }
+            for enum_type in &file.enum_type {
+                add_enum(&mut inner, &prefix, enum_type);
+            }
+
+            for service in &file.service {
+                let name = service.name().to_string();
+                let full_name = qualify(&prefix, &name);
+                let methods = service
+                    .method
+                    .iter()
+                    .map(|method| RawMethod {
+                        name: method.name().to_string(),
+                        input_type: strip_dot(method.input_type()),
+                        output_type: strip_dot(method.output_type()),
+                        client_streaming: method.client_streaming(),
+                        server_streaming: method.server_streaming(),
+                        http_rules: http_rules
+                            .get(&(full_name.clone(), method.name().to_string()))
+                            .cloned()
+                            .unwrap_or_default(),
+                    })
+                    .collect();
+                inner.methods.insert(full_name.clone(), methods);
+                inner.services.push((full_name, package.clone(), name));
+            }
+        }
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Look up a message type by fully-qualified name
+    pub fn message(&self, full_name: &str) -> Option<&MessageDescriptor> {
+        self.inner.messages.get(strip_leading_dot(full_name))
+    }
+
+    /// Look up an enum type by fully-qualified name
+    pub fn enum_type(&self, full_name: &str) -> Option<&EnumDescriptor> {
+        self.inner.enums.get(strip_leading_dot(full_name))
+    }
+
+    /// All message types
+    pub fn messages(&self) -> impl Iterator<Item = &MessageDescriptor> {
+        self.inner.messages.values()
+    }
+
+    /// Look up a service by fully-qualified name
+    pub fn service(&self, full_name: &str) -> Option<ServiceDescriptor> {
+        let (full_name, package, name) = self
+            .inner
+            .services
+            .iter()
+            .find(|(n, _, _)| n == strip_leading_dot(full_name))?;
+        let methods = self.inner.methods.get(full_name)?;
+        Some(ServiceDescriptor {
+            full_name: full_name.clone(),
+            name: name.clone(),
+            package: package.clone(),
+            methods: methods
+                .iter()
+                .map(|m| MethodDescriptor {
+                    name: m.name.clone(),
+                    full_path: format!("/{}/{}", full_name, m.name),
+                    input_type: m.input_type.clone(),
+                    output_type: m.output_type.clone(),
+                    client_streaming: m.client_streaming,
+                    server_streaming: m.server_streaming,
+                    http_rules: m.http_rules.clone(),
+                    pool: self.clone(),
+                })
+                .collect(),
+        })
+    }
+
+    /// All services
+    pub fn services(&self) -> Vec<ServiceDescriptor> {
+        self.inner
+            .services
+            .iter()
+            .filter_map(|(name, _, _)| self.service(name))
+            .collect()
+    }
+
+    /// Files of the descriptor set (for server reflection)
+    pub(crate) fn files(&self) -> &[DescriptorFile] {
+        &self.inner.files
+    }
+
+    /// Encode a proto3 JSON value as the given message type
+    pub fn encode_json(&self, message: &str, value: &Value) -> Result<Vec<u8>, JsonMappingError> {
+        json::encode_message(self, strip_leading_dot(message), value)
+    }
+
+    /// Decode protobuf bytes of the given message type into proto3 JSON
+    pub fn decode_to_json(&self, message: &str, bytes: &[u8]) -> Result<Value, JsonMappingError> {
+        json::decode_message(self, strip_leading_dot(message), bytes)
+    }
+}
+
+fn strip_leading_dot(name: &str) -> &str {
+    name.strip_prefix('.').unwrap_or(name)
+}
+
+fn strip_dot(name: &str) -> String {
+    strip_leading_dot(name).to_string()
+}
+
+fn qualify(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// `user_id` -> `userId` (used when `json_name` is not populated)
+pub(crate) fn to_lower_camel(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn add_message(
+    inner: &mut PoolInner,
+    prefix: &str,
+    message: &DescriptorProto,
+) -> Result<(), DescriptorError> {
+    let full_name = qualify(prefix, message.name());
+
+    let mut fields = Vec::new();
+    for field in &message.field {
+        let Some(kind) = FieldKind::from_proto(field.r#type()) else {
+            return Err(DescriptorError::new(format!(
+                "{}.{}: groups are not supported",
+                full_name,
+                field.name()
+            )));
+        };
+        let json_name = match field.json_name.as_deref() {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => to_lower_camel(field.name()),
+        };
+        fields.push(FieldDescriptor {
+            name: field.name().to_string(),
+            json_name,
+            number: u32::try_from(field.number()).unwrap_or_default(),
+            kind,
+            repeated: field.label() == Label::Repeated,
+            type_name: field.type_name.as_deref().map(strip_dot),
+        });
+    }
+
+    for nested in &message.nested_type {
+        add_message(inner, &full_name, nested)?;
+    }
+    for enum_type in &message.enum_type {
+        add_enum(inner, &full_name, enum_type);
+    }
+
+    let map_entry = message
+        .options
+        .as_ref()
+        .and_then(|o| o.map_entry)
+        .unwrap_or(false);
+    inner.messages.insert(
+        full_name.clone(),
+        MessageDescriptor {
+            full_name,
+            fields,
+            map_entry,
+        },
+    );
+    Ok(())
+}
+
+fn add_enum(inner: &mut PoolInner, prefix: &str, enum_type: &prost_types::EnumDescriptorProto) {
+    let full_name = qualify(prefix, enum_type.name());
+    let values = enum_type
+        .value
+        .iter()
+        .map(|v| (v.name().to_string(), v.number()))
+        .collect();
+    inner
+        .enums
+        .insert(full_name.clone(), EnumDescriptor { full_name, values });
+}
+
+/// Walk the raw descriptor set and collect `google.api.http` rules per (service, method).
+///
+/// prost_types drops extension fields, so `MethodOptions` are read from the wire format.
+fn read_http_rules(bytes: &[u8]) -> Result<HashMap<(String, String), Vec<HttpRule>>, String> {
+    let mut rules = HashMap::new();
+    for (number, value) in wire::fields(bytes)? {
+        let (1, WireValue::Bytes(file)) = (number, value) else {
+            continue;
+        };
+        let file_fields = wire::fields(file)?;
+        let package = file_fields
+            .iter()
+            .find_map(|(n, v)| match (n, v) {
+                (2, WireValue::Bytes(b)) => Some(String::from_utf8_lossy(b).into_owned()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        for (number, value) in file_fields {
+            let (6, WireValue::Bytes(service)) = (number, value) else {
+                continue;
+            };
+            let service_fields = wire::fields(service)?;
+            let service_name = string_field(&service_fields, 1);
+            let full_name = qualify(&package, &service_name);
+
+            for (number, value) in service_fields {
+                let (2, WireValue::Bytes(method)) = (number, value) else {
+                    continue;
+                };
+                let method_fields = wire::fields(method)?;
+                let method_name = string_field(&method_fields, 1);
+                let mut method_rules = Vec::new();
+                for (number, value) in &method_fields {
+                    if let (4, WireValue::Bytes(options)) = (number, value) {
+                        for (number, value) in wire::fields(options)? {
+                            if let (HTTP_RULE_EXTENSION, WireValue::Bytes(rule)) = (number, value) {
+                                parse_http_rule(rule, &mut method_rules)?;
+                            }
+                        }
+                    }
+                }
+                if !method_rules.is_empty() {
+                    rules.insert((full_name.clone(), method_name), method_rules);
+                }
+            }
+        }
+    }
+    Ok(rules)
+}
+
+fn string_field(fields: &[(u32, WireValue<'_>)], number: u32) -> String {
+    fields
+        .iter()
+        .rev()
+        .find_map(|(n, v)| match v {
+            WireValue::Bytes(b) if *n == number => Some(String::from_utf8_lossy(b).into_owned()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Parse a `google.api.HttpRule` (and its additional bindings, flattened)
+fn parse_http_rule(bytes: &[u8], out: &mut Vec<HttpRule>) -> Result<(), String> {
+    let fields = wire::fields(bytes)?;
+    let mut method = None;
+    let mut path = String::new();
+    let mut additional = Vec::new();
+
+    for (number, value) in &fields {
+        let WireValue::Bytes(b) = value else {
+            continue;
+        };
+        let text = String::from_utf8_lossy(b).into_owned();
+        match number {
+            2 => (method, path) = (Some("GET".to_string()), text),
+            3 => (method, path) = (Some("PUT".to_string()), text),
+            4 => (method, path) = (Some("POST".to_string()), text),
+            5 => (method, path) = (Some("DELETE".to_string()), text),
+            6 => (method, path) = (Some("PATCH".to_string()), text),
+            8 => {
+                let custom = wire::fields(b)?;
+                method = Some(string_field(&custom, 1).to_uppercase());
+                path = string_field(&custom, 2);
+            }
+            11 => additional.push(*b),
+            _ => {}
+        }
+    }
+
+    if let Some(method) = method {
+        let body = Some(string_field(&fields, 7)).filter(|b| !b.is_empty());
+        let response_body = Some(string_field(&fields, 12)).filter(|b| !b.is_empty());
+        out.push(HttpRule {
+            method,
+            path,
+            body,
+            response_body,
+        });
+    }
+    for rule in additional {
+        parse_http_rule(rule, out)?;
+    }
+    Ok(())
+}
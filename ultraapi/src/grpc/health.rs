@@ -0,0 +1,415 @@
+//! gRPC health checking (`grpc.health.v1.Health`)
+//!
+//! サービスごとの稼働状態を [`HealthReporter`] で管理し、`Check` / `Watch` で公開します。
+//! `UltraApiApp` に gRPC サービスがあるとレポーターが依存性として登録され、起動フックの完了までは
+//! `NOT_SERVING`、完了後に `SERVING`、シャットダウン (drain) 開始時に再び `NOT_SERVING` になります。
+//! ハンドラーや lifespan フックからは `Dep<HealthReporter>` / `state.get::<HealthReporter>()` で
+//! 状態を更新できます。
+//!
+//! ```ignore
+//! let app = UltraApiApp::new()
+//!     .grpc_service(GreeterServer::new(MyGreeter))
+//!     .lifecycle(|lifecycle| {
+//!         lifecycle.on_startup(|state| {
+//!             let health = state.get::<HealthReporter>().unwrap();
+//!             Box::pin(async move {
+//!                 if !warm_cache().await {
+//!                     health.set_not_serving("helloworld.Greeter");
+//!                 }
+//!             })
+//!         })
+//!     });
+//! ```
+
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use bytes::Bytes;
+use futures_util::Stream;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::watch;
+use tonic::codec::ProstCodec;
+use tonic::server::NamedService;
+use tower::BoxError;
+
+use super::descriptor::DescriptorFile;
+use super::GrpcError;
+use crate::AppState;
+
+/// Serving status of a service (`HealthCheckResponse.ServingStatus`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServingStatus {
+    Unknown,
+    Serving,
+    NotServing,
+    /// Only sent by `Watch` for services that are not registered
+    ServiceUnknown,
+}
+
+impl ServingStatus {
+    pub fn as_i32(self) -> i32 {
+        match self {
+            Self::Unknown => 0,
+            Self::Serving => 1,
+            Self::NotServing => 2,
+            Self::ServiceUnknown => 3,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unknown => "UNKNOWN",
+            Self::Serving => "SERVING",
+            Self::NotServing => "NOT_SERVING",
+            Self::ServiceUnknown => "SERVICE_UNKNOWN",
+        }
+    }
+}
+
+impl std::fmt::Display for ServingStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+struct Entry {
+    sender: watch::Sender<Option<ServingStatus>>,
+    /// Set by the framework (startup / drain) rather than by application code
+    managed: bool,
+}
+
+/// Per-service health status reported by `grpc.health.v1.Health`
+///
+/// The empty service name (`""`) is the overall server status. Clones share the
+/// same statuses.
+#[derive(Clone)]
+pub struct HealthReporter {
+    entries: Arc<parking_lot::Mutex<HashMap<String, Entry>>>,
+}
+
+impl HealthReporter {
+    /// A reporter where the server (`""`) is `SERVING`
+    pub fn new() -> Self {
+        let reporter = Self {
+            entries: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+        };
+        reporter.set_serving("");
+        reporter
+    }
+
+    /// Mark a service as `SERVING`
+    pub fn set_serving(&self, service: &str) {
+        self.set_status(service, ServingStatus::Serving);
+    }
+
+    /// Mark a service as `NOT_SERVING`
+    pub fn set_not_serving(&self, service: &str) {
+        self.set_status(service, ServingStatus::NotServing);
+    }
+
+    /// Set the status of a service; `Watch` streams are notified of changes
+    pub fn set_status(&self, service: &str, status: ServingStatus) {
+        self.update(service, status, false);
+    }
+
+    /// Forget a service: `Check` answers NOT_FOUND and watchers see `SERVICE_UNKNOWN`
+    pub fn clear_status(&self, service: &str) {
+        if let Some(entry) = self.entries.lock().remove(service) {
+            entry.sender.send_replace(None);
+        }
+    }
+
+    /// Current status of a service, `None` when it is not registered
+    pub fn status(&self, service: &str) -> Option<ServingStatus> {
+        let entries = self.entries.lock();
+        entries
+            .get(service)
+            .and_then(|entry| *entry.sender.borrow())
+    }
+
+    fn update(&self, service: &str, status: ServingStatus, managed: bool) {
+        let mut entries = self.entries.lock();
+        let entry = entries.entry(service.to_string()).or_insert_with(|| Entry {
+            sender: watch::Sender::new(None),
+            managed,
+        });
+        entry.managed = managed;
+        entry
+            .sender
+            .send_if_modified(|current| current.replace(status) != Some(status));
+    }
+
+    /// Statuses of the server and its services while startup hooks run
+    ///
+    /// Services whose status was already set by the application keep it.
+    pub(crate) fn starting<'a>(&self, services: impl IntoIterator<Item = &'a str>) {
+        self.update("", ServingStatus::NotServing, true);
+        for service in services {
+            if self.status(service).is_none() {
+                self.update(service, ServingStatus::NotServing, true);
+            }
+        }
+    }
+
+    /// Startup finished: everything not set by the application becomes `SERVING`
+    pub(crate) fn startup_complete(&self) {
+        let entries = self.entries.lock();
+        for entry in entries.values().filter(|entry| entry.managed) {
+            entry.sender.send_if_modified(|current| {
+                current.replace(ServingStatus::Serving) != Some(ServingStatus::Serving)
+            });
+        }
+    }
+
+    /// Shutdown started: every service stops serving
+    pub(crate) fn drain(&self) {
+        let entries = self.entries.lock();
+        for entry in entries.values() {
+            entry.sender.send_if_modified(|current| {
+                current.replace(ServingStatus::NotServing) != Some(ServingStatus::NotServing)
+            });
+        }
+    }
+
+    fn watch(&self, service: &str) -> watch::Receiver<Option<ServingStatus>> {
+        let mut entries = self.entries.lock();
+        match entries.get(service) {
+            Some(entry) => entry.sender.subscribe(),
+            // Watchers of unknown services wait for it to be registered
+            None => entries
+                .entry(service.to_string())
+                .or_insert_with(|| Entry {
+                    sender: watch::Sender::new(None),
+                    managed: false,
+                })
+                .sender
+                .subscribe(),
+        }
+    }
+}
+
+impl Default for HealthReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for HealthReporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries = self.entries.lock();
+        let statuses: HashMap<&str, Option<ServingStatus>> = entries
+            .iter()
+            .map(|(name, entry)| (name.as_str(), *entry.sender.borrow()))
+            .collect();
+        f.debug_struct("HealthReporter")
+            .field("statuses", &statuses)
+            .finish()
+    }
+}
+
+/// `grpc/health/v1/health.proto`, so reflection clients can describe the service
+pub(crate) fn descriptor_file() -> DescriptorFile {
+    use prost::Message as _;
+    use prost_types::field_descriptor_proto::{Label, Type};
+    use prost_types::{
+        DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+        FileDescriptorProto, MethodDescriptorProto, ServiceDescriptorProto,
+    };
+
+    let field = |name: &str, ty: Type, type_name: Option<&str>| FieldDescriptorProto {
+        name: Some(name.to_string()),
+        number: Some(1),
+        label: Some(Label::Optional as i32),
+        r#type: Some(ty as i32),
+        type_name: type_name.map(str::to_string),
+        json_name: Some(name.to_string()),
+        ..Default::default()
+    };
+    let method = |name: &str, server_streaming: bool| MethodDescriptorProto {
+        name: Some(name.to_string()),
+        input_type: Some(".grpc.health.v1.HealthCheckRequest".to_string()),
+        output_type: Some(".grpc.health.v1.HealthCheckResponse".to_string()),
+        server_streaming: Some(server_streaming),
+        ..Default::default()
+    };
+    let statuses = [
+        ServingStatus::Unknown,
+        ServingStatus::Serving,
+        ServingStatus::NotServing,
+        ServingStatus::ServiceUnknown,
+    ];
+
+    let file = FileDescriptorProto {
+        name: Some("grpc/health/v1/health.proto".to_string()),
+        package: Some("grpc.health.v1".to_string()),
+        syntax: Some("proto3".to_string()),
+        message_type: vec![
+            DescriptorProto {
+                name: Some("HealthCheckRequest".to_string()),
+                field: vec![field("service", Type::String, None)],
+                ..Default::default()
+            },
+            DescriptorProto {
+                name: Some("HealthCheckResponse".to_string()),
+                field: vec![field(
+                    "status",
+                    Type::Enum,
+                    Some(".grpc.health.v1.HealthCheckResponse.ServingStatus"),
+                )],
+                enum_type: vec![EnumDescriptorProto {
+                    name: Some("ServingStatus".to_string()),
+                    value: statuses
+                        .iter()
+                        .map(|status| EnumValueDescriptorProto {
+                            name: Some(status.as_str().to_string()),
+                            number: Some(status.as_i32()),
+                            ..Default::default()
+                        })
+                        .collect(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ],
+        service: vec![ServiceDescriptorProto {
+            name: Some("Health".to_string()),
+            method: vec![method("Check", false), method("Watch", true)],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    DescriptorFile::new(file.encode_to_vec()).expect("health.proto descriptor is valid")
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct HealthCheckRequest {
+    #[prost(string, tag = "1")]
+    service: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct HealthCheckResponse {
+    #[prost(int32, tag = "1")]
+    status: i32,
+}
+
+impl HealthCheckResponse {
+    fn new(status: ServingStatus) -> Self {
+        Self {
+            status: status.as_i32(),
+        }
+    }
+}
+
+/// Native `grpc.health.v1.Health` service backed by a [`HealthReporter`]
+#[derive(Clone)]
+pub(crate) struct HealthServer {
+    reporter: HealthReporter,
+}
+
+impl HealthServer {
+    pub(crate) fn new(reporter: HealthReporter) -> Self {
+        Self { reporter }
+    }
+}
+
+impl NamedService for HealthServer {
+    const NAME: &'static str = "grpc.health.v1.Health";
+}
+
+impl<B> tower::Service<axum::http::Request<B>> for HealthServer
+where
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError> + Send + 'static,
+{
+    type Response = axum::http::Response<tonic::body::BoxBody>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: axum::http::Request<B>) -> Self::Future {
+        let reporter = self.reporter.clone();
+        Box::pin(async move {
+            let codec = ProstCodec::<HealthCheckResponse, HealthCheckRequest>::default();
+            let mut grpc = tonic::server::Grpc::new(codec);
+            let response = match request.uri().path() {
+                "/grpc.health.v1.Health/Check" => {
+                    let check =
+                        tower::service_fn(move |request: tonic::Request<HealthCheckRequest>| {
+                            let status = reporter.status(&request.get_ref().service);
+                            async move {
+                                match status {
+                                    Some(status) => {
+                                        Ok(tonic::Response::new(HealthCheckResponse::new(status)))
+                                    }
+                                    None => Err(tonic::Status::not_found("unknown service")),
+                                }
+                            }
+                        });
+                    grpc.unary(check, request).await
+                }
+                "/grpc.health.v1.Health/Watch" => {
+                    let watch =
+                        tower::service_fn(move |request: tonic::Request<HealthCheckRequest>| {
+                            let statuses =
+                                watch_statuses(reporter.watch(&request.get_ref().service));
+                            async move { Ok(tonic::Response::new(statuses)) }
+                        });
+                    grpc.server_streaming(watch, request).await
+                }
+                _ => tonic::Status::unimplemented("").into_http(),
+            };
+            Ok(response)
+        })
+    }
+}
+
+/// The current status, then every change (`SERVICE_UNKNOWN` while unregistered)
+fn watch_statuses(
+    receiver: watch::Receiver<Option<ServingStatus>>,
+) -> Pin<Box<dyn Stream<Item = Result<HealthCheckResponse, tonic::Status>> + Send>> {
+    Box::pin(futures_util::stream::unfold(
+        (receiver, true),
+        |(mut receiver, first)| async move {
+            if !first {
+                receiver.changed().await.ok()?;
+            }
+            let status = receiver
+                .borrow_and_update()
+                .unwrap_or(ServingStatus::ServiceUnknown);
+            Some((Ok(HealthCheckResponse::new(status)), (receiver, false)))
+        },
+    ))
+}
+
+#[derive(serde::Deserialize)]
+pub(crate) struct HealthQuery {
+    #[serde(default)]
+    service: String,
+}
+
+/// `GET /grpc.health.v1.Health/Check?service=...` for HTTP/JSON clients
+pub(crate) async fn health_check_json(
+    State(state): State<AppState>,
+    Query(query): Query<HealthQuery>,
+) -> Response {
+    let status = match state.get::<HealthReporter>() {
+        Some(reporter) => reporter.status(&query.service),
+        // Standalone transcoder routers have no reporter
+        None => query.service.is_empty().then_some(ServingStatus::Serving),
+    };
+    match status {
+        Some(status) => Json(serde_json::json!({ "status": status.as_str() })).into_response(),
+        None => GrpcError::not_found("unknown service").into_response(),
+    }
+}
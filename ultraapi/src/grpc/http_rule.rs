@@ -0,0 +1,284 @@
+//! `google.api.http` rules and path templates
+//!
+//! テンプレート (`/v1/{name=shelves/*/books/*}:publish`) を axum のパスに変換し、
+//! マッチしたセグメントからフィールド値を復元します。
+
+use axum::http::Method;
+use axum::routing::MethodFilter;
+use std::collections::HashMap;
+
+/// An HTTP binding for a gRPC method (`google.api.HttpRule`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRule {
+    /// HTTP method (`GET`, `POST`, ...)
+    pub method: String,
+    /// Path template (`/v1/users/{id}`)
+    pub path: String,
+    /// Request field bound to the body: `*` for the whole message, `None` for no body
+    pub body: Option<String>,
+    /// Response field returned as the body instead of the whole message
+    pub response_body: Option<String>,
+}
+
+impl HttpRule {
+    fn new(method: &str, path: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: None,
+            response_body: None,
+        }
+    }
+
+    pub fn get(path: &str) -> Self {
+        Self::new("GET", path)
+    }
+
+    pub fn post(path: &str) -> Self {
+        Self::new("POST", path)
+    }
+
+    pub fn put(path: &str) -> Self {
+        Self::new("PUT", path)
+    }
+
+    pub fn patch(path: &str) -> Self {
+        Self::new("PATCH", path)
+    }
+
+    pub fn delete(path: &str) -> Self {
+        Self::new("DELETE", path)
+    }
+
+    /// Bind the request body to a field (`*` = whole message)
+    pub fn body(mut self, field: &str) -> Self {
+        self.body = Some(field.to_string());
+        self
+    }
+
+    /// Return only this response field as the HTTP body
+    pub fn response_body(mut self, field: &str) -> Self {
+        self.response_body = Some(field.to_string());
+        self
+    }
+
+    /// Check that the method can be routed and the path template parses
+    pub fn validate(&self) -> Result<(), String> {
+        self.compile().map(|_| ())
+    }
+
+    /// axum method filter and parsed path template of this rule
+    pub(crate) fn compile(&self) -> Result<(MethodFilter, PathTemplate), String> {
+        let filter = self
+            .method
+            .parse::<Method>()
+            .ok()
+            .and_then(|method| MethodFilter::try_from(method).ok())
+            .ok_or_else(|| format!("unsupported HTTP method {}", self.method))?;
+        Ok((filter, PathTemplate::parse(&self.path)?))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Literal(String),
+    /// Single segment capture (axum param index)
+    Param(usize),
+    /// Multi segment capture (`**`), always last
+    CatchAll(usize),
+}
+
+#[derive(Debug, Clone)]
+struct Variable {
+    /// Dotted request field path (`book.name`)
+    field_path: String,
+    segments: Vec<Segment>,
+    /// Position of the first segment within the template
+    start: usize,
+}
+
+/// A parsed path template
+#[derive(Debug, Clone)]
+pub(crate) struct PathTemplate {
+    segments: Vec<Segment>,
+    variables: Vec<Variable>,
+    verb: Option<String>,
+    params: usize,
+}
+
+impl PathTemplate {
+    pub(crate) fn parse(template: &str) -> Result<Self, String> {
+        let rest = template
+            .strip_prefix('/')
+            .ok_or_else(|| format!("path template must start with '/': {}", template))?;
+
+        // A trailing `:verb` follows the last segment (outside of braces)
+        let (rest, verb) = match rest.rfind(':') {
+            Some(pos) if !rest[pos..].contains('}') && !rest[pos..].contains('/') => {
+                (&rest[..pos], Some(rest[pos + 1..].to_string()))
+            }
+            _ => (rest, None),
+        };
+
+        let mut parsed = Self {
+            segments: Vec::new(),
+            variables: Vec::new(),
+            verb,
+            params: 0,
+        };
+
+        let mut chars = rest;
+        while !chars.is_empty() {
+            if let Some(inner) = chars.strip_prefix('{') {
+                let end = inner
+                    .find('}')
+                    .ok_or_else(|| format!("unclosed variable in {}", template))?;
+                let (field_path, pattern) = match inner[..end].split_once('=') {
+                    Some((field, pattern)) => (field.to_string(), pattern.to_string()),
+                    None => (inner[..end].to_string(), "*".to_string()),
+                };
+                let mut segments = Vec::new();
+                for part in pattern.split('/') {
+                    segments.push(parsed.segment(part));
+                }
+                parsed.variables.push(Variable {
+                    field_path,
+                    segments: segments.clone(),
+                    start: parsed.segments.len(),
+                });
+                parsed.segments.extend(segments);
+                chars = &inner[end + 1..];
+            } else {
+                let end = chars.find('/').unwrap_or(chars.len());
+                let part = &chars[..end];
+                if part.contains('{') {
+                    return Err(format!("variables must span whole segments: {}", template));
+                }
+                let segment = parsed.segment(part);
+                parsed.segments.push(segment);
+                chars = &chars[end..];
+            }
+            chars = chars.strip_prefix('/').unwrap_or(chars);
+        }
+
+        if parsed.segments[..parsed.segments.len().saturating_sub(1)]
+            .iter()
+            .any(|s| matches!(s, Segment::CatchAll(_)))
+        {
+            return Err(format!("'**' must be the last segment: {}", template));
+        }
+        Ok(parsed)
+    }
+
+    fn segment(&mut self, part: &str) -> Segment {
+        match part {
+            "*" => {
+                self.params += 1;
+                Segment::Param(self.params - 1)
+            }
+            "**" => {
+                self.params += 1;
+                Segment::CatchAll(self.params - 1)
+            }
+            literal => Segment::Literal(literal.to_string()),
+        }
+    }
+
+    fn param_name(index: usize) -> String {
+        format!("__p{}", index)
+    }
+
+    /// axum route path (`/v1/users/{__p0}`)
+    pub(crate) fn axum_path(&self) -> String {
+        let mut path = String::new();
+        for segment in &self.segments {
+            path.push('/');
+            match segment {
+                Segment::Literal(s) => path.push_str(s),
+                Segment::Param(i) => path.push_str(&format!("{{{}}}", Self::param_name(*i))),
+                Segment::CatchAll(i) => path.push_str(&format!("{{*{}}}", Self::param_name(*i))),
+            }
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        // A verb after a literal segment is matched literally; after a capture it
+        // is checked when binding (matchit has no dynamic suffixes).
+        if let (Some(verb), Some(Segment::Literal(_))) = (&self.verb, self.segments.last()) {
+            path.push(':');
+            path.push_str(verb);
+        }
+        path
+    }
+
+    /// OpenAPI path (`/v1/users/{id}`, multi-segment variables collapse to `{name}`)
+    pub(crate) fn openapi_path(&self) -> String {
+        let mut path = String::new();
+        let mut index = 0;
+        while index < self.segments.len() {
+            path.push('/');
+            if let Some(variable) = self.variables.iter().find(|v| v.start == index) {
+                path.push_str(&format!("{{{}}}", variable.field_path));
+                index += variable.segments.len();
+                continue;
+            }
+            match &self.segments[index] {
+                Segment::Literal(s) => path.push_str(s),
+                _ => path.push('*'),
+            }
+            index += 1;
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        if let Some(verb) = &self.verb {
+            path.push(':');
+            path.push_str(verb);
+        }
+        path
+    }
+
+    /// Custom verb (`publish` in `.../books/*}:publish`)
+    pub(crate) fn verb(&self) -> Option<&str> {
+        self.verb.as_deref()
+    }
+
+    /// Field paths bound from the URL
+    pub(crate) fn field_paths(&self) -> impl Iterator<Item = &str> {
+        self.variables.iter().map(|v| v.field_path.as_str())
+    }
+
+    /// Rebuild variable values from axum params. Returns `None` when a required
+    /// `:verb` suffix is missing.
+    pub(crate) fn bind(&self, params: &HashMap<String, String>) -> Option<Vec<(String, String)>> {
+        let mut params = params.clone();
+        if let (Some(verb), Some(Segment::Param(i) | Segment::CatchAll(i))) =
+            (&self.verb, self.segments.last())
+        {
+            let name = Self::param_name(*i);
+            let value = params.get(&name)?;
+            let stripped = value.strip_suffix(&format!(":{}", verb))?.to_string();
+            params.insert(name, stripped);
+        }
+
+        let values = self
+            .variables
+            .iter()
+            .map(|variable| {
+                let parts: Vec<String> = variable
+                    .segments
+                    .iter()
+                    .map(|segment| match segment {
+                        Segment::Literal(s) => s.clone(),
+                        Segment::Param(i) | Segment::CatchAll(i) => params
+                            .get(&Self::param_name(*i))
+                            .cloned()
+                            .unwrap_or_default(),
+                    })
+                    .collect();
+                (variable.field_path.clone(), parts.join("/"))
+            })
+            .collect();
+        Some(values)
+    }
+}
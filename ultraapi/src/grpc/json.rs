@@ -0,0 +1,1194 @@
+//! proto3 JSON mapping driven by descriptors
+//!
+//! JSON ⇔ protobuf バイナリをディスクリプタに従って変換します
+//! (<https://protobuf.dev/programming-guides/json/>)。
+//!
+//! - フィールド名は `json_name` (lowerCamelCase) と元の名前の両方を受け付け、出力は `json_name`
+//! - 64bit 整数は文字列、`bytes` は base64、enum は名前で出力
+//! - `Timestamp` / `Duration` / `FieldMask` / `Struct` / `Value` / `ListValue` / `Empty` /
+//!   ラッパー型は専用の JSON 表現を使用
+
+use base64::Engine as _;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+use super::descriptor::{DescriptorPool, FieldDescriptor, FieldKind, MessageDescriptor};
+use super::wire::{self, WireReader, WireValue};
+
+/// A JSON value that does not match the message definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonMappingError {
+    /// Dotted field path (`user.tags[1]`); empty for the root message
+    pub path: String,
+    pub message: String,
+}
+
+impl JsonMappingError {
+    fn new(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for JsonMappingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for JsonMappingError {}
+
+type Result<T> = std::result::Result<T, JsonMappingError>;
+
+/// Guard against self-referencing messages
+const MAX_DEPTH: usize = 64;
+
+fn child_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+// --- JSON -> protobuf ---
+
+pub(crate) fn encode_message(
+    pool: &DescriptorPool,
+    message: &str,
+    value: &Value,
+) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    Encoder { pool }.message(message, value, "", 0, &mut out)?;
+    Ok(out)
+}
+
+struct Encoder<'a> {
+    pool: &'a DescriptorPool,
+}
+
+impl Encoder<'_> {
+    fn message(
+        &self,
+        type_name: &str,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(JsonMappingError::new(path, "message nesting too deep"));
+        }
+        if let Some(result) = self.well_known(type_name, value, path, depth, out) {
+            return result;
+        }
+
+        let descriptor = self.pool.message(type_name).ok_or_else(|| {
+            JsonMappingError::new(path, format!("unknown message type {}", type_name))
+        })?;
+        let object = match value {
+            Value::Object(object) => object,
+            Value::Null => return Ok(()),
+            _ => return Err(JsonMappingError::new(path, "expected an object")),
+        };
+
+        for (key, field_value) in object {
+            let field = descriptor
+                .field(key)
+                .ok_or_else(|| JsonMappingError::new(path, format!("unknown field \"{}\"", key)))?;
+            let field_path = child_path(path, key);
+            if field_value.is_null() && field.type_name.as_deref() != Some("google.protobuf.Value")
+            {
+                continue;
+            }
+            self.field(field, field_value, &field_path, depth, out)?;
+        }
+        Ok(())
+    }
+
+    fn field(
+        &self,
+        field: &FieldDescriptor,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        if let Some(entry) = self.map_entry(field) {
+            let object = value
+                .as_object()
+                .ok_or_else(|| JsonMappingError::new(path, "expected an object"))?;
+            let key_field = entry.field_by_number(1);
+            let value_field = entry.field_by_number(2);
+            let (Some(key_field), Some(value_field)) = (key_field, value_field) else {
+                return Err(JsonMappingError::new(path, "invalid map entry"));
+            };
+            for (key, item) in object {
+                let item_path = child_path(path, key);
+                let mut buf = Vec::new();
+                self.single(
+                    key_field,
+                    &map_key_json(key_field.kind, key),
+                    &item_path,
+                    depth,
+                    &mut buf,
+                )?;
+                self.single(value_field, item, &item_path, depth, &mut buf)?;
+                wire::put_bytes(out, field.number, &buf);
+            }
+            return Ok(());
+        }
+
+        if field.repeated {
+            let items = value
+                .as_array()
+                .ok_or_else(|| JsonMappingError::new(path, "expected an array"))?;
+            if field.kind.is_packable() {
+                let mut packed = Vec::new();
+                for (index, item) in items.iter().enumerate() {
+                    let item_path = format!("{}[{}]", path, index);
+                    self.scalar(field, item, &item_path, &mut packed)?;
+                }
+                wire::put_bytes(out, field.number, &packed);
+            } else {
+                for (index, item) in items.iter().enumerate() {
+                    let item_path = format!("{}[{}]", path, index);
+                    self.single(field, item, &item_path, depth, out)?;
+                }
+            }
+            return Ok(());
+        }
+
+        self.single(field, value, path, depth, out)
+    }
+
+    fn map_entry(&self, field: &FieldDescriptor) -> Option<&MessageDescriptor> {
+        if field.kind != FieldKind::Message || !field.repeated {
+            return None;
+        }
+        self.pool
+            .message(field.type_name.as_deref()?)
+            .filter(|m| m.map_entry)
+    }
+
+    /// Encode one (non-repeated) value with its key
+    fn single(
+        &self,
+        field: &FieldDescriptor,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        match field.kind {
+            FieldKind::Message => {
+                let mut buf = Vec::new();
+                let type_name = field.type_name.as_deref().unwrap_or_default();
+                self.message(type_name, value, path, depth + 1, &mut buf)?;
+                wire::put_bytes(out, field.number, &buf);
+            }
+            FieldKind::String => {
+                let s = value
+                    .as_str()
+                    .ok_or_else(|| JsonMappingError::new(path, "expected a string"))?;
+                wire::put_bytes(out, field.number, s.as_bytes());
+            }
+            FieldKind::Bytes => {
+                let s = value
+                    .as_str()
+                    .ok_or_else(|| JsonMappingError::new(path, "expected a base64 string"))?;
+                wire::put_bytes(out, field.number, &decode_base64(s, path)?);
+            }
+            kind => {
+                let wire_type = match kind {
+                    FieldKind::Double | FieldKind::Fixed64 | FieldKind::Sfixed64 => 1,
+                    FieldKind::Float | FieldKind::Fixed32 | FieldKind::Sfixed32 => 5,
+                    _ => 0,
+                };
+                wire::put_key(out, field.number, wire_type);
+                self.scalar(field, value, path, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode a numeric/bool/enum value without its key
+    fn scalar(
+        &self,
+        field: &FieldDescriptor,
+        value: &Value,
+        path: &str,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        match field.kind {
+            FieldKind::Bool => {
+                let b = value
+                    .as_bool()
+                    .ok_or_else(|| JsonMappingError::new(path, "expected a boolean"))?;
+                wire::put_varint(out, u64::from(b));
+            }
+            FieldKind::Int32 => {
+                let v = int_value(value, path, i64::from(i32::MIN), i64::from(i32::MAX))?;
+                wire::put_varint(out, v as u64);
+            }
+            FieldKind::Int64 => {
+                let v = int_value(value, path, i64::MIN, i64::MAX)?;
+                wire::put_varint(out, v as u64);
+            }
+            FieldKind::Sint32 => {
+                let v = int_value(value, path, i64::from(i32::MIN), i64::from(i32::MAX))?;
+                wire::put_varint(out, wire::zigzag_encode(v));
+            }
+            FieldKind::Sint64 => {
+                let v = int_value(value, path, i64::MIN, i64::MAX)?;
+                wire::put_varint(out, wire::zigzag_encode(v));
+            }
+            FieldKind::Uint32 => {
+                let v = uint_value(value, path, u64::from(u32::MAX))?;
+                wire::put_varint(out, v);
+            }
+            FieldKind::Uint64 => {
+                let v = uint_value(value, path, u64::MAX)?;
+                wire::put_varint(out, v);
+            }
+            FieldKind::Fixed32 => {
+                let v = uint_value(value, path, u64::from(u32::MAX))?;
+                out.extend_from_slice(&(v as u32).to_le_bytes());
+            }
+            FieldKind::Sfixed32 => {
+                let v = int_value(value, path, i64::from(i32::MIN), i64::from(i32::MAX))?;
+                out.extend_from_slice(&(v as i32).to_le_bytes());
+            }
+            FieldKind::Fixed64 => {
+                let v = uint_value(value, path, u64::MAX)?;
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            FieldKind::Sfixed64 => {
+                let v = int_value(value, path, i64::MIN, i64::MAX)?;
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            FieldKind::Float => {
+                let v = float_value(value, path)?;
+                out.extend_from_slice(&(v as f32).to_le_bytes());
+            }
+            FieldKind::Double => {
+                let v = float_value(value, path)?;
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            FieldKind::Enum => {
+                let v = self.enum_value(field, value, path)?;
+                wire::put_varint(out, i64::from(v) as u64);
+            }
+            FieldKind::String | FieldKind::Bytes | FieldKind::Message => {
+                return Err(JsonMappingError::new(path, "not a scalar field"));
+            }
+        }
+        Ok(())
+    }
+
+    fn enum_value(&self, field: &FieldDescriptor, value: &Value, path: &str) -> Result<i32> {
+        if let Some(n) = value.as_i64() {
+            return i32::try_from(n)
+                .map_err(|_| JsonMappingError::new(path, "enum value out of range"));
+        }
+        let name = value
+            .as_str()
+            .ok_or_else(|| JsonMappingError::new(path, "expected an enum name or number"))?;
+        if field.type_name.as_deref() == Some("google.protobuf.NullValue") {
+            return Ok(0);
+        }
+        let descriptor = field
+            .type_name
+            .as_deref()
+            .and_then(|t| self.pool.enum_type(t))
+            .ok_or_else(|| JsonMappingError::new(path, "unknown enum type"))?;
+        descriptor
+            .values
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, number)| *number)
+            .ok_or_else(|| {
+                let expected: Vec<&str> =
+                    descriptor.values.iter().map(|(n, _)| n.as_str()).collect();
+                JsonMappingError::new(
+                    path,
+                    format!(
+                        "invalid enum value \"{}\", expected one of {}",
+                        name,
+                        expected.join(", ")
+                    ),
+                )
+            })
+    }
+
+    fn well_known(
+        &self,
+        type_name: &str,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<u8>,
+    ) -> Option<Result<()>> {
+        let result = match type_name {
+            "google.protobuf.Timestamp" => value
+                .as_str()
+                .ok_or_else(|| JsonMappingError::new(path, "expected an RFC 3339 timestamp"))
+                .and_then(|s| {
+                    parse_timestamp(s)
+                        .ok_or_else(|| JsonMappingError::new(path, "invalid RFC 3339 timestamp"))
+                })
+                .map(|(seconds, nanos)| put_seconds_nanos(out, seconds, nanos)),
+            "google.protobuf.Duration" => value
+                .as_str()
+                .and_then(parse_duration)
+                .ok_or_else(|| JsonMappingError::new(path, "expected a duration like \"1.5s\""))
+                .map(|(seconds, nanos)| put_seconds_nanos(out, seconds, nanos)),
+            "google.protobuf.FieldMask" => value
+                .as_str()
+                .ok_or_else(|| JsonMappingError::new(path, "expected a comma separated field mask"))
+                .map(|s| {
+                    for part in s.split(',').filter(|p| !p.is_empty()) {
+                        wire::put_bytes(out, 1, to_snake(part).as_bytes());
+                    }
+                }),
+            "google.protobuf.Empty" => match value {
+                Value::Object(map) if map.is_empty() => Ok(()),
+                Value::Null => Ok(()),
+                _ => Err(JsonMappingError::new(path, "expected an empty object")),
+            },
+            "google.protobuf.Struct" => self.struct_value(value, path, depth, out),
+            "google.protobuf.ListValue" => self.list_value(value, path, depth, out),
+            "google.protobuf.Value" => self.dynamic_value(value, path, depth, out),
+            _ => {
+                let kind = wrapper_kind(type_name)?;
+                let field = FieldDescriptor {
+                    name: "value".to_string(),
+                    json_name: "value".to_string(),
+                    number: 1,
+                    kind,
+                    repeated: false,
+                    type_name: None,
+                };
+                self.single(&field, value, path, depth, out)
+            }
+        };
+        Some(result)
+    }
+
+    fn struct_value(
+        &self,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| JsonMappingError::new(path, "expected an object"))?;
+        for (key, item) in object {
+            let mut entry = Vec::new();
+            wire::put_bytes(&mut entry, 1, key.as_bytes());
+            let mut buf = Vec::new();
+            self.dynamic_value(item, &child_path(path, key), depth + 1, &mut buf)?;
+            wire::put_bytes(&mut entry, 2, &buf);
+            wire::put_bytes(out, 1, &entry);
+        }
+        Ok(())
+    }
+
+    fn list_value(&self, value: &Value, path: &str, depth: usize, out: &mut Vec<u8>) -> Result<()> {
+        let items = value
+            .as_array()
+            .ok_or_else(|| JsonMappingError::new(path, "expected an array"))?;
+        for (index, item) in items.iter().enumerate() {
+            let mut buf = Vec::new();
+            self.dynamic_value(item, &format!("{}[{}]", path, index), depth + 1, &mut buf)?;
+            wire::put_bytes(out, 1, &buf);
+        }
+        Ok(())
+    }
+
+    fn dynamic_value(
+        &self,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(JsonMappingError::new(path, "value nesting too deep"));
+        }
+        match value {
+            Value::Null => {
+                wire::put_key(out, 1, 0);
+                wire::put_varint(out, 0);
+            }
+            Value::Number(n) => {
+                wire::put_key(out, 2, 1);
+                out.extend_from_slice(&n.as_f64().unwrap_or_default().to_le_bytes());
+            }
+            Value::String(s) => wire::put_bytes(out, 3, s.as_bytes()),
+            Value::Bool(b) => {
+                wire::put_key(out, 4, 0);
+                wire::put_varint(out, u64::from(*b));
+            }
+            Value::Object(_) => {
+                let mut buf = Vec::new();
+                self.struct_value(value, path, depth, &mut buf)?;
+                wire::put_bytes(out, 5, &buf);
+            }
+            Value::Array(_) => {
+                let mut buf = Vec::new();
+                self.list_value(value, path, depth, &mut buf)?;
+                wire::put_bytes(out, 6, &buf);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn map_key_json(kind: FieldKind, key: &str) -> Value {
+    match kind {
+        FieldKind::Bool => Value::Bool(key == "true"),
+        _ => Value::String(key.to_string()),
+    }
+}
+
+fn wrapper_kind(type_name: &str) -> Option<FieldKind> {
+    Some(match type_name {
+        "google.protobuf.DoubleValue" => FieldKind::Double,
+        "google.protobuf.FloatValue" => FieldKind::Float,
+        "google.protobuf.Int64Value" => FieldKind::Int64,
+        "google.protobuf.UInt64Value" => FieldKind::Uint64,
+        "google.protobuf.Int32Value" => FieldKind::Int32,
+        "google.protobuf.UInt32Value" => FieldKind::Uint32,
+        "google.protobuf.BoolValue" => FieldKind::Bool,
+        "google.protobuf.StringValue" => FieldKind::String,
+        "google.protobuf.BytesValue" => FieldKind::Bytes,
+        _ => return None,
+    })
+}
+
+fn int_value(value: &Value, path: &str, min: i64, max: i64) -> Result<i64> {
+    let parsed = match value {
+        Value::Number(n) => n.as_i64().or_else(|| {
+            n.as_f64()
+                .filter(|f| f.fract() == 0.0 && f.abs() < 9.3e18)
+                .map(|f| f as i64)
+        }),
+        Value::String(s) => s.trim().parse::<i64>().ok(),
+        _ => None,
+    };
+    parsed
+        .filter(|v| (min..=max).contains(v))
+        .ok_or_else(|| JsonMappingError::new(path, "expected an integer in range"))
+}
+
+fn uint_value(value: &Value, path: &str, max: u64) -> Result<u64> {
+    let parsed = match value {
+        Value::Number(n) => n.as_u64().or_else(|| {
+            n.as_f64()
+                .filter(|f| f.fract() == 0.0 && *f >= 0.0 && *f < 1.8e19)
+                .map(|f| f as u64)
+        }),
+        Value::String(s) => s.trim().parse::<u64>().ok(),
+        _ => None,
+    };
+    parsed
+        .filter(|v| *v <= max)
+        .ok_or_else(|| JsonMappingError::new(path, "expected an unsigned integer in range"))
+}
+
+fn float_value(value: &Value, path: &str) -> Result<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => match s.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            other => other.trim().parse::<f64>().ok(),
+        },
+        _ => None,
+    }
+    .ok_or_else(|| JsonMappingError::new(path, "expected a number"))
+}
+
+fn decode_base64(s: &str, path: &str) -> Result<Vec<u8>> {
+    use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+    STANDARD
+        .decode(s)
+        .or_else(|_| STANDARD_NO_PAD.decode(s))
+        .or_else(|_| URL_SAFE.decode(s))
+        .or_else(|_| URL_SAFE_NO_PAD.decode(s))
+        .map_err(|_| JsonMappingError::new(path, "invalid base64"))
+}
+
+fn put_seconds_nanos(out: &mut Vec<u8>, seconds: i64, nanos: i32) {
+    if seconds != 0 {
+        wire::put_key(out, 1, 0);
+        wire::put_varint(out, seconds as u64);
+    }
+    if nanos != 0 {
+        wire::put_key(out, 2, 0);
+        wire::put_varint(out, i64::from(nanos) as u64);
+    }
+}
+
+fn to_snake(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// --- protobuf -> JSON ---
+
+pub(crate) fn decode_message(pool: &DescriptorPool, message: &str, bytes: &[u8]) -> Result<Value> {
+    Decoder { pool }.message(message, bytes, "", 0)
+}
+
+struct Decoder<'a> {
+    pool: &'a DescriptorPool,
+}
+
+/// Values collected for one field while scanning the wire
+enum Collected {
+    Single(Value),
+    /// Message bytes are concatenated, which is protobuf's merge semantics
+    Message(Vec<u8>),
+    Repeated(Vec<Value>),
+    Map(Map<String, Value>),
+}
+
+fn wire_error(path: &str, message: String) -> JsonMappingError {
+    JsonMappingError::new(path, format!("invalid protobuf: {}", message))
+}
+
+impl Decoder<'_> {
+    fn message(&self, type_name: &str, bytes: &[u8], path: &str, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(JsonMappingError::new(path, "message nesting too deep"));
+        }
+        if let Some(result) = self.well_known(type_name, bytes, path, depth) {
+            return result;
+        }
+        let descriptor = self.pool.message(type_name).ok_or_else(|| {
+            JsonMappingError::new(path, format!("unknown message type {}", type_name))
+        })?;
+
+        let mut collected: HashMap<u32, Collected> = HashMap::new();
+        let mut reader = WireReader::new(bytes);
+        while let Some(field) = reader.next_field() {
+            let (number, value) = field.map_err(|e| wire_error(path, e))?;
+            let Some(field) = descriptor.field_by_number(number) else {
+                continue; // unknown fields are skipped
+            };
+            let field_path = child_path(path, &field.json_name);
+
+            if let Some(entry) = self.map_entry(field) {
+                let WireValue::Bytes(entry_bytes) = value else {
+                    return Err(JsonMappingError::new(&field_path, "invalid map entry"));
+                };
+                let (key, item) = self.map_entry_value(entry, entry_bytes, &field_path, depth)?;
+                match collected
+                    .entry(number)
+                    .or_insert_with(|| Collected::Map(Map::new()))
+                {
+                    Collected::Map(map) => {
+                        map.insert(key, item);
+                    }
+                    _ => unreachable!(),
+                }
+                continue;
+            }
+
+            if field.repeated {
+                let items = self.repeated_values(field, value, &field_path, depth)?;
+                match collected
+                    .entry(number)
+                    .or_insert_with(|| Collected::Repeated(Vec::new()))
+                {
+                    Collected::Repeated(all) => all.extend(items),
+                    _ => unreachable!(),
+                }
+                continue;
+            }
+
+            if field.kind == FieldKind::Message {
+                let WireValue::Bytes(b) = value else {
+                    return Err(JsonMappingError::new(
+                        &field_path,
+                        "expected a length-delimited message",
+                    ));
+                };
+                match collected
+                    .entry(number)
+                    .or_insert_with(|| Collected::Message(Vec::new()))
+                {
+                    Collected::Message(all) => all.extend_from_slice(b),
+                    _ => unreachable!(),
+                }
+                continue;
+            }
+
+            let single = self.scalar(field, value, &field_path)?;
+            collected.insert(number, Collected::Single(single));
+        }
+
+        let mut object = Map::new();
+        for field in &descriptor.fields {
+            let Some(value) = collected.remove(&field.number) else {
+                continue;
+            };
+            let field_path = child_path(path, &field.json_name);
+            let json = match value {
+                Collected::Single(v) => v,
+                Collected::Repeated(items) => Value::Array(items),
+                Collected::Map(map) => Value::Object(map),
+                Collected::Message(b) => {
+                    let type_name = field.type_name.as_deref().unwrap_or_default();
+                    self.message(type_name, &b, &field_path, depth + 1)?
+                }
+            };
+            object.insert(field.json_name.clone(), json);
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn map_entry(&self, field: &FieldDescriptor) -> Option<&MessageDescriptor> {
+        if field.kind != FieldKind::Message || !field.repeated {
+            return None;
+        }
+        self.pool
+            .message(field.type_name.as_deref()?)
+            .filter(|m| m.map_entry)
+    }
+
+    fn map_entry_value(
+        &self,
+        entry: &MessageDescriptor,
+        bytes: &[u8],
+        path: &str,
+        depth: usize,
+    ) -> Result<(String, Value)> {
+        let mut key = None;
+        let mut value = None;
+        let mut message = Vec::new();
+        for (number, raw) in wire::fields(bytes).map_err(|e| wire_error(path, e))? {
+            let Some(field) = entry.field_by_number(number) else {
+                continue;
+            };
+            if field.kind == FieldKind::Message {
+                if let WireValue::Bytes(b) = raw {
+                    message.extend_from_slice(b);
+                }
+                continue;
+            }
+            let decoded = self.scalar(field, raw, path)?;
+            if number == 1 {
+                key = Some(decoded);
+            } else {
+                value = Some(decoded);
+            }
+        }
+
+        let key_field = entry.field_by_number(1);
+        let key = match key {
+            Some(Value::String(s)) => s,
+            Some(other) => other.to_string(),
+            None => default_scalar(key_field.map(|f| f.kind).unwrap_or(FieldKind::String))
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| "0".to_string()),
+        };
+        let value_field = entry
+            .field_by_number(2)
+            .ok_or_else(|| JsonMappingError::new(path, "invalid map entry"))?;
+        let value = match value {
+            Some(v) => v,
+            None if value_field.kind == FieldKind::Message => {
+                let type_name = value_field.type_name.as_deref().unwrap_or_default();
+                self.message(type_name, &message, &child_path(path, &key), depth + 1)?
+            }
+            None => default_scalar(value_field.kind),
+        };
+        Ok((key, value))
+    }
+
+    fn repeated_values(
+        &self,
+        field: &FieldDescriptor,
+        value: WireValue<'_>,
+        path: &str,
+        depth: usize,
+    ) -> Result<Vec<Value>> {
+        match (field.kind, value) {
+            (FieldKind::Message, WireValue::Bytes(b)) => {
+                let type_name = field.type_name.as_deref().unwrap_or_default();
+                Ok(vec![self.message(type_name, b, path, depth + 1)?])
+            }
+            (kind, WireValue::Bytes(b)) if kind.is_packable() => {
+                let mut reader = WireReader::new(b);
+                let mut items = Vec::new();
+                loop {
+                    let next = match kind {
+                        FieldKind::Double | FieldKind::Fixed64 | FieldKind::Sfixed64 => {
+                            reader.packed_fixed(8).map(|r| r.map(WireValue::Fixed64))
+                        }
+                        FieldKind::Float | FieldKind::Fixed32 | FieldKind::Sfixed32 => reader
+                            .packed_fixed(4)
+                            .map(|r| r.map(|v| WireValue::Fixed32(v as u32))),
+                        _ => reader.packed_varint().map(|r| r.map(WireValue::Varint)),
+                    };
+                    let Some(next) = next else {
+                        break;
+                    };
+                    let raw = next.map_err(|e| wire_error(path, e))?;
+                    items.push(self.scalar(field, raw, path)?);
+                }
+                Ok(items)
+            }
+            (_, raw) => Ok(vec![self.scalar(field, raw, path)?]),
+        }
+    }
+
+    fn scalar(&self, field: &FieldDescriptor, value: WireValue<'_>, path: &str) -> Result<Value> {
+        let mismatch = || JsonMappingError::new(path, "wire type does not match field type");
+        Ok(match (field.kind, value) {
+            (FieldKind::Bool, WireValue::Varint(v)) => Value::Bool(v != 0),
+            (FieldKind::Int32, WireValue::Varint(v)) => Value::from(v as i32),
+            (FieldKind::Uint32, WireValue::Varint(v)) => Value::from(v as u32),
+            (FieldKind::Sint32, WireValue::Varint(v)) => Value::from(wire::zigzag_decode(v) as i32),
+            (FieldKind::Int64, WireValue::Varint(v)) => Value::String((v as i64).to_string()),
+            (FieldKind::Uint64, WireValue::Varint(v)) => Value::String(v.to_string()),
+            (FieldKind::Sint64, WireValue::Varint(v)) => {
+                Value::String(wire::zigzag_decode(v).to_string())
+            }
+            (FieldKind::Fixed32, WireValue::Fixed32(v)) => Value::from(v),
+            (FieldKind::Sfixed32, WireValue::Fixed32(v)) => Value::from(v as i32),
+            (FieldKind::Fixed64, WireValue::Fixed64(v)) => Value::String(v.to_string()),
+            (FieldKind::Sfixed64, WireValue::Fixed64(v)) => Value::String((v as i64).to_string()),
+            (FieldKind::Float, WireValue::Fixed32(v)) => float_json(f64::from(f32::from_bits(v))),
+            (FieldKind::Double, WireValue::Fixed64(v)) => float_json(f64::from_bits(v)),
+            (FieldKind::Enum, WireValue::Varint(v)) => self.enum_json(field, v as i32),
+            (FieldKind::String, WireValue::Bytes(b)) => Value::String(
+                String::from_utf8(b.to_vec())
+                    .map_err(|_| JsonMappingError::new(path, "invalid UTF-8"))?,
+            ),
+            (FieldKind::Bytes, WireValue::Bytes(b)) => {
+                Value::String(base64::engine::general_purpose::STANDARD.encode(b))
+            }
+            _ => return Err(mismatch()),
+        })
+    }
+
+    fn enum_json(&self, field: &FieldDescriptor, number: i32) -> Value {
+        if field.type_name.as_deref() == Some("google.protobuf.NullValue") {
+            return Value::Null;
+        }
+        field
+            .type_name
+            .as_deref()
+            .and_then(|t| self.pool.enum_type(t))
+            .and_then(|e| e.values.iter().find(|(_, n)| *n == number))
+            .map(|(name, _)| Value::String(name.clone()))
+            .unwrap_or_else(|| Value::from(number))
+    }
+
+    fn well_known(
+        &self,
+        type_name: &str,
+        bytes: &[u8],
+        path: &str,
+        depth: usize,
+    ) -> Option<Result<Value>> {
+        let fields = match wire::fields(bytes) {
+            Ok(fields) => fields,
+            Err(e) => return Some(Err(wire_error(path, e))),
+        };
+        let varint = |number: u32| {
+            fields.iter().rev().find_map(|(n, v)| match v {
+                WireValue::Varint(x) if *n == number => Some(*x),
+                _ => None,
+            })
+        };
+
+        let result = match type_name {
+            "google.protobuf.Timestamp" => {
+                let seconds = varint(1).unwrap_or_default() as i64;
+                let nanos = varint(2).unwrap_or_default() as i32;
+                format_timestamp(seconds, nanos)
+                    .map(Value::String)
+                    .ok_or_else(|| JsonMappingError::new(path, "timestamp out of range"))
+            }
+            "google.protobuf.Duration" => {
+                let seconds = varint(1).unwrap_or_default() as i64;
+                let nanos = varint(2).unwrap_or_default() as i32;
+                Ok(Value::String(format_duration(seconds, nanos)))
+            }
+            "google.protobuf.FieldMask" => {
+                let paths: Vec<String> = fields
+                    .iter()
+                    .filter_map(|(n, v)| match v {
+                        WireValue::Bytes(b) if *n == 1 => Some(super::descriptor::to_lower_camel(
+                            &String::from_utf8_lossy(b),
+                        )),
+                        _ => None,
+                    })
+                    .collect();
+                Ok(Value::String(paths.join(",")))
+            }
+            "google.protobuf.Empty" => Ok(Value::Object(Map::new())),
+            "google.protobuf.Struct" => self.struct_json(&fields, path, depth),
+            "google.protobuf.ListValue" => self.list_json(&fields, path, depth),
+            "google.protobuf.Value" => self.value_json(&fields, path, depth),
+            _ => {
+                let kind = wrapper_kind(type_name)?;
+                let field = FieldDescriptor {
+                    name: "value".to_string(),
+                    json_name: "value".to_string(),
+                    number: 1,
+                    kind,
+                    repeated: false,
+                    type_name: None,
+                };
+                match fields.iter().rev().find(|(n, _)| *n == 1) {
+                    Some((_, raw)) => self.scalar(&field, *raw, path),
+                    None => Ok(default_scalar(kind)),
+                }
+            }
+        };
+        Some(result)
+    }
+
+    fn struct_json(
+        &self,
+        fields: &[(u32, WireValue<'_>)],
+        path: &str,
+        depth: usize,
+    ) -> Result<Value> {
+        let mut object = Map::new();
+        for (number, raw) in fields {
+            let (1, WireValue::Bytes(entry)) = (number, raw) else {
+                continue;
+            };
+            let entry = wire::fields(entry).map_err(|e| wire_error(path, e))?;
+            let mut key = String::new();
+            let mut value = Vec::new();
+            for (n, v) in entry {
+                match (n, v) {
+                    (1, WireValue::Bytes(b)) => key = String::from_utf8_lossy(b).into_owned(),
+                    (2, WireValue::Bytes(b)) => value.extend_from_slice(b),
+                    _ => {}
+                }
+            }
+            let value_fields = wire::fields(&value).map_err(|e| wire_error(path, e))?;
+            let item = self.value_json(&value_fields, &child_path(path, &key), depth + 1)?;
+            object.insert(key, item);
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn list_json(
+        &self,
+        fields: &[(u32, WireValue<'_>)],
+        path: &str,
+        depth: usize,
+    ) -> Result<Value> {
+        let mut items = Vec::new();
+        for (number, raw) in fields {
+            if let (1, WireValue::Bytes(b)) = (number, raw) {
+                let value_fields = wire::fields(b).map_err(|e| wire_error(path, e))?;
+                items.push(self.value_json(&value_fields, path, depth + 1)?);
+            }
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn value_json(
+        &self,
+        fields: &[(u32, WireValue<'_>)],
+        path: &str,
+        depth: usize,
+    ) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(JsonMappingError::new(path, "value nesting too deep"));
+        }
+        let Some((number, raw)) = fields.last() else {
+            return Ok(Value::Null);
+        };
+        Ok(match (number, raw) {
+            (2, WireValue::Fixed64(v)) => float_json(f64::from_bits(*v)),
+            (3, WireValue::Bytes(b)) => Value::String(String::from_utf8_lossy(b).into_owned()),
+            (4, WireValue::Varint(v)) => Value::Bool(*v != 0),
+            (5, WireValue::Bytes(b)) => {
+                let inner = wire::fields(b).map_err(|e| wire_error(path, e))?;
+                self.struct_json(&inner, path, depth + 1)?
+            }
+            (6, WireValue::Bytes(b)) => {
+                let inner = wire::fields(b).map_err(|e| wire_error(path, e))?;
+                self.list_json(&inner, path, depth + 1)?
+            }
+            _ => Value::Null,
+        })
+    }
+}
+
+fn float_json(v: f64) -> Value {
+    if v.is_nan() {
+        Value::String("NaN".to_string())
+    } else if v == f64::INFINITY {
+        Value::String("Infinity".to_string())
+    } else if v == f64::NEG_INFINITY {
+        Value::String("-Infinity".to_string())
+    } else {
+        Number::from_f64(v)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+fn default_scalar(kind: FieldKind) -> Value {
+    match kind {
+        FieldKind::Bool => Value::Bool(false),
+        FieldKind::String | FieldKind::Bytes => Value::String(String::new()),
+        FieldKind::Int64
+        | FieldKind::Uint64
+        | FieldKind::Sint64
+        | FieldKind::Fixed64
+        | FieldKind::Sfixed64 => Value::String("0".to_string()),
+        FieldKind::Message => Value::Object(Map::new()),
+        _ => Value::from(0),
+    }
+}
+
+// --- Timestamp / Duration ---
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Fraction digits in groups of 3 (`.5` -> `.500`), as required by proto3 JSON
+fn format_nanos(nanos: i32) -> String {
+    if nanos == 0 {
+        String::new()
+    } else if nanos % 1_000_000 == 0 {
+        format!(".{:03}", nanos / 1_000_000)
+    } else if nanos % 1_000 == 0 {
+        format!(".{:06}", nanos / 1_000)
+    } else {
+        format!(".{:09}", nanos)
+    }
+}
+
+/// Valid range: 0001-01-01T00:00:00Z ..= 9999-12-31T23:59:59.999999999Z
+const MIN_TIMESTAMP: i64 = -62_135_596_800;
+const MAX_TIMESTAMP: i64 = 253_402_300_799;
+
+pub(crate) fn format_timestamp(seconds: i64, nanos: i32) -> Option<String> {
+    if !(MIN_TIMESTAMP..=MAX_TIMESTAMP).contains(&seconds) || !(0..1_000_000_000).contains(&nanos) {
+        return None;
+    }
+    let days = seconds.div_euclid(86_400);
+    let secs = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        format_nanos(nanos)
+    ))
+}
+
+pub(crate) fn parse_timestamp(s: &str) -> Option<(i64, i32)> {
+    let bytes = s.as_bytes();
+    let num = |range: std::ops::Range<usize>| -> Option<i64> { s.get(range)?.parse().ok() };
+    if bytes.len() < 20 || bytes[4] != b'-' || bytes[7] != b'-' || !matches!(bytes[10], b'T' | b't')
+    {
+        return None;
+    }
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let mut rest = &s[19..];
+    let mut nanos = 0i32;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 || digits > 9 {
+            return None;
+        }
+        nanos = format!("{:0<9}", &fraction[..digits]).parse().ok()?;
+        rest = &fraction[digits..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes().first()? {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let (h, m) = rest[1..].split_once(':')?;
+            sign * (h.parse::<i64>().ok()? * 3600 + m.parse::<i64>().ok()? * 60)
+        }
+    };
+
+    let seconds =
+        days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    (MIN_TIMESTAMP..=MAX_TIMESTAMP)
+        .contains(&seconds)
+        .then_some((seconds, nanos))
+}
+
+pub(crate) fn format_duration(seconds: i64, nanos: i32) -> String {
+    let negative = seconds < 0 || nanos < 0;
+    format!(
+        "{}{}{}s",
+        if negative { "-" } else { "" },
+        seconds.unsigned_abs(),
+        format_nanos(nanos.abs())
+    )
+}
+
+pub(crate) fn parse_duration(s: &str) -> Option<(i64, i32)> {
+    let body = s.strip_suffix('s')?;
+    let (negative, body) = match body.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, body),
+    };
+    let (whole, fraction) = body.split_once('.').unwrap_or((body, ""));
+    if whole.is_empty() || fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let seconds: i64 = whole.parse().ok()?;
+    let nanos: i32 = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<9}", fraction).parse().ok()?
+    };
+    if negative {
+        Some((-seconds, -nanos))
+    } else {
+        Some((seconds, nanos))
+    }
+}
+
+// --- HTTP binding helpers ---
+
+/// Set a request field from a URL string (path variable or query parameter).
+///
+/// `field_path` uses proto field names (`book.author_id`). Values are coerced using the
+/// descriptor: booleans are parsed, repeated fields accumulate, everything else stays a
+/// string (proto3 JSON accepts numbers, enums and bytes as strings).
+pub(crate) fn bind_url_value(
+    pool: &DescriptorPool,
+    message: &str,
+    target: &mut Map<String, Value>,
+    field_path: &str,
+    raw: &str,
+) -> std::result::Result<(), JsonMappingError> {
+    let mut type_name = message.to_string();
+    let mut object = target;
+    let segments: Vec<&str> = field_path.split('.').collect();
+
+    for (index, segment) in segments.iter().enumerate() {
+        let descriptor = pool.message(&type_name).ok_or_else(|| {
+            JsonMappingError::new(field_path, format!("unknown message type {}", type_name))
+        })?;
+        let field = descriptor.field(segment).ok_or_else(|| {
+            JsonMappingError::new(field_path, format!("unknown field \"{}\"", segment))
+        })?;
+        // Reuse whichever spelling the body already used for this field
+        let key = if object.contains_key(&field.json_name) || !object.contains_key(&field.name) {
+            field.json_name.clone()
+        } else {
+            field.name.clone()
+        };
+
+        if index + 1 < segments.len() {
+            if field.kind != FieldKind::Message || field.repeated {
+                return Err(JsonMappingError::new(
+                    field_path,
+                    "only singular message fields can be traversed",
+                ));
+            }
+            type_name = field.type_name.clone().unwrap_or_default();
+            let entry = object
+                .entry(key)
+                .or_insert_with(|| Value::Object(Map::new()));
+            if !entry.is_object() {
+                *entry = Value::Object(Map::new());
+            }
+            object = entry.as_object_mut().expect("just ensured object");
+            continue;
+        }
+
+        let value = match (field.kind, field.type_name.as_deref()) {
+            (FieldKind::Bool, _) | (FieldKind::Message, Some("google.protobuf.BoolValue")) => {
+                match raw {
+                    "true" | "1" => Value::Bool(true),
+                    "false" | "0" => Value::Bool(false),
+                    _ => return Err(JsonMappingError::new(field_path, "expected a boolean")),
+                }
+            }
+            _ => Value::String(raw.to_string()),
+        };
+        if field.repeated {
+            match object
+                .entry(key)
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                Value::Array(items) => items.push(value),
+                other => *other = Value::Array(vec![value]),
+            }
+        } else {
+            object.insert(key, value);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,308 @@
+//! Native gRPC (HTTP/2 + protobuf) services hosted on the app router
+//!
+//! tonic のサービスを REST ルートと同じポートで提供します。`content-type: application/grpc`
+//! のリクエストだけをサービス名 (`/package.Service/Method`) で振り分け、それ以外は通常の
+//! ルートへ流すため、同じパスの REST / トランスコーディングルートとも共存できます。
+//! 認証・レート制限などのミドルウェアが返した HTTP エラーは gRPC ステータスに変換されます。
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+    Router,
+};
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use tonic::server::NamedService;
+use tower::util::BoxCloneSyncService;
+use tower::{BoxError, Service, ServiceExt};
+
+use super::{status, web, GrpcCode, GrpcError};
+use crate::{AppState, Dep, Depends};
+
+/// A tonic service mounted next to the REST routes
+#[derive(Clone)]
+pub struct NativeGrpcService {
+    name: &'static str,
+    security: Vec<String>,
+    inner: BoxCloneSyncService<Request, Response, Infallible>,
+}
+
+impl NativeGrpcService {
+    /// Wrap a tonic server (`GreeterServer::new(..)`) or any `NamedService`
+    pub fn new<S, B>(service: S) -> Self
+    where
+        S: Service<Request, Response = axum::http::Response<B>, Error = Infallible>
+            + NamedService
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+        B: http_body::Body<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        Self::named(S::NAME, service)
+    }
+
+    /// Wrap a service answering under an explicit name (built-in services that are
+    /// served under several names)
+    pub(crate) fn named<S, B>(name: &'static str, service: S) -> Self
+    where
+        S: Service<Request, Response = axum::http::Response<B>, Error = Infallible>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+        B: http_body::Body<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        Self {
+            name,
+            security: Vec::new(),
+            inner: BoxCloneSyncService::new(service.map_response(|r| r.map(Body::new))),
+        }
+    }
+
+    /// Require a security scheme (validated by the app's auth middleware)
+    pub fn security(mut self, scheme: &str) -> Self {
+        self.security.push(scheme.to_string());
+        self
+    }
+
+    /// Fully-qualified service name (`helloworld.Greeter`)
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Security schemes required to call this service
+    pub fn security_schemes(&self) -> &[String] {
+        &self.security
+    }
+
+    /// Route pattern of all methods, for the auth middleware
+    pub(crate) fn path_pattern(&self) -> String {
+        format!("/{}/{{method}}", self.name)
+    }
+}
+
+impl<S, B> From<S> for NativeGrpcService
+where
+    S: Service<Request, Response = axum::http::Response<B>, Error = Infallible>
+        + NamedService
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    S::Future: Send + 'static,
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    fn from(service: S) -> Self {
+        Self::new(service)
+    }
+}
+
+impl std::fmt::Debug for NativeGrpcService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeGrpcService")
+            .field("name", &self.name)
+            .field("security", &self.security)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `application/grpc` requests (gRPC-Web is served by the transcoding routes)
+pub(crate) fn is_grpc_request<B>(request: &axum::http::Request<B>) -> bool {
+    is_grpc_content_type(request.headers())
+        && web::WebFormat::from_headers(request.headers()).is_none()
+}
+
+fn is_grpc_content_type(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/grpc"))
+}
+
+/// `/helloworld.Greeter/SayHello` -> `helloworld.Greeter`
+fn service_name(path: &str) -> Option<&str> {
+    let (service, method) = path.strip_prefix('/')?.split_once('/')?;
+    (!method.is_empty() && !method.contains('/')).then_some(service)
+}
+
+/// Dispatch `application/grpc` requests for known services before the REST routes.
+///
+/// `AppState` is added to the request extensions so tonic handlers can resolve
+/// dependencies with [`GrpcRequestExt`].
+pub(crate) fn mount(
+    services: &[NativeGrpcService],
+    router: Router<AppState>,
+    state: AppState,
+) -> Router<AppState> {
+    let services: Arc<HashMap<&'static str, NativeGrpcService>> = Arc::new(
+        services
+            .iter()
+            .map(|service| (service.name, service.clone()))
+            .collect(),
+    );
+    router.layer(axum::middleware::from_fn(
+        move |mut request: Request, next: Next| {
+            let services = services.clone();
+            let state = state.clone();
+            async move {
+                if !is_grpc_request(&request) {
+                    return next.run(request).await;
+                }
+                let path = request.uri().path();
+                let Some(service) = service_name(path)
+                    .and_then(|name| services.get(name))
+                    .cloned()
+                else {
+                    let message = format!("unknown service for {}", path);
+                    return trailers_only("application/grpc", &GrpcError::unimplemented(&message));
+                };
+                request.extensions_mut().insert(state);
+                match service.inner.oneshot(request).await {
+                    Ok(response) => response,
+                    Err(never) => match never {},
+                }
+            }
+        },
+    ))
+}
+
+/// Turn plain HTTP errors for gRPC requests (401 from auth, 429 from rate limiting,
+/// `ApiError` / `HttpException` from shared dependencies, ...) into trailers-only
+/// gRPC responses with `google.rpc.Status` details.
+pub(crate) async fn grpc_status_middleware(request: Request, next: Next) -> Response {
+    let content_type = match web::WebFormat::from_headers(request.headers()) {
+        Some(format) => format.content_type(),
+        None if is_grpc_request(&request) => "application/grpc",
+        None => return next.run(request).await,
+    };
+    let response = next.run(request).await;
+    // Responses from the gRPC service carry their status in headers or trailers
+    if is_grpc_content_type(response.headers()) {
+        return response;
+    }
+
+    let status = response.status();
+    let retry_after = status::retry_after(response.headers());
+    let body = axum::body::to_bytes(response.into_body(), 64 * 1024)
+        .await
+        .unwrap_or_default();
+    let body = serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default();
+    let message = body["detail"]
+        .as_str()
+        .or_else(|| body["error"].as_str())
+        .or_else(|| status.canonical_reason())
+        .unwrap_or("unknown error");
+
+    let mut error = GrpcError::new(GrpcCode::from_http_status(status), message);
+    for (field, description) in status::validation_violations(&body["detail"]) {
+        error = error.with_field_violation(&field, &description);
+    }
+    if let Some(delay) = retry_after {
+        error = error.with_retry_delay(delay);
+    }
+    let mut response = trailers_only(content_type, &error);
+    if content_type != "application/grpc" {
+        response.headers_mut().insert(
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            HeaderValue::from_static(web::EXPOSED_HEADERS),
+        );
+    }
+    response
+}
+
+/// A gRPC response without a body: the status is sent in the headers
+fn trailers_only(content_type: &'static str, error: &GrpcError) -> Response {
+    let mut response = Response::new(Body::empty());
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers.insert("grpc-status", HeaderValue::from(error.code));
+    if let Ok(value) = HeaderValue::from_str(&percent_encode(&error.message)) {
+        headers.insert("grpc-message", value);
+    }
+    if !error.details.is_empty() {
+        let details = status::encode_status_header(error.code, &error.message, &error.details);
+        if let Ok(value) = HeaderValue::from_str(&details) {
+            headers.insert("grpc-status-details-bin", value);
+        }
+    }
+    response
+}
+
+/// `grpc-message` is percent-encoded outside printable ASCII
+pub(super) fn percent_encode(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    for byte in message.bytes() {
+        if (0x20..0x7f).contains(&byte) && byte != b'%' {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// Access UltraAPI dependencies from tonic handlers
+///
+/// ```ignore
+/// async fn say_hello(&self, request: Request<HelloRequest>) -> Result<Response<HelloReply>, Status> {
+///     let db = request.dep::<Database>()?;
+///     let user = request.depends::<CurrentUser>().await?;
+///     // ...
+/// }
+/// ```
+pub trait GrpcRequestExt {
+    /// The application state, when the call was routed through `UltraApiApp`
+    fn app_state(&self) -> Option<&AppState>;
+
+    /// A dependency registered with `UltraApiApp::dep`
+    #[allow(clippy::result_large_err)]
+    fn dep<T: 'static + Send + Sync>(&self) -> Result<Dep<T>, tonic::Status> {
+        let state = self.app_state().ok_or_else(missing_state)?;
+        Dep::from_app_state(state).map_err(tonic::Status::from)
+    }
+
+    /// A `Depends` dependency (function-based dependencies are resolved with their chains)
+    fn depends<T: 'static + Send + Sync>(
+        &self,
+    ) -> impl Future<Output = Result<Depends<T>, tonic::Status>> + Send {
+        let state = self.app_state().cloned();
+        async move {
+            let state = state.ok_or_else(missing_state)?;
+            let value = match state.get_depends_resolver() {
+                Some(resolver) => resolver
+                    .resolve::<T>(&state)
+                    .await
+                    .map_err(|e| tonic::Status::internal(e.to_string()))?,
+                None => state.get::<T>().ok_or_else(|| {
+                    tonic::Status::internal(format!(
+                        "Dependency not registered: {}",
+                        std::any::type_name::<T>()
+                    ))
+                })?,
+            };
+            Ok(Depends(value))
+        }
+    }
+}
+
+impl<M> GrpcRequestExt for tonic::Request<M> {
+    fn app_state(&self) -> Option<&AppState> {
+        self.extensions().get::<AppState>()
+    }
+}
+
+fn missing_state() -> tonic::Status {
+    tonic::Status::internal("service is not mounted on an UltraApiApp")
+}
@@ -0,0 +1,394 @@
+//! gRPC server reflection (`grpc.reflection.v1` / `v1alpha`)
+//!
+//! 登録された `DescriptorPool` (`GrpcService::from_descriptor` のサービスと
+//! `GrpcExt::grpc_descriptors`) からファイル記述子を返し、grpcurl や Postman がサービスを
+//! 検出できるようにします。ネイティブ gRPC の双方向ストリームに加えて、同じパスへの
+//! HTTP/JSON リクエスト (`{"listServices": ""}` など) にも proto3 JSON で応答します。
+
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::Engine as _;
+use bytes::Bytes;
+use futures_util::StreamExt;
+use std::collections::{BTreeSet, HashMap};
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tonic::codec::ProstCodec;
+use tonic::server::NamedService;
+use tower::BoxError;
+
+use super::descriptor::{DescriptorFile, DescriptorPool};
+use super::health::{self, HealthServer};
+use super::{body_error_response, read_limited_body, GrpcCode, GrpcError};
+
+/// Files and services served by the reflection service
+#[derive(Debug, Default)]
+pub(crate) struct ReflectionIndex {
+    services: BTreeSet<String>,
+    files: HashMap<String, DescriptorFile>,
+    symbols: HashMap<String, String>,
+}
+
+impl ReflectionIndex {
+    /// Index the files of `pools` and list their services plus `services`
+    /// (native services) and the health service
+    pub(crate) fn new<'a>(
+        pools: &[DescriptorPool],
+        services: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let mut index = Self::default();
+        index.add_file(health::descriptor_file());
+        index.services.insert(HealthServer::NAME.to_string());
+        for pool in pools {
+            for service in pool.services() {
+                index.services.insert(service.full_name);
+            }
+            for file in pool.files() {
+                index.add_file(file.clone());
+            }
+        }
+        index
+            .services
+            .extend(services.into_iter().map(str::to_string));
+        index
+    }
+
+    pub(crate) fn add_file(&mut self, file: DescriptorFile) {
+        for symbol in &file.symbols {
+            self.symbols.insert(symbol.clone(), file.name.clone());
+        }
+        self.files.entry(file.name.clone()).or_insert(file);
+    }
+
+    /// A file followed by its transitive dependencies
+    fn file_with_dependencies(&self, name: &str) -> Option<Vec<Vec<u8>>> {
+        self.files.get(name)?;
+        let mut seen = BTreeSet::new();
+        let mut pending = vec![name.to_string()];
+        let mut files = Vec::new();
+        while let Some(name) = pending.pop() {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            // Well-known imports that are not in the pools are resolved by the client
+            let Some(file) = self.files.get(&name) else {
+                continue;
+            };
+            files.push(file.bytes.clone());
+            pending.extend(file.dependencies.iter().rev().cloned());
+        }
+        Some(files)
+    }
+
+    fn respond(&self, request: ServerReflectionRequest) -> ServerReflectionResponse {
+        let message_response = match &request.message_request {
+            Some(MessageRequest::ListServices(_)) => {
+                MessageResponse::ListServicesResponse(ListServiceResponse {
+                    service: self
+                        .services
+                        .iter()
+                        .map(|name| ServiceResponse { name: name.clone() })
+                        .collect(),
+                })
+            }
+            Some(MessageRequest::FileByFilename(name)) => {
+                self.file_response(self.file_with_dependencies(name), name)
+            }
+            Some(MessageRequest::FileContainingSymbol(symbol)) => {
+                let symbol = symbol.strip_prefix('.').unwrap_or(symbol);
+                let files = self
+                    .symbols
+                    .get(symbol)
+                    .and_then(|file| self.file_with_dependencies(file));
+                self.file_response(files, symbol)
+            }
+            Some(MessageRequest::AllExtensionNumbersOfType(type_name)) => {
+                let type_name = type_name.strip_prefix('.').unwrap_or(type_name);
+                if self.symbols.contains_key(type_name) {
+                    MessageResponse::AllExtensionNumbersResponse(ExtensionNumberResponse {
+                        base_type_name: type_name.to_string(),
+                        extension_number: Vec::new(),
+                    })
+                } else {
+                    error_response(GrpcCode::NotFound, &format!("type {} not found", type_name))
+                }
+            }
+            Some(MessageRequest::FileContainingExtension(extension)) => error_response(
+                GrpcCode::NotFound,
+                &format!(
+                    "extension {} of {} not found",
+                    extension.extension_number, extension.containing_type
+                ),
+            ),
+            None => error_response(GrpcCode::InvalidArgument, "empty reflection request"),
+        };
+        ServerReflectionResponse {
+            valid_host: request.host.clone(),
+            original_request: Some(request),
+            message_response: Some(message_response),
+        }
+    }
+
+    fn file_response(&self, files: Option<Vec<Vec<u8>>>, name: &str) -> MessageResponse {
+        match files {
+            Some(files) => MessageResponse::FileDescriptorResponse(FileDescriptorResponse {
+                file_descriptor_proto: files,
+            }),
+            None => error_response(GrpcCode::NotFound, &format!("{} not found", name)),
+        }
+    }
+}
+
+fn error_response(code: GrpcCode, message: &str) -> MessageResponse {
+    MessageResponse::ErrorResponse(ErrorResponse {
+        error_code: code.as_i32(),
+        error_message: message.to_string(),
+    })
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ServerReflectionRequest {
+    #[prost(string, tag = "1")]
+    host: String,
+    #[prost(oneof = "MessageRequest", tags = "3, 4, 5, 6, 7")]
+    message_request: Option<MessageRequest>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+enum MessageRequest {
+    #[prost(string, tag = "3")]
+    FileByFilename(String),
+    #[prost(string, tag = "4")]
+    FileContainingSymbol(String),
+    #[prost(message, tag = "5")]
+    FileContainingExtension(ExtensionRequest),
+    #[prost(string, tag = "6")]
+    AllExtensionNumbersOfType(String),
+    #[prost(string, tag = "7")]
+    ListServices(String),
+}
+
+#[derive(Clone, PartialEq, prost::Message, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtensionRequest {
+    #[prost(string, tag = "1")]
+    #[serde(default)]
+    containing_type: String,
+    #[prost(int32, tag = "2")]
+    #[serde(default)]
+    extension_number: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ServerReflectionResponse {
+    #[prost(string, tag = "1")]
+    valid_host: String,
+    #[prost(message, optional, tag = "2")]
+    original_request: Option<ServerReflectionRequest>,
+    #[prost(oneof = "MessageResponse", tags = "4, 5, 6, 7")]
+    message_response: Option<MessageResponse>,
+}
+
+// Variant names follow the `message_response` oneof of reflection.proto
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, PartialEq, prost::Oneof)]
+enum MessageResponse {
+    #[prost(message, tag = "4")]
+    FileDescriptorResponse(FileDescriptorResponse),
+    #[prost(message, tag = "5")]
+    AllExtensionNumbersResponse(ExtensionNumberResponse),
+    #[prost(message, tag = "6")]
+    ListServicesResponse(ListServiceResponse),
+    #[prost(message, tag = "7")]
+    ErrorResponse(ErrorResponse),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct FileDescriptorResponse {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    file_descriptor_proto: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ExtensionNumberResponse {
+    #[prost(string, tag = "1")]
+    base_type_name: String,
+    #[prost(int32, repeated, tag = "2")]
+    extension_number: Vec<i32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ListServiceResponse {
+    #[prost(message, repeated, tag = "1")]
+    service: Vec<ServiceResponse>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ServiceResponse {
+    #[prost(string, tag = "1")]
+    name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ErrorResponse {
+    #[prost(int32, tag = "1")]
+    error_code: i32,
+    #[prost(string, tag = "2")]
+    error_message: String,
+}
+
+/// Native `ServerReflection` service (bidirectional stream of requests)
+#[derive(Clone)]
+pub(crate) struct ReflectionServer {
+    index: Arc<ReflectionIndex>,
+}
+
+impl ReflectionServer {
+    pub(crate) const V1: &'static str = "grpc.reflection.v1.ServerReflection";
+    pub(crate) const V1ALPHA: &'static str = "grpc.reflection.v1alpha.ServerReflection";
+
+    pub(crate) fn new(index: Arc<ReflectionIndex>) -> Self {
+        Self { index }
+    }
+}
+
+impl<B> tower::Service<axum::http::Request<B>> for ReflectionServer
+where
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError> + Send + 'static,
+{
+    type Response = axum::http::Response<tonic::body::BoxBody>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: axum::http::Request<B>) -> Self::Future {
+        let index = self.index.clone();
+        Box::pin(async move {
+            if !request.uri().path().ends_with("/ServerReflectionInfo") {
+                return Ok(tonic::Status::unimplemented("").into_http());
+            }
+            let codec = ProstCodec::<ServerReflectionResponse, ServerReflectionRequest>::default();
+            let mut grpc = tonic::server::Grpc::new(codec);
+            #[allow(clippy::result_large_err)]
+            let info = tower::service_fn(
+                move |request: tonic::Request<tonic::Streaming<ServerReflectionRequest>>| {
+                    let index = index.clone();
+                    let responses = request
+                        .into_inner()
+                        .map(move |request| request.map(|request| index.respond(request)));
+                    async move { Ok(tonic::Response::new(responses)) }
+                },
+            );
+            Ok(grpc.streaming(info, request).await)
+        })
+    }
+}
+
+/// proto3 JSON form of `ServerReflectionRequest`
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct JsonReflectionRequest {
+    host: String,
+    file_by_filename: Option<String>,
+    file_containing_symbol: Option<String>,
+    file_containing_extension: Option<ExtensionRequest>,
+    all_extension_numbers_of_type: Option<String>,
+    list_services: Option<String>,
+}
+
+impl From<JsonReflectionRequest> for ServerReflectionRequest {
+    fn from(request: JsonReflectionRequest) -> Self {
+        let message_request = if let Some(name) = request.file_by_filename {
+            Some(MessageRequest::FileByFilename(name))
+        } else if let Some(symbol) = request.file_containing_symbol {
+            Some(MessageRequest::FileContainingSymbol(symbol))
+        } else if let Some(extension) = request.file_containing_extension {
+            Some(MessageRequest::FileContainingExtension(extension))
+        } else if let Some(type_name) = request.all_extension_numbers_of_type {
+            Some(MessageRequest::AllExtensionNumbersOfType(type_name))
+        } else {
+            request.list_services.map(MessageRequest::ListServices)
+        };
+        Self {
+            host: request.host,
+            message_request,
+        }
+    }
+}
+
+/// Reflection requests are a single symbol or file name, so a small cap is plenty
+const MAX_JSON_REQUEST_SIZE: usize = 64 * 1024;
+
+/// `POST .../ServerReflectionInfo` with a JSON request, for HTTP/JSON clients
+pub(crate) async fn reflection_json(index: Arc<ReflectionIndex>, request: Request) -> Response {
+    let bytes = match read_limited_body(request.into_body(), MAX_JSON_REQUEST_SIZE).await {
+        Ok(bytes) => bytes,
+        Err(e) => return body_error_response(e),
+    };
+    let request: JsonReflectionRequest = if bytes.is_empty() {
+        JsonReflectionRequest::default()
+    } else {
+        match serde_json::from_slice(&bytes) {
+            Ok(request) => request,
+            Err(e) => {
+                return GrpcError::invalid_argument(&format!("invalid JSON body: {}", e))
+                    .into_response()
+            }
+        }
+    };
+    let mut request = ServerReflectionRequest::from(request);
+    // An empty body lists the services
+    if request.message_request.is_none() && bytes.is_empty() {
+        request.message_request = Some(MessageRequest::ListServices(String::new()));
+    }
+    Json(response_json(&index.respond(request))).into_response()
+}
+
+fn response_json(response: &ServerReflectionResponse) -> serde_json::Value {
+    let mut body = serde_json::json!({ "validHost": response.valid_host });
+    let base64 = base64::engine::general_purpose::STANDARD;
+    match &response.message_response {
+        Some(MessageResponse::FileDescriptorResponse(files)) => {
+            body["fileDescriptorResponse"] = serde_json::json!({
+                "fileDescriptorProto": files
+                    .file_descriptor_proto
+                    .iter()
+                    .map(|file| base64.encode(file))
+                    .collect::<Vec<_>>(),
+            });
+        }
+        Some(MessageResponse::AllExtensionNumbersResponse(numbers)) => {
+            body["allExtensionNumbersResponse"] = serde_json::json!({
+                "baseTypeName": numbers.base_type_name,
+                "extensionNumber": numbers.extension_number,
+            });
+        }
+        Some(MessageResponse::ListServicesResponse(list)) => {
+            body["listServicesResponse"] = serde_json::json!({
+                "service": list
+                    .service
+                    .iter()
+                    .map(|service| serde_json::json!({ "name": service.name }))
+                    .collect::<Vec<_>>(),
+            });
+        }
+        Some(MessageResponse::ErrorResponse(error)) => {
+            body["errorResponse"] = serde_json::json!({
+                "errorCode": error.error_code,
+                "errorMessage": error.error_message,
+            });
+        }
+        None => {}
+    }
+    body
+}
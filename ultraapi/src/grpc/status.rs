@@ -0,0 +1,347 @@
+//! Canonical gRPC status codes and `google.rpc.Status` error details
+//!
+//! ステータスコードと HTTP ステータスの対応は `google/rpc/code.proto` (grpc-gateway と同じ) に従います。
+//! エラー詳細は JSON では `{"@type": "type.googleapis.com/google.rpc.BadRequest", ...}`、
+//! ネイティブ gRPC では `grpc-status-details-bin` (protobuf の `google.rpc.Status`) として返します。
+
+use axum::http::StatusCode;
+use base64::Engine as _;
+use prost::Message;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::json::{format_duration, parse_duration};
+use crate::{ApiError, HttpExceptionDetail, ValidationLocItem};
+
+pub(crate) const BAD_REQUEST_TYPE: &str = "type.googleapis.com/google.rpc.BadRequest";
+pub(crate) const RETRY_INFO_TYPE: &str = "type.googleapis.com/google.rpc.RetryInfo";
+pub(crate) const ERROR_INFO_TYPE: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+
+/// Canonical gRPC status codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum GrpcCode {
+    Ok = 0,
+    Cancelled = 1,
+    Unknown = 2,
+    InvalidArgument = 3,
+    DeadlineExceeded = 4,
+    NotFound = 5,
+    AlreadyExists = 6,
+    PermissionDenied = 7,
+    ResourceExhausted = 8,
+    FailedPrecondition = 9,
+    Aborted = 10,
+    OutOfRange = 11,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+    DataLoss = 15,
+    Unauthenticated = 16,
+}
+
+impl GrpcCode {
+    /// Unknown values map to `Unknown`
+    pub fn from_i32(code: i32) -> Self {
+        match code {
+            0 => Self::Ok,
+            1 => Self::Cancelled,
+            3 => Self::InvalidArgument,
+            4 => Self::DeadlineExceeded,
+            5 => Self::NotFound,
+            6 => Self::AlreadyExists,
+            7 => Self::PermissionDenied,
+            8 => Self::ResourceExhausted,
+            9 => Self::FailedPrecondition,
+            10 => Self::Aborted,
+            11 => Self::OutOfRange,
+            12 => Self::Unimplemented,
+            13 => Self::Internal,
+            14 => Self::Unavailable,
+            15 => Self::DataLoss,
+            16 => Self::Unauthenticated,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn as_i32(self) -> i32 {
+        self as i32
+    }
+
+    /// Canonical name (`NOT_FOUND`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Cancelled => "CANCELLED",
+            Self::Unknown => "UNKNOWN",
+            Self::InvalidArgument => "INVALID_ARGUMENT",
+            Self::DeadlineExceeded => "DEADLINE_EXCEEDED",
+            Self::NotFound => "NOT_FOUND",
+            Self::AlreadyExists => "ALREADY_EXISTS",
+            Self::PermissionDenied => "PERMISSION_DENIED",
+            Self::ResourceExhausted => "RESOURCE_EXHAUSTED",
+            Self::FailedPrecondition => "FAILED_PRECONDITION",
+            Self::Aborted => "ABORTED",
+            Self::OutOfRange => "OUT_OF_RANGE",
+            Self::Unimplemented => "UNIMPLEMENTED",
+            Self::Internal => "INTERNAL",
+            Self::Unavailable => "UNAVAILABLE",
+            Self::DataLoss => "DATA_LOSS",
+            Self::Unauthenticated => "UNAUTHENTICATED",
+        }
+    }
+
+    /// HTTP status used when transcoding to HTTP/JSON
+    pub fn http_status(self) -> StatusCode {
+        match self {
+            Self::Ok => StatusCode::OK,
+            Self::Cancelled => StatusCode::from_u16(499).unwrap(),
+            Self::Unknown | Self::Internal | Self::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::InvalidArgument | Self::FailedPrecondition | Self::OutOfRange => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::AlreadyExists | Self::Aborted => StatusCode::CONFLICT,
+            Self::PermissionDenied => StatusCode::FORBIDDEN,
+            Self::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+            Self::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+            Self::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Unauthenticated => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    /// gRPC code for an HTTP error raised by REST code (`ApiError`, `HttpException`, middleware)
+    pub fn from_http_status(status: StatusCode) -> Self {
+        match status.as_u16() {
+            200..=399 => Self::Ok,
+            400 | 422 => Self::InvalidArgument,
+            401 => Self::Unauthenticated,
+            403 => Self::PermissionDenied,
+            404 => Self::NotFound,
+            409 => Self::AlreadyExists,
+            412 => Self::FailedPrecondition,
+            416 => Self::OutOfRange,
+            429 => Self::ResourceExhausted,
+            499 => Self::Cancelled,
+            501 => Self::Unimplemented,
+            502 | 503 => Self::Unavailable,
+            504 => Self::DeadlineExceeded,
+            500..=599 => Self::Internal,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for GrpcCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// --- google.rpc messages (binary form for `grpc-status-details-bin`) ---
+
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<prost_types::Any>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct BadRequest {
+    #[prost(message, repeated, tag = "1")]
+    field_violations: Vec<FieldViolation>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct FieldViolation {
+    #[prost(string, tag = "1")]
+    field: String,
+    #[prost(string, tag = "2")]
+    description: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RetryInfo {
+    #[prost(message, optional, tag = "1")]
+    retry_delay: Option<prost_types::Duration>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ErrorInfo {
+    #[prost(string, tag = "1")]
+    reason: String,
+    #[prost(string, tag = "2")]
+    domain: String,
+    #[prost(map = "string, string", tag = "3")]
+    metadata: std::collections::HashMap<String, String>,
+}
+
+/// `google.rpc.BadRequest` JSON detail
+pub(crate) fn bad_request_detail(violations: Vec<(String, String)>) -> Value {
+    json!({
+        "@type": BAD_REQUEST_TYPE,
+        "fieldViolations": violations
+            .into_iter()
+            .map(|(field, description)| json!({ "field": field, "description": description }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// `google.rpc.RetryInfo` JSON detail
+pub(crate) fn retry_info_detail(delay: Duration) -> Value {
+    json!({
+        "@type": RETRY_INFO_TYPE,
+        "retryDelay": format_duration(delay.as_secs() as i64, delay.subsec_nanos() as i32),
+    })
+}
+
+/// Retry delay of a `RetryInfo` detail
+pub(crate) fn retry_delay(details: &[Value]) -> Option<Duration> {
+    let detail = details.iter().find(|d| d["@type"] == RETRY_INFO_TYPE)?;
+    let (seconds, nanos) = parse_duration(detail["retryDelay"].as_str()?)?;
+    Some(Duration::new(
+        u64::try_from(seconds).ok()?,
+        u32::try_from(nanos).ok()?,
+    ))
+}
+
+/// Encode `google.rpc.Status` for the `grpc-status-details-bin` trailer.
+///
+/// BadRequest / RetryInfo / ErrorInfo details are converted to protobuf; other JSON
+/// details have no binary form and are dropped.
+pub(crate) fn encode_status(code: i32, message: &str, details: &[Value]) -> Vec<u8> {
+    let details = details
+        .iter()
+        .filter_map(|detail| {
+            let value = match detail["@type"].as_str()? {
+                BAD_REQUEST_TYPE => BadRequest {
+                    field_violations: detail["fieldViolations"]
+                        .as_array()?
+                        .iter()
+                        .map(|v| FieldViolation {
+                            field: v["field"].as_str().unwrap_or_default().to_string(),
+                            description: v["description"].as_str().unwrap_or_default().to_string(),
+                        })
+                        .collect(),
+                }
+                .encode_to_vec(),
+                RETRY_INFO_TYPE => {
+                    let delay = retry_delay(std::slice::from_ref(detail))?;
+                    RetryInfo {
+                        retry_delay: Some(prost_types::Duration {
+                            seconds: delay.as_secs() as i64,
+                            nanos: delay.subsec_nanos() as i32,
+                        }),
+                    }
+                    .encode_to_vec()
+                }
+                ERROR_INFO_TYPE => ErrorInfo {
+                    reason: detail["reason"].as_str().unwrap_or_default().to_string(),
+                    domain: detail["domain"].as_str().unwrap_or_default().to_string(),
+                    metadata: detail["metadata"]
+                        .as_object()
+                        .map(|m| {
+                            m.iter()
+                                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                }
+                .encode_to_vec(),
+                _ => return None,
+            };
+            Some(prost_types::Any {
+                type_url: detail["@type"].as_str()?.to_string(),
+                value,
+            })
+        })
+        .collect();
+
+    RpcStatus {
+        code,
+        message: message.to_string(),
+        details,
+    }
+    .encode_to_vec()
+}
+
+/// `grpc-status-details-bin` header value
+pub(crate) fn encode_status_header(code: i32, message: &str, details: &[Value]) -> String {
+    base64::engine::general_purpose::STANDARD_NO_PAD.encode(encode_status(code, message, details))
+}
+
+/// Field violations from FastAPI-style validation errors (`loc` without the source)
+pub(crate) fn validation_violations(detail: &Value) -> Vec<(String, String)> {
+    detail
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let loc = item["loc"].as_array()?;
+                    let field = loc
+                        .iter()
+                        .skip(1)
+                        .map(|part| match part {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(".");
+                    Some((field, item["msg"].as_str()?.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn loc_field(loc: &[ValidationLocItem]) -> String {
+    loc.iter()
+        .skip(1)
+        .map(|part| match part {
+            ValidationLocItem::Str(s) => s.clone(),
+            ValidationLocItem::Int(i) => i.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+pub(crate) fn retry_after(headers: &axum::http::HeaderMap) -> Option<Duration> {
+    headers
+        .get(axum::http::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Message, code and details of an `ApiError`
+pub(crate) fn api_error_parts(error: &ApiError) -> (GrpcCode, String, Vec<Value>) {
+    let code = GrpcCode::from_http_status(error.status);
+    let message = match &error.http_detail {
+        Some(HttpExceptionDetail::String(detail)) => detail.clone(),
+        Some(HttpExceptionDetail::Json(detail)) => detail.to_string(),
+        None => error.error.clone(),
+    };
+    let mut details = Vec::new();
+    if !error.detail.is_empty() {
+        details.push(bad_request_detail(
+            error
+                .detail
+                .iter()
+                .map(|d| (loc_field(&d.loc), d.msg.clone()))
+                .collect(),
+        ));
+    }
+    if let Some(delay) = retry_after(&error.headers) {
+        details.push(retry_info_detail(delay));
+    }
+    (code, message, details)
+}
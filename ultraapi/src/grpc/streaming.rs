@@ -0,0 +1,214 @@
+//! Streaming gRPC methods over the HTTP/JSON gateway
+//!
+//! サーバーストリーミングのメソッドは `application/x-ndjson` (既定) で、
+//! `Accept: text/event-stream` のときは SSE で返します。NDJSON の各行は grpc-gateway と同じく
+//! `{"result": ...}` / `{"error": ...}` で包まれ、エラーの後にストリームは終了します。
+//! クライアントストリーミングのメソッドはリクエストボディを NDJSON (1 行 1 メッセージ) として
+//! 読み込み、[`GrpcMessageStream`] でハンドラーに渡します。
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap},
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures_util::{Stream, StreamExt};
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+use super::{select_response_field, GrpcError, GrpcRequest};
+use crate::StreamingResponse;
+
+/// Messages produced by a streaming handler (JSON-encoded, like `GrpcResponse::body`)
+pub type GrpcStream = Pin<Box<dyn Stream<Item = Result<serde_json::Value, GrpcError>> + Send>>;
+
+/// A server-streaming gRPC handler function
+///
+/// Returning `Err` before the first message is sent as a regular gRPC error
+/// response (mapped HTTP status); errors yielded by the stream end the stream.
+pub type GrpcStreamingHandler = Arc<
+    dyn Fn(
+            GrpcRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<GrpcStream, GrpcError>> + Send + Sync>>
+        + Send
+        + Sync,
+>;
+
+/// Request messages of a client-streaming call, read from an NDJSON request body
+///
+/// Blank lines are skipped. A line that is not a JSON object yields an
+/// `INVALID_ARGUMENT` error and a line longer than `GrpcService::max_body_size` a
+/// `RESOURCE_EXHAUSTED` error; either ends the stream.
+pub struct GrpcMessageStream {
+    receiver: mpsc::Receiver<Result<serde_json::Value, GrpcError>>,
+}
+
+impl GrpcMessageStream {
+    /// A stream of already decoded messages (useful when calling handlers directly)
+    pub fn new(messages: impl IntoIterator<Item = serde_json::Value>) -> Self {
+        let messages: Vec<_> = messages.into_iter().collect();
+        let (sender, receiver) = mpsc::channel(messages.len().max(1));
+        for message in messages {
+            let _ = sender.try_send(Ok(message));
+        }
+        Self { receiver }
+    }
+
+    /// Read the request body line by line in the background
+    pub(crate) fn from_body(body: Body, max_line: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(16);
+        tokio::spawn(async move {
+            let mut chunks = body.into_data_stream();
+            let mut buffer: Vec<u8> = Vec::new();
+            loop {
+                match chunks.next().await {
+                    Some(Ok(chunk)) => {
+                        // Only the new bytes can hold a newline not seen yet
+                        let mut search = buffer.len();
+                        buffer.extend_from_slice(&chunk);
+                        let mut start = 0;
+                        while let Some(offset) = buffer[search..].iter().position(|b| *b == b'\n') {
+                            let end = search + offset;
+                            if end - start > max_line {
+                                send_too_long(&sender, max_line).await;
+                                return;
+                            }
+                            if !send_line(&sender, &buffer[start..end]).await {
+                                return;
+                            }
+                            start = end + 1;
+                            search = start;
+                        }
+                        buffer.drain(..start);
+                        if buffer.len() > max_line {
+                            send_too_long(&sender, max_line).await;
+                            return;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        let _ = sender
+                            .send(Err(GrpcError::invalid_argument(&e.to_string())))
+                            .await;
+                        return;
+                    }
+                    None => {
+                        send_line(&sender, &buffer).await;
+                        return;
+                    }
+                }
+            }
+        });
+        Self { receiver }
+    }
+}
+
+impl Stream for GrpcMessageStream {
+    type Item = Result<serde_json::Value, GrpcError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl std::fmt::Debug for GrpcMessageStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GrpcMessageStream").finish_non_exhaustive()
+    }
+}
+
+/// Send one NDJSON line; `false` when the stream is over (bad line or receiver gone)
+async fn send_line(
+    sender: &mpsc::Sender<Result<serde_json::Value, GrpcError>>,
+    line: &[u8],
+) -> bool {
+    if line.trim_ascii().is_empty() {
+        return true;
+    }
+    let message = match serde_json::from_slice::<serde_json::Value>(line) {
+        Ok(message @ serde_json::Value::Object(_)) => Ok(message),
+        Ok(_) => Err(GrpcError::invalid_argument(
+            "each NDJSON line must be a JSON object",
+        )),
+        Err(e) => Err(GrpcError::invalid_argument(&format!(
+            "invalid NDJSON line: {}",
+            e
+        ))),
+    };
+    let keep_going = message.is_ok();
+    sender.send(message).await.is_ok() && keep_going
+}
+
+async fn send_too_long(sender: &mpsc::Sender<Result<serde_json::Value, GrpcError>>, limit: usize) {
+    let _ = sender
+        .send(Err(GrpcError::resource_exhausted(&format!(
+            "NDJSON line exceeds the limit of {} bytes",
+            limit
+        ))))
+        .await;
+}
+
+/// `Accept: text/event-stream` selects SSE, anything else NDJSON
+pub(crate) fn wants_sse(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("text/event-stream"))
+}
+
+/// Render a message stream as NDJSON or SSE, applying the rule's `response_body`
+pub(crate) fn stream_response(
+    stream: GrpcStream,
+    response_body: Option<&str>,
+    sse: bool,
+) -> Response {
+    let response_body = response_body.map(str::to_string);
+    let messages = until_error(stream).map(move |item| {
+        item.map(|message| match &response_body {
+            Some(field) => select_response_field(message, field),
+            None => message,
+        })
+    });
+
+    if sse {
+        let events = messages.map(|item| {
+            let event = match item {
+                Ok(message) => Event::default().data(message.to_string()),
+                Err(error) => Event::default()
+                    .event("error")
+                    .data(serde_json::to_string(&error).unwrap_or_default()),
+            };
+            Ok::<_, Infallible>(event)
+        });
+        return Sse::new(events).into_response();
+    }
+
+    let lines = messages.map(|item| {
+        let line = match item {
+            Ok(message) => serde_json::json!({ "result": message }),
+            Err(error) => serde_json::json!({ "error": error }),
+        };
+        Ok::<_, Infallible>(bytes::Bytes::from(format!("{}\n", line)))
+    });
+    StreamingResponse::from_infallible_stream(lines)
+        .content_type("application/x-ndjson")
+        .into_response()
+}
+
+/// Pass messages through up to and including the first error
+fn until_error(
+    stream: GrpcStream,
+) -> impl Stream<Item = Result<serde_json::Value, GrpcError>> + Send {
+    futures_util::stream::unfold(Some(stream), |stream| async move {
+        let mut stream = stream?;
+        match stream.next().await? {
+            Ok(message) => Some((Ok(message), Some(stream))),
+            Err(error) => Some((Err(error), None)),
+        }
+    })
+}
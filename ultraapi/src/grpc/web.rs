@@ -0,0 +1,365 @@
+//! gRPC-Web on the transcoding gateway
+//!
+//! ブラウザの grpc-web クライアント向けに、`POST /{package}.{Service}/{Method}` で
+//! `application/grpc-web(+proto)` (バイナリ) と `application/grpc-web-text` (base64) を受け付けます。
+//! トレーラー (`grpc-status` / `grpc-message` / `grpc-status-details-bin`) はボディ末尾の
+//! トレーラーフレームで返すため、Envoy などのプロキシは不要です。
+//!
+//! protobuf のメッセージはディスクリプタ (`GrpcService::from_descriptor`) で JSON に変換して
+//! ハンドラーに渡します。ディスクリプタのないサービスは `application/grpc-web+json` で呼び出せます。
+//! アプリに CORS が設定されていない場合は、プリフライトへの応答と `Access-Control-Allow-Origin`
+//! の付与もここで行います。
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use base64::Engine;
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::{Stream, StreamExt};
+use std::convert::Infallible;
+
+use super::native::percent_encode;
+use super::{call_unary, read_limited_body, GrpcError, GrpcMessageStream, GrpcMetadata};
+use super::{GrpcMethod, GrpcRequest, GrpcService, GrpcStream, MethodDescriptor};
+use crate::AppState;
+
+/// Response headers browsers may read (trailers-only responses carry the status there)
+pub(crate) const EXPOSED_HEADERS: &str = "grpc-status, grpc-message, grpc-status-details-bin";
+
+/// Request headers sent by the grpc-web clients
+const ALLOWED_HEADERS: &str = "content-type, x-grpc-web, x-user-agent, grpc-timeout, authorization";
+
+const DATA_FRAME: u8 = 0x00;
+const COMPRESSED_FLAG: u8 = 0x01;
+const TRAILER_FRAME: u8 = 0x80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Codec {
+    Proto,
+    Json,
+}
+
+/// Framing and message encoding selected by the request content-type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WebFormat {
+    text: bool,
+    codec: Codec,
+}
+
+impl WebFormat {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let content_type = headers.get(header::CONTENT_TYPE)?.to_str().ok()?;
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        let (text, subtype) = if let Some(rest) = essence.strip_prefix("application/grpc-web-text")
+        {
+            (true, rest)
+        } else {
+            (false, essence.strip_prefix("application/grpc-web")?)
+        };
+        let codec = match subtype {
+            "" | "+proto" => Codec::Proto,
+            "+json" => Codec::Json,
+            _ => return None,
+        };
+        Some(Self { text, codec })
+    }
+
+    pub(crate) fn content_type(self) -> &'static str {
+        match (self.text, self.codec) {
+            (false, Codec::Proto) => "application/grpc-web+proto",
+            (false, Codec::Json) => "application/grpc-web+json",
+            (true, Codec::Proto) => "application/grpc-web-text+proto",
+            (true, Codec::Json) => "application/grpc-web-text+json",
+        }
+    }
+}
+
+/// gRPC-Web endpoint of one method
+pub(crate) struct WebBinding {
+    service: GrpcService,
+    method: GrpcMethod,
+    /// Answer CORS requests here (the app has no CORS layer)
+    cors: bool,
+}
+
+impl WebBinding {
+    pub(crate) fn new(service: &GrpcService, method: &GrpcMethod, cors: bool) -> Self {
+        Self {
+            service: service.clone(),
+            method: method.clone(),
+            cors,
+        }
+    }
+
+    pub(crate) async fn handle(
+        &self,
+        state: AppState,
+        request: axum::extract::Request,
+    ) -> Response {
+        let Some(format) = WebFormat::from_headers(request.headers()) else {
+            return GrpcError::invalid_argument(
+                "gRPC-Web calls need an application/grpc-web content-type",
+            )
+            .into_response();
+        };
+        let (parts, body) = request.into_parts();
+
+        let frames = match self.call(state, &parts.headers, format, body).await {
+            Ok(messages) => self.encode_messages(format, messages).boxed(),
+            Err(error) => futures_util::stream::iter([trailer_frame(Some(&error))]).boxed(),
+        };
+        let frames = frames.map(move |frame| {
+            let frame = if format.text {
+                Bytes::from(base64::engine::general_purpose::STANDARD.encode(frame))
+            } else {
+                frame
+            };
+            Ok::<_, Infallible>(frame)
+        });
+
+        let mut response = Response::new(Body::from_stream(frames));
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(format.content_type()),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            HeaderValue::from_static(EXPOSED_HEADERS),
+        );
+        if self.cors {
+            if let Some(origin) = parts.headers.get(header::ORIGIN) {
+                headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+                headers.insert(header::VARY, HeaderValue::from_static("origin"));
+            }
+        }
+        response
+    }
+
+    /// Decode the request messages and run the handler
+    async fn call(
+        &self,
+        state: AppState,
+        headers: &HeaderMap,
+        format: WebFormat,
+        body: Body,
+    ) -> Result<GrpcStream, GrpcError> {
+        let bytes = read_limited_body(body, self.service.max_body_size).await?;
+        let bytes = if format.text {
+            decode_text(&bytes)?
+        } else {
+            bytes.to_vec()
+        };
+        let messages = read_frames(&bytes)?
+            .into_iter()
+            .map(|payload| self.decode_message(format, payload))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (body, messages) = if self.method.client_streaming {
+            (
+                serde_json::Value::Object(serde_json::Map::new()),
+                Some(GrpcMessageStream::new(messages)),
+            )
+        } else {
+            let mut messages = messages.into_iter();
+            match (messages.next(), messages.next()) {
+                (Some(message), None) => (message, None),
+                _ => {
+                    return Err(GrpcError::invalid_argument(
+                        "expected exactly one request message",
+                    ))
+                }
+            }
+        };
+        let request = GrpcRequest {
+            body,
+            path_params: Default::default(),
+            query_params: Default::default(),
+            method_path: self.method.full_path.clone(),
+            metadata: GrpcMetadata::from_headers(headers),
+            state,
+            descriptor: self.method.descriptor.clone(),
+            messages,
+        };
+
+        if self.method.streaming {
+            if let Some(handler) = self.service.get_streaming_handler(&self.method.name) {
+                return handler(request).await;
+            }
+        }
+        let message = call_unary(&self.service, &self.method, request).await?;
+        Ok(Box::pin(futures_util::stream::iter([Ok(message)])))
+    }
+
+    fn decode_message(
+        &self,
+        format: WebFormat,
+        payload: &[u8],
+    ) -> Result<serde_json::Value, GrpcError> {
+        match (format.codec, &self.method.descriptor) {
+            (Codec::Json, _) => serde_json::from_slice(payload)
+                .map_err(|e| GrpcError::invalid_argument(&format!("invalid JSON message: {}", e))),
+            (Codec::Proto, Some(descriptor)) => descriptor
+                .decode_request(payload)
+                .map_err(|e| GrpcError::invalid_argument(&e.to_string())),
+            (Codec::Proto, None) => Err(no_descriptor()),
+        }
+    }
+
+    /// Data frames up to the first error, then the trailer frame
+    fn encode_messages(
+        &self,
+        format: WebFormat,
+        messages: GrpcStream,
+    ) -> impl Stream<Item = Bytes> + Send + 'static {
+        let descriptor = self.method.descriptor.clone();
+        futures_util::stream::unfold(Some(messages), move |messages| {
+            let descriptor = descriptor.clone();
+            async move {
+                let mut messages = messages?;
+                let error = match messages.next().await {
+                    Some(Ok(message)) => {
+                        match encode_message(format.codec, descriptor.as_ref(), &message) {
+                            Ok(payload) => {
+                                return Some((frame(DATA_FRAME, &payload), Some(messages)))
+                            }
+                            Err(error) => Some(error),
+                        }
+                    }
+                    Some(Err(error)) => Some(error),
+                    None => None,
+                };
+                Some((trailer_frame(error.as_ref()), None))
+            }
+        })
+    }
+}
+
+fn encode_message(
+    codec: Codec,
+    descriptor: Option<&MethodDescriptor>,
+    message: &serde_json::Value,
+) -> Result<Vec<u8>, GrpcError> {
+    match (codec, descriptor) {
+        (Codec::Json, _) => {
+            serde_json::to_vec(message).map_err(|e| GrpcError::internal(&e.to_string()))
+        }
+        (Codec::Proto, Some(descriptor)) => descriptor
+            .encode_response(message)
+            .map_err(|e| GrpcError::internal(&e.to_string())),
+        (Codec::Proto, None) => Err(no_descriptor()),
+    }
+}
+
+fn no_descriptor() -> GrpcError {
+    GrpcError::unimplemented("method has no protobuf descriptor; use application/grpc-web+json")
+}
+
+/// Answer a CORS preflight for a gRPC-Web method
+pub(crate) fn preflight(headers: &HeaderMap) -> Response {
+    let mut response = StatusCode::NO_CONTENT.into_response();
+    let response_headers = response.headers_mut();
+    let origin = headers
+        .get(header::ORIGIN)
+        .cloned()
+        .unwrap_or(HeaderValue::from_static("*"));
+    let allow_headers = headers
+        .get(header::ACCESS_CONTROL_REQUEST_HEADERS)
+        .cloned()
+        .unwrap_or(HeaderValue::from_static(ALLOWED_HEADERS));
+    response_headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    response_headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static("POST, OPTIONS"),
+    );
+    response_headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, allow_headers);
+    response_headers.insert(
+        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static(EXPOSED_HEADERS),
+    );
+    response_headers.insert(
+        header::ACCESS_CONTROL_MAX_AGE,
+        HeaderValue::from_static("86400"),
+    );
+    response_headers.insert(header::VARY, HeaderValue::from_static("origin"));
+    response
+}
+
+/// A length-prefixed message: 1 flag byte and a 4-byte big-endian length
+fn frame(flag: u8, payload: &[u8]) -> Bytes {
+    let mut frame = BytesMut::with_capacity(5 + payload.len());
+    frame.put_u8(flag);
+    frame.put_u32(payload.len() as u32);
+    frame.put_slice(payload);
+    frame.freeze()
+}
+
+/// The status as an HTTP/1 header block in the body
+fn trailer_frame(error: Option<&GrpcError>) -> Bytes {
+    let mut trailers = String::new();
+    match error {
+        None => trailers.push_str("grpc-status:0\r\n"),
+        Some(error) => {
+            trailers.push_str(&format!("grpc-status:{}\r\n", error.code));
+            trailers.push_str(&format!(
+                "grpc-message:{}\r\n",
+                percent_encode(&error.message)
+            ));
+            if !error.details.is_empty() {
+                trailers.push_str(&format!(
+                    "grpc-status-details-bin:{}\r\n",
+                    super::status::encode_status_header(error.code, &error.message, &error.details)
+                ));
+            }
+        }
+    }
+    frame(TRAILER_FRAME, trailers.as_bytes())
+}
+
+/// Payloads of the data frames of a request body
+fn read_frames(mut bytes: &[u8]) -> Result<Vec<&[u8]>, GrpcError> {
+    let mut payloads = Vec::new();
+    while !bytes.is_empty() {
+        if bytes.len() < 5 {
+            return Err(GrpcError::invalid_argument("truncated gRPC-Web frame"));
+        }
+        let flag = bytes[0];
+        let length = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize;
+        let Some(payload) = bytes.get(5..5 + length) else {
+            return Err(GrpcError::invalid_argument("truncated gRPC-Web frame"));
+        };
+        if flag & COMPRESSED_FLAG != 0 {
+            return Err(GrpcError::unimplemented(
+                "compressed gRPC-Web messages are not supported",
+            ));
+        }
+        if flag & TRAILER_FRAME == 0 {
+            payloads.push(payload);
+        }
+        bytes = &bytes[5 + length..];
+    }
+    Ok(payloads)
+}
+
+/// `grpc-web-text` bodies may be several padded base64 chunks back to back
+fn decode_text(text: &[u8]) -> Result<Vec<u8>, GrpcError> {
+    let text: Vec<u8> = text
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    let mut decoded = Vec::new();
+    let mut start = 0;
+    for (index, quad) in text.chunks(4).enumerate() {
+        let end = index * 4 + quad.len();
+        if quad.contains(&b'=') || end == text.len() {
+            base64::engine::general_purpose::STANDARD
+                .decode_vec(&text[start..end], &mut decoded)
+                .map_err(|e| GrpcError::invalid_argument(&format!("invalid base64: {}", e)))?;
+            start = end;
+        }
+    }
+    Ok(decoded)
+}
@@ -0,0 +1,134 @@
+//! Minimal protobuf wire-format reader/writer
+//!
+//! prost は未知フィールド (例: `google.api.http` 拡張) を保持しないため、
+//! ディスクリプタのオプションや動的メッセージはこのリーダーで直接走査します。
+
+/// A decoded field value
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum WireValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+/// Iterates over `(field_number, value)` pairs of an encoded message.
+pub(crate) struct WireReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> WireReader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self
+                .buf
+                .get(self.pos)
+                .ok_or_else(|| "truncated varint".to_string())?;
+            self.pos += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("varint too long".to_string())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.buf.len())
+            .ok_or_else(|| "truncated field".to_string())?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Read the next field, or `None` at the end of the buffer.
+    pub(crate) fn next_field(&mut self) -> Option<Result<(u32, WireValue<'a>), String>> {
+        if self.pos >= self.buf.len() {
+            return None;
+        }
+        Some(self.read_field())
+    }
+
+    fn read_field(&mut self) -> Result<(u32, WireValue<'a>), String> {
+        let key = self.varint()?;
+        let number = u32::try_from(key >> 3).map_err(|_| "invalid field number".to_string())?;
+        let value = match key & 0x7 {
+            0 => WireValue::Varint(self.varint()?),
+            1 => {
+                let bytes = self.take(8)?;
+                WireValue::Fixed64(u64::from_le_bytes(bytes.try_into().unwrap()))
+            }
+            2 => {
+                let len =
+                    usize::try_from(self.varint()?).map_err(|_| "length overflow".to_string())?;
+                WireValue::Bytes(self.take(len)?)
+            }
+            5 => {
+                let bytes = self.take(4)?;
+                WireValue::Fixed32(u32::from_le_bytes(bytes.try_into().unwrap()))
+            }
+            other => return Err(format!("unsupported wire type {}", other)),
+        };
+        Ok((number, value))
+    }
+
+    /// Read a bare varint (packed repeated fields)
+    pub(crate) fn packed_varint(&mut self) -> Option<Result<u64, String>> {
+        (self.pos < self.buf.len()).then(|| self.varint())
+    }
+
+    /// Read a bare fixed-width value (packed repeated fields)
+    pub(crate) fn packed_fixed(&mut self, width: usize) -> Option<Result<u64, String>> {
+        (self.pos < self.buf.len()).then(|| {
+            let bytes = self.take(width)?;
+            let mut le = [0u8; 8];
+            le[..width].copy_from_slice(bytes);
+            Ok(u64::from_le_bytes(le))
+        })
+    }
+}
+
+/// Decode all fields of a message, failing on malformed input.
+pub(crate) fn fields(buf: &[u8]) -> Result<Vec<(u32, WireValue<'_>)>, String> {
+    let mut reader = WireReader::new(buf);
+    let mut out = Vec::new();
+    while let Some(field) = reader.next_field() {
+        out.push(field?);
+    }
+    Ok(out)
+}
+
+pub(crate) fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub(crate) fn put_key(out: &mut Vec<u8>, number: u32, wire_type: u8) {
+    put_varint(out, (u64::from(number) << 3) | u64::from(wire_type));
+}
+
+pub(crate) fn put_bytes(out: &mut Vec<u8>, number: u32, bytes: &[u8]) {
+    put_key(out, number, 2);
+    put_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+pub(crate) fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub(crate) fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
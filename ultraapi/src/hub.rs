@@ -0,0 +1,515 @@
+//! Connection hub for WebSocket and SSE handlers
+//!
+//! `Hub` は接続レジストリです。`UltraApiApp::dep(Hub::new())` で登録し、ハンドラでは
+//! `Dep<Hub>` として受け取ります。
+//!
+//! - 名前付きルームへの join / leave、ルーム・全体へのブロードキャスト
+//! - 接続 ID・ユーザー ID を指定した送信、ルームごとのプレゼンス一覧
+//! - 接続ごとの上限付き送信キュー。溢れた場合の挙動は [`SlowConsumer`] で選択
+//! - `HubConnection` の drop で切断扱い (全ルームから削除)、lifespan の shutdown で全接続を終了
+//!
+//! メッセージは送信時に一度だけ JSON にシリアライズされ、全接続で共有されます。
+//!
+//! ```ignore
+//! #[ws("/chat", incoming = ClientMsg, outgoing = ServerMsg)]
+//! async fn chat(mut socket: TypedSocket<ClientMsg, ServerMsg>, hub: Dep<Hub>) {
+//!     let mut connection = hub.connect(None);
+//!     let mut room = String::new();
+//!     loop {
+//!         tokio::select! {
+//!             incoming = socket.recv() => match incoming {
+//!                 Some(ClientMsg::Join { room: joined }) => {
+//!                     connection.leave(&room);
+//!                     connection.join(&joined);
+//!                     room = joined;
+//!                 }
+//!                 Some(ClientMsg::Say { text }) => {
+//!                     hub.broadcast(&room, &ServerMsg::Said { text });
+//!                 }
+//!                 None => break,
+//!             },
+//!             outgoing = connection.recv() => match outgoing {
+//!                 Some(message) => {
+//!                     if socket.send_raw(message.into()).await.is_err() {
+//!                         break;
+//!                     }
+//!                 }
+//!                 None => break,
+//!             },
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use futures_util::Stream;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::Notify;
+
+/// Identifier of a connection registered with a [`Hub`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(transparent)]
+pub struct ConnectionId(u64);
+
+impl fmt::Display for ConnectionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// What happens when a message arrives for a connection whose send queue is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlowConsumer {
+    /// Discard the oldest queued message to make room
+    #[default]
+    DropOldest,
+    /// Discard the new message
+    DropNewest,
+    /// Remove the connection from the hub; its `recv` returns `None`
+    Disconnect,
+}
+
+/// A message queued for a connection: JSON text, serialized once per broadcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HubMessage(Arc<str>);
+
+impl HubMessage {
+    /// The JSON text of the message
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Deserialize the message, e.g. into the `Out` type of a `TypedSocket`
+    pub fn decode<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(&self.0)
+    }
+}
+
+impl From<HubMessage> for axum::extract::ws::Message {
+    fn from(message: HubMessage) -> Self {
+        axum::extract::ws::Message::Text(message.as_str().into())
+    }
+}
+
+impl From<HubMessage> for axum::response::sse::Event {
+    fn from(message: HubMessage) -> Self {
+        axum::response::sse::Event::default().data(message.as_str())
+    }
+}
+
+/// A connection listed by [`Hub::members`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Presence {
+    pub connection_id: ConnectionId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+}
+
+/// Registry of live WebSocket / SSE connections. Clones share the same registry.
+#[derive(Clone)]
+pub struct Hub {
+    inner: Arc<HubInner>,
+    queue_capacity: usize,
+    slow_consumer: SlowConsumer,
+}
+
+struct HubInner {
+    state: Mutex<HubState>,
+    next_id: AtomicU64,
+}
+
+#[derive(Default)]
+struct HubState {
+    connections: HashMap<ConnectionId, Entry>,
+    rooms: BTreeMap<String, BTreeSet<ConnectionId>>,
+    closed: bool,
+}
+
+struct Entry {
+    user_id: Option<String>,
+    rooms: BTreeSet<String>,
+    queue: Arc<Queue>,
+}
+
+impl Default for Hub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hub {
+    /// A hub with 64-message send queues that drop the oldest message when full
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(HubInner {
+                state: Mutex::new(HubState::default()),
+                next_id: AtomicU64::new(1),
+            }),
+            queue_capacity: 64,
+            slow_consumer: SlowConsumer::default(),
+        }
+    }
+
+    /// Set the capacity of the send queue of connections registered from now on (at least 1)
+    pub fn queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = capacity.max(1);
+        self
+    }
+
+    /// Choose what happens when the send queue of a connection registered from now on is full
+    pub fn slow_consumer(mut self, policy: SlowConsumer) -> Self {
+        self.slow_consumer = policy;
+        self
+    }
+
+    /// Register a connection, optionally owned by a user.
+    ///
+    /// The connection is removed when the returned handle is dropped. After
+    /// [`Hub::close`], the handle is already closed.
+    pub fn connect(&self, user_id: Option<&str>) -> HubConnection {
+        let id = ConnectionId(self.inner.next_id.fetch_add(1, Ordering::Relaxed));
+        let queue = Arc::new(Queue::new(self.queue_capacity, self.slow_consumer));
+        let mut state = self.inner.state.lock();
+        if state.closed {
+            queue.close();
+        } else {
+            state.connections.insert(
+                id,
+                Entry {
+                    user_id: user_id.map(str::to_string),
+                    rooms: BTreeSet::new(),
+                    queue: queue.clone(),
+                },
+            );
+        }
+        HubConnection {
+            id,
+            hub: self.clone(),
+            queue,
+        }
+    }
+
+    /// Add a connection to a room. Returns `false` for unknown connections.
+    pub fn join(&self, connection: ConnectionId, room: &str) -> bool {
+        let mut state = self.inner.state.lock();
+        let Some(entry) = state.connections.get_mut(&connection) else {
+            return false;
+        };
+        entry.rooms.insert(room.to_string());
+        state
+            .rooms
+            .entry(room.to_string())
+            .or_default()
+            .insert(connection);
+        true
+    }
+
+    /// Remove a connection from a room. Returns `false` if it was not a member.
+    pub fn leave(&self, connection: ConnectionId, room: &str) -> bool {
+        let mut state = self.inner.state.lock();
+        let was_member = state
+            .connections
+            .get_mut(&connection)
+            .is_some_and(|entry| entry.rooms.remove(room));
+        if was_member {
+            state.remove_from_room(room, connection);
+        }
+        was_member
+    }
+
+    /// Remove a connection from the hub; its `recv` returns `None` once the queue is drained.
+    pub fn disconnect(&self, connection: ConnectionId) -> bool {
+        self.inner.state.lock().remove(connection)
+    }
+
+    /// Send a message to every member of a room. Returns the number of connections reached.
+    pub fn broadcast<T: Serialize + ?Sized>(&self, room: &str, message: &T) -> usize {
+        self.deliver(message, |state| state.members_of(room, None))
+    }
+
+    /// Send a message to every member of a room except one connection (usually the sender).
+    pub fn broadcast_except<T: Serialize + ?Sized>(
+        &self,
+        room: &str,
+        except: ConnectionId,
+        message: &T,
+    ) -> usize {
+        self.deliver(message, |state| state.members_of(room, Some(except)))
+    }
+
+    /// Send a message to every connection of the hub.
+    pub fn broadcast_all<T: Serialize + ?Sized>(&self, message: &T) -> usize {
+        self.deliver(message, |state| state.connections.keys().copied().collect())
+    }
+
+    /// Send a message to a single connection. Returns `false` if it was not delivered.
+    pub fn send_to<T: Serialize + ?Sized>(&self, connection: ConnectionId, message: &T) -> bool {
+        self.deliver(message, |_| vec![connection]) == 1
+    }
+
+    /// Send a message to every connection of a user.
+    pub fn send_to_user<T: Serialize + ?Sized>(&self, user_id: &str, message: &T) -> usize {
+        self.deliver(message, |state| {
+            state
+                .connections
+                .iter()
+                .filter(|(_, entry)| entry.user_id.as_deref() == Some(user_id))
+                .map(|(id, _)| *id)
+                .collect()
+        })
+    }
+
+    /// Connections in a room, ordered by connection id.
+    pub fn members(&self, room: &str) -> Vec<Presence> {
+        let state = self.inner.state.lock();
+        state
+            .rooms
+            .get(room)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| {
+                state.connections.get(id).map(|entry| Presence {
+                    connection_id: *id,
+                    user_id: entry.user_id.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Distinct users present in a room, sorted.
+    pub fn users(&self, room: &str) -> Vec<String> {
+        let users: BTreeSet<String> = self
+            .members(room)
+            .into_iter()
+            .filter_map(|presence| presence.user_id)
+            .collect();
+        users.into_iter().collect()
+    }
+
+    /// Names of the rooms with at least one member, sorted.
+    pub fn rooms(&self) -> Vec<String> {
+        self.inner.state.lock().rooms.keys().cloned().collect()
+    }
+
+    /// Rooms a connection has joined, sorted.
+    pub fn rooms_of(&self, connection: ConnectionId) -> Vec<String> {
+        self.inner
+            .state
+            .lock()
+            .connections
+            .get(&connection)
+            .map(|entry| entry.rooms.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Number of registered connections.
+    pub fn connection_count(&self) -> usize {
+        self.inner.state.lock().connections.len()
+    }
+
+    /// Remove every connection and refuse new ones. Runs automatically on lifespan
+    /// shutdown for a hub registered with `UltraApiApp::dep`.
+    pub fn close(&self) {
+        let mut state = self.inner.state.lock();
+        state.closed = true;
+        state.rooms.clear();
+        for (_, entry) in state.connections.drain() {
+            entry.queue.close();
+        }
+    }
+
+    /// True once [`Hub::close`] ran.
+    pub fn is_closed(&self) -> bool {
+        self.inner.state.lock().closed
+    }
+
+    fn deliver<T, F>(&self, message: &T, targets: F) -> usize
+    where
+        T: Serialize + ?Sized,
+        F: FnOnce(&HubState) -> Vec<ConnectionId>,
+    {
+        let Ok(text) = serde_json::to_string(message) else {
+            return 0;
+        };
+        let message = HubMessage(text.into());
+
+        let mut state = self.inner.state.lock();
+        let mut delivered = 0;
+        let mut overflowed = Vec::new();
+        for id in targets(&state) {
+            let Some(entry) = state.connections.get(&id) else {
+                continue;
+            };
+            match entry.queue.push(message.clone()) {
+                Push::Queued => delivered += 1,
+                Push::Dropped => {}
+                Push::Overflowed => overflowed.push(id),
+            }
+        }
+        for id in overflowed {
+            state.remove(id);
+        }
+        delivered
+    }
+}
+
+impl HubState {
+    fn members_of(&self, room: &str, except: Option<ConnectionId>) -> Vec<ConnectionId> {
+        self.rooms
+            .get(room)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|id| Some(*id) != except)
+            .collect()
+    }
+
+    fn remove_from_room(&mut self, room: &str, connection: ConnectionId) {
+        if let Some(members) = self.rooms.get_mut(room) {
+            members.remove(&connection);
+            if members.is_empty() {
+                self.rooms.remove(room);
+            }
+        }
+    }
+
+    fn remove(&mut self, connection: ConnectionId) -> bool {
+        let Some(entry) = self.connections.remove(&connection) else {
+            return false;
+        };
+        for room in &entry.rooms {
+            self.remove_from_room(room, connection);
+        }
+        entry.queue.close();
+        true
+    }
+}
+
+/// A connection registered with a [`Hub`]; dropping it removes the connection.
+pub struct HubConnection {
+    id: ConnectionId,
+    hub: Hub,
+    queue: Arc<Queue>,
+}
+
+impl HubConnection {
+    pub fn id(&self) -> ConnectionId {
+        self.id
+    }
+
+    /// The hub this connection belongs to
+    pub fn hub(&self) -> &Hub {
+        &self.hub
+    }
+
+    /// Join a room
+    pub fn join(&self, room: &str) -> bool {
+        self.hub.join(self.id, room)
+    }
+
+    /// Leave a room
+    pub fn leave(&self, room: &str) -> bool {
+        self.hub.leave(self.id, room)
+    }
+
+    /// Next message queued for this connection, or `None` once it was removed from the
+    /// hub (disconnect, slow-consumer policy or shutdown) and the queue is drained.
+    ///
+    /// Cancel safe: a message is only taken from the queue when it is returned.
+    pub async fn recv(&mut self) -> Option<HubMessage> {
+        self.queue.pop().await
+    }
+
+    /// The queued messages as a stream, e.g. for an `#[sse]` handler.
+    pub fn into_stream(self) -> impl Stream<Item = HubMessage> + Send + 'static {
+        futures_util::stream::unfold(self, |mut connection| async move {
+            let message = connection.recv().await?;
+            Some((message, connection))
+        })
+    }
+}
+
+impl Drop for HubConnection {
+    fn drop(&mut self) {
+        self.hub.disconnect(self.id);
+    }
+}
+
+enum Push {
+    Queued,
+    Dropped,
+    Overflowed,
+}
+
+/// Bounded single-consumer send queue of one connection
+struct Queue {
+    messages: Mutex<QueueState>,
+    notify: Notify,
+    capacity: usize,
+    slow_consumer: SlowConsumer,
+}
+
+struct QueueState {
+    messages: VecDeque<HubMessage>,
+    closed: bool,
+}
+
+impl Queue {
+    fn new(capacity: usize, slow_consumer: SlowConsumer) -> Self {
+        Self {
+            messages: Mutex::new(QueueState {
+                messages: VecDeque::new(),
+                closed: false,
+            }),
+            notify: Notify::new(),
+            capacity,
+            slow_consumer,
+        }
+    }
+
+    fn push(&self, message: HubMessage) -> Push {
+        let mut state = self.messages.lock();
+        if state.closed {
+            return Push::Dropped;
+        }
+        if state.messages.len() >= self.capacity {
+            match self.slow_consumer {
+                SlowConsumer::DropOldest => {
+                    state.messages.pop_front();
+                }
+                SlowConsumer::DropNewest => return Push::Dropped,
+                SlowConsumer::Disconnect => return Push::Overflowed,
+            }
+        }
+        state.messages.push_back(message);
+        drop(state);
+        self.notify.notify_one();
+        Push::Queued
+    }
+
+    async fn pop(&self) -> Option<HubMessage> {
+        loop {
+            {
+                let mut state = self.messages.lock();
+                if let Some(message) = state.messages.pop_front() {
+                    return Some(message);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            // notify_one stores a permit, so a push between the check and here is not lost
+            self.notify.notified().await;
+        }
+    }
+
+    fn close(&self) {
+        self.messages.lock().closed = true;
+        self.notify.notify_one();
+    }
+}
@@ -1,14 +1,21 @@
+pub mod asyncapi;
+pub mod deserialize;
+pub mod files;
 #[cfg(feature = "graphql")]
 pub mod graphql;
 pub mod grpc;
+pub mod hub;
 pub mod lifespan;
 pub mod middleware;
 pub mod openapi;
 pub mod response_tasks;
 pub mod session;
+pub mod sse;
 pub mod streaming;
 pub mod templates;
 pub mod test_client;
+pub mod upload;
+pub mod websocket;
 
 use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
 use axum::response::{IntoResponse, Response};
@@ -63,16 +70,21 @@ pub mod oauth2 {
 
 pub mod prelude {
     pub use crate::axum;
+    pub use crate::files::DiskFileResponse;
+    pub use crate::hub::{ConnectionId, Hub, HubConnection, HubMessage, SlowConsumer};
     pub use crate::inventory;
     pub use crate::response_tasks::{response_task_middleware, BackgroundTasks};
     pub use crate::schemars;
     pub use crate::serde;
     pub use crate::session::{SameSite, Session, SessionConfig};
+    pub use crate::sse::{EventLog, LastEventId, LoggedEvent, SseEvent};
     pub use crate::streaming::{
         bytes_stream, iter_stream, lines_stream, map_to_bytes, reader_stream,
-        reader_stream_infallible, string_stream,
+        reader_stream_infallible, string_stream, JsonArrayStream, JsonLines,
     };
     pub use crate::templates::{template_response, TemplateResponse, Templates};
+    pub use crate::upload::{MultipartForm, UploadFile, UploadLimits};
+    pub use crate::websocket::{InvalidMessage, TypedSocket};
     pub use crate::{
         lifespan::Lifecycle,
         middleware::{
@@ -100,16 +112,19 @@ pub mod prelude {
     };
     pub use crate::{sse_data, sse_event};
     pub use crate::{
-        test_client::{InProcessTestClient, TestClient, TestResponse},
+        test_client::{
+            InProcessTestClient, TestClient, TestEventStream, TestResponse, TestSseEvent,
+            TestWebSocket, TestWsMessage,
+        },
         ApiError, CookieOptions, CookieResponse, Dep, DependencyScope, Depends, FileResponse,
         Generator, HTTPException, HttpException, HttpExceptionDetail, RedirectResponse,
         ResponseClass, ResponseModelOptions, Scope, State, StreamingResponse, UltraApiApp,
-        UltraApiRouter, Validate, YieldDep,
+        UltraApiRouter, Validate, ValidationErrorDetail, ValidationSource, YieldDep,
     };
     pub use axum::extract::{Form, Multipart, Path, Query};
     pub use axum_extra::extract::{CookieJar, TypedHeader};
     pub use ultraapi_macros::{
-        api_model, delete, get, head, options, patch, post, put, sse, trace, ws,
+        api_model, delete, form_model, get, head, options, patch, post, put, sse, trace, ws,
     };
 }
 
@@ -163,6 +178,21 @@ macro_rules! sse_event {
 /// Validation trait generated by api_model attribute
 pub trait Validate {
     fn validate(&self) -> Result<(), Vec<String>>;
+
+    /// Structured variant of [`Validate::validate`].
+    ///
+    /// Each error carries a `loc` relative to the validated value, a machine-readable
+    /// `type` (e.g. `string_too_short`) and optionally the offending `input` and `ctx`.
+    /// The default implementation converts the `"field: message"` strings returned by
+    /// `validate()` into `value_error` entries.
+    fn validate_detailed(&self) -> Result<(), Vec<ValidationErrorDetail>> {
+        self.validate().map_err(|errors| {
+            errors
+                .into_iter()
+                .map(ValidationErrorDetail::from_message)
+                .collect()
+        })
+    }
 }
 
 /// Marker trait to indicate a type has custom validation logic via Validate.
@@ -223,8 +253,8 @@ pub trait HasValidate: Validate {}
 pub struct ValidatorInfo {
     /// Type name for matching at runtime
     pub type_name: &'static str,
-    /// Validation function - takes &dyn Any and returns Result
-    pub validate_fn: fn(&dyn std::any::Any) -> Result<(), Vec<String>>,
+    /// Validation function - takes &dyn Any and returns structured errors
+    pub validate_fn: fn(&dyn std::any::Any) -> Result<(), Vec<ValidationErrorDetail>>,
 }
 
 inventory::collect!(ValidatorInfo);
@@ -242,6 +272,12 @@ impl<T: 'static> ValidatedWrapper<T> {
     /// Validate the wrapped value if it has a registered validator.
     /// Returns Ok(()) if no validator is registered (non-api_model types).
     pub fn validate(value: &T) -> Result<(), Vec<String>> {
+        Self::validate_detailed(value)
+            .map_err(|errors| errors.iter().map(|e| e.to_message()).collect())
+    }
+
+    /// Like [`ValidatedWrapper::validate`], but returns structured errors.
+    pub fn validate_detailed(value: &T) -> Result<(), Vec<ValidationErrorDetail>> {
         // Get the simple type name (last segment of the full path)
         let full_type_name = std::any::type_name::<T>();
         let simple_name = full_type_name.rsplit("::").next().unwrap_or(full_type_name);
@@ -355,6 +391,7 @@ impl AppState {
 pub struct Dep<T: 'static + Send + Sync>(Arc<T>);
 
 impl<T: 'static + Send + Sync> Dep<T> {
+    #[allow(clippy::result_large_err)]
     pub fn from_app_state(state: &AppState) -> Result<Self, ApiError> {
         state.get::<T>().map(Dep).ok_or_else(|| {
             ApiError::internal(format!(
@@ -396,6 +433,7 @@ where
 pub struct State<T: 'static + Send + Sync>(Arc<T>);
 
 impl<T: 'static + Send + Sync> State<T> {
+    #[allow(clippy::result_large_err)]
     pub fn from_app_state(state: &AppState) -> Result<Self, ApiError> {
         state.get::<T>().map(State).ok_or_else(|| {
             ApiError::internal(format!(
@@ -504,8 +542,8 @@ where
         let result = Generator::generate(self.clone(), scope);
         Box::pin(async move {
             let output = result.await?;
-            // Wrap in a new Arc of type-erased Any
-            Ok(Arc::new(Erased(output)) as Arc<dyn std::any::Any + Send + Sync>)
+            // Type-erase as Arc<T> so that callers can downcast back to the output type
+            Ok(Arc::new(output) as Arc<dyn std::any::Any + Send + Sync>)
         })
     }
 
@@ -517,9 +555,6 @@ where
     }
 }
 
-/// Internal type for wrapping generator outputs
-struct Erased<T: ?Sized>(T);
-
 /// Marker trait for yield-based generators (FastAPI-style).
 ///
 /// Implementors yield a value, then run cleanup when the scope ends.
@@ -729,6 +764,7 @@ impl std::error::Error for DependencyError {}
 pub struct Depends<T: Send + Sync>(pub Arc<T>);
 
 impl<T: 'static + Send + Sync> Depends<T> {
+    #[allow(clippy::result_large_err)]
     pub fn from_app_state(_state: &AppState) -> Result<Self, ApiError> {
         // This is the fallback - actual resolution happens via DependsResolver
         // The macro-generated code uses DependsResolver for proper nested resolution
@@ -1502,6 +1538,24 @@ pub enum ValidationLocItem {
     Int(i64),
 }
 
+impl From<&str> for ValidationLocItem {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_string())
+    }
+}
+
+impl From<String> for ValidationLocItem {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl From<usize> for ValidationLocItem {
+    fn from(value: usize) -> Self {
+        Self::Int(value as i64)
+    }
+}
+
 impl ValidationLocItem {
     fn from_segment(segment: &str) -> Option<Self> {
         let segment = segment.trim();
@@ -1517,19 +1571,116 @@ impl ValidationLocItem {
     }
 }
 
+/// Request part a validation error originated from (first element of `loc`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationSource {
+    Body,
+    Query,
+    Path,
+    Header,
+    Cookie,
+}
+
+impl ValidationSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Body => "body",
+            Self::Query => "query",
+            Self::Path => "path",
+            Self::Header => "header",
+            Self::Cookie => "cookie",
+        }
+    }
+}
+
 /// FastAPI-compatible validation error item.
 ///
 /// FastAPI returns validation failures as:
-/// `{ "detail": [{"loc": [...], "msg": "...", "type": "..."}] }`
+/// `{ "detail": [{"loc": [...], "msg": "...", "type": "...", "input": ..., "ctx": {...}}] }`
+///
+/// `type` uses pydantic's error codes (`string_too_short`, `greater_than_equal`,
+/// `missing`, `json_invalid`, ...) so clients can branch on it and localise `msg`.
 #[derive(Debug, Clone, Serialize)]
 pub struct ValidationErrorDetail {
     pub loc: Vec<ValidationLocItem>,
     pub msg: String,
     #[serde(rename = "type")]
     pub error_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ctx: Option<serde_json::Value>,
 }
 
 impl ValidationErrorDetail {
+    /// Create an error with an empty location.
+    pub fn new(error_type: impl Into<String>, msg: impl Into<String>) -> Self {
+        Self {
+            loc: Vec::new(),
+            msg: msg.into(),
+            error_type: error_type.into(),
+            input: None,
+            ctx: None,
+        }
+    }
+
+    /// Append a field path (`"address.zip"`, `"items[3]"`) to the location.
+    pub fn at(mut self, field: &str) -> Self {
+        self.loc.extend(Self::parse_field_loc(field));
+        self
+    }
+
+    /// Attach the offending input value.
+    pub fn with_input<T: Serialize + ?Sized>(mut self, input: &T) -> Self {
+        self.input = serde_json::to_value(input).ok();
+        self
+    }
+
+    /// Attach error context such as `{"min_length": 3}`.
+    pub fn with_ctx(mut self, ctx: serde_json::Value) -> Self {
+        self.ctx = Some(ctx);
+        self
+    }
+
+    /// Re-root this error under the location of the field that holds the validated value.
+    pub fn nested_under(mut self, prefix: &[ValidationLocItem]) -> Self {
+        let mut loc = prefix.to_vec();
+        loc.append(&mut self.loc);
+        self.loc = loc;
+        self
+    }
+
+    /// Prefix the location with the request part the value came from.
+    pub fn with_source(self, source: ValidationSource) -> Self {
+        self.nested_under(&[ValidationLocItem::from(source.as_str())])
+    }
+
+    /// Render the location as a field path, e.g. `items[3].address.zip`.
+    pub fn field_path(&self) -> String {
+        let mut path = String::new();
+        for item in &self.loc {
+            match item {
+                ValidationLocItem::Int(index) => path.push_str(&format!("[{}]", index)),
+                ValidationLocItem::Str(name) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(name);
+                }
+            }
+        }
+        path
+    }
+
+    /// Render as the legacy `"field: message"` string used in `details`.
+    pub fn to_message(&self) -> String {
+        if self.loc.is_empty() {
+            self.msg.clone()
+        } else {
+            format!("{}: {}", self.field_path(), self.msg)
+        }
+    }
+
     fn parse_field_loc(field: &str) -> Vec<ValidationLocItem> {
         let mut parsed = Vec::new();
         let mut current = String::new();
@@ -1580,24 +1731,14 @@ impl ValidationErrorDetail {
         parsed
     }
 
-    fn from_message(message: String) -> Self {
-        // Convert "field: message" style errors into FastAPI-like shape.
+    /// Convert a `"field: message"` style error into a `value_error` entry
+    /// with a location relative to the validated value.
+    pub fn from_message(message: String) -> Self {
         if let Some((field, msg)) = message.split_once(':') {
-            let mut loc = vec![ValidationLocItem::Str("body".to_string())];
-            loc.extend(Self::parse_field_loc(field));
-
-            return Self {
-                loc,
-                msg: msg.trim().to_string(),
-                error_type: "value_error".to_string(),
-            };
+            return Self::new("value_error", msg.trim()).at(field);
         }
 
-        Self {
-            loc: vec![ValidationLocItem::Str("body".to_string())],
-            msg: message,
-            error_type: "value_error".to_string(),
-        }
+        Self::new("value_error", message)
     }
 }
 
@@ -1711,6 +1852,40 @@ impl ApiError {
         }
     }
 
+    pub fn payload_too_large(msg: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            error: msg.into(),
+            details: vec![],
+            detail: vec![],
+            http_detail: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Error with any status, for statuses without a dedicated constructor
+    pub fn with_status(status: StatusCode, msg: impl Into<String>) -> Self {
+        Self {
+            status,
+            error: msg.into(),
+            details: vec![],
+            detail: vec![],
+            http_detail: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    pub fn gateway_timeout(msg: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::GATEWAY_TIMEOUT,
+            error: msg.into(),
+            details: vec![],
+            detail: vec![],
+            http_detail: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
     /// Create FastAPI-like HTTPException payload (`{"detail": ...}`).
     pub fn http_exception(status: StatusCode, detail: impl Into<HttpExceptionDetail>) -> Self {
         HttpException::new(status, detail).into()
@@ -1732,7 +1907,7 @@ impl ApiError {
         let detail = errors
             .iter()
             .cloned()
-            .map(ValidationErrorDetail::from_message)
+            .map(|e| ValidationErrorDetail::from_message(e).with_source(ValidationSource::Body))
             .collect();
 
         Self {
@@ -1744,6 +1919,106 @@ impl ApiError {
             headers: HeaderMap::new(),
         }
     }
+
+    /// 422 error from structured validation errors whose `loc` is relative to `source`.
+    pub fn validation_errors(source: ValidationSource, errors: Vec<ValidationErrorDetail>) -> Self {
+        let details = errors
+            .iter()
+            .map(ValidationErrorDetail::to_message)
+            .collect();
+        let detail = errors.into_iter().map(|e| e.with_source(source)).collect();
+
+        Self {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            error: "Validation failed".into(),
+            details,
+            detail,
+            http_detail: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Map a `Path<T>` rejection to a 422 rooted at `path`.
+    ///
+    /// `names` are the path parameters in declaration order; they locate errors
+    /// axum reports by index (tuples) or without a key (single value).
+    pub fn from_path_rejection(
+        rejection: axum::extract::rejection::PathRejection,
+        names: &[&str],
+    ) -> Self {
+        use axum::extract::path::ErrorKind;
+        use axum::extract::rejection::PathRejection;
+
+        let kind = match rejection {
+            PathRejection::FailedToDeserializePathParams(e) => e.into_kind(),
+            other => return Self::bad_request(format!("Invalid path param: {}", other)),
+        };
+        let single = names.first().copied().unwrap_or_default();
+        let error = match kind {
+            ErrorKind::ParseErrorAtKey {
+                key,
+                value,
+                expected_type,
+            } => parse_error_detail(&value, expected_type).at(&key),
+            ErrorKind::ParseErrorAtIndex {
+                index,
+                value,
+                expected_type,
+            } => parse_error_detail(&value, expected_type)
+                .at(names.get(index).copied().unwrap_or(single)),
+            ErrorKind::ParseError {
+                value,
+                expected_type,
+            } => parse_error_detail(&value, expected_type).at(single),
+            ErrorKind::DeserializeError {
+                key,
+                value,
+                message,
+            } => ValidationErrorDetail::new("value_error", message)
+                .at(&key)
+                .with_input(&value),
+            ErrorKind::InvalidUtf8InPathParam { key } => {
+                ValidationErrorDetail::new("string_unicode", "Input should be a valid string")
+                    .at(&key)
+            }
+            other => return Self::bad_request(format!("Invalid path param: {}", other)),
+        };
+        Self::validation_errors(ValidationSource::Path, vec![error])
+    }
+
+    /// Map a `TypedHeader<T>` rejection to a 422 rooted at `header`.
+    pub fn from_typed_header_rejection(
+        rejection: axum_extra::typed_header::TypedHeaderRejection,
+    ) -> Self {
+        let name = rejection.name().as_str();
+        let error = if rejection.is_missing() {
+            ValidationErrorDetail::new("missing", "Field required").at(name)
+        } else {
+            ValidationErrorDetail::new("value_error", rejection.to_string()).at(name)
+        };
+        Self::validation_errors(ValidationSource::Header, vec![error])
+    }
+}
+
+/// pydantic-style error for a path segment that failed to parse as `expected_type`.
+fn parse_error_detail(value: &str, expected_type: &str) -> ValidationErrorDetail {
+    let (error_type, msg) = match expected_type {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => (
+            "int_parsing",
+            "Input should be a valid integer, unable to parse string as an integer".to_string(),
+        ),
+        "f32" | "f64" => (
+            "float_parsing",
+            "Input should be a valid number, unable to parse string as a number".to_string(),
+        ),
+        "bool" => (
+            "bool_parsing",
+            "Input should be a valid boolean, unable to interpret input".to_string(),
+        ),
+        other => ("value_error", format!("Input should be a valid {}", other)),
+    };
+    ValidationErrorDetail::new(error_type, msg).with_input(value)
 }
 
 impl IntoResponse for ApiError {
@@ -1832,6 +2107,8 @@ pub enum ResponseClass {
     Stream,
     /// Server-Sent Events response (text/event-stream)
     Sse,
+    /// Newline-delimited JSON stream (application/x-ndjson), returned as `JsonLines<S>`
+    NdJson,
     /// XML response (application/xml)
     Xml,
     /// File response with optional filename and content-type (application/octet-stream default)
@@ -1850,6 +2127,7 @@ impl ResponseClass {
             ResponseClass::Binary => "application/octet-stream",
             ResponseClass::Stream => "application/octet-stream",
             ResponseClass::Sse => "text/event-stream",
+            ResponseClass::NdJson => "application/x-ndjson",
             ResponseClass::Xml => "application/xml",
             ResponseClass::File => "application/octet-stream",
             // OpenAPI 用の最小対応（redirect は通常 body を持たない）
@@ -1866,6 +2144,8 @@ impl ResponseClass {
 /// - オプションのファイル名（Content-Disposition header で attachment; filename="..." を付与）
 /// - オプションの content-type（指定がない場合は application/octet-stream）
 ///
+/// 大きなファイルや Range リクエストが必要な場合は [`files::DiskFileResponse`] を使用してください。
+///
 /// # Example
 ///
 /// ```ignore
@@ -2717,6 +2997,16 @@ pub struct RouteInfo {
     pub external_docs_url: Option<&'static str>,
     /// External documentation description
     pub external_docs_description: Option<&'static str>,
+    /// Messages received from the client (`#[ws(..., incoming = T)]`), for AsyncAPI
+    pub incoming_message: Option<asyncapi::MessageSchema>,
+    /// Messages sent to the client (`#[ws(..., outgoing = T)]`, `SseEvent<T>` items), for AsyncAPI
+    pub outgoing_message: Option<asyncapi::MessageSchema>,
+    /// Event names of a typed `#[sse(..., events = [...])]` route
+    pub sse_events: &'static [&'static str],
+    /// Request body size limit in bytes (`#[body_limit("10MB")]`)
+    pub body_limit: Option<u64>,
+    /// Handler timeout (`#[timeout("5s")]`)
+    pub timeout: Option<std::time::Duration>,
     pub register_fn: fn(Router<AppState>) -> Router<AppState>,
     pub method_router_fn: fn() -> axum::routing::MethodRouter<AppState>,
 }
@@ -2746,6 +3036,40 @@ struct ProtectedRoute {
     required_scopes_by_scheme: HashMap<String, Vec<String>>,
 }
 
+/// A route and its `#[body_limit]` / `#[timeout]`, matched at runtime like `ProtectedRoute`.
+///
+/// Routes without limits are listed too, so a static route never inherits the limits of
+/// a parameterized sibling matching the same path.
+#[derive(Clone)]
+struct LimitedRoute {
+    method: String,
+    path_pattern: String,
+    limits: middleware::RequestLimits,
+}
+
+impl LimitedRoute {
+    fn new(route: &RouteInfo, path_pattern: String) -> Self {
+        Self {
+            method: route.method.to_string(),
+            path_pattern,
+            limits: middleware::RequestLimits {
+                body_limit: route.body_limit,
+                timeout: route.timeout,
+            },
+        }
+    }
+
+    /// Sort key putting static segments before parameters, like axum's router:
+    /// `/items/latest` wins over `/items/{id}` for `/items/latest`
+    fn specificity(&self) -> Vec<bool> {
+        self.path_pattern
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(is_path_param_segment)
+            .collect()
+    }
+}
+
 fn is_path_param_segment(segment: &str) -> bool {
     segment.starts_with('{') && segment.ends_with('}')
 }
@@ -3113,6 +3437,7 @@ pub struct UltraApiApp {
     docs_url: String,
     redoc_url: String,
     openapi_url: String,
+    asyncapi_url: String,
     servers: Vec<openapi::Server>,
     security_schemes: HashMap<String, openapi::SecurityScheme>,
     routers: Vec<UltraApiRouter>,
@@ -3136,8 +3461,18 @@ pub struct UltraApiApp {
     /// Catch panics in handlers and convert to 500 responses
     catch_panic: bool,
     /// Custom route additions for advanced use cases (GraphQL, WebSocket, etc.)
+    #[allow(clippy::type_complexity)]
     custom_route_additions:
         Vec<Box<dyn FnOnce(Router<AppState>) -> Router<AppState> + Send + Sync + 'static>>,
+    /// gRPC services added via `GrpcExt::grpc` (merged with `grpc_service!` registrations)
+    grpc_transcoder: Option<grpc::GrpcTranscoder>,
+    /// Native gRPC services added via `GrpcExt::grpc_service`
+    native_grpc_services: Vec<grpc::NativeGrpcService>,
+    /// Descriptor sets served by gRPC reflection (`GrpcExt::grpc_descriptors`)
+    grpc_descriptor_pools: Vec<grpc::DescriptorPool>,
+    /// GraphQL endpoints added via `graphql` (path, endpoint)
+    #[cfg(feature = "graphql")]
+    graphql_endpoints: Vec<(String, graphql::GraphQLEndpoint)>,
 }
 
 impl Default for UltraApiApp {
@@ -3162,6 +3497,7 @@ impl UltraApiApp {
             docs_url: "/docs".to_string(),
             redoc_url: "/redoc".to_string(),
             openapi_url: "/openapi.json".to_string(),
+            asyncapi_url: "/asyncapi.json".to_string(),
             servers: Vec::new(),
             security_schemes: HashMap::new(),
             routers: Vec::new(),
@@ -3175,6 +3511,11 @@ impl UltraApiApp {
             error_handler: None,
             catch_panic: false,
             custom_route_additions: Vec::new(),
+            grpc_transcoder: None,
+            native_grpc_services: Vec::new(),
+            grpc_descriptor_pools: Vec::new(),
+            #[cfg(feature = "graphql")]
+            graphql_endpoints: Vec::new(),
         }
     }
 
@@ -3262,6 +3603,22 @@ impl UltraApiApp {
         self
     }
 
+    /// Customize the AsyncAPI JSON endpoint path (default: `/asyncapi.json`).
+    ///
+    /// The document describes the `#[ws]` and `#[sse]` routes that OpenAPI leaves out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultraapi::prelude::*;
+    ///
+    /// let app = UltraApiApp::new().asyncapi_url("/api/asyncapi.json");
+    /// ```
+    pub fn asyncapi_url(mut self, path: &str) -> Self {
+        self.asyncapi_url = normalize_doc_path(path);
+        self
+    }
+
     pub fn dep<T: 'static + Send + Sync>(mut self, dep: T) -> Self {
         self.deps.insert(TypeId::of::<T>(), Arc::new(dep));
         self
@@ -3594,7 +3951,14 @@ impl UltraApiApp {
     pub fn webhook(mut self, name: &str, route: &'static RouteInfo) -> Self {
         let tags: Vec<String> = route.tags.iter().map(|s| s.to_string()).collect();
         let sec = Self::parse_security_requirements(route.security);
-        let operation = Self::build_operation(route, tags, &sec, &HashMap::new(), &HashSet::new());
+        let operation = Self::build_operation(
+            route,
+            tags,
+            &sec,
+            &HashMap::new(),
+            &HashSet::new(),
+            middleware::RequestLimits::default(),
+        );
         let mut path_item = openapi::PathItem::new();
         path_item.insert(route.method.to_lowercase(), operation);
         self.webhooks.insert(name.to_string(), path_item);
@@ -4040,6 +4404,29 @@ impl UltraApiApp {
         self
     }
 
+    /// Mount a GraphQL schema: POST / GET execution, GraphiQL for browsers and
+    /// WebSocket subscriptions at `{path}/ws`.
+    ///
+    /// The routes share the app middleware (CORS, auth, rate limiting, sessions), and
+    /// resolvers reach `AppState`, dependencies, the `Session` and the authenticated
+    /// `Credentials` through [`graphql::GraphQLContextExt`]. Subscriptions authenticate
+    /// with the `connection_init` payload.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let schema = Schema::build(Query, EmptyMutation, EmptySubscription).finish();
+    /// let app = UltraApiApp::new()
+    ///     .dep(Database::connect())
+    ///     .graphql("/graphql", schema);
+    /// ```
+    #[cfg(feature = "graphql")]
+    pub fn graphql(mut self, path: &str, endpoint: impl Into<graphql::GraphQLEndpoint>) -> Self {
+        self.graphql_endpoints
+            .push((path.to_string(), endpoint.into()));
+        self
+    }
+
     /// Set the templates directory for rendering HTML templates.
     ///
     /// The templates will be registered as a dependency that can be injected via `Dep<Templates>`.
@@ -4259,6 +4646,21 @@ impl UltraApiApp {
         self
     }
 
+    /// gRPC services to mount: explicit `grpc()` services plus `grpc_service!` registrations.
+    /// Returns `None` when the app has no gRPC services at all.
+    fn grpc_services(&self) -> Option<grpc::GrpcTranscoder> {
+        let transcoder = self
+            .grpc_transcoder
+            .clone()
+            .unwrap_or_default()
+            .with_registered_services();
+        if transcoder.services().next().is_some() {
+            Some(transcoder)
+        } else {
+            None
+        }
+    }
+
     fn collect_protected_routes(
         has_explicit: bool,
         resolved: &[ResolvedRoute],
@@ -4327,6 +4729,20 @@ impl UltraApiApp {
         protected_routes
     }
 
+    fn collect_limited_routes(has_explicit: bool, resolved: &[ResolvedRoute]) -> Vec<LimitedRoute> {
+        if has_explicit {
+            resolved
+                .iter()
+                .map(|r| LimitedRoute::new(r.route_info, r.full_axum_path()))
+                .collect()
+        } else {
+            inventory::iter::<&RouteInfo>
+                .into_iter()
+                .map(|route| LimitedRoute::new(route, route.axum_path.to_string()))
+                .collect()
+        }
+    }
+
     fn inferred_runtime_security_schemes(&self) -> Vec<middleware::SecuritySchemeConfig> {
         self.security_schemes
             .iter()
@@ -4472,9 +4888,16 @@ impl UltraApiApp {
         } else {
             Vec::new()
         };
+        let grpc_services = self.grpc_services();
+        let mut spec_value = spec.to_json_with_query_params(&self.routers);
+        if let Some(ref transcoder) = grpc_services {
+            transcoder.extend_openapi_parameters(&mut spec_value);
+        }
         let spec_json =
-            serde_json::to_string_pretty(&spec.to_json_with_query_params(&self.routers))
-                .expect("Failed to serialize OpenAPI spec");
+            serde_json::to_string_pretty(&spec_value).expect("Failed to serialize OpenAPI spec");
+        let asyncapi_url = self.asyncapi_url.clone();
+        let asyncapi_json = serde_json::to_string_pretty(&self.generate_asyncapi_spec())
+            .expect("Failed to serialize AsyncAPI document");
         let inferred_runtime_security_schemes = self.inferred_runtime_security_schemes();
 
         // Merge deps from routers
@@ -4497,6 +4920,26 @@ impl UltraApiApp {
             all_deps.insert(TypeId::of::<templates::Templates>(), Arc::new(tmpl));
         }
 
+        // gRPC health statuses shared by the Health service, handlers and lifespan hooks
+        let uses_grpc = grpc_services.is_some() || !self.native_grpc_services.is_empty();
+        let grpc_health = uses_grpc.then(|| {
+            let reporter = all_deps
+                .get(&TypeId::of::<grpc::HealthReporter>())
+                .and_then(|dep| dep.clone().downcast::<grpc::HealthReporter>().ok())
+                .map(|reporter| (*reporter).clone())
+                .unwrap_or_default();
+            all_deps.insert(
+                TypeId::of::<grpc::HealthReporter>(),
+                Arc::new(reporter.clone()),
+            );
+            reporter
+        });
+
+        // Connections registered with a Hub dependency end on lifespan shutdown
+        let connection_hub = all_deps
+            .get(&TypeId::of::<hub::Hub>())
+            .and_then(|dep| dep.clone().downcast::<hub::Hub>().ok());
+
         let state = AppState {
             deps: Arc::new(all_deps),
             request_dep_factories: Arc::new(self.request_dep_factories),
@@ -4526,6 +4969,14 @@ impl UltraApiApp {
             }),
         );
 
+        app = app.route(
+            &asyncapi_url,
+            axum::routing::get(move || {
+                let spec = asyncapi_json.clone();
+                async move { (StatusCode::OK, [("content-type", "application/json")], spec) }
+            }),
+        );
+
         app = app.route(
             &docs_url,
             axum::routing::get(move || {
@@ -4544,8 +4995,47 @@ impl UltraApiApp {
 
         // Add static files
         for (path, dir) in &self.static_files {
-            let static_service = tower_http::services::ServeDir::new(dir);
-            app = app.nest_service(path, static_service);
+            app = app.nest_service(path, files::static_files_service(dir.clone()));
+        }
+
+        // Reflection serves the descriptors of transcoded and registered services
+        let mut native_grpc_services = std::mem::take(&mut self.native_grpc_services);
+        let mut descriptor_pools = std::mem::take(&mut self.grpc_descriptor_pools);
+        if let Some(ref transcoder) = grpc_services {
+            descriptor_pools.extend(transcoder.descriptor_pools());
+        }
+        let reflection_index = Arc::new(grpc::ReflectionIndex::new(
+            &descriptor_pools,
+            native_grpc_services.iter().map(|service| service.name()),
+        ));
+
+        // Add gRPC transcoding routes (before CORS/auth so they share the same layers).
+        // gRPC-Web answers CORS itself only when the app has no CORS layer.
+        if let Some(ref transcoder) = grpc_services {
+            let web_cors = self.middleware.cors_config.is_none();
+            app = transcoder.add_routes(app, reflection_index.clone(), web_cors);
+        }
+
+        // GraphQL endpoints share the same layers as well
+        #[cfg(feature = "graphql")]
+        for (path, endpoint) in &self.graphql_endpoints {
+            app = app.merge(endpoint.clone().route(path));
+        }
+
+        // Native gRPC services are dispatched by content-type ahead of the routes above.
+        // Health and reflection are added unless the application provides its own.
+        if let Some(ref health) = grpc_health {
+            for builtin in grpc::builtin_services(health, &reflection_index) {
+                if !native_grpc_services
+                    .iter()
+                    .any(|service| service.name() == builtin.name())
+                {
+                    native_grpc_services.push(builtin);
+                }
+            }
+        }
+        if !native_grpc_services.is_empty() {
+            app = grpc::native::mount(&native_grpc_services, app, state.clone());
         }
 
         // Collect mounted apps before consuming self
@@ -4556,7 +5046,51 @@ impl UltraApiApp {
             app = app.layer(cors_config.clone().build());
         }
 
-        let protected = Arc::new(Self::collect_protected_routes(has_explicit, &resolved));
+        let mut protected = Self::collect_protected_routes(has_explicit, &resolved);
+        if let Some(ref transcoder) = grpc_services {
+            for service in transcoder.services() {
+                if service.security.is_empty() {
+                    continue;
+                }
+                for (method, path_pattern) in service.route_patterns() {
+                    protected.push(ProtectedRoute {
+                        method,
+                        path_pattern,
+                        allowed_security_schemes: service.security.clone(),
+                        required_scopes_by_scheme: HashMap::new(),
+                    });
+                }
+            }
+        }
+        #[cfg(feature = "graphql")]
+        for (path, endpoint) in &self.graphql_endpoints {
+            if endpoint.security_schemes().is_empty() {
+                continue;
+            }
+            let routes = [("GET", path.clone()), ("POST", path.clone())]
+                .into_iter()
+                .chain(endpoint.sdl_endpoint(path).map(|sdl| ("GET", sdl)));
+            for (method, path_pattern) in routes {
+                protected.push(ProtectedRoute {
+                    method: method.to_string(),
+                    path_pattern,
+                    allowed_security_schemes: endpoint.security_schemes().to_vec(),
+                    required_scopes_by_scheme: HashMap::new(),
+                });
+            }
+        }
+        for service in &native_grpc_services {
+            if service.security_schemes().is_empty() {
+                continue;
+            }
+            protected.push(ProtectedRoute {
+                method: "POST".to_string(),
+                path_pattern: service.path_pattern(),
+                allowed_security_schemes: service.security_schemes().to_vec(),
+                required_scopes_by_scheme: HashMap::new(),
+            });
+        }
+        let protected = Arc::new(protected);
 
         // If there are protected routes and auth isn't configured explicitly,
         // infer a reasonable default from declared OpenAPI security schemes.
@@ -4617,6 +5151,7 @@ impl UltraApiApp {
         // Add mounted sub-applications
         // For each mounted app, we need to add its routes with the path prefix
         // and set up its own /docs and /openapi.json handlers
+        let mut mounted_limited_routes = Vec::new();
         for (path, mut sub_app) in mounted_apps {
             // Get sub-app's resolved routes
             let sub_resolved = sub_app.resolve_routes();
@@ -4627,6 +5162,7 @@ impl UltraApiApp {
                 let full_path = ResolvedRoute::join_paths(&path, &r.full_axum_path());
                 let method_router = (r.route_info.method_router_fn)();
                 app = app.route(&full_path, method_router);
+                mounted_limited_routes.push(LimitedRoute::new(r.route_info, full_path));
             }
 
             // Generate sub-app's OpenAPI spec and swagger HTML
@@ -4671,16 +5207,58 @@ impl UltraApiApp {
             // Add sub-app's static files with path prefix
             for (sub_path, dir) in sub_app.static_files.drain(..) {
                 let full_path = ResolvedRoute::join_paths(&path, &sub_path);
-                let static_service = tower_http::services::ServeDir::new(dir);
-                app = app.nest_service(&full_path, static_service);
+                app = app.nest_service(&full_path, files::static_files_service(dir));
             }
         }
 
+        // GraphQL subscriptions authenticate during connection_init rather than in the
+        // auth layer: browsers cannot set headers on WebSocket handshakes.
+        #[cfg(feature = "graphql")]
+        for (path, endpoint) in std::mem::take(&mut self.graphql_endpoints) {
+            let auth = self
+                .middleware
+                .auth_layer
+                .clone()
+                .filter(|_| self.middleware.auth_enabled);
+            app = app.merge(endpoint.subscription_route(&path, auth));
+        }
+
         // Apply custom route additions (for GraphQL, WebSocket, etc.)
         for add_route in self.custom_route_additions {
             app = add_route(app);
         }
 
+        // Apply request body limits and timeouts (per-route attributes override the defaults)
+        let default_limits = middleware::RequestLimits {
+            body_limit: self.middleware.body_limit,
+            timeout: self.middleware.request_timeout,
+        };
+        let mut limited_routes = Self::collect_limited_routes(has_explicit, &resolved);
+        limited_routes.extend(mounted_limited_routes);
+        // The first match is the most specific one
+        limited_routes.sort_by_cached_key(LimitedRoute::specificity);
+        let limited_routes = Arc::new(limited_routes);
+        if !default_limits.is_empty() || limited_routes.iter().any(|route| !route.limits.is_empty())
+        {
+            app = app.layer(axum::middleware::from_fn(
+                move |req: axum::http::Request<axum::body::Body>, next: axum::middleware::Next| {
+                    let path = req.uri().path();
+                    let limits = limited_routes
+                        .iter()
+                        .find(|route| {
+                            route_method_matches(&route.method, req.method())
+                                && path_matches_pattern(&route.path_pattern, path)
+                        })
+                        .map(|route| route.limits)
+                        .unwrap_or_default();
+                    limits.or(default_limits).run(req, next)
+                },
+            ));
+        }
+
+        // Serve DiskFileResponse bodies with Range / conditional GET support
+        app = app.layer(axum::middleware::from_fn(files::file_response_middleware));
+
         // Apply session cookies if configured
         if let Some(ref session_config) = self.middleware.session_config {
             app = app.layer(crate::session::SessionLayer::new(session_config.clone()));
@@ -4754,8 +5332,50 @@ impl UltraApiApp {
             response_tasks::response_task_middleware,
         ));
 
+        // Report HTTP errors of gRPC calls (auth, rate limiting, ...) as gRPC statuses
+        if !native_grpc_services.is_empty() {
+            app = app.layer(axum::middleware::from_fn(
+                grpc::native::grpc_status_middleware,
+            ));
+        }
+
+        // gRPC health: NOT_SERVING until the startup hooks finished and again once
+        // shutdown (drain) starts
+        let mut lifecycle = self.lifecycle.clone();
+        if let Some(health) = grpc_health {
+            let mut services: Vec<String> = native_grpc_services
+                .iter()
+                .map(|service| service.name().to_string())
+                .collect();
+            if let Some(ref transcoder) = grpc_services {
+                services.extend(
+                    transcoder
+                        .services()
+                        .map(|service| service.full_path.trim_start_matches('/').to_string()),
+                );
+            }
+            health.starting(services.iter().map(String::as_str));
+
+            let started = health.clone();
+            lifecycle = lifecycle
+                .on_startup(move |_state| {
+                    let health = started.clone();
+                    async move { health.startup_complete() }
+                })
+                .before_shutdown(move |_state| {
+                    let health = health.clone();
+                    async move { health.drain() }
+                });
+        }
+
+        if let Some(hub) = connection_hub {
+            lifecycle = lifecycle.before_shutdown(move |_state| {
+                hub.close();
+                std::future::ready(())
+            });
+        }
+
         // Create lifespan runner
-        let lifecycle = self.lifecycle.clone();
         let lifespan_runner = lifespan::LifespanRunner::new(lifecycle, state);
 
         // Add lifespan layer to the router
@@ -5124,12 +5744,36 @@ impl UltraApiApp {
         Self::dedupe_security_requirements(merged)
     }
 
+    /// Schema of one event of a typed `#[sse]` route (`SseEvent<T>` items)
+    fn sse_event_schema(data_schema_name: &str, events: &[&str]) -> serde_json::Value {
+        let mut event = serde_json::json!({ "type": "string" });
+        if !events.is_empty() {
+            event["enum"] = serde_json::json!(events);
+        }
+        let mut schema = serde_json::json!({
+            "type": "object",
+            "description": "Server-Sent Event whose `data` is JSON",
+            "properties": {
+                "event": event,
+                "data": { "$ref": format!("#/components/schemas/{}", data_schema_name) },
+                "id": { "type": "string" },
+                "retry": { "type": "integer", "minimum": 0 }
+            },
+            "required": ["data"],
+        });
+        if !events.is_empty() {
+            schema["x-sse-events"] = serde_json::json!(events);
+        }
+        schema
+    }
+
     fn build_operation(
         route: &RouteInfo,
         tags: Vec<String>,
         security_requirements: &[HashMap<String, Vec<String>>],
         extra_responses: &HashMap<String, openapi::ResponseDef>,
         split_candidates: &HashSet<String>,
+        default_limits: middleware::RequestLimits,
     ) -> openapi::Operation {
         let description = if route.description.is_empty() {
             None
@@ -5180,7 +5824,10 @@ impl UltraApiApp {
             )
         };
 
-        let request_schema_ref = if route.request_body_content_type == "multipart/form-data" {
+        let request_schema_ref = if route.request_body_content_type == "multipart/form-data"
+            && route.body_type_name == "Multipart"
+        {
+            // Raw `Multipart` extractor: the fields are unknown
             Some("#/components/schemas/Multipart".to_string())
         } else if route.has_body {
             let request_schema_name = Self::mapped_schema_name_for_direction(
@@ -5196,6 +5843,21 @@ impl UltraApiApp {
         // For non-JSON responses, we may not have a schema ref
         let response_schema_ref = if route.response_class == ResponseClass::Json {
             schema_ref_value
+        } else if route.response_class == ResponseClass::NdJson {
+            // NDJSON: the schema describes a single line
+            let item_schema_name = Self::mapped_schema_name_for_direction(
+                route.response_type_name,
+                split_candidates,
+                false,
+            );
+            Some(serde_json::json!({
+                "$ref": format!("#/components/schemas/{}", item_schema_name)
+            }))
+        } else if let (true, Some(message)) = (route.is_sse, route.outgoing_message) {
+            // Typed SSE: like other streams, the schema describes a single event
+            let data_schema_name =
+                Self::mapped_schema_name_for_direction(message.name, split_candidates, false);
+            Some(Self::sse_event_schema(&data_schema_name, route.sse_events))
         } else {
             None // Non-JSON responses don't have JSON schema refs
         };
@@ -5289,6 +5951,43 @@ impl UltraApiApp {
                         },
                     );
                 }
+                let limits = middleware::RequestLimits {
+                    body_limit: route.body_limit,
+                    timeout: route.timeout,
+                }
+                .or(default_limits);
+                if let (true, Some(limit)) = (route.has_body, limits.body_limit) {
+                    map.insert(
+                        "413".to_string(),
+                        openapi::ResponseDef {
+                            description: format!(
+                                "Payload Too Large (request body over {} bytes)",
+                                limit
+                            ),
+                            schema_ref: Some(
+                                serde_json::json!({ "$ref": "#/components/schemas/ApiError" }),
+                            ),
+                            content_type: None,
+                            headers: HashMap::new(),
+                        },
+                    );
+                }
+                if let Some(timeout) = limits.timeout {
+                    map.insert(
+                        "504".to_string(),
+                        openapi::ResponseDef {
+                            description: format!(
+                                "Gateway Timeout (no response within {} ms)",
+                                timeout.as_millis()
+                            ),
+                            schema_ref: Some(
+                                serde_json::json!({ "$ref": "#/components/schemas/ApiError" }),
+                            ),
+                            content_type: None,
+                            headers: HashMap::new(),
+                        },
+                    );
+                }
                 map.insert(
                     "500".to_string(),
                     openapi::ResponseDef {
@@ -5382,6 +6081,42 @@ impl UltraApiApp {
         )
     }
 
+    /// AsyncAPI document for the `#[ws]` / `#[sse]` routes of this application.
+    fn generate_asyncapi_spec(&self) -> serde_json::Value {
+        let mut channels = Vec::new();
+        if self.has_explicit_routes() {
+            for r in self.resolve_routes() {
+                let route = r.route_info;
+                if r.include_in_schema && (route.is_websocket || route.is_sse) {
+                    channels.push(asyncapi::Channel {
+                        address: r.full_path(),
+                        tags: r.merged_tags(),
+                        route,
+                    });
+                }
+            }
+        } else {
+            for route in inventory::iter::<&RouteInfo> {
+                if route.is_websocket || route.is_sse {
+                    channels.push(asyncapi::Channel {
+                        address: route.path.to_string(),
+                        tags: route.tags.iter().map(|s| s.to_string()).collect(),
+                        route,
+                    });
+                }
+            }
+        }
+
+        let info = openapi::Info {
+            title: self.title.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            contact: self.contact.clone(),
+            license: self.license.clone(),
+        };
+        asyncapi::document(&info, &channels)
+    }
+
     fn generate_openapi_spec(&self) -> openapi::OpenApiSpec {
         let mut schemas = HashMap::new();
 
@@ -5403,16 +6138,19 @@ impl UltraApiApp {
             }
         }
 
-        // Only register multipart placeholder schema when needed by at least one route.
+        // Only register multipart placeholder schema when needed by at least one route
+        // taking a raw `Multipart` (`MultipartForm` / `UploadFile` routes have real schemas).
+        let is_raw_multipart = |route: &RouteInfo| {
+            route.has_body
+                && route.request_body_content_type == "multipart/form-data"
+                && route.body_type_name == "Multipart"
+        };
         let has_multipart_request_body = if self.has_explicit_routes() {
-            self.resolve_routes().iter().any(|r| {
-                r.route_info.has_body
-                    && r.route_info.request_body_content_type == "multipart/form-data"
-            })
+            self.resolve_routes()
+                .iter()
+                .any(|r| is_raw_multipart(r.route_info))
         } else {
-            inventory::iter::<&RouteInfo>().any(|route| {
-                route.has_body && route.request_body_content_type == "multipart/form-data"
-            })
+            inventory::iter::<&RouteInfo>().any(|route| is_raw_multipart(route))
         };
         if has_multipart_request_body {
             schemas.insert(
@@ -5469,6 +6207,10 @@ impl UltraApiApp {
             }
         }
 
+        let default_limits = middleware::RequestLimits {
+            body_limit: self.middleware.body_limit,
+            timeout: self.middleware.request_timeout,
+        };
         let mut paths = HashMap::new();
         // When explicit routers are used, `paths` keys are the resolved full path (prefix applied).
         // We keep a mapping so we can attach callbacks to the correct operation.
@@ -5493,8 +6235,14 @@ impl UltraApiApp {
 
                 let tags = r.merged_tags();
                 let sec = r.merged_security();
-                let operation =
-                    Self::build_operation(route, tags, &sec, &r.extra_responses, &split_candidates);
+                let operation = Self::build_operation(
+                    route,
+                    tags,
+                    &sec,
+                    &r.extra_responses,
+                    &split_candidates,
+                    default_limits,
+                );
                 let path_item = paths.entry(full_path).or_insert_with(HashMap::new);
                 path_item.insert(route.method.to_lowercase(), operation);
             }
@@ -5508,8 +6256,14 @@ impl UltraApiApp {
 
                 let tags: Vec<String> = route.tags.iter().map(|s| s.to_string()).collect();
                 let sec = Self::parse_security_requirements(route.security);
-                let operation =
-                    Self::build_operation(route, tags, &sec, &HashMap::new(), &split_candidates);
+                let operation = Self::build_operation(
+                    route,
+                    tags,
+                    &sec,
+                    &HashMap::new(),
+                    &split_candidates,
+                    default_limits,
+                );
                 let path_item = paths
                     .entry(route.path.to_string())
                     .or_insert_with(HashMap::new);
@@ -5543,6 +6297,7 @@ impl UltraApiApp {
                         &callback_sec,
                         &HashMap::new(),
                         &split_candidates,
+                        middleware::RequestLimits::default(),
                     );
 
                     // Create the callback PathItem
@@ -5587,6 +6342,7 @@ impl UltraApiApp {
                         &callback_sec,
                         &HashMap::new(),
                         &split_candidates,
+                        middleware::RequestLimits::default(),
                     );
 
                     // Create the callback PathItem
@@ -5604,6 +6360,11 @@ impl UltraApiApp {
             }
         }
 
+        // gRPC transcoded methods are documented as POST operations
+        if let Some(transcoder) = self.grpc_services() {
+            transcoder.extend_openapi(&mut paths, &mut schemas);
+        }
+
         openapi::OpenApiSpec {
             openapi: "3.1.0".to_string(),
             info: openapi::Info {
@@ -5690,27 +6451,11 @@ impl openapi::OpenApiSpec {
                             .unwrap_or_default();
                         let mut all_params = params;
                         for dp in &dyn_params {
-                            let mut schema = serde_json::json!({ "type": dp.schema_type });
-                            if let Some(v) = dp.minimum {
-                                schema["minimum"] = serde_json::json!(v);
-                            }
-                            if let Some(v) = dp.maximum {
-                                schema["maximum"] = serde_json::json!(v);
-                            }
-                            if let Some(v) = dp.min_length {
-                                schema["minLength"] = serde_json::json!(v);
-                            }
-                            if let Some(v) = dp.max_length {
-                                schema["maxLength"] = serde_json::json!(v);
-                            }
-                            if let Some(v) = &dp.pattern {
-                                schema["pattern"] = serde_json::json!(v);
-                            }
                             let mut param = serde_json::json!({
                                 "name": dp.name,
                                 "in": dp.location,
                                 "required": dp.required,
-                                "schema": schema
+                                "schema": dp.schema_json()
                             });
                             if let Some(desc) = &dp.description {
                                 param["description"] = serde_json::Value::String(desc.clone());
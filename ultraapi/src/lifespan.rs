@@ -96,6 +96,23 @@ impl Lifecycle {
         self
     }
 
+    /// Add a shutdown hook that runs before the hooks registered so far
+    /// (used by the framework to stop reporting healthy before resources are released)
+    pub(crate) fn before_shutdown<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn(&AppState) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + Sync + 'static,
+    {
+        self.shutdown_hooks.insert(
+            0,
+            Arc::new(move |state| {
+                Box::pin(hook(state))
+                    as std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + Sync>>
+            }),
+        );
+        self
+    }
+
     /// Run all startup hooks
     pub async fn run_startup(&self, state: &AppState) {
         for hook in &self.startup_hooks {
@@ -2,7 +2,7 @@
 use axum::{
     body::Body,
     extract::Request,
-    http::{header::HeaderName, Method, StatusCode},
+    http::{header::HeaderName, HeaderMap, Method, StatusCode, Uri},
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -105,6 +105,49 @@ pub fn parse_basic_header(auth_header: &str) -> Option<BasicCredentials> {
     }
 }
 
+/// `Sec-WebSocket-Protocol` value announcing a bearer token as the next protocol entry
+/// (`new WebSocket(url, ["bearer", token])`). `#[ws]` routes select it in the handshake.
+pub const WEBSOCKET_BEARER_PROTOCOL: &str = "bearer";
+
+/// Query parameter carrying a bearer token on WebSocket handshakes (RFC 6750 §2.3)
+pub const WEBSOCKET_TOKEN_QUERY_PARAM: &str = "access_token";
+
+/// WebSocket ハンドシェイクのベアラートークンを取り出す
+///
+/// ブラウザは WebSocket ハンドシェイクにヘッダーを設定できないため、
+/// `Sec-WebSocket-Protocol: bearer, <token>` または `?access_token=<token>` で渡されたトークンを返す。
+/// WebSocket のアップグレードリクエスト以外では常に `None`。
+pub fn websocket_bearer_token(headers: &HeaderMap, uri: &Uri) -> Option<String> {
+    let is_upgrade = headers
+        .get("upgrade")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    if !is_upgrade {
+        return None;
+    }
+
+    let from_protocol = headers
+        .get_all("sec-websocket-protocol")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .skip_while(|protocol| !protocol.eq_ignore_ascii_case(WEBSOCKET_BEARER_PROTOCOL))
+        .nth(1)
+        .filter(|token| !token.is_empty());
+    if let Some(token) = from_protocol {
+        return Some(token.to_string());
+    }
+
+    uri.query().and_then(|query| {
+        query.split('&').find_map(|pair| {
+            pair.split_once('=')
+                .filter(|(key, value)| *key == WEBSOCKET_TOKEN_QUERY_PARAM && !value.is_empty())
+                .map(|(_, value)| value.to_string())
+        })
+    })
+}
+
 /// Specifies where to look for credentials
 #[derive(Clone, Debug, Default, PartialEq)]
 pub enum CredentialLocation {
@@ -761,6 +804,22 @@ impl AuthLayer {
             }
         }
 
+        // WebSocket handshakes: bearer token from Sec-WebSocket-Protocol or ?access_token=
+        if let Some(token) = websocket_bearer_token(request.headers(), request.uri()) {
+            let bearer_scheme = self.security_schemes.iter().find(|scheme| {
+                scheme.location == CredentialLocation::Header
+                    && scheme.param_name.eq_ignore_ascii_case("authorization")
+                    && Self::allows_scheme_name(allowed_security_schemes, &scheme.name)
+            });
+            let creds = match bearer_scheme {
+                Some(scheme) => Credentials::with_scheme("bearer", token, &scheme.name),
+                None => Credentials::new("bearer", token),
+            };
+            if Self::credential_allowed(&creds, allowed_security_schemes) {
+                return Some(creds);
+            }
+        }
+
         None
     }
 
@@ -799,9 +858,32 @@ impl AuthLayer {
         vec![]
     }
 
+    /// Validate the credentials carried by a request that does not go through the layer
+    /// (e.g. a WebSocket handshake merged with its `connection_init` payload).
+    ///
+    /// Returns `Ok(None)` when the request carries no credentials.
+    #[cfg(feature = "graphql")]
+    pub(crate) fn authenticate(
+        &self,
+        request: &Request<Body>,
+        allowed_security_schemes: Option<&[String]>,
+    ) -> Result<Option<Credentials>, AuthError> {
+        let Some(creds) = self.extract_credentials(request, allowed_security_schemes) else {
+            return Ok(None);
+        };
+        let scope_lookup_scheme = creds
+            .security_scheme
+            .as_deref()
+            .unwrap_or(creds.scheme.as_str());
+        let required_scopes = self.get_required_scopes(scope_lookup_scheme, None);
+        self.validator.validate(&creds)?;
+        self.validator.validate_scopes(&creds, &required_scopes)?;
+        Ok(Some(creds))
+    }
+
     pub(crate) async fn run(
         &self,
-        request: Request<Body>,
+        mut request: Request<Body>,
         next: Next,
         allowed_security_schemes: Option<&[String]>,
         route_required_scopes_by_scheme: Option<&HashMap<String, Vec<String>>>,
@@ -830,7 +912,11 @@ impl AuthLayer {
                     Ok(()) => {
                         // Then validate scopes if required
                         match self.validator.validate_scopes(&creds, &required_scopes) {
-                            Ok(()) => next.run(request).await,
+                            Ok(()) => {
+                                // Handlers (e.g. GraphQL resolvers) can read who called
+                                request.extensions_mut().insert(creds);
+                                next.run(request).await
+                            }
                             Err(auth_error) => {
                                 let error = if auth_error.status == StatusCode::FORBIDDEN {
                                     super::ApiError::forbidden(auth_error.message)
@@ -1224,6 +1310,10 @@ pub struct MiddlewareBuilder {
     pub response_cache_config: Option<ResponseCacheConfig>,
     pub session_config: Option<SessionConfig>,
     pub dep_middleware_layers: Vec<DepMiddlewareLayer>,
+    /// Default request body size limit in bytes (`#[body_limit]` overrides it per route)
+    pub body_limit: Option<u64>,
+    /// Default handler timeout (`#[timeout]` overrides it per route)
+    pub request_timeout: Option<Duration>,
 }
 
 impl Default for MiddlewareBuilder {
@@ -1246,6 +1336,8 @@ impl MiddlewareBuilder {
             response_cache_config: None,
             session_config: None,
             dep_middleware_layers: Vec::new(),
+            body_limit: None,
+            request_timeout: None,
         }
     }
 
@@ -1363,6 +1455,123 @@ impl MiddlewareBuilder {
         self.session_config = Some(config);
         self
     }
+
+    /// Reject request bodies larger than `bytes` with 413
+    pub fn body_limit(mut self, bytes: u64) -> Self {
+        self.body_limit = Some(bytes);
+        self
+    }
+
+    /// Answer requests whose handler takes longer than `timeout` with 504
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+}
+
+// ============================================================================
+// Body Size Limit and Timeout Middleware
+// ============================================================================
+
+/// リクエストボディのサイズ制限とハンドラのタイムアウト
+///
+/// - Content-Length が制限を超える場合はボディを読まずに 413 を返します
+/// - Content-Length がない (chunked) ボディは読み込み中に制限を超えた時点で打ち切り、413 を返します
+/// - タイムアウトはレスポンスヘッダーが返るまでの時間に適用され、超過時は 504 を返します
+///   (ストリーミングレスポンスのボディには適用されません)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RequestLimits {
+    pub body_limit: Option<u64>,
+    pub timeout: Option<Duration>,
+}
+
+impl RequestLimits {
+    pub fn is_empty(&self) -> bool {
+        self.body_limit.is_none() && self.timeout.is_none()
+    }
+
+    /// Route values take precedence over `defaults`
+    pub fn or(self, defaults: RequestLimits) -> Self {
+        Self {
+            body_limit: self.body_limit.or(defaults.body_limit),
+            timeout: self.timeout.or(defaults.timeout),
+        }
+    }
+
+    pub async fn run(self, req: Request, next: Next) -> Response {
+        match self.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, self.run_limited(req, next)).await
+            {
+                Ok(response) => response,
+                Err(_) => crate::ApiError::gateway_timeout(format!(
+                    "Request timed out after {}",
+                    format_duration(timeout)
+                ))
+                .into_response(),
+            },
+            None => self.run_limited(req, next).await,
+        }
+    }
+
+    async fn run_limited(self, mut req: Request, next: Next) -> Response {
+        let Some(limit) = self.body_limit else {
+            return next.run(req).await;
+        };
+        // Extractors that read the body themselves (multipart uploads) follow the same limit
+        req.extensions_mut().insert(self);
+
+        let content_length = req
+            .headers()
+            .get(axum::http::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        if content_length.is_some_and(|length| length > limit) {
+            return payload_too_large(limit);
+        }
+
+        // Count the bytes of bodies without (or with a wrong) Content-Length while they are read
+        let exceeded = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = exceeded.clone();
+        let req = req.map(|body| {
+            let mut read: u64 = 0;
+            Body::from_stream(futures_util::StreamExt::map(
+                body.into_data_stream(),
+                move |chunk| {
+                    let chunk = chunk?;
+                    read += chunk.len() as u64;
+                    if read > limit {
+                        flag.store(true, std::sync::atomic::Ordering::Relaxed);
+                        return Err(axum::Error::new("length limit exceeded"));
+                    }
+                    Ok(chunk)
+                },
+            ))
+        });
+
+        // The limit replaces axum's default limit of the body extractors
+        let service = tower::Layer::layer(&axum::extract::DefaultBodyLimit::disable(), next);
+        let response = match tower::ServiceExt::oneshot(service, req).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        };
+        if exceeded.load(std::sync::atomic::Ordering::Relaxed) {
+            return payload_too_large(limit);
+        }
+        response
+    }
+}
+
+fn payload_too_large(limit: u64) -> Response {
+    crate::ApiError::payload_too_large(format!("Request body exceeds the limit of {} bytes", limit))
+        .into_response()
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration.subsec_millis() == 0 {
+        format!("{}s", duration.as_secs())
+    } else {
+        format!("{}ms", duration.as_millis())
+    }
 }
 
 // ============================================================================
@@ -1934,13 +2143,15 @@ where
                     ))
                 }
             }
-            None => {
+            None => match websocket_bearer_token(&parts.headers, &parts.uri) {
+                // WebSocket handshakes may carry the token as a subprotocol or query param
+                Some(token) => Ok(OAuth2PasswordBearer(token)),
                 // No Authorization header - return 401
-                Err(create_bearer_unauthorized_error(
+                None => Err(create_bearer_unauthorized_error(
                     "invalid_token",
                     Some("Missing Authorization header. Expected 'Bearer <token>'"),
-                ))
-            }
+                )),
+            },
         }
     }
 }
@@ -1966,7 +2177,10 @@ where
             }
             None => {
                 // No Authorization header - return None (not an error)
-                Ok(OptionalOAuth2PasswordBearer(None))
+                Ok(OptionalOAuth2PasswordBearer(websocket_bearer_token(
+                    &parts.headers,
+                    &parts.uri,
+                )))
             }
         }
     }
@@ -1998,10 +2212,13 @@ where
                     ))
                 }
             }
-            None => Err(create_bearer_unauthorized_error(
-                "invalid_token",
-                Some("Missing Authorization header. Expected 'Bearer <token>'"),
-            )),
+            None => match websocket_bearer_token(&parts.headers, &parts.uri) {
+                Some(token) => Ok(OAuth2AuthorizationCodeBearer(token)),
+                None => Err(create_bearer_unauthorized_error(
+                    "invalid_token",
+                    Some("Missing Authorization header. Expected 'Bearer <token>'"),
+                )),
+            },
         }
     }
 }
@@ -2023,7 +2240,9 @@ where
                 let token = parse_bearer_token(header_value);
                 Ok(OptionalOAuth2AuthorizationCodeBearer(token))
             }
-            None => Ok(OptionalOAuth2AuthorizationCodeBearer(None)),
+            None => Ok(OptionalOAuth2AuthorizationCodeBearer(
+                websocket_bearer_token(&parts.headers, &parts.uri),
+            )),
         }
     }
 }
@@ -453,15 +453,16 @@ pub struct DynParameter {
     pub min_length: Option<u32>,
     pub max_length: Option<u32>,
     pub pattern: Option<String>,
+    pub exclusive_minimum: Option<f64>,
+    pub exclusive_maximum: Option<f64>,
+    pub multiple_of: Option<f64>,
+    /// Allowed values, emitted as `enum`
+    pub enum_values: Option<Vec<serde_json::Value>>,
 }
 
-impl Serialize for DynParameter {
-    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        use serde::ser::SerializeMap;
-        let mut map = serializer.serialize_map(None)?;
-        map.serialize_entry("name", &self.name)?;
-        map.serialize_entry("in", &self.location)?;
-        map.serialize_entry("required", &self.required)?;
+impl DynParameter {
+    /// The parameter's `schema` object, including validation constraints.
+    pub fn schema_json(&self) -> serde_json::Value {
         let mut schema = serde_json::json!({"type": self.schema_type});
         if let Some(v) = self.minimum {
             schema["minimum"] = serde_json::json!(v);
@@ -469,6 +470,15 @@ impl Serialize for DynParameter {
         if let Some(v) = self.maximum {
             schema["maximum"] = serde_json::json!(v);
         }
+        if let Some(v) = self.exclusive_minimum {
+            schema["exclusiveMinimum"] = serde_json::json!(v);
+        }
+        if let Some(v) = self.exclusive_maximum {
+            schema["exclusiveMaximum"] = serde_json::json!(v);
+        }
+        if let Some(v) = self.multiple_of {
+            schema["multipleOf"] = serde_json::json!(v);
+        }
         if let Some(v) = self.min_length {
             schema["minLength"] = serde_json::json!(v);
         }
@@ -478,7 +488,21 @@ impl Serialize for DynParameter {
         if let Some(v) = &self.pattern {
             schema["pattern"] = serde_json::json!(v);
         }
-        map.serialize_entry("schema", &schema)?;
+        if let Some(v) = &self.enum_values {
+            schema["enum"] = serde_json::json!(v);
+        }
+        schema
+    }
+}
+
+impl Serialize for DynParameter {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("name", &self.name)?;
+        map.serialize_entry("in", &self.location)?;
+        map.serialize_entry("required", &self.required)?;
+        map.serialize_entry("schema", &self.schema_json())?;
         if let Some(desc) = &self.description {
             map.serialize_entry("description", desc)?;
         }
@@ -586,26 +610,29 @@ impl Schema {
             return obj;
         }
 
-        // Discriminated union (oneOf with discriminator)
-        if let (Some(one_of_refs), Some(discriminator)) = (&self.one_of, &self.discriminator) {
+        // Union (oneOf, with a discriminator when the variants are tagged)
+        if let Some(one_of_refs) = &self.one_of {
             let one_of: Vec<serde_json::Value> = one_of_refs
                 .iter()
                 .map(|r| serde_json::json!({ "$ref": r }))
                 .collect();
-
-            let mapping: serde_json::Map<String, serde_json::Value> = discriminator
-                .mapping
-                .iter()
-                .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
-                .collect();
-
-            return serde_json::json!({
-                "oneOf": one_of,
-                "discriminator": {
+            let mut obj = serde_json::json!({ "oneOf": one_of });
+
+            if let Some(discriminator) = &self.discriminator {
+                let mapping: serde_json::Map<String, serde_json::Value> = discriminator
+                    .mapping
+                    .iter()
+                    .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+                    .collect();
+                obj["discriminator"] = serde_json::json!({
                     "propertyName": discriminator.property_name,
                     "mapping": mapping,
-                }
-            });
+                });
+            }
+            if let Some(desc) = &self.description {
+                obj["description"] = serde_json::Value::String(desc.clone());
+            }
+            return obj;
         }
 
         let mut props = serde_json::Map::new();
@@ -632,7 +659,7 @@ impl Serialize for Schema {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Property {
     pub type_name: String,
     pub format: Option<String>,
@@ -642,6 +669,15 @@ pub struct Property {
     pub maximum: Option<f64>,
     pub pattern: Option<String>,
     pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+    pub unique_items: bool,
+    pub multiple_of: Option<f64>,
+    pub exclusive_minimum: Option<f64>,
+    pub exclusive_maximum: Option<f64>,
+    /// Allowed values, emitted as `enum`
+    pub enum_values: Option<Vec<serde_json::Value>>,
+    /// Value the array must contain, emitted as `contains: {"const": ...}`
+    pub contains: Option<serde_json::Value>,
     pub description: Option<String>,
     pub ref_path: Option<String>,
     pub items: Option<Box<Property>>,
@@ -747,6 +783,27 @@ impl Property {
         if let Some(v) = self.min_items {
             obj.insert("minItems".into(), serde_json::Value::Number(v.into()));
         }
+        if let Some(v) = self.max_items {
+            obj.insert("maxItems".into(), serde_json::Value::Number(v.into()));
+        }
+        if self.unique_items {
+            obj.insert("uniqueItems".into(), serde_json::Value::Bool(true));
+        }
+        if let Some(v) = self.multiple_of {
+            obj.insert("multipleOf".into(), serde_json::json!(v));
+        }
+        if let Some(v) = self.exclusive_minimum {
+            obj.insert("exclusiveMinimum".into(), serde_json::json!(v));
+        }
+        if let Some(v) = self.exclusive_maximum {
+            obj.insert("exclusiveMaximum".into(), serde_json::json!(v));
+        }
+        if let Some(values) = &self.enum_values {
+            obj.insert("enum".into(), serde_json::Value::Array(values.clone()));
+        }
+        if let Some(v) = &self.contains {
+            obj.insert("contains".into(), serde_json::json!({ "const": v }));
+        }
         if let Some(items) = &self.items {
             obj.insert("items".into(), items.to_json_value());
         }
@@ -771,6 +828,13 @@ pub struct PropertyPatch {
     pub maximum: Option<f64>,
     pub pattern: Option<String>,
     pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+    pub unique_items: Option<bool>,
+    pub multiple_of: Option<f64>,
+    pub exclusive_minimum: Option<f64>,
+    pub exclusive_maximum: Option<f64>,
+    pub enum_values: Option<Vec<serde_json::Value>>,
+    pub contains: Option<serde_json::Value>,
     pub description: Option<String>,
     pub example: Option<String>,
     pub read_only: Option<bool>,
@@ -849,6 +913,130 @@ pub fn schema_from_schemars_full(_name: &str, root: &schemars::schema::RootSchem
     }
 }
 
+/// Convert the schemars schema of a data-carrying enum into a `oneOf` union.
+///
+/// Each variant becomes a `{Name}_{Variant}` component schema. Internally tagged enums
+/// (`#[serde(tag = "type")]`) also get a discriminator on the tag property.
+pub fn schema_from_schemars_union(name: &str, root: &schemars::schema::RootSchema) -> SchemaResult {
+    let mut nested = schema_from_schemars_full(name, root).nested;
+    let variants = root
+        .schema
+        .subschemas
+        .as_ref()
+        .and_then(|subschemas| subschemas.one_of.as_ref().or(subschemas.any_of.as_ref()))
+        .cloned()
+        .unwrap_or_default();
+
+    let mut one_of = Vec::new();
+    let mut tag_values: Vec<(String, String, String)> = Vec::new();
+    for (index, variant) in variants.iter().enumerate() {
+        let schemars::schema::Schema::Object(obj) = variant else {
+            continue;
+        };
+        let unit_value = single_string_enum_value(obj);
+        let tag = obj.object.as_ref().and_then(|object| {
+            object
+                .properties
+                .iter()
+                .find_map(|(prop_name, prop_schema)| match prop_schema {
+                    schemars::schema::Schema::Object(prop) => {
+                        single_string_enum_value(prop).map(|value| (prop_name.clone(), value))
+                    }
+                    _ => None,
+                })
+        });
+        let variant_name = tag
+            .as_ref()
+            .map(|(_, value)| value.clone())
+            .or_else(|| unit_value.clone())
+            .or_else(|| {
+                obj.object
+                    .as_ref()
+                    .filter(|object| object.required.len() == 1)
+                    .and_then(|object| object.required.iter().next().cloned())
+            })
+            .unwrap_or_else(|| index.to_string());
+
+        let mut variant_schema = Schema {
+            type_name: "object".to_string(),
+            properties: HashMap::new(),
+            required: vec![],
+            description: obj.metadata.as_ref().and_then(|m| m.description.clone()),
+            enum_values: None,
+            example: None,
+            one_of: None,
+            discriminator: None,
+        };
+        if let Some(value) = unit_value {
+            variant_schema.type_name = "string".to_string();
+            variant_schema.enum_values = Some(vec![value]);
+        } else if let Some(object) = &obj.object {
+            for (prop_name, prop_schema) in &object.properties {
+                let prop = match (&tag, prop_schema) {
+                    (Some((tag_property, value)), schemars::schema::Schema::Object(tag_schema))
+                        if tag_property == prop_name =>
+                    {
+                        // Converted without its enum: inline string enums are matched against
+                        // the registered schemas, including the one being initialized.
+                        let mut plain = tag_schema.clone();
+                        plain.enum_values = None;
+                        let mut prop =
+                            property_from_schemars_schema(&plain.into(), &root.definitions);
+                        prop.enum_values = Some(vec![serde_json::Value::String(value.clone())]);
+                        prop
+                    }
+                    _ => property_from_schemars_schema(prop_schema, &root.definitions),
+                };
+                variant_schema.properties.insert(prop_name.clone(), prop);
+            }
+            variant_schema.required = object.required.iter().cloned().collect();
+        }
+
+        let component = format!("{}_{}", name, variant_name);
+        let ref_path = format!("#/components/schemas/{}", component);
+        nested.insert(component, variant_schema);
+        if let Some((tag_property, value)) = tag {
+            tag_values.push((tag_property, value, ref_path.clone()));
+        }
+        one_of.push(ref_path);
+    }
+
+    // A discriminator is only emitted when every variant carries the same tag property
+    let discriminator = tag_values.first().and_then(|(property, _, _)| {
+        let shared = tag_values.len() == one_of.len()
+            && tag_values.iter().all(|(other, _, _)| other == property);
+        shared.then(|| Discriminator {
+            property_name: property.clone(),
+            mapping: tag_values
+                .iter()
+                .map(|(_, value, ref_path)| (value.clone(), ref_path.clone()))
+                .collect(),
+        })
+    });
+
+    SchemaResult {
+        schema: Schema {
+            type_name: "object".to_string(),
+            properties: HashMap::new(),
+            required: vec![],
+            description: None,
+            enum_values: None,
+            example: None,
+            one_of: Some(one_of),
+            discriminator,
+        },
+        nested,
+    }
+}
+
+/// `{"type": "string", "enum": ["x"]}` -> `x`
+fn single_string_enum_value(obj: &schemars::schema::SchemaObject) -> Option<String> {
+    match obj.enum_values.as_deref() {
+        Some([serde_json::Value::String(value)]) => Some(value.clone()),
+        _ => None,
+    }
+}
+
 /// Extract query parameters from a schemars RootSchema
 pub fn query_params_from_schema(root: &schemars::schema::RootSchema) -> Vec<DynParameter> {
     let mut params = Vec::new();
@@ -873,6 +1061,10 @@ pub fn query_params_from_schema(root: &schemars::schema::RootSchema) -> Vec<DynP
                 min_length: constraints.2,
                 max_length: constraints.3,
                 pattern: constraints.4,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+                multiple_of: None,
+                enum_values: None,
             });
         }
     }
@@ -1096,6 +1288,7 @@ pub fn multipart_placeholder_schema() -> Schema {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     );
     properties.insert(
@@ -1118,6 +1311,7 @@ pub fn multipart_placeholder_schema() -> Schema {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     );
     Schema {
@@ -1166,6 +1360,7 @@ pub fn validation_error_schema() -> Schema {
                 read_only: false,
                 write_only: false,
                 deprecated: false,
+                ..Default::default()
             })),
             nullable: false,
             example: None,
@@ -1173,6 +1368,7 @@ pub fn validation_error_schema() -> Schema {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     );
     properties.insert(
@@ -1195,6 +1391,7 @@ pub fn validation_error_schema() -> Schema {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     );
     properties.insert(
@@ -1217,6 +1414,7 @@ pub fn validation_error_schema() -> Schema {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     );
 
@@ -1266,6 +1464,7 @@ pub fn http_validation_error_schema() -> Schema {
                 read_only: false,
                 write_only: false,
                 deprecated: false,
+                ..Default::default()
             })),
             nullable: false,
             example: None,
@@ -1273,6 +1472,7 @@ pub fn http_validation_error_schema() -> Schema {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     );
 
@@ -1311,6 +1511,7 @@ pub fn api_error_schema() -> Schema {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     );
     properties.insert(
@@ -1344,6 +1545,7 @@ pub fn api_error_schema() -> Schema {
                 read_only: false,
                 write_only: false,
                 deprecated: false,
+                ..Default::default()
             })),
             nullable: false,
             example: None,
@@ -1351,6 +1553,7 @@ pub fn api_error_schema() -> Schema {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     );
     Schema {
@@ -1395,6 +1598,7 @@ fn property_from_schemars_schema(
                     read_only: false,
                     write_only: false,
                     deprecated: false,
+                    ..Default::default()
                 };
             }
 
@@ -1439,6 +1643,17 @@ fn property_from_schemars_schema(
                         } else {
                             "string".to_string()
                         };
+                        if let (true, [single]) = (has_null, non_null.as_slice()) {
+                            // `Option<Vec<T>>` etc.: keep the items of the non-null type
+                            let mut inner = obj.clone();
+                            inner.instance_type = Some((**single).into());
+                            let mut prop = property_from_schemars_schema(
+                                &schemars::schema::Schema::Object(inner),
+                                definitions,
+                            );
+                            prop.nullable = true;
+                            return prop;
+                        }
                         if has_null {
                             return Property {
                                 type_name: tn,
@@ -1458,6 +1673,7 @@ fn property_from_schemars_schema(
                                 read_only,
                                 write_only,
                                 deprecated,
+                                ..Default::default()
                             };
                         }
                         tn
@@ -1500,6 +1716,7 @@ fn property_from_schemars_schema(
                                         read_only: false,
                                         write_only: false,
                                         deprecated: false,
+                                        ..Default::default()
                                     };
                                 }
                             }
@@ -1530,6 +1747,7 @@ fn property_from_schemars_schema(
                                 read_only,
                                 write_only,
                                 deprecated,
+                                ..Default::default()
                             };
                         }
                     }
@@ -1568,6 +1786,7 @@ fn property_from_schemars_schema(
                         read_only,
                         write_only,
                         deprecated,
+                        ..Default::default()
                     };
                 }
 
@@ -1589,6 +1808,7 @@ fn property_from_schemars_schema(
                     read_only,
                     write_only,
                     deprecated,
+                    ..Default::default()
                 };
             }
 
@@ -1610,6 +1830,7 @@ fn property_from_schemars_schema(
                 read_only,
                 write_only,
                 deprecated,
+                ..Default::default()
             }
         }
         _ => Property {
@@ -1630,6 +1851,7 @@ fn property_from_schemars_schema(
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     }
 }
@@ -0,0 +1,389 @@
+//! Typed events and resumption for `#[sse]` routes
+//!
+//! - [`SseEvent`]: `data:` を JSON にシリアライズする型付きイベント。`Stream<Item = SseEvent<T>>` を
+//!   返すハンドラは `T` のスキーマとイベント名が OpenAPI / AsyncAPI に記録されます
+//!
+//! 再接続した `EventSource` は最後に受信したイベントの ID を `Last-Event-ID` ヘッダーで送ります。
+//!
+//! - [`LastEventId`]: `Last-Event-ID` ヘッダーを受け取るエクストラクタ
+//! - [`EventLog`]: ストリーム名ごとのリプレイバッファ。`UltraApiApp::dep(EventLog::new())` で登録し、
+//!   `publish` したイベントを保持 (件数・経過時間で制限) します。`subscribe` は取りこぼした
+//!   イベントを送ってからライブ配信に切り替え、`retry:` ヒントを先頭に付けます。
+//!
+//! ```ignore
+//! #[sse("/notifications")]
+//! async fn notifications(
+//!     log: Dep<EventLog>,
+//!     last_event_id: LastEventId,
+//! ) -> impl Stream<Item = Result<Event, Infallible>> {
+//!     log.subscribe("notifications", &last_event_id)
+//! }
+//!
+//! // elsewhere
+//! log.publish_json("notifications", Some("created"), &item)?;
+//! ```
+
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::response::sse::Event;
+use futures_util::Stream;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// An SSE event whose `data:` is `T` serialized as JSON.
+///
+/// ```ignore
+/// #[sse("/orders", events = ["created", "shipped"])]
+/// async fn orders(hub: Dep<Hub>) -> impl Stream<Item = SseEvent<Order>> {
+///     order_stream(hub).map(|order| SseEvent::new(order).event("created"))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SseEvent<T> {
+    data: T,
+    event: Option<Cow<'static, str>>,
+    id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl<T> SseEvent<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            event: None,
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// Set the `event:` name (clients receive `message` without one)
+    pub fn event(mut self, event: impl Into<Cow<'static, str>>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Set the `id:` echoed back in `Last-Event-ID` on reconnect
+    pub fn id(mut self, id: impl ToString) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Set the `retry:` reconnection delay
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    pub fn event_name(&self) -> Option<&str> {
+        self.event.as_deref()
+    }
+}
+
+impl<T: Serialize> SseEvent<T> {
+    /// Render the event. Fails when `T` cannot be serialized, which ends the stream.
+    pub fn into_event(self) -> Result<Event, axum::Error> {
+        let mut event = Event::default();
+        if let Some(name) = &self.event {
+            event = event.event(name.as_ref());
+        }
+        event = event.json_data(&self.data)?;
+        if let Some(id) = &self.id {
+            event = event.id(id);
+        }
+        if let Some(retry) = self.retry {
+            event = event.retry(retry);
+        }
+        Ok(event)
+    }
+}
+
+/// The `Last-Event-ID` header of a reconnecting `EventSource`, if any.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LastEventId(pub Option<String>);
+
+impl LastEventId {
+    pub fn as_deref(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+}
+
+impl<S> FromRequestParts<S> for LastEventId
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(LastEventId(
+            parts
+                .headers
+                .get("last-event-id")
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+        ))
+    }
+}
+
+/// An event kept by an [`EventLog`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedEvent {
+    /// Sent as `id:`; increases with every publish on the log
+    pub id: u64,
+    /// Sent as `event:`
+    pub event: Option<String>,
+    /// Sent as `data:`
+    pub data: String,
+}
+
+impl From<&LoggedEvent> for Event {
+    fn from(logged: &LoggedEvent) -> Self {
+        let event = match &logged.event {
+            Some(name) => Event::default().event(name),
+            None => Event::default(),
+        };
+        event.data(&logged.data).id(logged.id.to_string())
+    }
+}
+
+/// Replay buffers of SSE streams, keyed by stream name. Clones share the same buffers.
+#[derive(Clone)]
+pub struct EventLog {
+    inner: Arc<Mutex<LogState>>,
+    max_events: usize,
+    max_age: Option<Duration>,
+    retry: Option<Duration>,
+}
+
+struct LogState {
+    next_id: u64,
+    streams: HashMap<String, StreamLog>,
+}
+
+struct StreamLog {
+    events: VecDeque<(Instant, Arc<LoggedEvent>)>,
+    live: broadcast::Sender<Arc<LoggedEvent>>,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventLog {
+    /// A log keeping the last 1000 events of each stream, with no age limit and no `retry:` hint
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(LogState {
+                next_id: 1,
+                streams: HashMap::new(),
+            })),
+            max_events: 1000,
+            max_age: None,
+            retry: None,
+        }
+    }
+
+    /// Keep at most this many events per stream (at least 1)
+    pub fn max_events(mut self, max_events: usize) -> Self {
+        self.max_events = max_events.max(1);
+        self
+    }
+
+    /// Drop events older than this
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Send `retry:` with this reconnection delay at the start of every subscription
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Append an event to a stream and deliver it to its subscribers. Returns the event id.
+    pub fn publish(&self, stream: &str, event: Option<&str>, data: impl Into<String>) -> u64 {
+        let mut state = self.inner.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+
+        let logged = Arc::new(LoggedEvent {
+            id,
+            event: event.map(str::to_string),
+            data: data.into(),
+        });
+        let log = state.stream(stream);
+        log.events.push_back((Instant::now(), logged.clone()));
+        while log.events.len() > self.max_events {
+            log.events.pop_front();
+        }
+        self.prune(log);
+        // No receivers is fine: the event stays in the buffer
+        let _ = log.live.send(logged);
+        id
+    }
+
+    /// Publish `data` serialized as JSON.
+    pub fn publish_json<T: Serialize + ?Sized>(
+        &self,
+        stream: &str,
+        event: Option<&str>,
+        data: &T,
+    ) -> serde_json::Result<u64> {
+        let data = serde_json::to_string(data)?;
+        Ok(self.publish(stream, event, data))
+    }
+
+    /// Retained events of a stream published after `last_event_id`.
+    ///
+    /// Without an id nothing is replayed. An id that is not a number replays every
+    /// retained event.
+    pub fn since(&self, stream: &str, last_event_id: &LastEventId) -> Vec<LoggedEvent> {
+        let mut state = self.inner.lock();
+        let log = state.stream(stream);
+        self.prune(log);
+        Self::replay(log, last_event_id)
+            .into_iter()
+            .map(|event| (*event).clone())
+            .collect()
+    }
+
+    /// Event stream for an `#[sse]` handler: the `retry:` hint, the events missed since
+    /// `last_event_id`, then live events.
+    pub fn subscribe(
+        &self,
+        stream: &str,
+        last_event_id: &LastEventId,
+    ) -> impl Stream<Item = Result<Event, Infallible>> + Send + 'static {
+        let (backlog, live, cursor) = {
+            let mut state = self.inner.lock();
+            let cursor = state.next_id - 1;
+            let log = state.stream(stream);
+            self.prune(log);
+            // Subscribing under the lock: nothing published in between is lost
+            (
+                Self::replay(log, last_event_id),
+                log.live.subscribe(),
+                cursor,
+            )
+        };
+
+        let subscription = Subscription {
+            log: self.clone(),
+            stream: stream.to_string(),
+            backlog: backlog.into(),
+            live,
+            cursor,
+            retry: self.retry,
+        };
+        futures_util::stream::unfold(subscription, |mut subscription| async move {
+            let event = subscription.next().await?;
+            Some((Ok(event), subscription))
+        })
+    }
+
+    /// Names of the streams with retained events or subscribers
+    pub fn streams(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.inner.lock().streams.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    fn prune(&self, log: &mut StreamLog) {
+        if let Some(max_age) = self.max_age {
+            while log
+                .events
+                .front()
+                .is_some_and(|(at, _)| at.elapsed() > max_age)
+            {
+                log.events.pop_front();
+            }
+        }
+    }
+
+    fn replay(log: &StreamLog, last_event_id: &LastEventId) -> Vec<Arc<LoggedEvent>> {
+        let Some(last) = last_event_id.as_deref() else {
+            return Vec::new();
+        };
+        // Ids are global, so an id absent from this stream still orders its events
+        let after = last.parse::<u64>().unwrap_or(0);
+        log.events
+            .iter()
+            .filter(|(_, event)| event.id > after)
+            .map(|(_, event)| event.clone())
+            .collect()
+    }
+}
+
+impl LogState {
+    fn stream(&mut self, name: &str) -> &mut StreamLog {
+        self.streams
+            .entry(name.to_string())
+            .or_insert_with(|| StreamLog {
+                events: VecDeque::new(),
+                live: broadcast::channel(256).0,
+            })
+    }
+}
+
+struct Subscription {
+    log: EventLog,
+    stream: String,
+    backlog: VecDeque<Arc<LoggedEvent>>,
+    live: broadcast::Receiver<Arc<LoggedEvent>>,
+    /// Highest id published before subscribing or delivered since
+    cursor: u64,
+    retry: Option<Duration>,
+}
+
+impl Subscription {
+    async fn next(&mut self) -> Option<Event> {
+        if let Some(retry) = self.retry.take() {
+            return Some(Event::default().retry(retry));
+        }
+        if let Some(event) = self.backlog.pop_front() {
+            return Some(Event::from(event.as_ref()));
+        }
+        loop {
+            match self.live.recv().await {
+                // Skip what a lag recovery already delivered from the buffer
+                Ok(event) if event.id <= self.cursor => continue,
+                Ok(event) => {
+                    self.cursor = event.id;
+                    return Some(Event::from(event.as_ref()));
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    // Fell behind the live channel: catch up from the buffer
+                    let mut state = self.log.inner.lock();
+                    let log = state.stream(&self.stream);
+                    self.backlog = log
+                        .events
+                        .iter()
+                        .filter(|(_, event)| event.id > self.cursor)
+                        .map(|(_, event)| event.clone())
+                        .collect();
+                    let Some(event) = self.backlog.pop_front() else {
+                        continue;
+                    };
+                    self.cursor = self.backlog.back().unwrap_or(&event).id;
+                    return Some(Event::from(event.as_ref()));
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
@@ -6,6 +6,7 @@
 //!
 //! - `reader_stream`: `tokio::io::AsyncRead` からストリームを作成
 //! - `StreamingResponse::from_reader`: AsyncRead から直接 StreamingResponse を作成
+//! - [`JsonLines`] / [`JsonArrayStream`]: `Stream<Item = T>` を NDJSON / JSON 配列として逐次シリアライズ
 //!
 //! # Backpressure（背圧制御）について
 //!
@@ -46,9 +47,17 @@
 //! - 接続は閉じられ、空のレスポンスがクライアントに送信されます
 //! - 本番環境では、適切なロギングシステムを設定してください
 
-use crate::StreamingResponse;
-use bytes::Bytes;
-use futures_util::stream::TryStreamExt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::{ResponseModelOptions, StreamingResponse};
+use axum::body::Body;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::stream::{Stream, TryStreamExt};
+use serde::Serialize;
 use tokio::io::{AsyncRead, AsyncReadExt};
 
 /// AsyncRead からストリームを作成します
@@ -322,6 +331,270 @@ where
         .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> { Box::new(e) })
 }
 
+// ============================================================================
+// Typed JSON streams (NDJSON / JSON array)
+// ============================================================================
+
+/// Buffered bytes are sent once they reach this size, or as soon as the stream has no
+/// item ready
+const DEFAULT_FLUSH_THRESHOLD: usize = 8 * 1024;
+
+type ItemShaper = Arc<dyn Fn(serde_json::Value) -> serde_json::Value + Send + Sync>;
+
+#[derive(Clone)]
+struct JsonStreamOptions {
+    flush_threshold: usize,
+    shaper: Option<ItemShaper>,
+}
+
+impl Default for JsonStreamOptions {
+    fn default() -> Self {
+        Self {
+            flush_threshold: DEFAULT_FLUSH_THRESHOLD,
+            shaper: None,
+        }
+    }
+}
+
+impl JsonStreamOptions {
+    fn response_model(
+        &mut self,
+        options: ResponseModelOptions,
+        type_name: Option<&'static str>,
+        by_alias: bool,
+    ) {
+        self.shaper = Some(Arc::new(move |value| {
+            options.apply_with_aliases_and_field_set(value, type_name, by_alias, None)
+        }));
+    }
+}
+
+/// NDJSON (`application/x-ndjson`) レスポンス
+///
+/// `Stream<Item = T>` の各要素を 1 行の JSON としてシリアライズしながら送信します。
+/// ストリーム全体をメモリに溜めることはありません。
+///
+/// - 要素はクライアントの受信に合わせて 1 つずつ取り出されます (背圧)
+/// - 次の要素がすぐに用意できない場合はバッファ済みのデータを即座に送信します。
+///   続けて用意できる要素は `flush_threshold` (デフォルト 8KB) までまとめて送信されます
+/// - `#[response_model(...)]` は要素ごとに適用され、OpenAPI には要素のスキーマが
+///   `application/x-ndjson` として出力されます
+/// - シリアライズに失敗した場合は接続を打ち切ります
+///
+/// # Example
+///
+/// ```ignore
+/// use ultraapi::prelude::*;
+///
+/// #[get("/events")]
+/// async fn events() -> JsonLines<impl Stream<Item = Event> + Send> {
+///     JsonLines::new(futures_util::stream::iter(load_events()))
+/// }
+/// ```
+pub struct JsonLines<S> {
+    stream: S,
+    options: JsonStreamOptions,
+}
+
+impl<S> JsonLines<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            options: JsonStreamOptions::default(),
+        }
+    }
+
+    /// Bytes buffered before a chunk is sent while items keep arriving (0 sends every item)
+    pub fn flush_threshold(mut self, bytes: usize) -> Self {
+        self.options.flush_threshold = bytes;
+        self
+    }
+
+    /// Shape every item like `#[response_model(...)]` (used by the route macros)
+    #[doc(hidden)]
+    pub fn response_model(
+        mut self,
+        options: ResponseModelOptions,
+        type_name: Option<&'static str>,
+        by_alias: bool,
+    ) -> Self {
+        self.options.response_model(options, type_name, by_alias);
+        self
+    }
+}
+
+impl<S, T> IntoResponse for JsonLines<S>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        let body = JsonStreamBody::new(self.stream, Framing::Lines, self.options);
+        (
+            [(header::CONTENT_TYPE, "application/x-ndjson")],
+            Body::from_stream(body),
+        )
+            .into_response()
+    }
+}
+
+/// 要素を順にシリアライズする JSON 配列 (`application/json`) レスポンス
+///
+/// [`JsonLines`] と同じ背圧とフラッシュの規則で `[`, 要素, `,`, ..., `]` を送信します。
+/// OpenAPI には要素の配列として出力されます。途中でシリアライズに失敗した場合は
+/// 接続を打ち切るため、クライアントが不完全な配列を正しい JSON として受け取ることはありません。
+///
+/// # Example
+///
+/// ```ignore
+/// use ultraapi::prelude::*;
+///
+/// #[get("/users")]
+/// async fn users(db: Dep<Db>) -> JsonArrayStream<BoxStream<'static, User>> {
+///     JsonArrayStream::new(db.stream_users())
+/// }
+/// ```
+pub struct JsonArrayStream<S> {
+    stream: S,
+    options: JsonStreamOptions,
+}
+
+impl<S> JsonArrayStream<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            options: JsonStreamOptions::default(),
+        }
+    }
+
+    /// Bytes buffered before a chunk is sent while items keep arriving (0 sends every item)
+    pub fn flush_threshold(mut self, bytes: usize) -> Self {
+        self.options.flush_threshold = bytes;
+        self
+    }
+
+    /// Shape every item like `#[response_model(...)]` (used by the route macros)
+    #[doc(hidden)]
+    pub fn response_model(
+        mut self,
+        options: ResponseModelOptions,
+        type_name: Option<&'static str>,
+        by_alias: bool,
+    ) -> Self {
+        self.options.response_model(options, type_name, by_alias);
+        self
+    }
+}
+
+impl<S, T> IntoResponse for JsonArrayStream<S>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        let body = JsonStreamBody::new(self.stream, Framing::Array, self.options);
+        (
+            [(header::CONTENT_TYPE, "application/json")],
+            Body::from_stream(body),
+        )
+            .into_response()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    Lines,
+    Array,
+}
+
+/// Serializes the items of `stream` into body chunks
+struct JsonStreamBody<S> {
+    stream: Pin<Box<S>>,
+    framing: Framing,
+    options: JsonStreamOptions,
+    buf: BytesMut,
+    items: usize,
+    done: bool,
+}
+
+impl<S> JsonStreamBody<S> {
+    fn new(stream: S, framing: Framing, options: JsonStreamOptions) -> Self {
+        Self {
+            stream: Box::pin(stream),
+            framing,
+            options,
+            buf: BytesMut::new(),
+            items: 0,
+            done: false,
+        }
+    }
+
+    fn encode<T: Serialize>(&mut self, item: &T) -> serde_json::Result<()> {
+        if self.framing == Framing::Array {
+            self.buf
+                .extend_from_slice(if self.items == 0 { b"[" } else { b"," });
+        }
+        match &self.options.shaper {
+            Some(shaper) => {
+                let value = shaper(serde_json::to_value(item)?);
+                serde_json::to_writer((&mut self.buf).writer(), &value)?;
+            }
+            None => serde_json::to_writer((&mut self.buf).writer(), item)?,
+        }
+        if self.framing == Framing::Lines {
+            self.buf.extend_from_slice(b"\n");
+        }
+        self.items += 1;
+        Ok(())
+    }
+
+    fn take_chunk(&mut self) -> Poll<Option<std::io::Result<Bytes>>> {
+        Poll::Ready(Some(Ok(self.buf.split().freeze())))
+    }
+}
+
+impl<S, T> Stream for JsonStreamBody<S>
+where
+    S: Stream<Item = T>,
+    T: Serialize,
+{
+    type Item = std::io::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        loop {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    if let Err(e) = this.encode(&item) {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(std::io::Error::other(e))));
+                    }
+                    if this.buf.len() >= this.options.flush_threshold {
+                        return this.take_chunk();
+                    }
+                }
+                Poll::Ready(None) => {
+                    this.done = true;
+                    if this.framing == Framing::Array {
+                        this.buf
+                            .extend_from_slice(if this.items == 0 { b"[]" } else { b"]" });
+                    }
+                    if this.buf.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    return this.take_chunk();
+                }
+                // Nothing more is ready: send what has been buffered so far
+                Poll::Pending if this.buf.is_empty() => return Poll::Pending,
+                Poll::Pending => return this.take_chunk(),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -21,6 +21,9 @@
 //! - **Better control**: Direct access to the Router for testing
 //! - **No port conflicts**: No need to bind to network ports
 //!
+//! `#[ws]` routes are reached with `websocket()` (the router is served over an in-memory
+//! pipe so the upgrade works) and `#[sse]` routes with `sse()`, which parses the events.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -53,6 +56,7 @@ use axum::Router;
 use reqwest::Client;
 use tokio::net::TcpListener;
 use tokio::spawn;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tower::ServiceExt;
 
 /// Test client for UltraAPI applications
@@ -688,6 +692,19 @@ impl InProcessTestClient {
         }
     }
 
+    /// Open a WebSocket connection to a `#[ws]` route
+    ///
+    /// The handshake runs through the router over an in-memory connection. A rejected
+    /// handshake (e.g. 401 or 403) returns the HTTP response instead.
+    pub async fn websocket(&self, path: &str) -> Result<TestWebSocket, TestResponse> {
+        open_websocket(self.router.clone(), path, &[]).await
+    }
+
+    /// Open a Server-Sent Events stream and parse its events
+    pub async fn sse(&self, path: &str) -> TestEventStream {
+        open_event_stream(self.router.clone(), path, &[]).await
+    }
+
     /// Add custom headers to requests
     ///
     /// Returns a new client with the specified default headers.
@@ -750,6 +767,16 @@ impl InProcessTestClientWithHeader {
         .await
     }
 
+    /// Open a WebSocket connection with default headers
+    pub async fn websocket(&self, path: &str) -> Result<TestWebSocket, TestResponse> {
+        open_websocket(self.router.clone(), path, &self.default_headers).await
+    }
+
+    /// Open a Server-Sent Events stream with default headers
+    pub async fn sse(&self, path: &str) -> TestEventStream {
+        open_event_stream(self.router.clone(), path, &self.default_headers).await
+    }
+
     /// Send a request with custom headers merged with default headers
     pub async fn request(
         &self,
@@ -822,3 +849,299 @@ impl std::fmt::Debug for InProcessTestClientWithHeader {
             .finish()
     }
 }
+
+// ============================================================================
+// In-Process WebSocket / SSE
+// ============================================================================
+
+/// A message received by a [`TestWebSocket`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestWsMessage {
+    Text(String),
+    Binary(Vec<u8>),
+    /// Close frame with its code and reason, if any
+    Close(Option<(u16, String)>),
+}
+
+/// In-memory WebSocket connection opened by `InProcessTestClient::websocket`
+///
+/// Pings and pongs are answered and skipped automatically.
+pub struct TestWebSocket {
+    socket: tokio_tungstenite::WebSocketStream<tokio::io::DuplexStream>,
+    headers: axum::http::HeaderMap,
+}
+
+impl TestWebSocket {
+    /// Headers of the handshake response
+    pub fn headers(&self) -> &axum::http::HeaderMap {
+        &self.headers
+    }
+
+    /// Send a text message
+    pub async fn send_text(&mut self, text: impl Into<String>) {
+        self.send(WsMessage::text(text.into())).await;
+    }
+
+    /// Send a binary message
+    pub async fn send_binary(&mut self, data: impl Into<Vec<u8>>) {
+        self.send(WsMessage::binary(data.into())).await;
+    }
+
+    /// Send a value serialized as a JSON text message
+    pub async fn send_json<T: serde::Serialize>(&mut self, value: &T) {
+        let text = serde_json::to_string(value).expect("Failed to serialize message");
+        self.send_text(text).await;
+    }
+
+    /// Receive the next message; `None` once the connection is gone
+    pub async fn receive(&mut self) -> Option<TestWsMessage> {
+        use futures_util::StreamExt;
+
+        loop {
+            match self.socket.next().await? {
+                Ok(WsMessage::Text(text)) => return Some(TestWsMessage::Text(text.to_string())),
+                Ok(WsMessage::Binary(data)) => return Some(TestWsMessage::Binary(data.to_vec())),
+                Ok(WsMessage::Close(frame)) => {
+                    return Some(TestWsMessage::Close(
+                        frame.map(|frame| (u16::from(frame.code), frame.reason.to_string())),
+                    ))
+                }
+                Ok(_) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Receive a text message, panicking on anything else
+    pub async fn receive_text(&mut self) -> String {
+        match self.receive().await {
+            Some(TestWsMessage::Text(text)) => text,
+            other => panic!("expected a text message, got {:?}", other),
+        }
+    }
+
+    /// Receive a binary message, panicking on anything else
+    pub async fn receive_binary(&mut self) -> Vec<u8> {
+        match self.receive().await {
+            Some(TestWsMessage::Binary(data)) => data,
+            other => panic!("expected a binary message, got {:?}", other),
+        }
+    }
+
+    /// Receive a text message and deserialize it from JSON
+    pub async fn receive_json<T: serde::de::DeserializeOwned>(&mut self) -> T {
+        let text = self.receive_text().await;
+        serde_json::from_str(&text).expect("Failed to deserialize message")
+    }
+
+    /// Close the connection with a normal close frame
+    pub async fn close(mut self) {
+        let _ = self.socket.close(None).await;
+    }
+
+    async fn send(&mut self, message: WsMessage) {
+        use futures_util::SinkExt;
+
+        self.socket
+            .send(message)
+            .await
+            .expect("WebSocket send failed");
+    }
+}
+
+impl std::fmt::Debug for TestWebSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestWebSocket").finish()
+    }
+}
+
+/// Serve the router on one end of an in-memory duplex pipe and run the client
+/// handshake on the other, so upgrades work without a network listener.
+async fn open_websocket(
+    router: Router,
+    path: &str,
+    headers: &[(String, String)],
+) -> Result<TestWebSocket, TestResponse> {
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::tungstenite::Error as WsError;
+
+    let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+    spawn(async move {
+        let service = hyper_util::service::TowerToHyperService::new(router);
+        let _ = hyper::server::conn::http1::Builder::new()
+            .serve_connection(hyper_util::rt::TokioIo::new(server_io), service)
+            .with_upgrades()
+            .await;
+    });
+
+    let mut request = format!("ws://localhost{}", path)
+        .into_client_request()
+        .expect("Invalid WebSocket path");
+    for (key, value) in headers {
+        request.headers_mut().insert(
+            axum::http::HeaderName::from_bytes(key.as_bytes()).expect("Invalid header name"),
+            value.parse().expect("Invalid header value"),
+        );
+    }
+
+    match tokio_tungstenite::client_async(request, client_io).await {
+        Ok((socket, response)) => Ok(TestWebSocket {
+            socket,
+            headers: response.headers().clone(),
+        }),
+        Err(WsError::Http(response)) => {
+            let (parts, body) = response.into_parts();
+            Err(TestResponse {
+                status: parts.status,
+                headers: parts.headers,
+                body: body.unwrap_or_default().into(),
+            })
+        }
+        Err(e) => panic!("WebSocket handshake failed: {}", e),
+    }
+}
+
+/// An event parsed from a [`TestEventStream`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestSseEvent {
+    pub event: Option<String>,
+    /// `data:` lines joined with `\n`
+    pub data: String,
+    pub id: Option<String>,
+    pub retry: Option<u64>,
+}
+
+impl TestSseEvent {
+    /// Deserialize `data` from JSON
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.data)
+    }
+}
+
+/// Event stream opened by `InProcessTestClient::sse`
+///
+/// Yields every frame with at least one field (so `retry:`-only frames are visible);
+/// comment frames such as keep-alives are skipped.
+pub struct TestEventStream {
+    status: StatusCode,
+    headers: axum::http::HeaderMap,
+    body: axum::body::BodyDataStream,
+    buffer: Vec<u8>,
+}
+
+impl TestEventStream {
+    /// Get the HTTP status code
+    pub fn status(&self) -> u16 {
+        self.status.as_u16()
+    }
+
+    /// Get a header value
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// Get all headers
+    pub fn headers(&self) -> &axum::http::HeaderMap {
+        &self.headers
+    }
+
+    /// Next event; `None` when the stream ends
+    pub async fn next_event(&mut self) -> Option<TestSseEvent> {
+        futures_util::StreamExt::next(self).await
+    }
+
+    fn parse_buffered(&mut self) -> Option<TestSseEvent> {
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let frame: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            if let Some(event) = parse_sse_frame(&String::from_utf8_lossy(&frame)) {
+                return Some(event);
+            }
+        }
+        None
+    }
+}
+
+impl futures_util::Stream for TestEventStream {
+    type Item = TestSseEvent;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::task::Poll;
+
+        loop {
+            if let Some(event) = self.parse_buffered() {
+                return Poll::Ready(Some(event));
+            }
+            match futures_util::ready!(std::pin::Pin::new(&mut self.body).poll_next(cx)) {
+                // CRLF line endings are folded into LF
+                Some(Ok(chunk)) => self.buffer.extend(chunk.iter().filter(|b| **b != b'\r')),
+                Some(Err(_)) | None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for TestEventStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestEventStream")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish()
+    }
+}
+
+fn parse_sse_frame(frame: &str) -> Option<TestSseEvent> {
+    let mut event = TestSseEvent::default();
+    let mut data = Vec::new();
+    let mut has_field = false;
+
+    for line in frame.split('\n') {
+        if line.is_empty() || line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        has_field = true;
+        match field {
+            "event" => event.event = Some(value.to_string()),
+            "data" => data.push(value),
+            "id" => event.id = Some(value.to_string()),
+            "retry" => event.retry = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    event.data = data.join("\n");
+    has_field.then_some(event)
+}
+
+async fn open_event_stream(
+    router: Router,
+    path: &str,
+    headers: &[(String, String)],
+) -> TestEventStream {
+    let mut request_builder = Request::builder()
+        .uri(path)
+        .header("accept", "text/event-stream");
+    for (key, value) in headers {
+        request_builder = request_builder.header(key.as_str(), value.as_str());
+    }
+    let request = request_builder
+        .body(Body::empty())
+        .expect("Failed to build request");
+
+    let response = router.oneshot(request).await.expect("Request failed");
+    let status = response.status();
+    let headers = response.headers().clone();
+
+    TestEventStream {
+        status,
+        headers,
+        body: response.into_body().into_data_stream(),
+        buffer: Vec::new(),
+    }
+}
@@ -0,0 +1,742 @@
+//! File uploads: `UploadFile`, `#[form_model]` and upload limits
+//!
+//! multipart/form-data のリクエストボディをストリーミングで読み込みます。
+//!
+//! - [`UploadFile`]: アップロードされたファイル。`memory_threshold` を超えると一時ファイルに
+//!   書き出され (スプール)、ハンドラ終了後に削除されます
+//! - `#[form_model]`: multipart のフィールドを型付き構造体にバインドします。
+//!   [`MultipartForm<T>`] で受け取り、OpenAPI には各フィールドのスキーマ
+//!   (ファイルは `format: binary`) が出力されます
+//! - [`UploadLimits`]: `UltraApiApp::dep(UploadLimits::new()...)` で登録するアップロード制限。
+//!   フィールドごとの `#[upload(max_size = "5MB", content_types = [...])]` が優先されます。
+//!   リクエスト全体の上限は既定で [`DEFAULT_MAX_UPLOAD_SIZE`] (10 MiB) で、`#[body_limit]` や
+//!   グローバルの `body_limit` があればそちらに従います
+//!
+//! サイズ超過は 413、許可されていない Content-Type は 415、不正なファイル名や
+//! 欠落したフィールドは 422 を返します。
+//!
+//! ```ignore
+//! #[form_model]
+//! struct Avatar {
+//!     /// Display name
+//!     name: String,
+//!     #[upload(max_size = "2MB", content_types = ["image/png", "image/jpeg"])]
+//!     image: UploadFile,
+//!     attachments: Option<Vec<UploadFile>>,
+//! }
+//!
+//! #[post("/avatars")]
+//! async fn upload_avatar(form: MultipartForm<Avatar>) -> Result<String, ApiError> {
+//!     form.image.persist(format!("./avatars/{}", form.name)).await?;
+//!     Ok(form.name.clone())
+//! }
+//!
+//! // A single file: the parameter name is the field name
+//! #[post("/files")]
+//! async fn upload(file: UploadFile) -> String {
+//!     file.filename().unwrap_or_default().to_string()
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Cursor;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::{header, HeaderMap, StatusCode};
+use bytes::BytesMut;
+use tokio::io::{AsyncRead, AsyncWriteExt};
+
+use crate::middleware::RequestLimits;
+use crate::{ApiError, AppState, ValidationErrorDetail, ValidationSource};
+
+/// Request size limit of multipart bodies without `max_total_size` or a body limit
+pub const DEFAULT_MAX_UPLOAD_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Limits applied while reading multipart bodies.
+///
+/// Register with `UltraApiApp::dep`; routes without one use [`UploadLimits::default`].
+#[derive(Debug, Clone)]
+pub struct UploadLimits {
+    memory_threshold: usize,
+    max_file_size: Option<u64>,
+    max_total_size: Option<u64>,
+    max_files: Option<usize>,
+    max_field_size: usize,
+    allowed_content_types: Vec<String>,
+    spool_dir: Option<PathBuf>,
+}
+
+impl Default for UploadLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UploadLimits {
+    /// Files up to 1 MiB stay in memory, text fields up to 1 MiB, requests up to
+    /// [`DEFAULT_MAX_UPLOAD_SIZE`] unless the route has a body limit
+    pub fn new() -> Self {
+        Self {
+            memory_threshold: 1024 * 1024,
+            max_file_size: None,
+            max_total_size: None,
+            max_files: None,
+            max_field_size: 1024 * 1024,
+            allowed_content_types: Vec::new(),
+            spool_dir: None,
+        }
+    }
+
+    /// Files larger than this are written to a temporary file
+    pub fn memory_threshold(mut self, bytes: usize) -> Self {
+        self.memory_threshold = bytes;
+        self
+    }
+
+    /// Maximum size of each file (`#[upload(max_size = ...)]` overrides it per field).
+    /// Defaults to the request size limit.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Maximum size of all fields and files of a request. Defaults to the route's
+    /// `#[body_limit]` or the global body limit, else [`DEFAULT_MAX_UPLOAD_SIZE`].
+    pub fn max_total_size(mut self, bytes: u64) -> Self {
+        self.max_total_size = Some(bytes);
+        self
+    }
+
+    /// Maximum number of files in a request
+    pub fn max_files(mut self, count: usize) -> Self {
+        self.max_files = Some(count);
+        self
+    }
+
+    /// Maximum size of each text field
+    pub fn max_field_size(mut self, bytes: usize) -> Self {
+        self.max_field_size = bytes;
+        self
+    }
+
+    /// Accepted file content types; `image/*` matches any image type. Empty accepts all.
+    pub fn allowed_content_types<I, S>(mut self, content_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_content_types = content_types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Directory for spooled files (the system temp directory by default)
+    pub fn spool_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spool_dir = Some(dir.into());
+        self
+    }
+}
+
+/// A file received in a multipart request.
+pub struct UploadFile {
+    field_name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    headers: HeaderMap,
+    size: u64,
+    storage: Storage,
+}
+
+enum Storage {
+    Memory(Bytes),
+    /// Deleted when the `UploadFile` is dropped
+    Disk(tempfile::NamedTempFile),
+}
+
+impl fmt::Debug for UploadFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadFile")
+            .field("field_name", &self.field_name)
+            .field("filename", &self.filename)
+            .field("content_type", &self.content_type)
+            .field("size", &self.size)
+            .field("path", &self.path())
+            .finish()
+    }
+}
+
+impl UploadFile {
+    pub fn field_name(&self) -> &str {
+        &self.field_name
+    }
+
+    /// The client's file name without any directory part
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Headers of the multipart part
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Size in bytes
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn is_in_memory(&self) -> bool {
+        matches!(self.storage, Storage::Memory(_))
+    }
+
+    /// The temporary file holding a spooled upload
+    pub fn path(&self) -> Option<&Path> {
+        match &self.storage {
+            Storage::Memory(_) => None,
+            Storage::Disk(file) => Some(file.path()),
+        }
+    }
+
+    /// Read the whole file into memory
+    pub async fn bytes(&self) -> std::io::Result<Bytes> {
+        match &self.storage {
+            Storage::Memory(bytes) => Ok(bytes.clone()),
+            Storage::Disk(file) => tokio::fs::read(file.path()).await.map(Bytes::from),
+        }
+    }
+
+    /// Read the whole file as UTF-8 text
+    pub async fn text(&self) -> std::io::Result<String> {
+        let bytes = self.bytes().await?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Read the file without loading it into memory
+    pub async fn reader(&self) -> std::io::Result<Pin<Box<dyn AsyncRead + Send + Sync>>> {
+        match &self.storage {
+            Storage::Memory(bytes) => Ok(Box::pin(Cursor::new(bytes.clone()))),
+            Storage::Disk(file) => Ok(Box::pin(tokio::fs::File::open(file.path()).await?)),
+        }
+    }
+
+    /// Copy the file to `dest`. Returns the number of bytes written.
+    pub async fn save(&self, dest: impl AsRef<Path>) -> std::io::Result<u64> {
+        match &self.storage {
+            Storage::Memory(bytes) => {
+                tokio::fs::write(dest, bytes).await?;
+                Ok(bytes.len() as u64)
+            }
+            Storage::Disk(file) => tokio::fs::copy(file.path(), dest).await,
+        }
+    }
+
+    /// Move the file to `dest`, renaming the temporary file when possible
+    pub async fn persist(self, dest: impl AsRef<Path>) -> std::io::Result<()> {
+        let dest = dest.as_ref().to_path_buf();
+        match self.storage {
+            Storage::Memory(bytes) => tokio::fs::write(dest, bytes).await,
+            Storage::Disk(file) => {
+                let renamed = {
+                    let dest = dest.clone();
+                    tokio::task::spawn_blocking(move || file.persist(dest))
+                        .await
+                        .map_err(std::io::Error::other)?
+                };
+                match renamed {
+                    Ok(_) => Ok(()),
+                    // e.g. across file systems: copy, then the temporary file is removed
+                    Err(e) => tokio::fs::copy(e.file.path(), dest).await.map(|_| ()),
+                }
+            }
+        }
+    }
+}
+
+impl schemars::JsonSchema for UploadFile {
+    fn schema_name() -> String {
+        "UploadFile".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            format: Some("binary".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Upload rules of a `#[form_model]` field, from `#[upload(...)]`
+#[derive(Debug, Clone, Copy)]
+pub struct FormField {
+    pub name: &'static str,
+    pub max_size: Option<u64>,
+    pub content_types: &'static [&'static str],
+}
+
+/// A struct bound from multipart fields. Implemented by `#[form_model]`.
+pub trait FormModel: Sized {
+    fn form_fields() -> &'static [FormField];
+
+    fn from_form_data(form: &mut FormData) -> Result<Self, Vec<ValidationErrorDetail>>;
+}
+
+/// The text fields and files of a multipart body, by field name
+#[derive(Debug, Default)]
+pub struct FormData {
+    text: HashMap<String, Vec<String>>,
+    files: HashMap<String, Vec<UploadFile>>,
+}
+
+impl FormData {
+    /// Read a multipart body, enforcing `limits` and the rules of `fields`.
+    ///
+    /// When `fields` is not empty, parts with other names are read and discarded.
+    pub async fn read(
+        req: Request,
+        limits: &UploadLimits,
+        fields: &[FormField],
+    ) -> Result<Self, ApiError> {
+        let boundary = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| multer::parse_boundary(value).ok())
+            .ok_or_else(|| {
+                ApiError::bad_request(
+                    "Invalid multipart: expected multipart/form-data with a boundary".to_string(),
+                )
+            })?;
+        // A body limit set on the route or the app replaces the default request limit
+        let max_total_size = limits.max_total_size.unwrap_or_else(|| {
+            req.extensions()
+                .get::<RequestLimits>()
+                .and_then(|request_limits| request_limits.body_limit)
+                .unwrap_or(DEFAULT_MAX_UPLOAD_SIZE)
+        });
+        let mut multipart = multer::Multipart::new(req.into_body().into_data_stream(), boundary);
+
+        let mut form = FormData::default();
+        let mut reader = BodyReader {
+            limits,
+            max_total_size,
+            total: 0,
+            files: 0,
+        };
+        while let Some(mut field) = multipart.next_field().await.map_err(invalid_multipart)? {
+            let name = field.name().unwrap_or_default().to_string();
+            let rule = fields.iter().find(|rule| rule.name == name);
+            if !fields.is_empty() && rule.is_none() {
+                reader.discard(&mut field).await?;
+                continue;
+            }
+
+            match field.file_name().map(str::to_string) {
+                // Browsers send an empty part when no file was chosen
+                Some(raw) if raw.is_empty() => reader.discard(&mut field).await?,
+                Some(raw) => {
+                    let file = reader.read_file(&mut field, &name, &raw, rule).await?;
+                    form.files.entry(name).or_default().push(file);
+                }
+                None => {
+                    let value = reader.read_text(&mut field, &name).await?;
+                    form.text.entry(name).or_default().push(value);
+                }
+            }
+        }
+        Ok(form)
+    }
+
+    /// Remove and return the values of a text field
+    pub fn take_text(&mut self, name: &str) -> Vec<String> {
+        self.text.remove(name).unwrap_or_default()
+    }
+
+    /// Remove and return the files of a field
+    pub fn take_files(&mut self, name: &str) -> Vec<UploadFile> {
+        self.files.remove(name).unwrap_or_default()
+    }
+
+    /// Whether the request had a text field or a file with this name
+    pub fn contains(&self, name: &str) -> bool {
+        self.text.contains_key(name) || self.files.contains_key(name)
+    }
+}
+
+struct BodyReader<'a> {
+    limits: &'a UploadLimits,
+    max_total_size: u64,
+    total: u64,
+    files: usize,
+}
+
+impl BodyReader<'_> {
+    async fn next_chunk(
+        &mut self,
+        field: &mut multer::Field<'_>,
+    ) -> Result<Option<Bytes>, ApiError> {
+        let Some(chunk) = field.chunk().await.map_err(invalid_multipart)? else {
+            return Ok(None);
+        };
+        self.total += chunk.len() as u64;
+        if self.total > self.max_total_size {
+            return Err(too_large(format!(
+                "Request body exceeds the maximum size of {} bytes",
+                self.max_total_size
+            )));
+        }
+        Ok(Some(chunk))
+    }
+
+    async fn discard(&mut self, field: &mut multer::Field<'_>) -> Result<(), ApiError> {
+        while self.next_chunk(field).await?.is_some() {}
+        Ok(())
+    }
+
+    async fn read_text(
+        &mut self,
+        field: &mut multer::Field<'_>,
+        name: &str,
+    ) -> Result<String, ApiError> {
+        let mut value = BytesMut::new();
+        while let Some(chunk) = self.next_chunk(field).await? {
+            if value.len() + chunk.len() > self.limits.max_field_size {
+                return Err(too_large(format!(
+                    "Form field \"{}\" exceeds the maximum size of {} bytes",
+                    name, self.limits.max_field_size
+                )));
+            }
+            value.extend_from_slice(&chunk);
+        }
+        String::from_utf8(value.to_vec()).map_err(|_| {
+            ApiError::bad_request(format!(
+                "Invalid multipart: field \"{}\" is not UTF-8",
+                name
+            ))
+        })
+    }
+
+    async fn read_file(
+        &mut self,
+        field: &mut multer::Field<'_>,
+        name: &str,
+        raw_filename: &str,
+        rule: Option<&FormField>,
+    ) -> Result<UploadFile, ApiError> {
+        let Some(filename) = sanitize_filename(raw_filename) else {
+            return Err(ApiError::validation_errors(
+                ValidationSource::Body,
+                vec![
+                    ValidationErrorDetail::new("value_error", "Invalid filename")
+                        .at(name)
+                        .with_input(raw_filename),
+                ],
+            ));
+        };
+
+        self.files += 1;
+        if let Some(max) = self.limits.max_files.filter(|max| self.files > *max) {
+            return Err(too_large(format!("Too many files (maximum {})", max)));
+        }
+
+        let content_type = field.content_type().map(|mime| mime.to_string());
+        let allowed = match rule {
+            Some(rule) if !rule.content_types.is_empty() => {
+                content_type_allowed(rule.content_types, content_type.as_deref())
+            }
+            _ => content_type_allowed(&self.limits.allowed_content_types, content_type.as_deref()),
+        };
+        if !allowed {
+            return Err(ApiError::with_status(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!(
+                    "File \"{}\": content type \"{}\" is not allowed",
+                    name,
+                    content_type.as_deref().unwrap_or("")
+                ),
+            ));
+        }
+
+        let max_size = rule
+            .and_then(|rule| rule.max_size)
+            .or(self.limits.max_file_size);
+        let headers = field.headers().clone();
+
+        let mut size = 0u64;
+        let mut buffer = BytesMut::new();
+        let mut spooled: Option<(tempfile::NamedTempFile, tokio::fs::File)> = None;
+        while let Some(chunk) = self.next_chunk(field).await? {
+            size += chunk.len() as u64;
+            if let Some(max) = max_size.filter(|max| size > *max) {
+                return Err(too_large(format!(
+                    "File \"{}\" exceeds the maximum size of {} bytes",
+                    name, max
+                )));
+            }
+            match &mut spooled {
+                Some((_, file)) => file.write_all(&chunk).await.map_err(spool_error)?,
+                None if buffer.len() + chunk.len() > self.limits.memory_threshold => {
+                    let temp = match &self.limits.spool_dir {
+                        Some(dir) => tempfile::NamedTempFile::new_in(dir),
+                        None => tempfile::NamedTempFile::new(),
+                    }
+                    .map_err(spool_error)?;
+                    let mut file = tokio::fs::File::from_std(temp.reopen().map_err(spool_error)?);
+                    file.write_all(&buffer).await.map_err(spool_error)?;
+                    file.write_all(&chunk).await.map_err(spool_error)?;
+                    buffer.clear();
+                    spooled = Some((temp, file));
+                }
+                None => buffer.extend_from_slice(&chunk),
+            }
+        }
+
+        let storage = match spooled {
+            Some((temp, mut file)) => {
+                file.flush().await.map_err(spool_error)?;
+                Storage::Disk(temp)
+            }
+            None => Storage::Memory(buffer.freeze()),
+        };
+        Ok(UploadFile {
+            field_name: name.to_string(),
+            filename: Some(filename),
+            content_type,
+            headers,
+            size,
+            storage,
+        })
+    }
+}
+
+/// Keep the last path component; reject names that are empty, `.`/`..`, too long or
+/// contain control characters
+fn sanitize_filename(raw: &str) -> Option<String> {
+    let name = raw.rsplit(['/', '\\']).next().unwrap_or(raw).trim();
+    if name.is_empty()
+        || name == "."
+        || name == ".."
+        || name.len() > 255
+        || name.chars().any(char::is_control)
+    {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+fn content_type_allowed<S: AsRef<str>>(allowed: &[S], content_type: Option<&str>) -> bool {
+    if allowed.is_empty() {
+        return true;
+    }
+    let Some(content_type) = content_type else {
+        return false;
+    };
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    allowed.iter().any(|allowed| {
+        let allowed = allowed.as_ref().trim().to_ascii_lowercase();
+        match allowed.strip_suffix("/*") {
+            Some("*") => true,
+            Some(major) => essence.split('/').next() == Some(major),
+            None => allowed == essence,
+        }
+    })
+}
+
+fn too_large(error: String) -> ApiError {
+    ApiError::payload_too_large(error)
+}
+
+fn invalid_multipart(error: multer::Error) -> ApiError {
+    ApiError::bad_request(format!("Invalid multipart: {}", error))
+}
+
+fn spool_error(error: std::io::Error) -> ApiError {
+    ApiError::internal(format!("Failed to spool upload: {}", error))
+}
+
+/// A `#[form_model]` struct read from a multipart body.
+#[derive(Debug)]
+pub struct MultipartForm<T>(pub T);
+
+impl<T> MultipartForm<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for MultipartForm<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MultipartForm<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> FromRequest<AppState> for MultipartForm<T>
+where
+    T: FormModel + Send,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &AppState) -> Result<Self, Self::Rejection> {
+        let limits = state.get::<UploadLimits>().unwrap_or_default();
+        let mut form = FormData::read(req, &limits, T::form_fields()).await?;
+        T::from_form_data(&mut form)
+            .map(MultipartForm)
+            .map_err(|errors| ApiError::validation_errors(ValidationSource::Body, errors))
+    }
+}
+
+/// Conversion of a `#[form_model]` field from the form data
+pub trait FromFormField: Sized {
+    #[allow(clippy::result_large_err)]
+    fn from_form_field(name: &str, form: &mut FormData) -> Result<Self, ValidationErrorDetail>;
+}
+
+/// Text field values parsed from a single string
+pub trait FormScalar: Sized {
+    #[allow(clippy::result_large_err)]
+    fn parse_form_value(value: &str) -> Result<Self, ValidationErrorDetail>;
+}
+
+fn missing(name: &str) -> ValidationErrorDetail {
+    ValidationErrorDetail::new("missing", "Field required").at(name)
+}
+
+impl FromFormField for UploadFile {
+    fn from_form_field(name: &str, form: &mut FormData) -> Result<Self, ValidationErrorDetail> {
+        let mut files = form.take_files(name);
+        if files.is_empty() {
+            let text = form.take_text(name);
+            return Err(match text.first() {
+                Some(value) => ValidationErrorDetail::new("value_error", "Expected a file upload")
+                    .at(name)
+                    .with_input(value),
+                None => missing(name),
+            });
+        }
+        Ok(files.swap_remove(0))
+    }
+}
+
+impl FromFormField for Vec<UploadFile> {
+    fn from_form_field(name: &str, form: &mut FormData) -> Result<Self, ValidationErrorDetail> {
+        let files = form.take_files(name);
+        if files.is_empty() {
+            return Err(missing(name));
+        }
+        Ok(files)
+    }
+}
+
+impl<T: FormScalar> FromFormField for T {
+    fn from_form_field(name: &str, form: &mut FormData) -> Result<Self, ValidationErrorDetail> {
+        let values = form.take_text(name);
+        let Some(value) = values.first() else {
+            return Err(if form.take_files(name).is_empty() {
+                missing(name)
+            } else {
+                ValidationErrorDetail::new("value_error", "Expected a text field").at(name)
+            });
+        };
+        T::parse_form_value(value).map_err(|e| e.at(name).with_input(value))
+    }
+}
+
+impl<T: FormScalar> FromFormField for Vec<T> {
+    #[allow(clippy::result_large_err)]
+    fn from_form_field(name: &str, form: &mut FormData) -> Result<Self, ValidationErrorDetail> {
+        let values = form.take_text(name);
+        if values.is_empty() {
+            return Err(missing(name));
+        }
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                T::parse_form_value(value)
+                    .map_err(|e| e.at(&format!("{}[{}]", name, index)).with_input(value))
+            })
+            .collect()
+    }
+}
+
+/// Absent fields are `None`
+impl<T: FromFormField> FromFormField for Option<T> {
+    fn from_form_field(name: &str, form: &mut FormData) -> Result<Self, ValidationErrorDetail> {
+        if !form.contains(name) {
+            return Ok(None);
+        }
+        T::from_form_field(name, form).map(Some)
+    }
+}
+
+impl FormScalar for String {
+    fn parse_form_value(value: &str) -> Result<Self, ValidationErrorDetail> {
+        Ok(value.to_string())
+    }
+}
+
+impl FormScalar for bool {
+    fn parse_form_value(value: &str) -> Result<Self, ValidationErrorDetail> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "on" | "yes" | "1" => Ok(true),
+            "false" | "off" | "no" | "0" => Ok(false),
+            _ => Err(ValidationErrorDetail::new(
+                "bool_parsing",
+                "Input should be a valid boolean, unable to interpret input",
+            )),
+        }
+    }
+}
+
+macro_rules! form_scalar {
+    ($error_type:literal, $msg:literal: $($ty:ty),*) => {
+        $(
+            impl FormScalar for $ty {
+                fn parse_form_value(value: &str) -> Result<Self, ValidationErrorDetail> {
+                    value
+                        .trim()
+                        .parse()
+                        .map_err(|_| ValidationErrorDetail::new($error_type, $msg))
+                }
+            }
+        )*
+    };
+}
+
+form_scalar!(
+    "int_parsing",
+    "Input should be a valid integer, unable to parse string as an integer":
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+form_scalar!(
+    "float_parsing",
+    "Input should be a valid number, unable to parse string as a number":
+    f32, f64
+);
@@ -0,0 +1,257 @@
+//! Typed WebSocket messages for `#[ws]` routes
+//!
+//! `TypedSocket<In, Out>` は axum の `WebSocket` をラップし、テキスト / バイナリフレームを
+//! JSON として `In` にデシリアライズ・バリデーションし、`Out` を JSON テキストフレームとして送信します。
+//! 不正なメッセージは [`InvalidMessage`] に従い、エラーメッセージ (`{"detail": [...]}`、
+//! HTTP の 422 と同じ形式) として返すか、close フレーム (1007) で接続を閉じます。
+//!
+//! `#[ws]` ハンドラの引数に `TypedSocket` を書くと、マクロがアップグレードを行い、
+//! メッセージ型を AsyncAPI ドキュメント (`/asyncapi.json`) に登録します。
+//! `#[ws(..., ping_interval_secs = 30, idle_timeout_secs = 90)]` を指定すると、
+//! `recv` の待機中に ping を送信し、無通信の接続を閉じます。
+//!
+//! ```ignore
+//! #[api_model]
+//! #[serde(tag = "type")]
+//! enum ClientMsg {
+//!     Join { room: String },
+//!     Say { text: String },
+//! }
+//!
+//! #[api_model]
+//! #[serde(tag = "type")]
+//! enum ServerMsg {
+//!     Said { room: String, text: String },
+//! }
+//!
+//! #[ws("/chat", incoming = ClientMsg, outgoing = ServerMsg)]
+//! async fn chat(mut socket: TypedSocket<ClientMsg, ServerMsg>) {
+//!     let mut room = String::new();
+//!     while let Some(message) = socket.recv().await {
+//!         match message {
+//!             ClientMsg::Join { room: joined } => room = joined,
+//!             ClientMsg::Say { text } => {
+//!                 let _ = socket.send(&ServerMsg::Said { room: room.clone(), text }).await;
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use axum::extract::ws::{CloseFrame, Message, WebSocket};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+use crate::{Validate, ValidationErrorDetail};
+
+/// Close code sent when a message is rejected with [`InvalidMessage::Close`]
+/// (RFC 6455 "invalid frame payload data").
+pub const CLOSE_INVALID_PAYLOAD: u16 = 1007;
+
+/// Close code sent when a connection exceeds its idle timeout (RFC 6455 "going away").
+pub const CLOSE_GOING_AWAY: u16 = 1001;
+
+/// What [`TypedSocket::recv`] does with a message that fails to parse or validate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidMessage {
+    /// Reply with `{"detail": [...]}` and keep waiting for the next message
+    #[default]
+    ErrorMessage,
+    /// Close the connection with code 1007 and the first error as the reason
+    Close,
+}
+
+/// A WebSocket exchanging JSON messages: `In` from the client, `Out` to the client.
+pub struct TypedSocket<In, Out> {
+    socket: WebSocket,
+    on_invalid: InvalidMessage,
+    ping: Option<Interval>,
+    idle_timeout: Option<Duration>,
+    last_activity: Instant,
+    _messages: PhantomData<fn(Out) -> In>,
+}
+
+impl<In, Out> TypedSocket<In, Out>
+where
+    In: DeserializeOwned + Validate,
+    Out: Serialize,
+{
+    pub fn new(socket: WebSocket) -> Self {
+        Self {
+            socket,
+            on_invalid: InvalidMessage::default(),
+            ping: None,
+            idle_timeout: None,
+            last_activity: Instant::now(),
+            _messages: PhantomData,
+        }
+    }
+
+    /// Choose how invalid incoming messages are answered (default: error message).
+    pub fn on_invalid(mut self, policy: InvalidMessage) -> Self {
+        self.on_invalid = policy;
+        self
+    }
+
+    /// Send a ping frame at this interval while waiting in `recv` / `try_recv`.
+    pub fn ping_interval(mut self, period: Duration) -> Self {
+        let mut ping = tokio::time::interval_at(Instant::now() + period, period);
+        ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        self.ping = Some(ping);
+        self
+    }
+
+    /// Close the connection (code 1001) when no frame, pongs included, arrived for this long.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Receive the next valid message, or `None` once the connection is closed.
+    ///
+    /// Invalid messages are handled according to [`InvalidMessage`] and never returned.
+    pub async fn recv(&mut self) -> Option<In> {
+        loop {
+            match self.try_recv().await? {
+                Ok(message) => return Some(message),
+                Err(errors) => match self.on_invalid {
+                    InvalidMessage::ErrorMessage => {
+                        self.send_errors(&errors).await.ok()?;
+                    }
+                    InvalidMessage::Close => {
+                        let reason = errors
+                            .first()
+                            .map(|error| format!("{}: {}", error.field_path(), error.msg))
+                            .unwrap_or_default();
+                        let _ = self.close(CLOSE_INVALID_PAYLOAD, &reason).await;
+                        return None;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Receive the next message without handling errors: parse and validation
+    /// failures are returned to the caller. Control frames are skipped.
+    pub async fn try_recv(&mut self) -> Option<Result<In, Vec<ValidationErrorDetail>>> {
+        loop {
+            let parsed = match self.next_frame().await? {
+                Message::Text(text) => serde_json::from_str(text.as_str()),
+                Message::Binary(bytes) => serde_json::from_slice(&bytes),
+                Message::Close(_) => return None,
+                Message::Ping(_) | Message::Pong(_) => continue,
+            };
+            let value = match parsed {
+                Ok(value) => value,
+                Err(error) => {
+                    return Some(Err(vec![ValidationErrorDetail::new(
+                        "json_invalid",
+                        "JSON decode error",
+                    )
+                    .with_ctx(serde_json::json!({ "error": error.to_string() }))]));
+                }
+            };
+            return Some(
+                crate::deserialize::from_json_value::<In>(value).and_then(|message| {
+                    message.validate_detailed()?;
+                    Ok(message)
+                }),
+            );
+        }
+    }
+
+    /// Next frame from the client, sending pings and enforcing the idle timeout meanwhile.
+    async fn next_frame(&mut self) -> Option<Message> {
+        enum Event {
+            Frame(Option<Result<Message, axum::Error>>),
+            Ping,
+            Idle,
+        }
+
+        loop {
+            let idle_deadline = self
+                .idle_timeout
+                .map(|timeout| self.last_activity + timeout);
+            let event = tokio::select! {
+                frame = self.socket.recv() => Event::Frame(frame),
+                _ = tick(self.ping.as_mut()) => Event::Ping,
+                _ = sleep_until(idle_deadline) => Event::Idle,
+            };
+            match event {
+                Event::Frame(frame) => {
+                    self.last_activity = Instant::now();
+                    return frame?.ok();
+                }
+                Event::Ping => {
+                    self.socket
+                        .send(Message::Ping(Default::default()))
+                        .await
+                        .ok()?;
+                }
+                Event::Idle => {
+                    let _ = self.close(CLOSE_GOING_AWAY, "idle timeout").await;
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Send a message as a JSON text frame.
+    pub async fn send(&mut self, message: &Out) -> Result<(), axum::Error> {
+        let text = serde_json::to_string(message).map_err(axum::Error::new)?;
+        self.socket.send(Message::Text(text.into())).await
+    }
+
+    /// Send a frame as is, e.g. a [`HubMessage`](crate::hub::HubMessage) already serialized to JSON.
+    pub async fn send_raw(&mut self, message: Message) -> Result<(), axum::Error> {
+        self.socket.send(message).await
+    }
+
+    /// Send validation errors as `{"detail": [...]}`.
+    pub async fn send_errors(
+        &mut self,
+        errors: &[ValidationErrorDetail],
+    ) -> Result<(), axum::Error> {
+        let text = serde_json::json!({ "detail": errors }).to_string();
+        self.socket.send(Message::Text(text.into())).await
+    }
+
+    /// Close the connection. The reason is truncated to the 123 bytes a close frame allows.
+    pub async fn close(&mut self, code: u16, reason: &str) -> Result<(), axum::Error> {
+        let mut end = reason.len().min(123);
+        while !reason.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.socket
+            .send(Message::Close(Some(CloseFrame {
+                code,
+                reason: reason[..end].into(),
+            })))
+            .await
+    }
+
+    /// Access the underlying socket, e.g. to send raw frames.
+    pub fn into_inner(self) -> WebSocket {
+        self.socket
+    }
+}
+
+async fn tick(interval: Option<&mut Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
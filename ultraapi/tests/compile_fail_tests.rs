@@ -24,3 +24,11 @@ fn test_response_model_parser_variants() {
     t.compile_fail("tests/ui/response_model_parser_invalid_bool.rs");
     t.compile_fail("tests/ui/response_model_parser_invalid_selector_syntax.rs");
 }
+
+#[test]
+fn test_ws_raw_handler_rejects_keepalive() {
+    let t = TestCases::new();
+
+    // Raw WebSocketUpgrade handlers get no keepalive
+    t.compile_fail("tests/ui/ws_raw_handler_keepalive.rs");
+}
@@ -383,6 +383,7 @@ async fn test_depends_no_cache_re_evaluates_only_target_dependency() {
 }
 
 #[derive(Clone)]
+#[allow(dead_code)]
 struct RouteDecoratorService {
     invocation: usize,
 }
@@ -0,0 +1,165 @@
+//! Deserialization Error Tests
+//!
+//! JSON bodies that fail serde deserialization (missing fields, wrong types,
+//! unknown enum variants) are reported as 422 entries with exact `loc` paths,
+//! collecting as many errors as possible in one response.
+
+use serde_json::json;
+use ultraapi::prelude::*;
+
+// --- Test Models ---
+
+#[api_model]
+#[derive(Debug, Clone)]
+enum DeserShipping {
+    Standard,
+    Express,
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct DeserLine {
+    sku: String,
+    quantity: i64,
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct DeserOrder {
+    customer: String,
+    paid: bool,
+    shipping: DeserShipping,
+    lines: Vec<DeserLine>,
+    note: Option<String>,
+}
+
+// --- Test Routes ---
+
+#[post("/__test_deser_orders")]
+async fn create_deser_order(body: DeserOrder) -> DeserOrder {
+    body
+}
+
+// --- Helpers ---
+
+fn create_app() -> UltraApiApp {
+    UltraApiApp::new()
+        .title("Deserialization Error Test API")
+        .version("0.1.0")
+}
+
+fn valid_order() -> serde_json::Value {
+    json!({
+        "customer": "alice",
+        "paid": true,
+        "shipping": "Express",
+        "lines": [{ "sku": "A-1", "quantity": 2 }],
+        "note": null
+    })
+}
+
+async fn post_invalid(client: &TestClient, body: serde_json::Value) -> Vec<serde_json::Value> {
+    let response = client.post("/__test_deser_orders", &body).await;
+    assert_eq!(response.status(), 422, "expected 422 for {}", body);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["detail"].as_array().unwrap().clone()
+}
+
+// --- Tests ---
+
+#[tokio::test]
+async fn test_valid_body_still_deserializes() {
+    let client = TestClient::new(create_app()).await;
+    let response = client.post("/__test_deser_orders", &valid_order()).await;
+    assert_eq!(response.status(), 201);
+}
+
+#[tokio::test]
+async fn test_missing_field_reports_missing() {
+    let client = TestClient::new(create_app()).await;
+    let mut order = valid_order();
+    order.as_object_mut().unwrap().remove("customer");
+
+    let errors = post_invalid(&client, order).await;
+    assert_eq!(
+        errors,
+        vec![json!({ "loc": ["body", "customer"], "msg": "Field required", "type": "missing" })]
+    );
+}
+
+#[tokio::test]
+async fn test_wrong_type_reports_type_error_with_input() {
+    let client = TestClient::new(create_app()).await;
+    let mut order = valid_order();
+    order["lines"][0]["quantity"] = json!("two");
+
+    let errors = post_invalid(&client, order).await;
+    assert_eq!(
+        errors,
+        vec![json!({
+            "loc": ["body", "lines", 0, "quantity"],
+            "msg": "Input should be a valid integer",
+            "type": "type_error",
+            "input": "two"
+        })]
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_variant_reports_enum() {
+    let client = TestClient::new(create_app()).await;
+    let mut order = valid_order();
+    order["shipping"] = json!("Overnight");
+
+    let errors = post_invalid(&client, order).await;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["loc"], json!(["body", "shipping"]));
+    assert_eq!(errors[0]["type"], "enum");
+    assert_eq!(errors[0]["msg"], "Input should be 'Standard' or 'Express'");
+    assert_eq!(errors[0]["input"], "Overnight");
+}
+
+#[tokio::test]
+async fn test_multiple_errors_are_collected() {
+    let client = TestClient::new(create_app()).await;
+    let order = json!({
+        "paid": "yes",
+        "shipping": "Overnight",
+        "lines": [{ "sku": "A-1" }, { "sku": 7, "quantity": 1 }],
+        "note": 3
+    });
+
+    // Missing fields come first, then present fields in name order
+    let errors = post_invalid(&client, order).await;
+    let summary: Vec<(serde_json::Value, serde_json::Value)> = errors
+        .iter()
+        .map(|e| (e["loc"].clone(), e["type"].clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (json!(["body", "customer"]), json!("missing")),
+            (json!(["body", "lines", 0, "quantity"]), json!("missing")),
+            (json!(["body", "lines", 1, "sku"]), json!("type_error")),
+            (json!(["body", "note"]), json!("type_error")),
+            (json!(["body", "paid"]), json!("type_error")),
+            (json!(["body", "shipping"]), json!("enum")),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_malformed_json_is_json_invalid() {
+    let client = TestClient::new(create_app()).await;
+    let response = client
+        .client()
+        .post(format!("{}/__test_deser_orders", client.base_url()))
+        .header("content-type", "application/json")
+        .body("{not json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 422);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["detail"][0]["type"], "json_invalid");
+}
@@ -191,7 +191,7 @@ async fn test_create_user_invalid_email_returns_422() {
 }
 
 #[tokio::test]
-async fn test_create_user_malformed_json_returns_400() {
+async fn test_create_user_malformed_json_returns_422() {
     let base = spawn_app().await;
     let client = reqwest::Client::new();
     let resp = client
@@ -201,7 +201,7 @@ async fn test_create_user_malformed_json_returns_400() {
         .send()
         .await
         .unwrap();
-    assert_eq!(resp.status(), 400);
+    assert_eq!(resp.status(), 422);
 }
 
 #[tokio::test]
@@ -0,0 +1,262 @@
+// Tests for DiskFileResponse and static files: Range requests and conditional GET
+
+use std::path::PathBuf;
+
+use tempfile::TempDir;
+use ultraapi::prelude::*;
+
+const CONTENT: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+#[derive(Clone)]
+struct FileDir(PathBuf);
+
+#[get("/files/report")]
+#[response_class("file")]
+async fn report(dir: Dep<FileDir>) -> DiskFileResponse {
+    DiskFileResponse::new(dir.0.join("report.txt")).filename("report.txt")
+}
+
+#[get("/files/missing")]
+#[response_class("file")]
+async fn missing(dir: Dep<FileDir>) -> DiskFileResponse {
+    DiskFileResponse::new(dir.0.join("missing.txt"))
+}
+
+fn setup() -> (TempDir, UltraApiApp) {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("report.txt"), CONTENT).unwrap();
+    std::fs::create_dir(temp_dir.path().join("docs")).unwrap();
+    std::fs::write(temp_dir.path().join("docs/index.html"), "<h1>docs</h1>").unwrap();
+
+    let app = UltraApiApp::new()
+        .dep(FileDir(temp_dir.path().to_path_buf()))
+        .static_files("/static", temp_dir.path().to_str().unwrap());
+    (temp_dir, app)
+}
+
+async fn get(client: &TestClient, path: &str, headers: &[(&str, &str)]) -> reqwest::Response {
+    let mut request = reqwest::Client::new().get(format!("{}{}", client.base_url(), path));
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    request.send().await.unwrap()
+}
+
+fn header<'a>(resp: &'a reqwest::Response, name: &str) -> &'a str {
+    resp.headers()[name].to_str().unwrap()
+}
+
+#[tokio::test]
+async fn test_full_response_has_validators() {
+    let (_dir, app) = setup();
+    let client = TestClient::new(app).await;
+
+    let resp = get(&client, "/files/report", &[]).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(header(&resp, "accept-ranges"), "bytes");
+    assert_eq!(header(&resp, "content-type"), "text/plain");
+    assert_eq!(
+        header(&resp, "content-disposition"),
+        "attachment; filename=\"report.txt\""
+    );
+    assert_eq!(header(&resp, "content-length"), CONTENT.len().to_string());
+    assert!(header(&resp, "etag").starts_with('"'));
+    assert!(resp.headers().contains_key("last-modified"));
+    assert_eq!(resp.text().await.unwrap(), CONTENT);
+}
+
+#[tokio::test]
+async fn test_single_range() {
+    let (_dir, app) = setup();
+    let client = TestClient::new(app).await;
+
+    let resp = get(&client, "/files/report", &[("range", "bytes=10-15")]).await;
+    assert_eq!(resp.status(), 206);
+    assert_eq!(header(&resp, "content-range"), "bytes 10-15/36");
+    assert_eq!(header(&resp, "content-length"), "6");
+    assert_eq!(resp.text().await.unwrap(), "abcdef");
+
+    // Suffix and open-ended ranges
+    let resp = get(&client, "/files/report", &[("range", "bytes=-4")]).await;
+    assert_eq!(header(&resp, "content-range"), "bytes 32-35/36");
+    assert_eq!(resp.text().await.unwrap(), "wxyz");
+
+    let resp = get(&client, "/files/report", &[("range", "bytes=30-")]).await;
+    assert_eq!(resp.text().await.unwrap(), "uvwxyz");
+}
+
+#[tokio::test]
+async fn test_multiple_ranges_are_multipart() {
+    let (_dir, app) = setup();
+    let client = TestClient::new(app).await;
+
+    let resp = get(&client, "/files/report", &[("range", "bytes=0-1, 10-11")]).await;
+    assert_eq!(resp.status(), 206);
+    let content_type = header(&resp, "content-type").to_string();
+    let boundary = content_type
+        .strip_prefix("multipart/byteranges; boundary=")
+        .unwrap()
+        .to_string();
+    let length: usize = header(&resp, "content-length").parse().unwrap();
+
+    let body = resp.text().await.unwrap();
+    assert_eq!(body.len(), length);
+    assert_eq!(
+        body,
+        format!(
+            "--{b}\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-1/36\r\n\r\n01\
+             \r\n--{b}\r\nContent-Type: text/plain\r\nContent-Range: bytes 10-11/36\r\n\r\nab\
+             \r\n--{b}--\r\n",
+            b = boundary
+        )
+    );
+
+    // Overlapping ranges are merged into one
+    let resp = get(&client, "/files/report", &[("range", "bytes=0-3,2-5")]).await;
+    assert_eq!(header(&resp, "content-range"), "bytes 0-5/36");
+    assert_eq!(resp.text().await.unwrap(), "012345");
+}
+
+#[tokio::test]
+async fn test_unsatisfiable_and_invalid_ranges() {
+    let (_dir, app) = setup();
+    let client = TestClient::new(app).await;
+
+    let resp = get(&client, "/files/report", &[("range", "bytes=100-200")]).await;
+    assert_eq!(resp.status(), 416);
+    assert_eq!(header(&resp, "content-range"), "bytes */36");
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["error"], "Range not satisfiable");
+
+    // Malformed headers are ignored
+    let resp = get(&client, "/files/report", &[("range", "bytes=5-2")]).await;
+    assert_eq!(resp.status(), 200);
+    let resp = get(&client, "/files/report", &[("range", "lines=1-2")]).await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_conditional_get() {
+    let (_dir, app) = setup();
+    let client = TestClient::new(app).await;
+
+    let resp = get(&client, "/files/report", &[]).await;
+    let etag = header(&resp, "etag").to_string();
+    let last_modified = header(&resp, "last-modified").to_string();
+
+    let resp = get(&client, "/files/report", &[("if-none-match", &etag)]).await;
+    assert_eq!(resp.status(), 304);
+    assert_eq!(header(&resp, "etag"), etag);
+    assert!(resp.text().await.unwrap().is_empty());
+
+    let weak = format!("W/{}", etag);
+    let resp = get(&client, "/files/report", &[("if-none-match", &weak)]).await;
+    assert_eq!(resp.status(), 304);
+
+    let resp = get(&client, "/files/report", &[("if-none-match", "\"other\"")]).await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = get(
+        &client,
+        "/files/report",
+        &[("if-modified-since", &last_modified)],
+    )
+    .await;
+    assert_eq!(resp.status(), 304);
+
+    let resp = get(
+        &client,
+        "/files/report",
+        &[("if-modified-since", "Thu, 01 Jan 1970 00:00:00 GMT")],
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_if_range() {
+    let (_dir, app) = setup();
+    let client = TestClient::new(app).await;
+    let etag = header(&get(&client, "/files/report", &[]).await, "etag").to_string();
+
+    let resp = get(
+        &client,
+        "/files/report",
+        &[("range", "bytes=0-1"), ("if-range", &etag)],
+    )
+    .await;
+    assert_eq!(resp.status(), 206);
+
+    // A changed representation gets the whole file
+    let resp = get(
+        &client,
+        "/files/report",
+        &[("range", "bytes=0-1"), ("if-range", "\"stale\"")],
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.text().await.unwrap(), CONTENT);
+}
+
+#[tokio::test]
+async fn test_head_and_missing_file() {
+    let (_dir, app) = setup();
+    let client = TestClient::new(app).await;
+
+    let resp = reqwest::Client::new()
+        .head(format!("{}/files/report", client.base_url()))
+        .header("range", "bytes=0-9")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(header(&resp, "content-length"), "36");
+    assert!(resp.text().await.unwrap().is_empty());
+
+    let resp = get(&client, "/files/missing", &[]).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_static_files_support_ranges_and_validators() {
+    let (_dir, app) = setup();
+    let client = TestClient::new(app).await;
+
+    let resp = get(&client, "/static/report.txt", &[("range", "bytes=0-2")]).await;
+    assert_eq!(resp.status(), 206);
+    assert_eq!(header(&resp, "content-range"), "bytes 0-2/36");
+    assert_eq!(resp.text().await.unwrap(), "012");
+
+    let resp = get(&client, "/static/report.txt", &[]).await;
+    let etag = header(&resp, "etag").to_string();
+    let resp = get(&client, "/static/report.txt", &[("if-none-match", &etag)]).await;
+    assert_eq!(resp.status(), 304);
+
+    let resp = get(&client, "/static/docs/", &[]).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(header(&resp, "content-type"), "text/html");
+    assert_eq!(resp.text().await.unwrap(), "<h1>docs</h1>");
+
+    let resp = get(&client, "/static/docs", &[]).await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_static_files_reject_traversal() {
+    let (dir, app) = setup();
+    std::fs::write(dir.path().parent().unwrap().join("secret.txt"), "secret").ok();
+    let client = TestClient::new(app).await;
+
+    let resp = get(&client, "/static/%2e%2e/secret.txt", &[]).await;
+    assert_eq!(resp.status(), 404);
+    let resp = get(&client, "/static/..%2fsecret.txt", &[]).await;
+    assert_eq!(resp.status(), 404);
+
+    let resp = reqwest::Client::new()
+        .post(format!("{}/static/report.txt", client.base_url()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 405);
+    assert_eq!(header(&resp, "allow"), "GET, HEAD");
+}
@@ -0,0 +1,210 @@
+// Tests for mounting GraphQL schemas on UltraApiApp
+
+#[cfg(feature = "graphql")]
+mod tests {
+    use async_graphql::{Context, EmptySubscription, Object, Schema};
+    use serde_json::{json, Value};
+    use ultraapi::graphql::{GraphQLContextExt, GraphQLEndpoint};
+    use ultraapi::prelude::*;
+
+    struct Greeting(&'static str);
+
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {
+        async fn hello(&self, ctx: &Context<'_>) -> async_graphql::Result<String> {
+            let greeting = ctx.dep::<Greeting>()?;
+            Ok(greeting.0.to_string())
+        }
+
+        async fn has_state(&self, ctx: &Context<'_>) -> bool {
+            ctx.app_state().is_some()
+        }
+
+        async fn user_id(&self, ctx: &Context<'_>) -> Option<i64> {
+            ctx.session().and_then(|s| s.get::<i64>("user_id"))
+        }
+
+        async fn token(&self, ctx: &Context<'_>) -> Option<String> {
+            ctx.credentials().map(|c| c.value.clone())
+        }
+    }
+
+    struct MutationRoot;
+
+    #[Object]
+    impl MutationRoot {
+        async fn ping(&self) -> &str {
+            "pong"
+        }
+    }
+
+    type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+    fn schema() -> AppSchema {
+        Schema::build(QueryRoot, MutationRoot, EmptySubscription).finish()
+    }
+
+    #[get("/login")]
+    #[response_class("text")]
+    async fn login(session: Session) -> String {
+        session.insert("user_id", 7_i64).unwrap();
+        "ok".to_string()
+    }
+
+    async fn query(client: &TestClient, query: &str) -> reqwest::Response {
+        client.post("/graphql", &json!({ "query": query })).await
+    }
+
+    #[tokio::test]
+    async fn test_post_query_resolves_dependencies() {
+        let app = UltraApiApp::new()
+            .dep(Greeting("Hello"))
+            .graphql("/graphql", schema());
+        let client = TestClient::new(app).await;
+
+        let response = query(&client, "{ hello hasState }").await;
+        assert_eq!(response.status(), 200);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["data"]["hello"], "Hello");
+        assert_eq!(body["data"]["hasState"], true);
+    }
+
+    #[tokio::test]
+    async fn test_missing_dependency_is_graphql_error() {
+        let client = TestClient::new(UltraApiApp::new().graphql("/graphql", schema())).await;
+
+        let response = query(&client, "{ hello }").await;
+        assert_eq!(response.status(), 200);
+        let body: Value = response.json().await.unwrap();
+        assert!(body["errors"][0]["message"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_get_query_and_mutation() {
+        let app = UltraApiApp::new()
+            .dep(Greeting("Hi"))
+            .graphql("/graphql", schema());
+        let client = TestClient::new(app).await;
+
+        let response = client.get("/graphql?query=%7B%20hello%20%7D").await;
+        assert_eq!(response.status(), 200);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["data"]["hello"], "Hi");
+
+        let response = client
+            .get("/graphql?query=mutation%20%7B%20ping%20%7D")
+            .await;
+        assert_eq!(response.status(), 405);
+        assert_eq!(response.headers()["allow"], "POST");
+
+        let response = query(&client, "mutation { ping }").await;
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["data"]["ping"], "pong");
+    }
+
+    #[tokio::test]
+    async fn test_ide_served_to_browsers() {
+        let client = TestClient::new(UltraApiApp::new().graphql("/graphql", schema())).await;
+
+        let response = client
+            .client()
+            .get(format!("{}/graphql", client.base_url()))
+            .header("accept", "text/html")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let html = response.text().await.unwrap();
+        assert!(html.contains("graphiql"));
+
+        let client = TestClient::new(
+            UltraApiApp::new().graphql("/graphql", GraphQLEndpoint::new(schema()).ide(false)),
+        )
+        .await;
+        let response = client
+            .client()
+            .get(format!("{}/graphql", client.base_url()))
+            .header("accept", "text/html")
+            .send()
+            .await
+            .unwrap();
+        assert!(!response.text().await.unwrap().contains("graphiql"));
+    }
+
+    #[tokio::test]
+    async fn test_session_is_shared_with_rest_routes() {
+        let app = UltraApiApp::new()
+            .session_cookies(SessionConfig::new("dev-secret"))
+            .include(UltraApiRouter::new("").route(__ULTRAAPI_ROUTE_LOGIN))
+            .graphql("/graphql", schema());
+        let client = TestClient::new(app).await;
+
+        let response = client.get("/login").await;
+        let sid = ultraapi::session::extract_session_cookie(response.headers(), "session_id")
+            .expect("session cookie");
+
+        let response = client
+            .client()
+            .post(format!("{}/graphql", client.base_url()))
+            .header("cookie", format!("session_id={}", sid))
+            .json(&json!({ "query": "{ userId }" }))
+            .send()
+            .await
+            .unwrap();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["data"]["userId"], 7);
+    }
+
+    #[tokio::test]
+    async fn test_security_requires_credentials() {
+        let app = UltraApiApp::new().bearer_auth().graphql(
+            "/graphql",
+            GraphQLEndpoint::new(schema()).security("bearerAuth"),
+        );
+        let client = TestClient::new(app).await;
+
+        let response = query(&client, "{ token }").await;
+        assert_eq!(response.status(), 401);
+
+        let response = client
+            .client()
+            .post(format!("{}/graphql", client.base_url()))
+            .bearer_auth("valid-token")
+            .json(&json!({ "query": "{ token }" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["data"]["token"], "valid-token");
+    }
+
+    #[tokio::test]
+    async fn test_app_cors_applies() {
+        let app = UltraApiApp::new()
+            .graphql("/graphql", schema())
+            .middleware(|builder| {
+                builder.cors(
+                    CorsConfig::new()
+                        .allow_origins(vec!["https://app.example".into()])
+                        .allow_credentials(false),
+                )
+            });
+        let client = TestClient::new(app).await;
+
+        let response = client
+            .client()
+            .post(format!("{}/graphql", client.base_url()))
+            .header("origin", "https://app.example")
+            .json(&json!({ "query": "{ hasState }" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()["access-control-allow-origin"],
+            "https://app.example"
+        );
+    }
+}
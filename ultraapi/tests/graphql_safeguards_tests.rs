@@ -0,0 +1,270 @@
+// Tests for GraphQL endpoint safeguards, SDL export and the embedded IDE
+
+#[cfg(feature = "graphql")]
+mod tests {
+    use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+    use serde_json::{json, Value};
+    use ultraapi::graphql::{GraphQLEndpoint, GraphQLIdeMode, InMemoryPersistedQueryStore};
+    use ultraapi::prelude::*;
+
+    #[derive(SimpleObject)]
+    struct User {
+        name: String,
+        friends: Vec<User>,
+    }
+
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {
+        async fn hello(&self) -> &str {
+            "world"
+        }
+
+        async fn me(&self) -> User {
+            User {
+                name: "ada".to_string(),
+                friends: Vec::new(),
+            }
+        }
+    }
+
+    type AppSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+    fn schema() -> AppSchema {
+        Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish()
+    }
+
+    async fn serve(endpoint: GraphQLEndpoint) -> TestClient {
+        TestClient::new(UltraApiApp::new().graphql("/graphql", endpoint)).await
+    }
+
+    async fn execute(client: &TestClient, body: Value) -> Value {
+        client.post("/graphql", &body).await.json().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_depth_and_complexity_limits() {
+        let client = serve(
+            GraphQLEndpoint::new(schema())
+                .max_depth(2)
+                .max_complexity(4),
+        )
+        .await;
+
+        let body = execute(&client, json!({ "query": "{ me { name } }" })).await;
+        assert_eq!(body["data"]["me"]["name"], "ada");
+
+        let body = execute(&client, json!({ "query": "{ me { friends { name } } }" })).await;
+        assert_eq!(body["errors"][0]["message"], "Query is nested too deep.");
+
+        // Fragments count towards the limits
+        let body = execute(
+            &client,
+            json!({
+                "query": "{ hello me { ...F } } fragment F on User { name n2: name n3: name }"
+            }),
+        )
+        .await;
+        assert_eq!(body["errors"][0]["message"], "Query is too complex.");
+    }
+
+    #[tokio::test]
+    async fn test_repeated_fragment_spreads_are_measured_once() {
+        let client = serve(GraphQLEndpoint::new(schema()).max_complexity(1000)).await;
+
+        // F0 spreads F1 twice, F1 spreads F2 twice, ... — expanding the spreads
+        // would visit 2^40 fields, measuring each fragment once stays linear
+        let mut query = String::from("{ me { ...F0 } }");
+        for i in 0..40 {
+            query.push_str(&format!(
+                " fragment F{i} on User {{ friends {{ ...F{next} }} f: friends {{ ...F{next} }} }}",
+                next = i + 1
+            ));
+        }
+        query.push_str(" fragment F40 on User { name }");
+
+        let body = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            execute(&client, json!({ "query": query })),
+        )
+        .await
+        .expect("measuring the query should not expand every spread");
+        assert_eq!(body["errors"][0]["message"], "Query is too complex.");
+    }
+
+    #[tokio::test]
+    async fn test_batch_limit_and_mixed_results() {
+        let client = serve(
+            GraphQLEndpoint::new(schema())
+                .max_depth(1)
+                .max_batch_size(2),
+        )
+        .await;
+
+        let body = execute(
+            &client,
+            json!([{ "query": "{ hello }" }, { "query": "{ me { name } }" }]),
+        )
+        .await;
+        assert_eq!(body[0]["data"]["hello"], "world");
+        assert_eq!(body[1]["errors"][0]["message"], "Query is nested too deep.");
+
+        let response = client
+            .post(
+                "/graphql",
+                &json!([{ "query": "{ hello }" }, { "query": "{ hello }" }, { "query": "{ hello }" }]),
+            )
+            .await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_introspection_can_be_disabled() {
+        let client = serve(GraphQLEndpoint::new(schema())).await;
+        let body = execute(
+            &client,
+            json!({ "query": "{ __schema { queryType { name } } }" }),
+        )
+        .await;
+        assert_eq!(body["data"]["__schema"]["queryType"]["name"], "QueryRoot");
+        let response = client.get("/graphql/schema.graphql").await;
+        assert_eq!(response.status(), 200);
+        assert!(response.text().await.unwrap().contains("type QueryRoot"));
+
+        let client = serve(GraphQLEndpoint::new(schema()).introspection(false)).await;
+        let body = execute(
+            &client,
+            json!({ "query": "{ __schema { queryType { name } } }" }),
+        )
+        .await;
+        assert!(body["data"]["__schema"].is_null());
+        let body = execute(&client, json!({ "query": "{ hello }" })).await;
+        assert_eq!(body["data"]["hello"], "world");
+        assert_eq!(client.get("/graphql/schema.graphql").await.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_automatic_persisted_queries() {
+        let client = serve(
+            GraphQLEndpoint::new(schema()).persisted_queries(InMemoryPersistedQueryStore::new(8)),
+        )
+        .await;
+        let query = "{ hello }";
+        let hash = "a4bcb3f5a8d7ba6c4bd5e7ed5ac3bd7e5dd6f06ec5e6a3e9b4c73b2df7f2a1a0";
+        let extensions =
+            |hash: &str| json!({ "persistedQuery": { "version": 1, "sha256Hash": hash } });
+
+        // Unknown hash
+        let body = execute(&client, json!({ "extensions": extensions(hash) })).await;
+        assert_eq!(
+            body["errors"][0]["extensions"]["code"],
+            "PERSISTED_QUERY_NOT_FOUND"
+        );
+
+        // Mismatching hash is rejected
+        let body = execute(
+            &client,
+            json!({ "query": query, "extensions": extensions(hash) }),
+        )
+        .await;
+        assert!(body["errors"].is_array());
+
+        // Register with the real hash, then execute by hash over GET
+        let real = sha256_hex(query);
+        let body = execute(
+            &client,
+            json!({ "query": query, "extensions": extensions(&real) }),
+        )
+        .await;
+        assert_eq!(body["data"]["hello"], "world");
+        let response = client
+            .client()
+            .get(format!("{}/graphql", client.base_url()))
+            .query(&[("extensions", extensions(&real).to_string())])
+            .send()
+            .await
+            .unwrap();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["data"]["hello"], "world");
+    }
+
+    #[tokio::test]
+    async fn test_persisted_queries_not_supported_without_store() {
+        let client = serve(GraphQLEndpoint::new(schema())).await;
+        let body = execute(
+            &client,
+            json!({ "extensions": { "persistedQuery": { "version": 1, "sha256Hash": "00" } } }),
+        )
+        .await;
+        assert_eq!(
+            body["errors"][0]["extensions"]["code"],
+            "PERSISTED_QUERY_NOT_SUPPORTED"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_embedded_ide_is_offline() {
+        let client = serve(GraphQLEndpoint::new(schema()).ide_mode(GraphQLIdeMode::Embedded)).await;
+        let html = client
+            .client()
+            .get(format!("{}/graphql", client.base_url()))
+            .header("accept", "text/html")
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(html.contains(r#"data-url="/graphql""#));
+        assert!(html.contains(r#"data-subscription-url="/graphql/ws""#));
+        assert!(html.contains(r#"data-sdl-url="/graphql/schema.graphql""#));
+        assert!(!html.contains("jsdelivr"));
+        assert!(!html.contains("unpkg"));
+    }
+
+    #[tokio::test]
+    async fn test_cdn_ide_loads_react() {
+        let client = serve(
+            GraphQLEndpoint::new(schema()).ide_mode(GraphQLIdeMode::Cdn("/static/npm/".into())),
+        )
+        .await;
+        let html = client
+            .client()
+            .get(format!("{}/graphql", client.base_url()))
+            .header("accept", "text/html")
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        let react = html
+            .find("/static/npm/react@18/umd/react.production.min.js")
+            .unwrap();
+        let react_dom = html
+            .find("/static/npm/react-dom@18/umd/react-dom.production.min.js")
+            .unwrap();
+        let graphiql = html.find("/static/npm/graphiql@3/graphiql.min.js").unwrap();
+        assert!(react < react_dom && react_dom < graphiql);
+        assert!(html.contains("/static/npm/graphiql@3/graphiql.min.css"));
+    }
+
+    #[tokio::test]
+    async fn test_sdl_export_is_protected_with_the_endpoint() {
+        let app = UltraApiApp::new().bearer_auth().graphql(
+            "/graphql",
+            GraphQLEndpoint::new(schema()).security("bearerAuth"),
+        );
+        let client = TestClient::new(app).await;
+        assert_eq!(client.get("/graphql/schema.graphql").await.status(), 401);
+    }
+
+    fn sha256_hex(text: &str) -> String {
+        use sha2::{Digest, Sha256};
+        Sha256::digest(text.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
@@ -0,0 +1,239 @@
+// Tests for GraphQL subscriptions over WebSocket
+
+#[cfg(feature = "graphql")]
+mod tests {
+    use async_graphql::{Context, EmptyMutation, Object, Schema, Subscription};
+    use futures_util::{SinkExt, Stream, StreamExt};
+    use serde_json::{json, Value};
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+    use ultraapi::graphql::{GraphQLContextExt, GraphQLEndpoint};
+    use ultraapi::prelude::*;
+
+    type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    struct Greeting(&'static str);
+
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {
+        async fn ok(&self) -> bool {
+            true
+        }
+    }
+
+    struct SubscriptionRoot;
+
+    #[Subscription]
+    impl SubscriptionRoot {
+        async fn counter(&self) -> impl Stream<Item = i32> {
+            futures_util::stream::iter(1..=3)
+        }
+
+        async fn greeting(
+            &self,
+            ctx: &Context<'_>,
+        ) -> async_graphql::Result<impl Stream<Item = String>> {
+            let greeting = ctx.dep::<Greeting>()?;
+            Ok(futures_util::stream::once(
+                async move { greeting.0.to_string() },
+            ))
+        }
+
+        async fn token(&self, ctx: &Context<'_>) -> impl Stream<Item = Option<String>> {
+            let token = ctx.credentials().map(|c| c.value.clone());
+            futures_util::stream::once(async move { token })
+        }
+    }
+
+    type AppSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+    fn schema() -> AppSchema {
+        Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot).finish()
+    }
+
+    async fn connect(client: &TestClient, path: &str, protocol: &str) -> Socket {
+        let url = format!("{}{}", client.base_url().replace("http://", "ws://"), path);
+        let mut request = url.into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert("sec-websocket-protocol", protocol.parse().unwrap());
+        let (socket, response) = tokio_tungstenite::connect_async(request).await.unwrap();
+        assert_eq!(response.headers()["sec-websocket-protocol"], protocol);
+        socket
+    }
+
+    async fn send(socket: &mut Socket, message: Value) {
+        socket
+            .send(Message::text(message.to_string()))
+            .await
+            .unwrap();
+    }
+
+    async fn recv(socket: &mut Socket) -> Message {
+        tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+            .await
+            .expect("timed out")
+            .expect("socket closed")
+            .unwrap()
+    }
+
+    async fn recv_json(socket: &mut Socket) -> Value {
+        match recv(socket).await {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_graphql_transport_ws_subscription() {
+        let client = TestClient::new(UltraApiApp::new().graphql("/graphql", schema())).await;
+        let mut socket = connect(&client, "/graphql/ws", "graphql-transport-ws").await;
+
+        send(&mut socket, json!({ "type": "connection_init" })).await;
+        assert_eq!(recv_json(&mut socket).await["type"], "connection_ack");
+
+        send(
+            &mut socket,
+            json!({
+                "id": "1",
+                "type": "subscribe",
+                "payload": { "query": "subscription { counter }" }
+            }),
+        )
+        .await;
+        for expected in 1..=3 {
+            let message = recv_json(&mut socket).await;
+            assert_eq!(message["type"], "next");
+            assert_eq!(message["id"], "1");
+            assert_eq!(message["payload"]["data"]["counter"], expected);
+        }
+        let message = recv_json(&mut socket).await;
+        assert_eq!(message["type"], "complete");
+    }
+
+    #[tokio::test]
+    async fn test_legacy_graphql_ws_subscription_with_dependencies() {
+        let app = UltraApiApp::new()
+            .dep(Greeting("Hello"))
+            .graphql("/graphql", schema());
+        let client = TestClient::new(app).await;
+        let mut socket = connect(&client, "/graphql/ws", "graphql-ws").await;
+
+        send(&mut socket, json!({ "type": "connection_init" })).await;
+        assert_eq!(recv_json(&mut socket).await["type"], "connection_ack");
+
+        send(
+            &mut socket,
+            json!({
+                "id": "g",
+                "type": "start",
+                "payload": { "query": "subscription { greeting }" }
+            }),
+        )
+        .await;
+        let message = recv_json(&mut socket).await;
+        assert_eq!(message["type"], "data");
+        assert_eq!(message["payload"]["data"]["greeting"], "Hello");
+        assert_eq!(recv_json(&mut socket).await["type"], "complete");
+    }
+
+    #[tokio::test]
+    async fn test_connection_init_authentication() {
+        let app = UltraApiApp::new().bearer_auth().graphql(
+            "/graphql",
+            GraphQLEndpoint::new(schema()).security("bearerAuth"),
+        );
+        let client = TestClient::new(app).await;
+
+        // Missing credentials close the connection
+        let mut socket = connect(&client, "/graphql/ws", "graphql-transport-ws").await;
+        send(&mut socket, json!({ "type": "connection_init" })).await;
+        assert!(matches!(recv(&mut socket).await, Message::Close(Some(_))));
+
+        // Legacy protocol reports a connection_error
+        let mut socket = connect(&client, "/graphql/ws", "graphql-ws").await;
+        send(
+            &mut socket,
+            json!({ "type": "connection_init", "payload": { "Authorization": "Bearer nope" } }),
+        )
+        .await;
+        assert_eq!(recv_json(&mut socket).await["type"], "connection_error");
+
+        let mut socket = connect(&client, "/graphql/ws", "graphql-transport-ws").await;
+        send(
+            &mut socket,
+            json!({
+                "type": "connection_init",
+                "payload": { "headers": { "Authorization": "Bearer valid-token" } }
+            }),
+        )
+        .await;
+        assert_eq!(recv_json(&mut socket).await["type"], "connection_ack");
+        send(
+            &mut socket,
+            json!({
+                "id": "1",
+                "type": "subscribe",
+                "payload": { "query": "subscription { token }" }
+            }),
+        )
+        .await;
+        let message = recv_json(&mut socket).await;
+        assert_eq!(message["payload"]["data"]["token"], "valid-token");
+    }
+
+    #[tokio::test]
+    async fn test_ide_points_at_subscription_endpoint() {
+        let app = UltraApiApp::new().graphql(
+            "/graphql",
+            GraphQLEndpoint::new(schema()).subscription_path("/subscriptions"),
+        );
+        let client = TestClient::new(app).await;
+
+        let html = client
+            .client()
+            .get(format!("{}/graphql", client.base_url()))
+            .header("accept", "text/html")
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(html.contains("subscriptionUrl"));
+        assert!(html.contains("'/subscriptions'"));
+
+        let mut socket = connect(&client, "/subscriptions", "graphql-transport-ws").await;
+        send(&mut socket, json!({ "type": "connection_init" })).await;
+        assert_eq!(recv_json(&mut socket).await["type"], "connection_ack");
+    }
+
+    #[tokio::test]
+    async fn test_safeguards_apply_to_subscriptions() {
+        let app =
+            UltraApiApp::new().graphql("/graphql", GraphQLEndpoint::new(schema()).max_depth(0));
+        let client = TestClient::new(app).await;
+        let mut socket = connect(&client, "/graphql/ws", "graphql-transport-ws").await;
+
+        send(&mut socket, json!({ "type": "connection_init" })).await;
+        assert_eq!(recv_json(&mut socket).await["type"], "connection_ack");
+        send(
+            &mut socket,
+            json!({
+                "id": "1",
+                "type": "subscribe",
+                "payload": { "query": "subscription { counter }" }
+            }),
+        )
+        .await;
+        let message = recv_json(&mut socket).await;
+        assert_eq!(
+            message["payload"]["errors"][0]["message"],
+            "Query is nested too deep."
+        );
+    }
+}
@@ -0,0 +1,741 @@
+//! gRPC Descriptor Transcoding Tests
+//!
+//! Services built from a `FileDescriptorSet` are routed by their `google.api.http`
+//! annotations, bind path / query / body into the request message using the
+//! proto3 JSON mapping, and call handlers that take prost messages.
+
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+    FileDescriptorProto, MessageOptions, MethodDescriptorProto,
+};
+use serde_json::json;
+use ultraapi::grpc::{
+    DescriptorPool, GrpcError, GrpcExt, GrpcMethod, GrpcRequest, GrpcService, HttpRule,
+};
+use ultraapi::prelude::*;
+
+// --- prost messages matching the descriptor below ---
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Book {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(string, tag = "2")]
+    title: String,
+    #[prost(int64, tag = "3")]
+    page_count: i64,
+    #[prost(enumeration = "Genre", tag = "4")]
+    genre: i32,
+    #[prost(string, repeated, tag = "5")]
+    tags: Vec<String>,
+    #[prost(message, optional, tag = "6")]
+    published: Option<prost_types::Timestamp>,
+    #[prost(map = "string, string", tag = "7")]
+    labels: std::collections::HashMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+enum Genre {
+    Unspecified = 0,
+    Fiction = 1,
+    Science = 2,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct GetBookRequest {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(bool, tag = "2")]
+    include_drafts: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct CreateBookRequest {
+    #[prost(string, tag = "1")]
+    parent: String,
+    #[prost(message, optional, tag = "2")]
+    book: Option<Book>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ListBooksRequest {
+    #[prost(string, tag = "1")]
+    parent: String,
+    #[prost(int32, tag = "2")]
+    page_size: i32,
+    #[prost(enumeration = "Genre", tag = "3")]
+    genre: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ListBooksResponse {
+    #[prost(message, repeated, tag = "1")]
+    books: Vec<Book>,
+    #[prost(string, tag = "2")]
+    next_page_token: String,
+}
+
+// --- FileDescriptorSet (what `protoc --descriptor_set_out` would produce) ---
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_bytes(out: &mut Vec<u8>, number: u64, bytes: &[u8]) {
+    put_varint(out, (number << 3) | 2);
+    put_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn field(name: &str, number: i32, ty: Type, type_name: Option<&str>) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_string()),
+        number: Some(number),
+        label: Some(Label::Optional as i32),
+        r#type: Some(ty as i32),
+        type_name: type_name.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+fn repeated(mut field: FieldDescriptorProto) -> FieldDescriptorProto {
+    field.label = Some(Label::Repeated as i32);
+    field
+}
+
+fn message(name: &str, fields: Vec<FieldDescriptorProto>) -> DescriptorProto {
+    DescriptorProto {
+        name: Some(name.to_string()),
+        field: fields,
+        ..Default::default()
+    }
+}
+
+/// `google.api.HttpRule`: (verb field number, path), body, response_body, additional bindings
+fn http_rule(
+    verb: (u64, &str),
+    body: Option<&str>,
+    response_body: Option<&str>,
+    additional: &[Vec<u8>],
+) -> Vec<u8> {
+    let mut rule = Vec::new();
+    put_bytes(&mut rule, verb.0, verb.1.as_bytes());
+    if let Some(body) = body {
+        put_bytes(&mut rule, 7, body.as_bytes());
+    }
+    for binding in additional {
+        put_bytes(&mut rule, 11, binding);
+    }
+    if let Some(response_body) = response_body {
+        put_bytes(&mut rule, 12, response_body.as_bytes());
+    }
+    rule
+}
+
+const GET: u64 = 2;
+const POST: u64 = 4;
+
+fn method(name: &str, input: &str, output: &str, rule: Vec<u8>) -> Vec<u8> {
+    let mut bytes = MethodDescriptorProto {
+        name: Some(name.to_string()),
+        input_type: Some(format!(".bookstore.v1.{}", input)),
+        output_type: Some(format!(".bookstore.v1.{}", output)),
+        ..Default::default()
+    }
+    .encode_to_vec();
+    // MethodOptions { (google.api.http) = rule }
+    let mut options = Vec::new();
+    put_bytes(&mut options, 72295728, &rule);
+    put_bytes(&mut bytes, 4, &options);
+    bytes
+}
+
+fn descriptor_set() -> Vec<u8> {
+    let timestamp = FileDescriptorProto {
+        name: Some("google/protobuf/timestamp.proto".to_string()),
+        package: Some("google.protobuf".to_string()),
+        message_type: vec![message(
+            "Timestamp",
+            vec![
+                field("seconds", 1, Type::Int64, None),
+                field("nanos", 2, Type::Int32, None),
+            ],
+        )],
+        syntax: Some("proto3".to_string()),
+        ..Default::default()
+    };
+
+    let mut labels_entry = message(
+        "LabelsEntry",
+        vec![
+            field("key", 1, Type::String, None),
+            field("value", 2, Type::String, None),
+        ],
+    );
+    labels_entry.options = Some(MessageOptions {
+        map_entry: Some(true),
+        ..Default::default()
+    });
+    let mut book = message(
+        "Book",
+        vec![
+            field("name", 1, Type::String, None),
+            field("title", 2, Type::String, None),
+            field("page_count", 3, Type::Int64, None),
+            field("genre", 4, Type::Enum, Some(".bookstore.v1.Genre")),
+            repeated(field("tags", 5, Type::String, None)),
+            field(
+                "published",
+                6,
+                Type::Message,
+                Some(".google.protobuf.Timestamp"),
+            ),
+            repeated(field(
+                "labels",
+                7,
+                Type::Message,
+                Some(".bookstore.v1.Book.LabelsEntry"),
+            )),
+        ],
+    );
+    book.nested_type = vec![labels_entry];
+
+    let genre = EnumDescriptorProto {
+        name: Some("Genre".to_string()),
+        value: ["GENRE_UNSPECIFIED", "FICTION", "SCIENCE"]
+            .iter()
+            .enumerate()
+            .map(|(number, name)| EnumValueDescriptorProto {
+                name: Some(name.to_string()),
+                number: Some(number as i32),
+                options: None,
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let bookstore = FileDescriptorProto {
+        name: Some("bookstore.proto".to_string()),
+        package: Some("bookstore.v1".to_string()),
+        dependency: vec!["google/protobuf/timestamp.proto".to_string()],
+        message_type: vec![
+            book,
+            message(
+                "GetBookRequest",
+                vec![
+                    field("name", 1, Type::String, None),
+                    field("include_drafts", 2, Type::Bool, None),
+                ],
+            ),
+            message(
+                "CreateBookRequest",
+                vec![
+                    field("parent", 1, Type::String, None),
+                    field("book", 2, Type::Message, Some(".bookstore.v1.Book")),
+                ],
+            ),
+            message(
+                "ListBooksRequest",
+                vec![
+                    field("parent", 1, Type::String, None),
+                    field("page_size", 2, Type::Int32, None),
+                    field("genre", 3, Type::Enum, Some(".bookstore.v1.Genre")),
+                ],
+            ),
+            message(
+                "ListBooksResponse",
+                vec![
+                    repeated(field("books", 1, Type::Message, Some(".bookstore.v1.Book"))),
+                    field("next_page_token", 2, Type::String, None),
+                ],
+            ),
+        ],
+        enum_type: vec![genre],
+        syntax: Some("proto3".to_string()),
+        ..Default::default()
+    };
+
+    let mut service = Vec::new();
+    put_bytes(&mut service, 1, b"Library");
+    let methods = [
+        method(
+            "GetBook",
+            "GetBookRequest",
+            "Book",
+            http_rule((GET, "/v1/{name=shelves/*/books/*}"), None, None, &[]),
+        ),
+        method(
+            "CreateBook",
+            "CreateBookRequest",
+            "Book",
+            http_rule(
+                (POST, "/v1/{parent=shelves/*}/books"),
+                Some("book"),
+                None,
+                &[],
+            ),
+        ),
+        method(
+            "ListBooks",
+            "ListBooksRequest",
+            "ListBooksResponse",
+            http_rule(
+                (GET, "/v1/{parent=shelves/*}/books"),
+                None,
+                Some("books"),
+                &[http_rule((POST, "/v1/books:search"), Some("*"), None, &[])],
+            ),
+        ),
+        method(
+            "ArchiveBook",
+            "GetBookRequest",
+            "Book",
+            http_rule(
+                (POST, "/v1/{name=shelves/*/books/*}:archive"),
+                Some("*"),
+                None,
+                &[],
+            ),
+        ),
+    ];
+    for method in &methods {
+        put_bytes(&mut service, 2, method);
+    }
+
+    let mut file = bookstore.encode_to_vec();
+    put_bytes(&mut file, 6, &service);
+
+    let mut set = Vec::new();
+    put_bytes(&mut set, 1, &timestamp.encode_to_vec());
+    put_bytes(&mut set, 1, &file);
+    set
+}
+
+fn pool() -> DescriptorPool {
+    DescriptorPool::decode(&descriptor_set()).expect("valid descriptor set")
+}
+
+fn sample_book(name: &str) -> Book {
+    Book {
+        name: name.to_string(),
+        title: "Dune".to_string(),
+        page_count: 412,
+        genre: Genre::Science as i32,
+        tags: vec!["classic".to_string()],
+        published: Some(prost_types::Timestamp {
+            seconds: 1_704_164_645,
+            nanos: 0,
+        }),
+        labels: [("lang".to_string(), "en".to_string())].into(),
+    }
+}
+
+fn library() -> GrpcService {
+    GrpcService::from_descriptor(&pool(), "bookstore.v1.Library")
+        .expect("service in descriptor set")
+        .with_typed_handler(
+            "GetBook",
+            |req: GetBookRequest, _ctx: GrpcRequest| async move {
+                if req.name.ends_with("/missing") {
+                    return Err(GrpcError::not_found("book not found"));
+                }
+                let mut book = sample_book(&req.name);
+                if req.include_drafts {
+                    book.tags.push("draft".to_string());
+                }
+                Ok(book)
+            },
+        )
+        .with_typed_handler(
+            "CreateBook",
+            |req: CreateBookRequest, _ctx: GrpcRequest| async move {
+                let mut book = req.book.unwrap_or_default();
+                book.name = format!("{}/books/new", req.parent);
+                Ok(book)
+            },
+        )
+        .with_typed_handler(
+            "ListBooks",
+            |req: ListBooksRequest, _ctx: GrpcRequest| async move {
+                let count = req.page_size.max(1) as usize;
+                let books = (0..count)
+                    .map(|i| {
+                        let mut book = sample_book(&format!("{}/books/{}", req.parent, i));
+                        book.genre = req.genre;
+                        book
+                    })
+                    .collect();
+                Ok(ListBooksResponse {
+                    books,
+                    next_page_token: "next".to_string(),
+                })
+            },
+        )
+        .with_typed_handler(
+            "ArchiveBook",
+            |req: GetBookRequest, _ctx: GrpcRequest| async move {
+                let mut book = sample_book(&req.name);
+                book.tags = vec!["archived".to_string()];
+                Ok(book)
+            },
+        )
+}
+
+fn create_app() -> UltraApiApp {
+    UltraApiApp::new()
+        .title("Bookstore")
+        .version("1.0.0")
+        .grpc(ultraapi::grpc::GrpcTranscoder::new().register_service(library()))
+}
+
+#[test]
+fn test_pool_loads_services_and_http_rules() {
+    let pool = pool();
+    let service = pool.service("bookstore.v1.Library").unwrap();
+    assert_eq!(service.name, "Library");
+    assert_eq!(service.package.as_deref(), Some("bookstore.v1"));
+
+    let list = service
+        .methods
+        .iter()
+        .find(|m| m.name == "ListBooks")
+        .unwrap();
+    assert_eq!(list.full_path, "/bookstore.v1.Library/ListBooks");
+    assert_eq!(list.http_rules.len(), 2);
+    assert_eq!(list.http_rules[0].method, "GET");
+    assert_eq!(list.http_rules[0].response_body.as_deref(), Some("books"));
+    assert_eq!(list.http_rules[1].path, "/v1/books:search");
+    assert_eq!(list.http_rules[1].body.as_deref(), Some("*"));
+}
+
+#[test]
+fn test_invalid_http_rule_in_descriptor_is_an_error() {
+    let mut service = Vec::new();
+    put_bytes(&mut service, 1, b"Broken");
+    let rule = http_rule((GET, "/v1/{name=shelves/*"), None, None, &[]);
+    put_bytes(
+        &mut service,
+        2,
+        &method("GetBook", "GetBookRequest", "Book", rule),
+    );
+    let broken = FileDescriptorProto {
+        name: Some("broken.proto".to_string()),
+        package: Some("bookstore.v1".to_string()),
+        message_type: vec![
+            message("Book", vec![field("name", 1, Type::String, None)]),
+            message("GetBookRequest", vec![field("name", 1, Type::String, None)]),
+        ],
+        syntax: Some("proto3".to_string()),
+        ..Default::default()
+    };
+    let mut file = broken.encode_to_vec();
+    put_bytes(&mut file, 6, &service);
+    let mut set = Vec::new();
+    put_bytes(&mut set, 1, &file);
+
+    let pool = DescriptorPool::decode(&set).unwrap();
+    let error = match GrpcService::from_descriptor(&pool, "bookstore.v1.Broken") {
+        Ok(_) => panic!("invalid rule accepted"),
+        Err(error) => error,
+    };
+    assert!(error.message.contains("/bookstore.v1.Broken/GetBook"));
+    assert!(error.message.contains("unclosed variable"));
+}
+
+#[tokio::test]
+async fn test_invalid_http_rules_are_skipped() {
+    let custom = |method: &str, path: &str| HttpRule {
+        method: method.to_string(),
+        path: path.to_string(),
+        body: None,
+        response_body: None,
+    };
+    assert!(custom("FETCH", "/v1/books").validate().is_err());
+    assert!(HttpRule::get("v1/books").validate().is_err());
+
+    let mut method = GrpcMethod::unary("GetBook", "/bookstore.v1.Library/GetBook")
+        .http(HttpRule::get("/v1/books/{name}"))
+        .http(custom("FETCH", "/v1/books"))
+        .http(HttpRule::get("/v1/{name"));
+    assert_eq!(method.http_rules.len(), 1);
+
+    // Rules pushed directly are skipped when routes are built instead of panicking
+    method.http_rules.push(custom("FETCH", "/v1/books"));
+    let service = GrpcService::new("Library").method(method).with_handler(
+        "GetBook",
+        std::sync::Arc::new(|req: GrpcRequest| {
+            Box::pin(async move {
+                ultraapi::grpc::GrpcResponse {
+                    body: req.body,
+                    status_code: 0,
+                }
+            })
+        }),
+    );
+    let app =
+        UltraApiApp::new().grpc(ultraapi::grpc::GrpcTranscoder::new().register_service(service));
+    let client = TestClient::new(app).await;
+
+    let response = client.get("/v1/books/dune").await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["name"], "dune");
+
+    let spec: serde_json::Value = client.get("/openapi.json").await.json().await.unwrap();
+    assert!(spec["paths"]["/v1/books/{name}"]["get"].is_object());
+    assert!(spec["paths"].get("/v1/books").is_none());
+}
+
+#[tokio::test]
+async fn test_custom_verbs_share_a_template() {
+    // Both templates become `POST /v1/ops/{__p0}`; the verb picks the method
+    let handler = |action: &'static str| -> ultraapi::grpc::GrpcHandler {
+        std::sync::Arc::new(move |req: GrpcRequest| {
+            Box::pin(async move {
+                ultraapi::grpc::GrpcResponse {
+                    body: json!({ "action": action, "name": req.body["name"] }),
+                    status_code: 0,
+                }
+            })
+        })
+    };
+    let service = GrpcService::new("Operations")
+        .method(
+            GrpcMethod::unary("CancelOperation", "/ops.Operations/CancelOperation")
+                .http(HttpRule::post("/v1/{name=ops/*}:cancel")),
+        )
+        .method(
+            GrpcMethod::unary("ArchiveOperation", "/ops.Operations/ArchiveOperation")
+                .http(HttpRule::post("/v1/{name=ops/*}:archive")),
+        )
+        .with_handler("CancelOperation", handler("cancel"))
+        .with_handler("ArchiveOperation", handler("archive"));
+    let app =
+        UltraApiApp::new().grpc(ultraapi::grpc::GrpcTranscoder::new().register_service(service));
+    let client = TestClient::new(app).await;
+
+    for action in ["cancel", "archive"] {
+        let response = client
+            .post(&format!("/v1/ops/42:{}", action), &json!({}))
+            .await;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body, json!({ "action": action, "name": "ops/42" }));
+    }
+
+    let response = client.post("/v1/ops/42:delete", &json!({})).await;
+    assert_eq!(response.status(), 404);
+}
+
+#[test]
+fn test_proto3_json_round_trip() {
+    let pool = pool();
+    let book = sample_book("shelves/1/books/2");
+    let json = pool
+        .decode_to_json("bookstore.v1.Book", &book.encode_to_vec())
+        .unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "name": "shelves/1/books/2",
+            "title": "Dune",
+            "pageCount": "412",
+            "genre": "SCIENCE",
+            "tags": ["classic"],
+            "published": "2024-01-02T03:04:05Z",
+            "labels": { "lang": "en" },
+        })
+    );
+
+    let bytes = pool.encode_json("bookstore.v1.Book", &json).unwrap();
+    assert_eq!(Book::decode(bytes.as_slice()).unwrap(), book);
+
+    // Proto field names, numeric int64 and enum numbers are accepted too
+    let bytes = pool
+        .encode_json(
+            "bookstore.v1.Book",
+            &json!({ "page_count": 7, "genre": 1, "title": null }),
+        )
+        .unwrap();
+    let decoded = Book::decode(bytes.as_slice()).unwrap();
+    assert_eq!(decoded.page_count, 7);
+    assert_eq!(decoded.genre, Genre::Fiction as i32);
+
+    let error = pool
+        .encode_json("bookstore.v1.Book", &json!({ "pageCount": "many" }))
+        .unwrap_err();
+    assert_eq!(error.path, "pageCount");
+    assert!(pool
+        .encode_json("bookstore.v1.Book", &json!({ "unknown": 1 }))
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_binds_path_and_query() {
+    let client = TestClient::new(create_app()).await;
+    let response = client
+        .get("/v1/shelves/s1/books/b1?includeDrafts=true")
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["name"], "shelves/s1/books/b1");
+    assert_eq!(body["pageCount"], "412");
+    assert_eq!(body["tags"], json!(["classic", "draft"]));
+    assert_eq!(body["published"], "2024-01-02T03:04:05Z");
+}
+
+#[tokio::test]
+async fn test_body_field_binding() {
+    let client = TestClient::new(create_app()).await;
+    let response = client
+        .post(
+            "/v1/shelves/s1/books",
+            &json!({ "title": "Solaris", "pageCount": "204", "genre": "FICTION" }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["name"], "shelves/s1/books/new");
+    assert_eq!(body["title"], "Solaris");
+    assert_eq!(body["genre"], "FICTION");
+}
+
+#[tokio::test]
+async fn test_response_body_and_additional_bindings() {
+    let client = TestClient::new(create_app()).await;
+    let response = client
+        .get("/v1/shelves/s2/books?pageSize=2&genre=FICTION")
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let books = body
+        .as_array()
+        .expect("response_body selects the books field");
+    assert_eq!(books.len(), 2);
+    assert_eq!(books[1]["name"], "shelves/s2/books/1");
+    assert_eq!(books[0]["genre"], "FICTION");
+
+    let response = client
+        .post(
+            "/v1/books:search",
+            &json!({ "parent": "shelves/s3", "pageSize": 1 }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["books"][0]["name"], "shelves/s3/books/0");
+    assert_eq!(body["nextPageToken"], "next");
+}
+
+#[tokio::test]
+async fn test_custom_verb_after_variable() {
+    let client = TestClient::new(create_app()).await;
+    let response = client
+        .post("/v1/shelves/s1/books/b7:archive", &json!({}))
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["name"], "shelves/s1/books/b7");
+    assert_eq!(body["tags"], json!(["archived"]));
+
+    // Same path without the verb does not match the archive binding
+    let response = client.post("/v1/shelves/s1/books/b7", &json!({})).await;
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_invalid_input_is_invalid_argument() {
+    let client = TestClient::new(create_app()).await;
+
+    let response = client.get("/v1/shelves/s1/books/b1?unknown=1").await;
+    assert_eq!(response.status(), 400);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 3);
+
+    let response = client
+        .post("/v1/shelves/s1/books", &json!({ "pageCount": "many" }))
+        .await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 3);
+    assert!(body["message"].as_str().unwrap().contains("pageCount"));
+
+    let response = client.get("/v1/shelves/s1/books/missing").await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, json!({ "code": 5, "message": "book not found" }));
+}
+
+#[tokio::test]
+async fn test_body_over_max_body_size_is_resource_exhausted() {
+    let app = UltraApiApp::new()
+        .grpc(ultraapi::grpc::GrpcTranscoder::new().register_service(library().max_body_size(256)));
+    let client = TestClient::new(app).await;
+
+    let response = client
+        .post("/v1/shelves/s1/books", &json!({ "title": "Solaris" }))
+        .await;
+    assert_eq!(response.status(), 200);
+
+    let response = client
+        .post("/v1/shelves/s1/books", &json!({ "title": "x".repeat(300) }))
+        .await;
+    assert_eq!(response.status(), 413);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 8);
+    assert_eq!(
+        body["message"],
+        "request body exceeds the limit of 256 bytes"
+    );
+}
+
+#[tokio::test]
+async fn test_descriptor_operations_in_openapi() {
+    let client = TestClient::new(create_app()).await;
+    let spec: serde_json::Value = client.get("/openapi.json").await.json().await.unwrap();
+
+    let get = &spec["paths"]["/v1/{name}"]["get"];
+    assert_eq!(get["operationId"], "Library_GetBook");
+    assert!(get.get("requestBody").is_none());
+    let params = get["parameters"].as_array().unwrap();
+    assert!(params
+        .iter()
+        .any(|p| p["name"] == "name" && p["in"] == "path" && p["required"] == true));
+    assert!(params
+        .iter()
+        .any(|p| p["name"] == "includeDrafts" && p["in"] == "query"));
+
+    let create = &spec["paths"]["/v1/{parent}/books"]["post"];
+    assert_eq!(
+        create["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/bookstore.v1.Book"
+    );
+    let list = &spec["paths"]["/v1/{parent}/books"]["get"];
+    let genre = list["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "genre")
+        .unwrap();
+    assert_eq!(
+        genre["schema"]["enum"],
+        json!(["GENRE_UNSPECIFIED", "FICTION", "SCIENCE"])
+    );
+    assert_eq!(
+        spec["paths"]["/v1/books:search"]["post"]["operationId"],
+        "Library_ListBooks2"
+    );
+    assert!(spec["paths"]["/v1/{name}:archive"]["post"].is_object());
+
+    let book = &spec["components"]["schemas"]["bookstore.v1.Book"]["properties"];
+    assert_eq!(book["pageCount"]["type"], "string");
+    assert_eq!(book["pageCount"]["format"], "int64");
+    assert_eq!(book["published"]["format"], "date-time");
+    assert_eq!(book["tags"]["type"], "array");
+    assert_eq!(book["labels"]["additionalProperties"]["type"], "string");
+}
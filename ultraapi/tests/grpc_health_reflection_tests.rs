@@ -0,0 +1,416 @@
+//! gRPC Health and Reflection Tests
+//!
+//! Apps with gRPC services serve `grpc.health.v1.Health` (Check / Watch) with
+//! per-service statuses updated by application code and the lifespan, and
+//! `grpc.reflection.v1` / `v1alpha` built from the registered descriptors.
+
+use axum::http::uri::PathAndQuery;
+use prost::Message;
+use prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    MethodDescriptorProto, ServiceDescriptorProto,
+};
+use serde_json::json;
+use tonic::codec::ProstCodec;
+use tonic::Code;
+use ultraapi::grpc::{DescriptorPool, GrpcExt, HealthReporter, ServingStatus};
+use ultraapi::prelude::*;
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct HealthCheckRequest {
+    #[prost(string, tag = "1")]
+    service: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct HealthCheckResponse {
+    #[prost(int32, tag = "1")]
+    status: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ServerReflectionRequest {
+    #[prost(string, tag = "1")]
+    host: String,
+    #[prost(oneof = "MessageRequest", tags = "3, 4, 7")]
+    message_request: Option<MessageRequest>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+enum MessageRequest {
+    #[prost(string, tag = "3")]
+    FileByFilename(String),
+    #[prost(string, tag = "4")]
+    FileContainingSymbol(String),
+    #[prost(string, tag = "7")]
+    ListServices(String),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ServerReflectionResponse {
+    #[prost(string, tag = "1")]
+    valid_host: String,
+    #[prost(oneof = "MessageResponse", tags = "4, 6, 7")]
+    message_response: Option<MessageResponse>,
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, PartialEq, prost::Oneof)]
+enum MessageResponse {
+    #[prost(message, tag = "4")]
+    FileDescriptorResponse(FileDescriptorResponse),
+    #[prost(message, tag = "6")]
+    ListServicesResponse(ListServiceResponse),
+    #[prost(message, tag = "7")]
+    ErrorResponse(ErrorResponse),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct FileDescriptorResponse {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    file_descriptor_proto: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ListServiceResponse {
+    #[prost(message, repeated, tag = "1")]
+    service: Vec<ServiceResponse>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ServiceResponse {
+    #[prost(string, tag = "1")]
+    name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ErrorResponse {
+    #[prost(int32, tag = "1")]
+    error_code: i32,
+    #[prost(string, tag = "2")]
+    error_message: String,
+}
+
+/// `helloworld/greeter.proto` importing `helloworld/common.proto`
+fn greeter_descriptors() -> DescriptorPool {
+    let message = |name: &str, field: &str, type_name: Option<&str>| DescriptorProto {
+        name: Some(name.to_string()),
+        field: vec![FieldDescriptorProto {
+            name: Some(field.to_string()),
+            number: Some(1),
+            r#type: Some(if type_name.is_some() { 11 } else { 9 }),
+            type_name: type_name.map(str::to_string),
+            json_name: Some(field.to_string()),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let common = FileDescriptorProto {
+        name: Some("helloworld/common.proto".to_string()),
+        package: Some("helloworld".to_string()),
+        syntax: Some("proto3".to_string()),
+        message_type: vec![message("Name", "value", None)],
+        ..Default::default()
+    };
+    let greeter = FileDescriptorProto {
+        name: Some("helloworld/greeter.proto".to_string()),
+        package: Some("helloworld".to_string()),
+        syntax: Some("proto3".to_string()),
+        dependency: vec!["helloworld/common.proto".to_string()],
+        message_type: vec![
+            message("HelloRequest", "name", Some(".helloworld.Name")),
+            message("HelloReply", "message", None),
+        ],
+        service: vec![ServiceDescriptorProto {
+            name: Some("Greeter".to_string()),
+            method: vec![MethodDescriptorProto {
+                name: Some("SayHello".to_string()),
+                input_type: Some(".helloworld.HelloRequest".to_string()),
+                output_type: Some(".helloworld.HelloReply".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let set = FileDescriptorSet {
+        file: vec![common, greeter],
+    };
+    DescriptorPool::decode(&set.encode_to_vec()).unwrap()
+}
+
+#[post("/admin/maintenance")]
+async fn start_maintenance(health: Dep<HealthReporter>) -> String {
+    health.set_not_serving("helloworld.Greeter");
+    "ok".to_string()
+}
+
+fn create_app() -> UltraApiApp {
+    let greeter =
+        ultraapi::grpc::GrpcService::from_descriptor(&greeter_descriptors(), "helloworld.Greeter")
+            .unwrap();
+    UltraApiApp::new()
+        .grpc(ultraapi::grpc::GrpcTranscoder::new().register_service(greeter))
+        .lifecycle(|lifecycle| {
+            lifecycle.on_startup(|state| {
+                let health = state.get::<HealthReporter>().expect("health reporter");
+                Box::pin(async move {
+                    // Still NOT_SERVING while startup hooks run
+                    assert_eq!(health.status(""), Some(ServingStatus::NotServing));
+                    health.set_status("billing", ServingStatus::NotServing);
+                })
+            })
+        })
+}
+
+async fn grpc_client(client: &TestClient) -> tonic::client::Grpc<tonic::transport::Channel> {
+    let channel = tonic::transport::Channel::from_shared(client.base_url().to_string())
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let mut grpc = tonic::client::Grpc::new(channel);
+    grpc.ready().await.unwrap();
+    grpc
+}
+
+async fn check(client: &TestClient, service: &str) -> Result<i32, tonic::Status> {
+    let mut grpc = grpc_client(client).await;
+    grpc.unary(
+        tonic::Request::new(HealthCheckRequest {
+            service: service.to_string(),
+        }),
+        PathAndQuery::from_static("/grpc.health.v1.Health/Check"),
+        ProstCodec::<HealthCheckRequest, HealthCheckResponse>::default(),
+    )
+    .await
+    .map(|response| response.into_inner().status)
+}
+
+async fn reflect(
+    client: &TestClient,
+    path: &'static str,
+    requests: Vec<MessageRequest>,
+) -> Vec<MessageResponse> {
+    let mut grpc = grpc_client(client).await;
+    let requests =
+        tokio_stream::iter(requests.into_iter().map(|request| ServerReflectionRequest {
+            host: "localhost".to_string(),
+            message_request: Some(request),
+        }));
+    let mut responses = grpc
+        .streaming(
+            tonic::Request::new(requests),
+            PathAndQuery::from_static(path),
+            ProstCodec::<ServerReflectionRequest, ServerReflectionResponse>::default(),
+        )
+        .await
+        .unwrap()
+        .into_inner();
+    let mut out = Vec::new();
+    while let Some(response) = responses.message().await.unwrap() {
+        assert_eq!(response.valid_host, "localhost");
+        out.push(response.message_response.unwrap());
+    }
+    out
+}
+
+fn file_names(response: &MessageResponse) -> Vec<String> {
+    let MessageResponse::FileDescriptorResponse(files) = response else {
+        panic!("expected files, got {:?}", response);
+    };
+    files
+        .file_descriptor_proto
+        .iter()
+        .map(|bytes| {
+            FileDescriptorProto::decode(bytes.as_slice())
+                .unwrap()
+                .name
+                .unwrap()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_health_check_per_service() {
+    let client = TestClient::new(create_app()).await;
+
+    assert_eq!(check(&client, "").await.unwrap(), 1);
+    assert_eq!(check(&client, "helloworld.Greeter").await.unwrap(), 1);
+    // Set by the startup hook: not overridden when startup completes
+    assert_eq!(check(&client, "billing").await.unwrap(), 2);
+    let status = check(&client, "nope.Missing").await.unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+
+    let response = client.post("/admin/maintenance", &json!({})).await;
+    assert_eq!(response.status(), 201);
+    assert_eq!(check(&client, "helloworld.Greeter").await.unwrap(), 2);
+
+    // HTTP/JSON clients
+    let response = client
+        .get("/grpc.health.v1.Health/Check?service=helloworld.Greeter")
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, json!({ "status": "NOT_SERVING" }));
+    let response = client
+        .get("/grpc.health.v1.Health/Check?service=nope")
+        .await;
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_health_watch_streams_changes() {
+    let app = create_app();
+    let client = TestClient::new(app).await;
+    let mut grpc = grpc_client(&client).await;
+
+    let mut updates = grpc
+        .server_streaming(
+            tonic::Request::new(HealthCheckRequest {
+                service: "helloworld.Greeter".to_string(),
+            }),
+            PathAndQuery::from_static("/grpc.health.v1.Health/Watch"),
+            ProstCodec::<HealthCheckRequest, HealthCheckResponse>::default(),
+        )
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(updates.message().await.unwrap().unwrap().status, 1);
+
+    client.post("/admin/maintenance", &json!({})).await;
+    assert_eq!(updates.message().await.unwrap().unwrap().status, 2);
+
+    // Unknown services are reported as SERVICE_UNKNOWN instead of failing
+    grpc.ready().await.unwrap();
+    let mut unknown = grpc
+        .server_streaming(
+            tonic::Request::new(HealthCheckRequest {
+                service: "later.Service".to_string(),
+            }),
+            PathAndQuery::from_static("/grpc.health.v1.Health/Watch"),
+            ProstCodec::<HealthCheckRequest, HealthCheckResponse>::default(),
+        )
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(unknown.message().await.unwrap().unwrap().status, 3);
+}
+
+#[tokio::test]
+async fn test_health_follows_lifespan() {
+    let (_router, runner) = create_app().into_router_with_lifespan();
+    let health = runner.state().get::<HealthReporter>().unwrap();
+    assert_eq!(health.status(""), Some(ServingStatus::NotServing));
+    assert_eq!(
+        health.status("helloworld.Greeter"),
+        Some(ServingStatus::NotServing)
+    );
+
+    runner.ensure_startup().await;
+    assert_eq!(health.status(""), Some(ServingStatus::Serving));
+    assert_eq!(
+        health.status("helloworld.Greeter"),
+        Some(ServingStatus::Serving)
+    );
+
+    // Drain: everything stops serving before the shutdown hooks release resources
+    runner.shutdown().await;
+    assert_eq!(health.status(""), Some(ServingStatus::NotServing));
+    assert_eq!(
+        health.status("helloworld.Greeter"),
+        Some(ServingStatus::NotServing)
+    );
+}
+
+#[tokio::test]
+async fn test_reflection_lists_and_describes_services() {
+    let client = TestClient::new(create_app()).await;
+
+    let responses = reflect(
+        &client,
+        "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo",
+        vec![
+            MessageRequest::ListServices(String::new()),
+            MessageRequest::FileContainingSymbol("helloworld.Greeter.SayHello".to_string()),
+            MessageRequest::FileByFilename("helloworld/common.proto".to_string()),
+            MessageRequest::FileContainingSymbol("grpc.health.v1.Health".to_string()),
+            MessageRequest::FileByFilename("missing.proto".to_string()),
+        ],
+    )
+    .await;
+    assert_eq!(responses.len(), 5);
+
+    let MessageResponse::ListServicesResponse(list) = &responses[0] else {
+        panic!("expected services, got {:?}", responses[0]);
+    };
+    let names: Vec<&str> = list.service.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["grpc.health.v1.Health", "helloworld.Greeter"]);
+
+    // The file comes with its imports
+    assert_eq!(
+        file_names(&responses[1]),
+        vec!["helloworld/greeter.proto", "helloworld/common.proto"]
+    );
+    assert_eq!(file_names(&responses[2]), vec!["helloworld/common.proto"]);
+    assert_eq!(
+        file_names(&responses[3]),
+        vec!["grpc/health/v1/health.proto"]
+    );
+    let MessageResponse::ErrorResponse(error) = &responses[4] else {
+        panic!("expected an error, got {:?}", responses[4]);
+    };
+    assert_eq!(error.error_code, Code::NotFound as i32);
+}
+
+#[tokio::test]
+async fn test_reflection_v1alpha_and_registered_descriptors() {
+    // A native-only app registers its descriptors explicitly
+    let app = UltraApiApp::new().grpc_descriptors(greeter_descriptors());
+    let app = app.grpc(
+        ultraapi::grpc::GrpcTranscoder::new().register_service(
+            ultraapi::grpc::service("Plain")
+                .method_unary("Ping", "/Plain/Ping")
+                .build(),
+        ),
+    );
+    let client = TestClient::new(app).await;
+
+    let responses = reflect(
+        &client,
+        "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo",
+        vec![MessageRequest::ListServices(String::new())],
+    )
+    .await;
+    let MessageResponse::ListServicesResponse(list) = &responses[0] else {
+        panic!("expected services, got {:?}", responses[0]);
+    };
+    // Services without descriptors cannot be described, so they are not listed
+    let names: Vec<&str> = list.service.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["grpc.health.v1.Health", "helloworld.Greeter"]);
+
+    // HTTP/JSON clients use proto3 JSON
+    let response = client
+        .post(
+            "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo",
+            &json!({ "fileContainingSymbol": "helloworld.HelloReply" }),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let files = body["fileDescriptorResponse"]["fileDescriptorProto"]
+        .as_array()
+        .unwrap();
+    assert_eq!(files.len(), 2);
+
+    // JSON requests are small; anything large is refused before parsing
+    let response = client
+        .post(
+            "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo",
+            &json!({ "fileContainingSymbol": "x".repeat(100 * 1024) }),
+        )
+        .await;
+    assert_eq!(response.status(), 413);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 8);
+}
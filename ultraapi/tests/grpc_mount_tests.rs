@@ -0,0 +1,179 @@
+//! gRPC Mount Tests
+//!
+//! Services added with `GrpcExt::grpc` and `grpc_service!` are mounted into the
+//! UltraApiApp router: they share dependencies, auth / rate limiting / CORS
+//! middleware, and are documented in `/openapi.json`.
+
+use serde_json::json;
+use std::sync::Arc;
+use ultraapi::grpc::{service, GrpcExt, GrpcHandler, GrpcRequest, GrpcResponse, GrpcTranscoder};
+use ultraapi::middleware::CorsConfig;
+use ultraapi::prelude::*;
+
+ultraapi::grpc_service!("InventoryService", {
+    "ListItems" => "/InventoryService/ListItems",
+});
+
+struct Greeting(&'static str);
+
+fn greeter_transcoder() -> GrpcTranscoder {
+    let say_hello: GrpcHandler = Arc::new(|req: GrpcRequest| {
+        Box::pin(async move {
+            let greeting = req.dep::<Greeting>().map(|g| g.0).unwrap_or("?");
+            let name = req.body["name"].as_str().unwrap_or("world").to_string();
+            GrpcResponse {
+                body: json!({
+                    "message": format!("{}, {}", greeting, name),
+                    "agent": req.metadata.get("x-agent"),
+                }),
+                status_code: 0,
+            }
+        })
+    });
+
+    GrpcTranscoder::new()
+        .register_service(
+            service("Greeter")
+                .package("hello")
+                .method_unary("SayHello", "/hello.Greeter/SayHello")
+                .with_handler("SayHello", say_hello),
+        )
+        .register_service(
+            service("Vault")
+                .method_unary("Open", "/Vault/Open")
+                .security("bearerAuth")
+                .build(),
+        )
+}
+
+fn create_app() -> UltraApiApp {
+    UltraApiApp::new()
+        .title("gRPC Mount Test API")
+        .version("0.1.0")
+        .dep(Greeting("Hello"))
+        .grpc(greeter_transcoder())
+}
+
+#[tokio::test]
+async fn test_transcoded_call_shares_app_state() {
+    let client = TestClient::new(create_app()).await;
+    let response = client
+        .client()
+        .post(format!("{}/hello/Greeter/SayHello", client.base_url()))
+        .header("x-agent", "tests")
+        .json(&json!({ "name": "Ada" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body, json!({ "message": "Hello, Ada", "agent": "tests" }));
+}
+
+#[tokio::test]
+async fn test_inventory_service_is_mounted() {
+    let client = TestClient::new(create_app()).await;
+    // Registered without a handler: the route exists and reports NOT_FOUND
+    let response = client.post("/InventoryService/ListItems", &json!({})).await;
+    assert_eq!(response.status(), 404);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 5);
+
+    let response = client.get("/grpc.health.v1.Health/Check").await;
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_grpc_routes_share_auth_middleware() {
+    let client = TestClient::new(create_app().bearer_auth()).await;
+    let url = format!("{}/Vault/Open", client.base_url());
+
+    let response = client
+        .client()
+        .post(&url)
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    // Authenticated, but the service has no handler
+    let response = client
+        .client()
+        .post(&url)
+        .bearer_auth("valid-token")
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_grpc_routes_share_cors_and_rate_limit() {
+    let app = create_app()
+        .middleware(|builder| {
+            builder.cors(
+                CorsConfig::new()
+                    .allow_origins(vec!["https://app.example".into()])
+                    .allow_credentials(false),
+            )
+        })
+        .rate_limit_max(1, 60);
+    let client = TestClient::new(app).await;
+    let url = format!("{}/hello/Greeter/SayHello", client.base_url());
+
+    let response = client
+        .client()
+        .post(&url)
+        .header("origin", "https://app.example")
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "https://app.example"
+    );
+
+    let response = client
+        .client()
+        .post(&url)
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 429);
+}
+
+#[tokio::test]
+async fn test_grpc_operations_in_openapi() {
+    let client = TestClient::new(create_app()).await;
+    let spec: serde_json::Value = client.get("/openapi.json").await.json().await.unwrap();
+
+    let op = &spec["paths"]["/hello/Greeter/SayHello"]["post"];
+    assert_eq!(op["operationId"], "Greeter_SayHello");
+    assert_eq!(op["tags"], json!(["Greeter"]));
+    assert_eq!(
+        op["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/SayHelloRequest"
+    );
+    assert_eq!(
+        op["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/SayHelloResponse"
+    );
+    assert_eq!(
+        op["responses"]["default"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/GrpcError"
+    );
+    assert!(spec["components"]["schemas"]["SayHelloRequest"].is_object());
+
+    let vault = &spec["paths"]["/Vault/Open"]["post"];
+    assert_eq!(vault["security"], json!([{ "bearerAuth": [] }]));
+
+    assert_eq!(
+        spec["paths"]["/InventoryService/ListItems"]["post"]["operationId"],
+        "InventoryService_ListItems"
+    );
+}
@@ -0,0 +1,309 @@
+//! Native gRPC Tests
+//!
+//! tonic services added with `GrpcExt::grpc_service` are served on the same port
+//! as the REST routes (switched on `content-type: application/grpc`) and share
+//! dependencies, auth middleware and lifespan hooks with them.
+
+use axum::http::uri::PathAndQuery;
+use bytes::Bytes;
+use prost::Message;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll};
+use tonic::codec::ProstCodec;
+use tonic::server::NamedService;
+use tonic::{Code, Status};
+use ultraapi::grpc::{GrpcError, GrpcExt, GrpcRequestExt, NativeGrpcService};
+use ultraapi::prelude::*;
+use ultraapi::{AppState, DependencyError};
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct HelloRequest {
+    #[prost(string, tag = "1")]
+    name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct HelloReply {
+    #[prost(string, tag = "1")]
+    message: String,
+}
+
+/// `google.rpc.Status` as decoded by clients
+#[derive(Clone, PartialEq, prost::Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<prost_types::Any>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct BadRequest {
+    #[prost(message, repeated, tag = "1")]
+    field_violations: Vec<FieldViolation>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct FieldViolation {
+    #[prost(string, tag = "1")]
+    field: String,
+    #[prost(string, tag = "2")]
+    description: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct RetryInfo {
+    #[prost(message, optional, tag = "1")]
+    retry_delay: Option<prost_types::Duration>,
+}
+
+struct Greeting(&'static str);
+
+struct Ready(AtomicBool);
+
+struct Punctuation {
+    mark: String,
+}
+
+async fn get_punctuation(_state: AppState) -> Result<Punctuation, DependencyError> {
+    Ok(Punctuation {
+        mark: "!".to_string(),
+    })
+}
+
+async fn say_hello(
+    request: tonic::Request<HelloRequest>,
+) -> Result<tonic::Response<HelloReply>, Status> {
+    if request.get_ref().name.is_empty() {
+        return Err(GrpcError::invalid_argument("invalid greeting")
+            .with_field_violation("name", "must not be empty")
+            .into());
+    }
+    let greeting = request.dep::<Greeting>()?;
+    let ready = request.dep::<Ready>()?;
+    if !ready.0.load(Ordering::SeqCst) {
+        return Err(Status::unavailable("startup has not run"));
+    }
+    let punctuation = request.depends::<Punctuation>().await?;
+    Ok(tonic::Response::new(HelloReply {
+        message: format!(
+            "{}, {}{}",
+            greeting.0,
+            request.get_ref().name,
+            punctuation.mark
+        ),
+    }))
+}
+
+/// What `tonic-build` generates for `service Greeter { rpc SayHello(...) }`
+#[derive(Clone)]
+struct GreeterServer;
+
+impl NamedService for GreeterServer {
+    const NAME: &'static str = "helloworld.Greeter";
+}
+
+impl<B> tower::Service<axum::http::Request<B>> for GreeterServer
+where
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<tower::BoxError> + Send + 'static,
+{
+    type Response = axum::http::Response<tonic::body::BoxBody>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: axum::http::Request<B>) -> Self::Future {
+        Box::pin(async move {
+            match request.uri().path() {
+                "/helloworld.Greeter/SayHello" => {
+                    let mut grpc =
+                        tonic::server::Grpc::new(ProstCodec::<HelloReply, HelloRequest>::default());
+                    Ok(grpc.unary(tower::service_fn(say_hello), request).await)
+                }
+                _ => Ok(Status::unimplemented("").into_http()),
+            }
+        })
+    }
+}
+
+#[get("/native/health")]
+async fn rest_health() -> String {
+    "ok".to_string()
+}
+
+fn create_app() -> UltraApiApp {
+    UltraApiApp::new()
+        .dep(Greeting("Hello"))
+        .dep(Ready(AtomicBool::new(false)))
+        .depends(get_punctuation)
+        .lifecycle(|lifecycle| {
+            lifecycle.on_startup(|state| {
+                let ready = state.get::<Ready>().expect("Ready dep missing");
+                Box::pin(async move {
+                    ready.0.store(true, Ordering::SeqCst);
+                })
+            })
+        })
+}
+
+async fn call(
+    client: &TestClient,
+    name: &str,
+    token: Option<&str>,
+    path: &'static str,
+) -> Result<HelloReply, Status> {
+    let channel = tonic::transport::Channel::from_shared(client.base_url().to_string())
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let mut grpc = tonic::client::Grpc::new(channel);
+    grpc.ready().await.unwrap();
+
+    let mut request = tonic::Request::new(HelloRequest {
+        name: name.to_string(),
+    });
+    if let Some(token) = token {
+        request.metadata_mut().insert(
+            "authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+    }
+    grpc.unary(
+        request,
+        PathAndQuery::from_static(path),
+        ProstCodec::<HelloRequest, HelloReply>::default(),
+    )
+    .await
+    .map(tonic::Response::into_inner)
+}
+
+#[tokio::test]
+async fn test_grpc_and_rest_share_one_port() {
+    let client = TestClient::new(create_app().grpc_service(GreeterServer)).await;
+
+    let reply = call(&client, "Ada", None, "/helloworld.Greeter/SayHello")
+        .await
+        .unwrap();
+    assert_eq!(reply.message, "Hello, Ada!");
+
+    let response = client.get("/native/health").await;
+    assert_eq!(response.status(), 200);
+
+    // JSON requests to the gRPC path are not dispatched to the tonic service
+    let response = client
+        .post("/helloworld.Greeter/SayHello", &serde_json::json!({}))
+        .await;
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_unknown_methods_are_unimplemented() {
+    let client = TestClient::new(create_app().grpc_service(GreeterServer)).await;
+
+    let status = call(&client, "Ada", None, "/helloworld.Greeter/SayGoodbye")
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unimplemented);
+
+    let status = call(&client, "Ada", None, "/helloworld.Farewell/SayGoodbye")
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unimplemented);
+}
+
+#[tokio::test]
+async fn test_grpc_shares_auth_middleware() {
+    let app = create_app()
+        .bearer_auth()
+        .grpc_service(NativeGrpcService::new(GreeterServer).security("bearerAuth"));
+    let client = TestClient::new(app).await;
+
+    let status = call(&client, "Ada", None, "/helloworld.Greeter/SayHello")
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let reply = call(
+        &client,
+        "Ada",
+        Some("valid-token"),
+        "/helloworld.Greeter/SayHello",
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply.message, "Hello, Ada!");
+}
+
+#[tokio::test]
+async fn test_rate_limited_calls_are_resource_exhausted() {
+    let app = create_app()
+        .grpc_service(GreeterServer)
+        .rate_limit_max(1, 60);
+    let client = TestClient::new(app).await;
+
+    assert!(call(&client, "Ada", None, "/helloworld.Greeter/SayHello")
+        .await
+        .is_ok());
+    let status = call(&client, "Ada", None, "/helloworld.Greeter/SayHello")
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+
+    let details = RpcStatus::decode(status.details()).unwrap();
+    assert_eq!(details.code, Code::ResourceExhausted as i32);
+    assert_eq!(
+        details.details[0].type_url,
+        "type.googleapis.com/google.rpc.RetryInfo"
+    );
+    let retry = RetryInfo::decode(details.details[0].value.as_slice()).unwrap();
+    assert!(retry.retry_delay.unwrap().seconds > 0);
+}
+
+#[tokio::test]
+async fn test_handler_errors_carry_status_details() {
+    let client = TestClient::new(create_app().grpc_service(GreeterServer)).await;
+
+    let status = call(&client, "", None, "/helloworld.Greeter/SayHello")
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(status.message(), "invalid greeting");
+
+    let details = RpcStatus::decode(status.details()).unwrap();
+    assert_eq!(details.code, Code::InvalidArgument as i32);
+    assert_eq!(details.details.len(), 1);
+    assert_eq!(
+        details.details[0].type_url,
+        "type.googleapis.com/google.rpc.BadRequest"
+    );
+    let bad_request = BadRequest::decode(details.details[0].value.as_slice()).unwrap();
+    assert_eq!(
+        bad_request.field_violations,
+        vec![FieldViolation {
+            field: "name".to_string(),
+            description: "must not be empty".to_string(),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_missing_dependency_is_internal_error() {
+    let app = UltraApiApp::new().grpc_service(GreeterServer);
+    let client = TestClient::new(app).await;
+
+    let status = call(&client, "Ada", None, "/helloworld.Greeter/SayHello")
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Internal);
+    assert!(status.message().contains("Greeting"));
+}
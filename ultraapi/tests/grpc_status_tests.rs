@@ -0,0 +1,206 @@
+//! gRPC Status Mapping Tests
+//!
+//! Canonical gRPC codes map to HTTP statuses when transcoding, errors carry
+//! `google.rpc.Status` details (BadRequest, RetryInfo), and `ApiError` /
+//! `HttpException` raised by shared code become gRPC statuses.
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use ultraapi::grpc::{
+    service, GrpcCode, GrpcError, GrpcExt, GrpcHandler, GrpcRequest, GrpcResponse, GrpcTranscoder,
+};
+use ultraapi::prelude::*;
+use ultraapi::{ValidationErrorDetail, ValidationSource};
+
+#[test]
+fn test_code_to_http_status_mapping() {
+    let cases = [
+        (GrpcCode::Ok, 200),
+        (GrpcCode::Cancelled, 499),
+        (GrpcCode::Unknown, 500),
+        (GrpcCode::InvalidArgument, 400),
+        (GrpcCode::DeadlineExceeded, 504),
+        (GrpcCode::NotFound, 404),
+        (GrpcCode::AlreadyExists, 409),
+        (GrpcCode::PermissionDenied, 403),
+        (GrpcCode::ResourceExhausted, 429),
+        (GrpcCode::FailedPrecondition, 400),
+        (GrpcCode::Aborted, 409),
+        (GrpcCode::OutOfRange, 400),
+        (GrpcCode::Unimplemented, 501),
+        (GrpcCode::Internal, 500),
+        (GrpcCode::Unavailable, 503),
+        (GrpcCode::DataLoss, 500),
+        (GrpcCode::Unauthenticated, 401),
+    ];
+    for (code, http) in cases {
+        assert_eq!(code.http_status().as_u16(), http, "{}", code);
+        assert_eq!(GrpcCode::from_i32(code.as_i32()), code);
+    }
+    assert_eq!(GrpcCode::from_i32(99), GrpcCode::Unknown);
+    assert_eq!(GrpcError::permission_denied("no").code, 7);
+}
+
+#[test]
+fn test_http_status_to_code_mapping() {
+    let cases = [
+        (400, GrpcCode::InvalidArgument),
+        (401, GrpcCode::Unauthenticated),
+        (403, GrpcCode::PermissionDenied),
+        (404, GrpcCode::NotFound),
+        (409, GrpcCode::AlreadyExists),
+        (422, GrpcCode::InvalidArgument),
+        (429, GrpcCode::ResourceExhausted),
+        (500, GrpcCode::Internal),
+        (501, GrpcCode::Unimplemented),
+        (503, GrpcCode::Unavailable),
+        (504, GrpcCode::DeadlineExceeded),
+    ];
+    for (http, code) in cases {
+        let status = StatusCode::from_u16(http).unwrap();
+        assert_eq!(GrpcCode::from_http_status(status), code, "{}", http);
+    }
+}
+
+#[test]
+fn test_api_errors_become_grpc_errors() {
+    let error = GrpcError::from(HttpException::new(StatusCode::CONFLICT, "already there"));
+    assert_eq!(error.grpc_code(), GrpcCode::AlreadyExists);
+    assert_eq!(error.message, "already there");
+
+    let error = GrpcError::from(ApiError::validation_errors(
+        ValidationSource::Body,
+        vec![ValidationErrorDetail::new("missing", "Field required").at("user.email")],
+    ));
+    assert_eq!(error.grpc_code(), GrpcCode::InvalidArgument);
+    assert_eq!(
+        error.details,
+        vec![json!({
+            "@type": "type.googleapis.com/google.rpc.BadRequest",
+            "fieldViolations": [{ "field": "user.email", "description": "Field required" }],
+        })]
+    );
+
+    let error = GrpcError::from(
+        HttpException::new(StatusCode::SERVICE_UNAVAILABLE, "maintenance")
+            .with_header(header::RETRY_AFTER, HeaderValue::from_static("30")),
+    );
+    assert_eq!(error.grpc_code(), GrpcCode::Unavailable);
+    assert_eq!(error.retry_delay(), Some(Duration::from_secs(30)));
+
+    let status = tonic::Status::from(ApiError::unauthorized("who are you"));
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+    assert_eq!(status.message(), "who are you");
+}
+
+/// Shared code used by REST handlers too
+#[allow(clippy::result_large_err)]
+fn check_quota(remaining: u32) -> Result<(), HttpException> {
+    if remaining == 0 {
+        return Err(
+            HttpException::new(StatusCode::TOO_MANY_REQUESTS, "quota exceeded")
+                .with_header(header::RETRY_AFTER, HeaderValue::from_static("7")),
+        );
+    }
+    Ok(())
+}
+
+fn create_app() -> UltraApiApp {
+    let fail: GrpcHandler = Arc::new(|req: GrpcRequest| {
+        Box::pin(async move {
+            let error = match req.body["case"].as_str().unwrap_or_default() {
+                "not_found" => GrpcError::not_found("no such user"),
+                "denied" => GrpcError::permission_denied("admins only"),
+                "unauthenticated" => GrpcError::unauthenticated("login required"),
+                "invalid" => GrpcError::invalid_argument("bad request")
+                    .with_field_violation("email", "must contain @")
+                    .with_field_violation("age", "must be positive"),
+                "unavailable" => GrpcError::unavailable("try later")
+                    .with_retry_delay(Duration::from_millis(1500)),
+                "quota" => match check_quota(0) {
+                    Ok(()) => return GrpcResponse::from(GrpcError::ok("")),
+                    Err(e) => GrpcError::from(e),
+                },
+                _ => {
+                    return GrpcResponse {
+                        body: json!({ "reason": "raw" }),
+                        status_code: 10,
+                    }
+                }
+            };
+            GrpcResponse::from(error)
+        })
+    });
+
+    UltraApiApp::new().grpc(
+        GrpcTranscoder::new().register_service(
+            service("Errors")
+                .method_unary("Fail", "/Errors/Fail")
+                .with_handler("Fail", fail),
+        ),
+    )
+}
+
+async fn fail(client: &TestClient, case: &str) -> (u16, HeaderMap, serde_json::Value) {
+    let response = client.post("/Errors/Fail", &json!({ "case": case })).await;
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    (status, headers, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_transcoded_errors_use_mapped_http_status() {
+    let client = TestClient::new(create_app()).await;
+
+    let (status, _, body) = fail(&client, "not_found").await;
+    assert_eq!(status, 404);
+    assert_eq!(body, json!({ "code": 5, "message": "no such user" }));
+
+    assert_eq!(fail(&client, "denied").await.0, 403);
+    assert_eq!(fail(&client, "unauthenticated").await.0, 401);
+
+    // A non-error body is kept as the single detail
+    let (status, _, body) = fail(&client, "raw").await;
+    assert_eq!(status, 409);
+    assert_eq!(
+        body,
+        json!({ "code": 10, "message": "ABORTED", "details": [{ "reason": "raw" }] })
+    );
+}
+
+#[tokio::test]
+async fn test_transcoded_errors_carry_details() {
+    let client = TestClient::new(create_app()).await;
+
+    let (status, _, body) = fail(&client, "invalid").await;
+    assert_eq!(status, 400);
+    assert_eq!(
+        body["details"],
+        json!([{
+            "@type": "type.googleapis.com/google.rpc.BadRequest",
+            "fieldViolations": [
+                { "field": "email", "description": "must contain @" },
+                { "field": "age", "description": "must be positive" },
+            ],
+        }])
+    );
+
+    let (status, headers, body) = fail(&client, "unavailable").await;
+    assert_eq!(status, 503);
+    assert_eq!(headers["retry-after"], "2");
+    assert_eq!(
+        body["details"],
+        json!([{
+            "@type": "type.googleapis.com/google.rpc.RetryInfo",
+            "retryDelay": "1.500s",
+        }])
+    );
+
+    let (status, headers, body) = fail(&client, "quota").await;
+    assert_eq!(status, 429);
+    assert_eq!(headers["retry-after"], "7");
+    assert_eq!(body["code"], 8);
+    assert_eq!(body["message"], "quota exceeded");
+}
@@ -0,0 +1,255 @@
+//! gRPC Streaming Tests
+//!
+//! Server-streaming methods are sent as NDJSON (or SSE with `Accept:
+//! text/event-stream`) by the transcoding gateway, and client-streaming methods
+//! read their messages from an NDJSON request body.
+
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use ultraapi::grpc::{
+    service, GrpcError, GrpcExt, GrpcHandler, GrpcMethod, GrpcRequest, GrpcResponse, GrpcStream,
+    GrpcStreamingHandler, GrpcTranscoder, HttpRule,
+};
+use ultraapi::prelude::*;
+
+struct Prefix(&'static str);
+
+fn create_app() -> UltraApiApp {
+    create_app_with_limit(ultraapi::grpc::DEFAULT_MAX_BODY_SIZE)
+}
+
+fn create_app_with_limit(max_body_size: usize) -> UltraApiApp {
+    let count: GrpcStreamingHandler = Arc::new(|req: GrpcRequest| {
+        Box::pin(async move {
+            let prefix = req.dep::<Prefix>().map(|p| p.0).unwrap_or("?");
+            // Bound from the path as a string when there is no descriptor
+            let upto = match &req.body["upto"] {
+                Value::String(upto) => upto.parse().unwrap_or(0),
+                upto => upto.as_i64().unwrap_or(0),
+            };
+            if upto < 0 {
+                return Err(GrpcError::invalid_argument("upto must not be negative"));
+            }
+            let fail_at = req.body["failAt"].as_i64();
+            let messages = (1..=upto).map(move |n| match fail_at {
+                Some(at) if at == n => Err(GrpcError::aborted("counter broke")),
+                _ => Ok(json!({ "n": n, "label": format!("{}{}", prefix, n) })),
+            });
+            Ok(Box::pin(tokio_stream::iter(messages)) as GrpcStream)
+        })
+    });
+
+    let deposit: GrpcHandler = Arc::new(|mut req: GrpcRequest| {
+        Box::pin(async move {
+            let mut messages = req.messages.take().expect("client streaming");
+            let mut total = 0;
+            let mut deposits = 0;
+            while let Some(message) = messages.next().await {
+                match message {
+                    Ok(message) => {
+                        total += message["amount"].as_i64().unwrap_or(0);
+                        deposits += 1;
+                    }
+                    Err(error) => return GrpcResponse::from(error),
+                }
+            }
+            GrpcResponse {
+                body: json!({
+                    "account": req.body["account"],
+                    "deposits": deposits,
+                    "total": total,
+                }),
+                status_code: 0,
+            }
+        })
+    });
+
+    // Server streaming method with only a unary handler: a single JSON response
+    let legacy: GrpcHandler = Arc::new(|_req: GrpcRequest| {
+        Box::pin(async move {
+            GrpcResponse {
+                body: json!({ "n": 1 }),
+                status_code: 0,
+            }
+        })
+    });
+
+    let counter = service("Counter")
+        .package("demo")
+        .method_server_streaming("Count", "/demo.Counter/Count")
+        .method_server_streaming("Legacy", "/demo.Counter/Legacy")
+        .method(
+            GrpcMethod::server_streaming("Labels", "/demo.Counter/Labels")
+                .http(HttpRule::get("/v1/counter/{upto}/labels").response_body("label")),
+        )
+        .method(
+            GrpcMethod::client_streaming("Deposit", "/demo.Counter/Deposit")
+                .http(HttpRule::post("/v1/accounts/{account}:deposit").body("*")),
+        )
+        .with_streaming_handler("Count", count.clone())
+        .with_streaming_handler("Labels", count)
+        .with_handler("Legacy", legacy)
+        .with_handler("Deposit", deposit)
+        .max_body_size(max_body_size);
+
+    UltraApiApp::new()
+        .title("gRPC Streaming Test API")
+        .version("0.1.0")
+        .dep(Prefix("#"))
+        .grpc(GrpcTranscoder::new().register_service(counter))
+}
+
+fn ndjson(text: &str) -> Vec<Value> {
+    text.lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_server_streaming_as_ndjson() {
+    let client = TestClient::new(create_app()).await;
+
+    let response = client
+        .post("/demo/Counter/Count", &json!({ "upto": 3 }))
+        .await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+    assert_eq!(
+        ndjson(&response.text().await.unwrap()),
+        vec![
+            json!({ "result": { "n": 1, "label": "#1" } }),
+            json!({ "result": { "n": 2, "label": "#2" } }),
+            json!({ "result": { "n": 3, "label": "#3" } }),
+        ]
+    );
+
+    // `response_body` selects a field of every message
+    let response = client.get("/v1/counter/2/labels").await;
+    assert_eq!(
+        ndjson(&response.text().await.unwrap()),
+        vec![json!({ "result": "#1" }), json!({ "result": "#2" })]
+    );
+
+    let response = client.post("/demo/Counter/Legacy", &json!({})).await;
+    assert_eq!(response.headers()["content-type"], "application/json");
+    assert_eq!(response.json::<Value>().await.unwrap(), json!({ "n": 1 }));
+}
+
+#[tokio::test]
+async fn test_server_streaming_as_sse() {
+    let client = TestClient::new(create_app()).await;
+
+    let response = client
+        .client()
+        .post(format!("{}/demo/Counter/Count", client.base_url()))
+        .header("accept", "text/event-stream")
+        .json(&json!({ "upto": 2, "failAt": 2 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    let text = response.text().await.unwrap();
+    let events: Vec<&str> = text.split("\n\n").filter(|e| !e.is_empty()).collect();
+    assert_eq!(events.len(), 2);
+    let message: Value = serde_json::from_str(events[0].strip_prefix("data: ").unwrap()).unwrap();
+    assert_eq!(message, json!({ "n": 1, "label": "#1" }));
+    assert!(events[1].starts_with("event: error\ndata: "));
+    let error: Value = serde_json::from_str(&events[1]["event: error\ndata: ".len()..]).unwrap();
+    assert_eq!(error["code"], 10);
+    assert_eq!(error["message"], "counter broke");
+}
+
+#[tokio::test]
+async fn test_streaming_errors() {
+    let client = TestClient::new(create_app()).await;
+
+    // An error in the stream is the last line
+    let response = client
+        .post("/demo/Counter/Count", &json!({ "upto": 5, "failAt": 2 }))
+        .await;
+    assert_eq!(response.status(), 200);
+    let lines = ndjson(&response.text().await.unwrap());
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["result"]["n"], 1);
+    assert_eq!(lines[1]["error"]["code"], 10);
+    assert_eq!(lines[1]["error"]["message"], "counter broke");
+
+    // An error before the stream starts is a regular error response
+    let response = client
+        .post("/demo/Counter/Count", &json!({ "upto": -1 }))
+        .await;
+    assert_eq!(response.status(), 400);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 3);
+}
+
+#[tokio::test]
+async fn test_client_streaming_from_ndjson() {
+    let client = TestClient::new(create_app()).await;
+
+    let body = "{\"amount\": 10}\n\n{\"amount\": 25}\n{\"amount\": 7}";
+    let response = client
+        .client()
+        .post(format!("{}/v1/accounts/acme:deposit", client.base_url()))
+        .header("content-type", "application/x-ndjson")
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<Value>().await.unwrap(),
+        json!({ "account": "acme", "deposits": 3, "total": 42 })
+    );
+
+    let response = client
+        .post_raw("/v1/accounts/acme:deposit", "{\"amount\": 1}\n[1, 2]\n")
+        .await;
+    assert_eq!(response.status(), 400);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 3);
+    assert_eq!(body["message"], "each NDJSON line must be a JSON object");
+}
+
+#[tokio::test]
+async fn test_client_streaming_line_limit() {
+    let client = TestClient::new(create_app_with_limit(64)).await;
+
+    // The whole body may exceed the limit as long as each line fits
+    let body = "{\"amount\": 1}\n".repeat(20);
+    let response = client.post_raw("/v1/accounts/acme:deposit", body).await;
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["deposits"], 20);
+
+    let long = format!("{{\"amount\": 1, \"note\": \"{}\"}}\n", "x".repeat(100));
+    for body in [long.clone(), long.trim_end().to_string()] {
+        let response = client.post_raw("/v1/accounts/acme:deposit", body).await;
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["code"], 8);
+        assert_eq!(body["message"], "NDJSON line exceeds the limit of 64 bytes");
+    }
+}
+
+#[tokio::test]
+async fn test_streaming_operations_in_openapi() {
+    let client = TestClient::new(create_app()).await;
+    let spec: Value = client.get("/openapi.json").await.json().await.unwrap();
+
+    let count = &spec["paths"]["/demo/Counter/Count"]["post"];
+    assert_eq!(
+        count["responses"]["200"]["content"]["application/x-ndjson"]["schema"]["$ref"],
+        "#/components/schemas/StreamResultOfCountResponse"
+    );
+    let result = &spec["components"]["schemas"]["StreamResultOfCountResponse"];
+    assert_eq!(
+        result["properties"]["error"]["$ref"],
+        "#/components/schemas/GrpcError"
+    );
+
+    let deposit = &spec["paths"]["/v1/accounts/{account}:deposit"]["post"];
+    assert!(deposit["requestBody"]["content"]["application/x-ndjson"].is_object());
+}
@@ -0,0 +1,441 @@
+//! gRPC-Web Tests
+//!
+//! Browser clients call transcoded services with gRPC-Web framing (binary and
+//! base64 text) on the gRPC method path; the status is sent in a trailer frame.
+
+use base64::Engine;
+use prost::Message;
+use prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    MethodDescriptorProto, ServiceDescriptorProto,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use ultraapi::grpc::{
+    service, DescriptorPool, GrpcError, GrpcExt, GrpcHandler, GrpcRequest, GrpcResponse,
+    GrpcService, GrpcStream, GrpcStreamingHandler, GrpcTranscoder,
+};
+use ultraapi::middleware::CorsConfig;
+use ultraapi::prelude::*;
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct HelloRequest {
+    #[prost(string, tag = "1")]
+    name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct HelloReply {
+    #[prost(string, tag = "1")]
+    message: String,
+}
+
+fn greeter_descriptors() -> DescriptorPool {
+    let message = |name: &str, field: &str| DescriptorProto {
+        name: Some(name.to_string()),
+        field: vec![FieldDescriptorProto {
+            name: Some(field.to_string()),
+            number: Some(1),
+            r#type: Some(9),
+            json_name: Some(field.to_string()),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let method = |name: &str, server_streaming: bool| MethodDescriptorProto {
+        name: Some(name.to_string()),
+        input_type: Some(".helloworld.HelloRequest".to_string()),
+        output_type: Some(".helloworld.HelloReply".to_string()),
+        server_streaming: Some(server_streaming),
+        ..Default::default()
+    };
+    let file = FileDescriptorProto {
+        name: Some("helloworld.proto".to_string()),
+        package: Some("helloworld".to_string()),
+        syntax: Some("proto3".to_string()),
+        message_type: vec![
+            message("HelloRequest", "name"),
+            message("HelloReply", "message"),
+        ],
+        service: vec![ServiceDescriptorProto {
+            name: Some("Greeter".to_string()),
+            method: vec![method("SayHello", false), method("SayHellos", true)],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let set = FileDescriptorSet { file: vec![file] };
+    DescriptorPool::decode(&set.encode_to_vec()).unwrap()
+}
+
+fn greeter() -> GrpcService {
+    let say_hello: GrpcHandler = Arc::new(|req: GrpcRequest| {
+        Box::pin(async move {
+            let name = req.body["name"].as_str().unwrap_or_default().to_string();
+            if name.is_empty() {
+                return GrpcResponse::from(
+                    GrpcError::invalid_argument("name is required ✗")
+                        .with_field_violation("name", "must not be empty"),
+                );
+            }
+            GrpcResponse {
+                body: json!({ "message": format!("Hello, {}", name) }),
+                status_code: 0,
+            }
+        })
+    });
+    let say_hellos: GrpcStreamingHandler = Arc::new(|req: GrpcRequest| {
+        Box::pin(async move {
+            let name = req.body["name"].as_str().unwrap_or_default().to_string();
+            let replies =
+                (1..=3).map(move |n| Ok(json!({ "message": format!("{} #{}", name, n) })));
+            Ok(Box::pin(tokio_stream::iter(replies)) as GrpcStream)
+        })
+    });
+    GrpcService::from_descriptor(&greeter_descriptors(), "helloworld.Greeter")
+        .unwrap()
+        .with_handler("SayHello", say_hello)
+        .with_streaming_handler("SayHellos", say_hellos)
+}
+
+fn transcoder() -> GrpcTranscoder {
+    let echo: GrpcHandler = Arc::new(|req: GrpcRequest| {
+        Box::pin(async move {
+            GrpcResponse {
+                body: json!({ "echo": req.body["text"] }),
+                status_code: 0,
+            }
+        })
+    });
+    GrpcTranscoder::new()
+        .register_service(greeter())
+        // No package: the default transcoding path is also the gRPC path
+        .register_service(
+            service("Plain")
+                .method_unary("Echo", "/Plain/Echo")
+                .with_handler("Echo", echo),
+        )
+        .register_service(
+            service("Vault")
+                .method_unary("Open", "/Vault/Open")
+                .security("bearerAuth")
+                .build(),
+        )
+}
+
+fn frame(flag: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![flag];
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// `(flag, payload)` of every frame
+fn parse_frames(mut bytes: &[u8]) -> Vec<(u8, Vec<u8>)> {
+    let mut frames = Vec::new();
+    while !bytes.is_empty() {
+        let length = u32::from_be_bytes(bytes[1..5].try_into().unwrap()) as usize;
+        frames.push((bytes[0], bytes[5..5 + length].to_vec()));
+        bytes = &bytes[5 + length..];
+    }
+    frames
+}
+
+fn trailers(frames: &[(u8, Vec<u8>)]) -> HashMap<String, String> {
+    let (flag, payload) = frames.last().unwrap();
+    assert_eq!(*flag, 0x80);
+    String::from_utf8(payload.clone())
+        .unwrap()
+        .split("\r\n")
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn trailers_status(frames: &[(u8, Vec<u8>)]) -> String {
+    trailers(frames)["grpc-status"].clone()
+}
+
+/// Responses in text mode are base64 chunks, each padded
+fn decode_text(text: &str) -> Vec<u8> {
+    let mut decoded = Vec::new();
+    let mut chunk = String::new();
+    for c in text.chars() {
+        chunk.push(c);
+        if chunk.len().is_multiple_of(4) && chunk.ends_with('=') {
+            decoded.extend(
+                base64::engine::general_purpose::STANDARD
+                    .decode(&chunk)
+                    .unwrap(),
+            );
+            chunk.clear();
+        }
+    }
+    decoded.extend(
+        base64::engine::general_purpose::STANDARD
+            .decode(&chunk)
+            .unwrap(),
+    );
+    decoded
+}
+
+async fn call(
+    client: &TestClient,
+    path: &str,
+    content_type: &str,
+    body: Vec<u8>,
+) -> reqwest::Response {
+    client
+        .client()
+        .post(format!("{}{}", client.base_url(), path))
+        .header("content-type", content_type)
+        .header("x-grpc-web", "1")
+        .body(body)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_binary_unary_call() {
+    let client = TestClient::new(UltraApiApp::new().grpc(transcoder())).await;
+
+    let request = HelloRequest {
+        name: "Ada".to_string(),
+    };
+    let response = call(
+        &client,
+        "/helloworld.Greeter/SayHello",
+        "application/grpc-web+proto",
+        frame(0, &request.encode_to_vec()),
+    )
+    .await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/grpc-web+proto"
+    );
+    let frames = parse_frames(&response.bytes().await.unwrap());
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].0, 0);
+    let reply = HelloReply::decode(frames[0].1.as_slice()).unwrap();
+    assert_eq!(reply.message, "Hello, Ada");
+    assert_eq!(trailers(&frames)["grpc-status"], "0");
+
+    // The HTTP/JSON binding is unchanged
+    let response = client
+        .post("/helloworld/Greeter/SayHello", &json!({ "name": "Bob" }))
+        .await;
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body, json!({ "message": "Hello, Bob" }));
+}
+
+#[tokio::test]
+async fn test_text_framing_and_server_streaming() {
+    let client = TestClient::new(UltraApiApp::new().grpc(transcoder())).await;
+
+    let request = HelloRequest {
+        name: "Ada".to_string(),
+    };
+    let body = base64::engine::general_purpose::STANDARD
+        .encode(frame(0, &request.encode_to_vec()))
+        .into_bytes();
+    let response = call(
+        &client,
+        "/helloworld.Greeter/SayHellos",
+        "application/grpc-web-text",
+        body,
+    )
+    .await;
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/grpc-web-text+proto"
+    );
+    let frames = parse_frames(&decode_text(&response.text().await.unwrap()));
+    let messages: Vec<String> = frames[..frames.len() - 1]
+        .iter()
+        .map(|(_, payload)| HelloReply::decode(payload.as_slice()).unwrap().message)
+        .collect();
+    assert_eq!(messages, vec!["Ada #1", "Ada #2", "Ada #3"]);
+    assert_eq!(trailers(&frames)["grpc-status"], "0");
+}
+
+#[tokio::test]
+async fn test_errors_are_sent_as_trailers() {
+    let client = TestClient::new(UltraApiApp::new().grpc(transcoder())).await;
+
+    let response = call(
+        &client,
+        "/helloworld.Greeter/SayHello",
+        "application/grpc-web+proto",
+        frame(0, &HelloRequest::default().encode_to_vec()),
+    )
+    .await;
+    assert_eq!(response.status(), 200);
+    let frames = parse_frames(&response.bytes().await.unwrap());
+    assert_eq!(frames.len(), 1);
+    let trailers = trailers(&frames);
+    assert_eq!(trailers["grpc-status"], "3");
+    assert_eq!(trailers["grpc-message"], "name is required %E2%9C%97");
+    assert!(!trailers["grpc-status-details-bin"].is_empty());
+
+    // Malformed framing
+    let response = call(
+        &client,
+        "/helloworld.Greeter/SayHello",
+        "application/grpc-web+proto",
+        vec![0, 0, 0, 0, 9, 1],
+    )
+    .await;
+    let frames = parse_frames(&response.bytes().await.unwrap());
+    assert_eq!(trailers_status(&frames), "3");
+
+    // Bodies past GrpcService::max_body_size are refused before decoding
+    let app = UltraApiApp::new()
+        .grpc(GrpcTranscoder::new().register_service(greeter().max_body_size(64)));
+    let client = TestClient::new(app).await;
+    let request = HelloRequest {
+        name: "x".repeat(100),
+    };
+    let response = call(
+        &client,
+        "/helloworld.Greeter/SayHello",
+        "application/grpc-web+proto",
+        frame(0, &request.encode_to_vec()),
+    )
+    .await;
+    let frames = parse_frames(&response.bytes().await.unwrap());
+    assert_eq!(trailers_status(&frames), "8");
+}
+
+#[tokio::test]
+async fn test_json_messages_without_descriptor() {
+    let client = TestClient::new(UltraApiApp::new().grpc(transcoder())).await;
+
+    let response = call(
+        &client,
+        "/Plain/Echo",
+        "application/grpc-web+json",
+        frame(0, br#"{"text":"hi"}"#),
+    )
+    .await;
+    let frames = parse_frames(&response.bytes().await.unwrap());
+    let message: Value = serde_json::from_slice(&frames[0].1).unwrap();
+    assert_eq!(message, json!({ "echo": "hi" }));
+    assert_eq!(trailers_status(&frames), "0");
+
+    // Protobuf needs a descriptor
+    let response = call(
+        &client,
+        "/Plain/Echo",
+        "application/grpc-web",
+        frame(0, b""),
+    )
+    .await;
+    let frames = parse_frames(&response.bytes().await.unwrap());
+    assert_eq!(trailers_status(&frames), "12");
+
+    // The same path still serves HTTP/JSON
+    let response = client.post("/Plain/Echo", &json!({ "text": "yo" })).await;
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body, json!({ "echo": "yo" }));
+}
+
+#[tokio::test]
+async fn test_cors_preflight_without_app_cors() {
+    let client = TestClient::new(UltraApiApp::new().grpc(transcoder())).await;
+
+    let response = client
+        .client()
+        .request(
+            reqwest::Method::OPTIONS,
+            format!("{}/helloworld.Greeter/SayHello", client.base_url()),
+        )
+        .header("origin", "https://app.example")
+        .header("access-control-request-method", "POST")
+        .header("access-control-request-headers", "content-type,x-grpc-web")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+    let headers = response.headers();
+    assert_eq!(
+        headers["access-control-allow-origin"],
+        "https://app.example"
+    );
+    assert_eq!(
+        headers["access-control-allow-headers"],
+        "content-type,x-grpc-web"
+    );
+    assert!(headers["access-control-allow-methods"]
+        .to_str()
+        .unwrap()
+        .contains("POST"));
+
+    let response = client
+        .client()
+        .post(format!("{}/Plain/Echo", client.base_url()))
+        .header("origin", "https://app.example")
+        .header("content-type", "application/grpc-web+json")
+        .body(frame(0, b"{}"))
+        .send()
+        .await
+        .unwrap();
+    let headers = response.headers();
+    assert_eq!(
+        headers["access-control-allow-origin"],
+        "https://app.example"
+    );
+    assert!(headers["access-control-expose-headers"]
+        .to_str()
+        .unwrap()
+        .contains("grpc-status"));
+}
+
+#[tokio::test]
+async fn test_app_cors_policy_applies() {
+    let app = UltraApiApp::new().grpc(transcoder()).middleware(|builder| {
+        builder.cors(
+            CorsConfig::new()
+                .allow_origins(vec!["https://app.example".into()])
+                .allow_credentials(false),
+        )
+    });
+    let client = TestClient::new(app).await;
+
+    let response = client
+        .client()
+        .post(format!("{}/Plain/Echo", client.base_url()))
+        .header("origin", "https://evil.example")
+        .header("content-type", "application/grpc-web+json")
+        .body(frame(0, b"{}"))
+        .send()
+        .await
+        .unwrap();
+    assert!(response
+        .headers()
+        .get("access-control-allow-origin")
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_auth_errors_are_trailers_only() {
+    let client = TestClient::new(UltraApiApp::new().grpc(transcoder()).bearer_auth()).await;
+
+    let response = call(
+        &client,
+        "/Vault/Open",
+        "application/grpc-web+json",
+        frame(0, b"{}"),
+    )
+    .await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/grpc-web+json"
+    );
+    assert_eq!(response.headers()["grpc-status"], "16");
+    assert!(response.bytes().await.unwrap().is_empty());
+}
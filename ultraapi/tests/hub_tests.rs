@@ -0,0 +1,298 @@
+// Tests for the Hub connection registry (rooms, broadcast, presence, slow consumers)
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use ultraapi::axum::response::sse::Event;
+use ultraapi::prelude::*;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[api_model]
+#[derive(Debug, Clone)]
+#[serde(tag = "type")]
+enum ClientMsg {
+    Join { room: String },
+    Say { text: String },
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+#[serde(tag = "type")]
+enum ServerMsg {
+    Joined { room: String },
+    Said { text: String },
+}
+
+#[ws("/hub/chat")]
+async fn hub_chat(mut socket: TypedSocket<ClientMsg, ServerMsg>, hub: Dep<Hub>) {
+    let mut connection = hub.connect(None);
+    let mut room = String::new();
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => match incoming {
+                Some(ClientMsg::Join { room: joined }) => {
+                    connection.leave(&room);
+                    connection.join(&joined);
+                    room = joined;
+                    let _ = socket.send(&ServerMsg::Joined { room: room.clone() }).await;
+                }
+                Some(ClientMsg::Say { text }) => {
+                    hub.broadcast(&room, &ServerMsg::Said { text });
+                }
+                None => break,
+            },
+            outgoing = connection.recv() => match outgoing {
+                Some(message) => {
+                    if socket.send_raw(message.into()).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+}
+
+#[sse("/hub/events")]
+async fn hub_events(
+    hub: Dep<Hub>,
+) -> impl ultraapi::tokio_stream::Stream<Item = Result<Event, Infallible>> {
+    let connection = hub.connect(Some("watcher"));
+    connection.join("news");
+    connection
+        .into_stream()
+        .map(|message| Ok(Event::from(message)))
+}
+
+fn app(hub: Hub) -> UltraApiApp {
+    UltraApiApp::new().dep(hub).include(
+        UltraApiRouter::new("")
+            .route(__ULTRAAPI_WS_HUB_CHAT)
+            .route(__ULTRAAPI_SSE_HUB_EVENTS),
+    )
+}
+
+async fn connect(client: &TestClient, path: &str) -> Socket {
+    let url = format!("{}{}", client.base_url().replace("http://", "ws://"), path);
+    let (socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    socket
+}
+
+async fn recv_json(socket: &mut Socket) -> Value {
+    let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+        .await
+        .expect("timed out")
+        .expect("socket closed")
+        .unwrap();
+    match message {
+        Message::Text(text) => serde_json::from_str(&text).unwrap(),
+        other => panic!("unexpected message: {:?}", other),
+    }
+}
+
+async fn send_json(socket: &mut Socket, value: Value) {
+    socket.send(Message::text(value.to_string())).await.unwrap();
+}
+
+async fn wait_until(mut condition: impl FnMut() -> bool) {
+    for _ in 0..100 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("condition not reached");
+}
+
+#[tokio::test]
+async fn test_rooms_and_presence() {
+    let hub = Hub::new();
+    let alice = hub.connect(Some("alice"));
+    let alice_phone = hub.connect(Some("alice"));
+    let bob = hub.connect(Some("bob"));
+
+    assert!(alice.join("rust"));
+    assert!(alice_phone.join("rust"));
+    assert!(bob.join("rust"));
+    assert!(bob.join("go"));
+
+    assert_eq!(hub.rooms(), vec!["go", "rust"]);
+    assert_eq!(hub.users("rust"), vec!["alice", "bob"]);
+    assert_eq!(hub.members("rust").len(), 3);
+    assert_eq!(hub.members("go")[0].connection_id, bob.id());
+    assert_eq!(hub.members("go")[0].user_id.as_deref(), Some("bob"));
+    assert_eq!(hub.rooms_of(bob.id()), vec!["go", "rust"]);
+
+    assert!(bob.leave("go"));
+    assert!(!bob.leave("go"));
+    assert_eq!(hub.rooms(), vec!["rust"]);
+
+    // Dropping a connection removes it from every room
+    drop(alice_phone);
+    assert_eq!(hub.members("rust").len(), 2);
+    assert_eq!(hub.connection_count(), 2);
+    drop(alice);
+    drop(bob);
+    assert!(hub.rooms().is_empty());
+    assert_eq!(hub.connection_count(), 0);
+}
+
+#[tokio::test]
+async fn test_broadcast_and_targeted_sends() {
+    let hub = Hub::new();
+    let mut alice = hub.connect(Some("alice"));
+    let mut bob = hub.connect(Some("bob"));
+    let mut bob_phone = hub.connect(Some("bob"));
+    alice.join("rust");
+    bob.join("rust");
+
+    assert_eq!(hub.broadcast("rust", &json!({"n": 1})), 2);
+    assert_eq!(alice.recv().await.unwrap().as_str(), r#"{"n":1}"#);
+    assert_eq!(
+        bob.recv().await.unwrap().decode::<Value>().unwrap(),
+        json!({"n": 1})
+    );
+
+    assert_eq!(
+        hub.broadcast_except("rust", alice.id(), &json!({"n": 2})),
+        1
+    );
+    assert_eq!(bob.recv().await.unwrap().as_str(), r#"{"n":2}"#);
+
+    assert!(hub.send_to(alice.id(), &json!({"n": 3})));
+    assert_eq!(alice.recv().await.unwrap().as_str(), r#"{"n":3}"#);
+
+    assert_eq!(hub.send_to_user("bob", &json!({"n": 4})), 2);
+    assert_eq!(bob.recv().await.unwrap().as_str(), r#"{"n":4}"#);
+    assert_eq!(bob_phone.recv().await.unwrap().as_str(), r#"{"n":4}"#);
+
+    assert_eq!(hub.broadcast_all(&json!({"n": 5})), 3);
+    assert_eq!(hub.broadcast("nobody", &json!({"n": 6})), 0);
+    assert_eq!(hub.send_to_user("carol", &json!({"n": 7})), 0);
+}
+
+#[tokio::test]
+async fn test_slow_consumer_policies() {
+    let hub = Hub::new().queue_capacity(2);
+    let mut oldest = hub.connect(None);
+    for n in 1..=3 {
+        hub.send_to(oldest.id(), &n);
+    }
+    assert_eq!(oldest.recv().await.unwrap().as_str(), "2");
+    assert_eq!(oldest.recv().await.unwrap().as_str(), "3");
+
+    let hub = hub.slow_consumer(SlowConsumer::DropNewest);
+    let mut newest = hub.connect(None);
+    assert!(hub.send_to(newest.id(), &1));
+    assert!(hub.send_to(newest.id(), &2));
+    assert!(!hub.send_to(newest.id(), &3));
+    assert_eq!(newest.recv().await.unwrap().as_str(), "1");
+    assert_eq!(newest.recv().await.unwrap().as_str(), "2");
+
+    let hub = hub.slow_consumer(SlowConsumer::Disconnect);
+    let mut slow = hub.connect(None);
+    slow.join("room");
+    hub.broadcast("room", &1);
+    hub.broadcast("room", &2);
+    assert_eq!(hub.broadcast("room", &3), 0);
+    assert!(hub.members("room").is_empty());
+    // Queued messages are still drained before the connection ends
+    assert_eq!(slow.recv().await.unwrap().as_str(), "1");
+    assert_eq!(slow.recv().await.unwrap().as_str(), "2");
+    assert!(slow.recv().await.is_none());
+}
+
+#[tokio::test]
+async fn test_close_ends_connections_and_refuses_new_ones() {
+    let hub = Hub::new();
+    let mut connection = hub.connect(None);
+    connection.join("room");
+
+    let waiting = tokio::spawn(async move { connection.recv().await });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    hub.close();
+    assert!(waiting.await.unwrap().is_none());
+
+    assert!(hub.is_closed());
+    assert!(hub.rooms().is_empty());
+    let mut late = hub.connect(None);
+    assert!(!late.join("room"));
+    assert!(late.recv().await.is_none());
+}
+
+#[tokio::test]
+async fn test_websocket_handlers_share_the_hub() {
+    let hub = Hub::new();
+    let client = TestClient::new(app(hub.clone())).await;
+    let mut alice = connect(&client, "/hub/chat").await;
+    let mut bob = connect(&client, "/hub/chat").await;
+
+    send_json(&mut alice, json!({"type": "Join", "room": "rust"})).await;
+    assert_eq!(recv_json(&mut alice).await["type"], "Joined");
+    send_json(&mut bob, json!({"type": "Join", "room": "rust"})).await;
+    assert_eq!(recv_json(&mut bob).await["type"], "Joined");
+    assert_eq!(hub.members("rust").len(), 2);
+
+    send_json(&mut alice, json!({"type": "Say", "text": "hello"})).await;
+    let expected = json!({"type": "Said", "text": "hello"});
+    assert_eq!(recv_json(&mut alice).await, expected);
+    assert_eq!(recv_json(&mut bob).await, expected);
+
+    // Messages sent from outside a handler reach the sockets too
+    hub.broadcast(
+        "rust",
+        &ServerMsg::Said {
+            text: "server".into(),
+        },
+    );
+    assert_eq!(recv_json(&mut bob).await["text"], "server");
+
+    // Disconnecting removes the connection from its rooms
+    alice.close(None).await.unwrap();
+    wait_until(|| hub.members("rust").len() == 1).await;
+}
+
+#[tokio::test]
+async fn test_sse_handlers_stream_hub_messages() {
+    let hub = Hub::new();
+    let client = TestClient::new(app(hub.clone())).await;
+    let mut resp = client.get("/hub/events").await;
+    assert_eq!(resp.status(), 200);
+    wait_until(|| hub.users("news") == vec!["watcher"]).await;
+
+    hub.broadcast("news", &json!({"headline": "hub"}));
+    let chunk = tokio::time::timeout(Duration::from_secs(5), resp.chunk())
+        .await
+        .expect("timed out")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&chunk),
+        "data: {\"headline\":\"hub\"}\n\n"
+    );
+}
+
+#[tokio::test]
+async fn test_lifespan_shutdown_closes_the_hub() {
+    let hub = Hub::new();
+    let client = TestClient::new(app(hub.clone())).await;
+    let mut socket = connect(&client, "/hub/chat").await;
+    send_json(&mut socket, json!({"type": "Join", "room": "rust"})).await;
+    recv_json(&mut socket).await;
+
+    client.shutdown().await;
+    assert!(hub.is_closed());
+    assert_eq!(hub.connection_count(), 0);
+
+    // The handler's connection ended, so the socket is closed
+    let next = tokio::time::timeout(Duration::from_secs(5), socket.next())
+        .await
+        .expect("timed out");
+    assert!(!matches!(next, Some(Ok(Message::Text(_)))));
+}
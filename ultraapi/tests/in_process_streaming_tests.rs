@@ -0,0 +1,272 @@
+// Tests for WebSocket and SSE support in InProcessTestClient
+
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use ultraapi::axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use ultraapi::axum::response::sse::Event;
+use ultraapi::prelude::*;
+use ultraapi::tokio_stream::{self, Stream, StreamExt};
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct Echo {
+    text: String,
+}
+
+#[ws("/ip/echo")]
+async fn raw_echo(ws: WebSocketUpgrade) -> ultraapi::axum::response::Response {
+    ws.on_upgrade(|mut socket: WebSocket| async move {
+        while let Some(Ok(message)) = socket.recv().await {
+            if matches!(message, Message::Close(_)) || socket.send(message).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[ws("/ip/typed")]
+async fn typed_echo(mut socket: TypedSocket<Echo, Echo>) {
+    while let Some(message) = socket.recv().await {
+        let reply = Echo {
+            text: message.text.to_uppercase(),
+        };
+        if socket.send(&reply).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[ws("/ip/secure")]
+#[security("bearer")]
+async fn secure_echo(mut socket: TypedSocket<Echo, Echo>) {
+    while let Some(message) = socket.recv().await {
+        let _ = socket.send(&message).await;
+    }
+}
+
+#[ws("/ip/idle", idle_timeout_secs = 1)]
+async fn idle(mut socket: TypedSocket<Echo, Echo>) {
+    while socket.recv().await.is_some() {}
+}
+
+#[ws("/ip/hub")]
+async fn hub_socket(mut socket: TypedSocket<Echo, Echo>, hub: Dep<Hub>) {
+    let mut connection = hub.connect(None);
+    connection.join("lobby");
+    while let Some(message) = connection.recv().await {
+        if socket.send_raw(message.into()).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[sse("/ip/events")]
+async fn events() -> impl Stream<Item = Result<Event, Infallible>> {
+    tokio_stream::iter(vec![
+        Ok(Event::default().retry(Duration::from_secs(2))),
+        Ok(Event::default().comment("keep-alive")),
+        Ok(Event::default().event("greeting").data("hello").id("1")),
+        Ok(Event::default().data("line one\nline two")),
+    ])
+}
+
+#[sse("/ip/typed-events", events = ["echo"])]
+async fn typed_events() -> impl Stream<Item = SseEvent<Echo>> {
+    tokio_stream::iter(["a", "b"]).map(|text| {
+        SseEvent::new(Echo {
+            text: text.to_string(),
+        })
+        .event("echo")
+    })
+}
+
+#[sse("/ip/resume")]
+async fn resume(
+    log: Dep<EventLog>,
+    last_event_id: LastEventId,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    log.subscribe("resume", &last_event_id)
+}
+
+fn app() -> UltraApiApp {
+    UltraApiApp::new().include(
+        UltraApiRouter::new("")
+            .route(__ULTRAAPI_WS_RAW_ECHO)
+            .route(__ULTRAAPI_WS_TYPED_ECHO)
+            .route(__ULTRAAPI_WS_SECURE_ECHO)
+            .route(__ULTRAAPI_WS_IDLE)
+            .route(__ULTRAAPI_WS_HUB_SOCKET)
+            .route(__ULTRAAPI_SSE_EVENTS)
+            .route(__ULTRAAPI_SSE_TYPED_EVENTS)
+            .route(__ULTRAAPI_SSE_RESUME),
+    )
+}
+
+#[tokio::test]
+async fn test_websocket_text_binary_and_json() {
+    let client = TestClient::new_in_process(app()).await;
+    let mut socket = client.websocket("/ip/echo").await.unwrap();
+
+    socket.send_text("hello").await;
+    assert_eq!(socket.receive_text().await, "hello");
+
+    socket.send_binary(vec![1, 2, 3]).await;
+    assert_eq!(socket.receive_binary().await, vec![1, 2, 3]);
+
+    socket.send_json(&json!({"n": 1})).await;
+    assert_eq!(socket.receive_json::<Value>().await, json!({"n": 1}));
+
+    socket.close().await;
+}
+
+#[tokio::test]
+async fn test_typed_websocket_route() {
+    let client = TestClient::new_in_process(app()).await;
+    let mut socket = client.websocket("/ip/typed").await.unwrap();
+
+    socket.send_json(&json!({"text": "shout"})).await;
+    let reply: Value = socket.receive_json().await;
+    assert_eq!(reply["text"], "SHOUT");
+}
+
+#[tokio::test]
+async fn test_rejected_handshake_returns_the_response() {
+    let app = app()
+        .bearer_auth()
+        .middleware(|builder| builder.enable_auth());
+    let client = TestClient::new_in_process(app).await;
+
+    let rejected = client.websocket("/ip/secure").await.unwrap_err();
+    assert_eq!(rejected.status(), 401);
+
+    let mut socket = client
+        .with_header("authorization", "Bearer valid-token")
+        .websocket("/ip/secure")
+        .await
+        .unwrap();
+    socket.send_json(&json!({"text": "ok"})).await;
+    assert_eq!(socket.receive_json::<Value>().await["text"], "ok");
+}
+
+#[tokio::test]
+async fn test_close_frames_are_reported() {
+    let client = TestClient::new_in_process(app()).await;
+    let mut socket = client.websocket("/ip/idle").await.unwrap();
+
+    assert_eq!(
+        socket.receive().await,
+        Some(TestWsMessage::Close(Some((
+            1001,
+            "idle timeout".to_string()
+        ))))
+    );
+}
+
+#[tokio::test]
+async fn test_websocket_sees_dependencies_and_lifespan_shutdown() {
+    let hub = Hub::new();
+    let client = TestClient::new_in_process(app().dep(hub.clone())).await;
+    let mut socket = client.websocket("/ip/hub").await.unwrap();
+
+    for _ in 0..100 {
+        if hub.members("lobby").len() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    hub.broadcast("lobby", &json!({"text": "from hub"}));
+    assert_eq!(socket.receive_json::<Value>().await["text"], "from hub");
+
+    client.shutdown().await;
+    assert!(hub.is_closed());
+    assert!(!matches!(
+        socket.receive().await,
+        Some(TestWsMessage::Text(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_sse_events_are_parsed() {
+    let client = TestClient::new_in_process(app()).await;
+    let mut stream = client.sse("/ip/events").await;
+    assert_eq!(stream.status(), 200);
+    assert_eq!(stream.header("content-type"), Some("text/event-stream"));
+
+    // retry-only frames are reported, comment frames skipped
+    let retry = stream.next_event().await.unwrap();
+    assert_eq!(retry.retry, Some(2000));
+    assert_eq!(retry.data, "");
+
+    let greeting = stream.next_event().await.unwrap();
+    assert_eq!(greeting.event.as_deref(), Some("greeting"));
+    assert_eq!(greeting.data, "hello");
+    assert_eq!(greeting.id.as_deref(), Some("1"));
+
+    let multiline = stream.next_event().await.unwrap();
+    assert_eq!(multiline.data, "line one\nline two");
+
+    assert!(stream.next_event().await.is_none());
+}
+
+#[tokio::test]
+async fn test_sse_stream_can_be_collected() {
+    let client = TestClient::new_in_process(app()).await;
+    let events: Vec<TestSseEvent> = client.sse("/ip/typed-events").await.collect().await;
+
+    let texts: Vec<String> = events
+        .iter()
+        .map(|event| {
+            event.json::<Value>().unwrap()["text"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(texts, vec!["a", "b"]);
+    assert!(events
+        .iter()
+        .all(|event| event.event.as_deref() == Some("echo")));
+}
+
+#[tokio::test]
+async fn test_sse_with_headers_resumes_from_last_event_id() {
+    let log = EventLog::new();
+    let first = log.publish("resume", None, "one");
+    let second = log.publish("resume", None, "two");
+    let client = TestClient::new_in_process(app().dep(log.clone())).await;
+
+    let mut stream = client
+        .with_header("last-event-id", &first.to_string())
+        .sse("/ip/resume")
+        .await;
+    let missed = stream.next_event().await.unwrap();
+    assert_eq!(missed.data, "two");
+    assert_eq!(missed.id, Some(second.to_string()));
+
+    log.publish("resume", None, "three");
+    assert_eq!(stream.next_event().await.unwrap().data, "three");
+}
+
+#[tokio::test]
+async fn test_startup_hooks_run_before_streaming() {
+    let started = Arc::new(AtomicBool::new(false));
+    let flag = started.clone();
+    let app = app().lifecycle(|lifecycle| {
+        lifecycle.on_startup(move |_state| {
+            let flag = flag.clone();
+            Box::pin(async move {
+                flag.store(true, Ordering::SeqCst);
+            })
+        })
+    });
+    let client = TestClient::new_in_process(app).await;
+    assert!(started.load(Ordering::SeqCst));
+
+    let mut socket = client.websocket("/ip/echo").await.unwrap();
+    socket.send_text("after startup").await;
+    assert_eq!(socket.receive_text().await, "after startup");
+}
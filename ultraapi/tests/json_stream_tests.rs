@@ -0,0 +1,217 @@
+// Tests for JsonLines / JsonArrayStream typed streaming responses
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use axum::response::IntoResponse;
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use serde_json::Value;
+use tower::ServiceExt;
+use ultraapi::axum;
+use ultraapi::prelude::*;
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct Reading {
+    sensor: String,
+    value: i64,
+    secret: Option<String>,
+}
+
+fn readings(count: i64) -> Vec<Reading> {
+    (0..count)
+        .map(|value| Reading {
+            sensor: format!("s{}", value),
+            value,
+            secret: Some("hidden".to_string()),
+        })
+        .collect()
+}
+
+#[get("/readings/lines")]
+async fn reading_lines() -> JsonLines<impl Stream<Item = Reading> + Send> {
+    JsonLines::new(stream::iter(readings(3)))
+}
+
+#[get("/readings/array")]
+#[response_model(exclude = {"secret"})]
+async fn reading_array() -> JsonArrayStream<BoxStream<'static, Reading>> {
+    JsonArrayStream::new(stream::iter(readings(2)).boxed())
+}
+
+#[get("/readings/empty")]
+async fn reading_empty() -> JsonArrayStream<BoxStream<'static, Reading>> {
+    JsonArrayStream::new(stream::empty().boxed())
+}
+
+#[get("/readings/shaped")]
+#[response_model(exclude = {"secret"})]
+async fn reading_shaped() -> JsonLines<impl Stream<Item = Reading> + Send> {
+    JsonLines::new(stream::iter(readings(2)))
+}
+
+#[get("/readings/{id}")]
+async fn reading_by_id(
+    id: i64,
+) -> Result<JsonLines<std::pin::Pin<Box<dyn Stream<Item = Reading> + Send>>>, ApiError> {
+    if id > 0 {
+        return Err(ApiError::not_found(format!("Sensor {} not found", id)));
+    }
+    Ok(JsonLines::new(Box::pin(stream::iter(readings(1)))))
+}
+
+async fn get(path: &str) -> axum::response::Response {
+    UltraApiApp::new()
+        .into_router()
+        .oneshot(Request::builder().uri(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn text(response: axum::response::Response) -> String {
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_json_lines_response() {
+    let response = get("/readings/lines").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+
+    let body = text(response).await;
+    let lines: Vec<Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(body.ends_with('\n'));
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[2]["sensor"], "s2");
+    assert_eq!(lines[2]["secret"], "hidden");
+}
+
+#[tokio::test]
+async fn test_json_array_stream_response() {
+    let response = get("/readings/array").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/json");
+
+    let body: Value = serde_json::from_str(&text(response).await).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!([
+            { "sensor": "s0", "value": 0 },
+            { "sensor": "s1", "value": 1 }
+        ])
+    );
+
+    assert_eq!(text(get("/readings/empty").await).await, "[]");
+}
+
+#[tokio::test]
+async fn test_response_model_shapes_each_item() {
+    let body = text(get("/readings/shaped").await).await;
+    assert_eq!(
+        body,
+        "{\"sensor\":\"s0\",\"value\":0}\n{\"sensor\":\"s1\",\"value\":1}\n"
+    );
+}
+
+#[tokio::test]
+async fn test_result_return() {
+    let response = get("/readings/0").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(text(response).await.lines().count(), 1);
+
+    let response = get("/readings/7").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body: Value = serde_json::from_str(&text(response).await).unwrap();
+    assert_eq!(body["error"], "Sensor 7 not found");
+}
+
+#[tokio::test]
+async fn test_items_are_flushed_when_the_stream_waits() {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<i64>();
+    let items = stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|v| (v, rx)) });
+    let mut body = JsonArrayStream::new(items)
+        .into_response()
+        .into_body()
+        .into_data_stream();
+
+    // Each item is sent as soon as the producer has nothing more ready
+    tx.send(1).unwrap();
+    assert_eq!(body.next().await.unwrap().unwrap(), "[1");
+    tx.send(2).unwrap();
+    tx.send(3).unwrap();
+    assert_eq!(body.next().await.unwrap().unwrap(), ",2,3");
+    drop(tx);
+    assert_eq!(body.next().await.unwrap().unwrap(), "]");
+    assert!(body.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_flush_threshold() {
+    let chunks = |lines: JsonLines<stream::Iter<std::ops::Range<i64>>>| async move {
+        lines
+            .into_response()
+            .into_body()
+            .into_data_stream()
+            .map(|chunk| chunk.unwrap())
+            .collect::<Vec<_>>()
+            .await
+    };
+
+    // Items that are ready are batched up to the threshold
+    let batched = chunks(JsonLines::new(stream::iter(0..100))).await;
+    assert_eq!(batched.len(), 1);
+    assert_eq!(batched[0].iter().filter(|b| **b == b'\n').count(), 100);
+
+    let per_item = chunks(JsonLines::new(stream::iter(0..100)).flush_threshold(0)).await;
+    assert_eq!(per_item.len(), 100);
+    assert_eq!(per_item[42], "42\n");
+
+    let limited = chunks(JsonLines::new(stream::iter(0..100)).flush_threshold(40)).await;
+    assert!(limited.len() > 1);
+    assert!(limited.iter().all(|chunk| chunk.ends_with(b"\n")));
+}
+
+struct Unserializable;
+
+impl serde::Serialize for Unserializable {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("cannot serialize"))
+    }
+}
+
+#[tokio::test]
+async fn test_serialization_error_aborts_the_body() {
+    let response = JsonArrayStream::new(stream::iter([Unserializable])).into_response();
+    assert!(to_bytes(response.into_body(), usize::MAX).await.is_err());
+}
+
+#[tokio::test]
+async fn test_openapi_item_schemas() {
+    let spec: Value = serde_json::from_str(&text(get("/openapi.json").await).await).unwrap();
+    let paths = &spec["paths"];
+
+    let lines = &paths["/readings/lines"]["get"]["responses"]["200"]["content"];
+    assert_eq!(
+        lines["application/x-ndjson"]["schema"]["$ref"],
+        "#/components/schemas/Reading"
+    );
+    assert!(lines.get("application/json").is_none());
+
+    let array = &paths["/readings/array"]["get"]["responses"]["200"]["content"];
+    assert_eq!(array["application/json"]["schema"]["type"], "array");
+    assert_eq!(
+        array["application/json"]["schema"]["items"]["$ref"],
+        "#/components/schemas/Reading"
+    );
+
+    let by_id = &paths["/readings/{id}"]["get"]["responses"];
+    assert_eq!(
+        by_id["200"]["content"]["application/x-ndjson"]["schema"]["$ref"],
+        "#/components/schemas/Reading"
+    );
+    assert!(by_id.get("404").is_some());
+    assert!(spec["components"]["schemas"]["Reading"].is_object());
+}
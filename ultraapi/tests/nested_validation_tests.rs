@@ -0,0 +1,154 @@
+//! Nested Validation Tests
+//!
+//! Validation of an `#[api_model]` descends into nested models, including
+//! models held in `Option`, `Vec`, `Box` and maps, and reports errors with
+//! their full location (`body.items[1].address.zip`).
+
+use serde_json::json;
+use std::collections::HashMap;
+use ultraapi::prelude::*;
+
+// --- Test Models ---
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct NestedAddress {
+    #[validate(min_length = 5)]
+    zip: String,
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct NestedLineItem {
+    #[validate(minimum = 1)]
+    quantity: i64,
+    address: NestedAddress,
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct NestedOrder {
+    #[validate(min_length = 1)]
+    reference: String,
+    items: Vec<NestedLineItem>,
+    billing: Option<NestedAddress>,
+    fallback: Option<Box<NestedAddress>>,
+    warehouses: HashMap<String, NestedAddress>,
+}
+
+// --- Test Routes ---
+
+#[post("/__test_nested_validation")]
+async fn create_nested_order(body: NestedOrder) -> NestedOrder {
+    body
+}
+
+// --- Helpers ---
+
+fn valid_order() -> serde_json::Value {
+    json!({
+        "reference": "A-1",
+        "items": [
+            { "quantity": 1, "address": { "zip": "10001" } },
+            { "quantity": 2, "address": { "zip": "10002" } }
+        ],
+        "billing": null,
+        "fallback": null,
+        "warehouses": {}
+    })
+}
+
+fn create_app() -> UltraApiApp {
+    UltraApiApp::new()
+        .title("Nested Validation Test API")
+        .version("0.1.0")
+}
+
+async fn post_invalid(client: &TestClient, body: serde_json::Value) -> serde_json::Value {
+    let response = client.post("/__test_nested_validation", &body).await;
+    assert_eq!(response.status(), 422, "expected 422 for {}", body);
+    response.json().await.unwrap()
+}
+
+// --- Tests ---
+
+#[tokio::test]
+async fn test_valid_nested_payload_passes() {
+    let client = TestClient::new(create_app()).await;
+    let response = client
+        .post("/__test_nested_validation", &valid_order())
+        .await;
+    assert_eq!(response.status(), 201);
+}
+
+#[tokio::test]
+async fn test_error_inside_vec_item_reports_full_loc() {
+    let client = TestClient::new(create_app()).await;
+    let mut order = valid_order();
+    order["items"][1]["address"]["zip"] = json!("1");
+
+    let body = post_invalid(&client, order).await;
+    assert_eq!(
+        body["details"],
+        json!(["items[1].address.zip: must be at least 5 characters"])
+    );
+    assert_eq!(
+        body["detail"][0]["loc"],
+        json!(["body", "items", 1, "address", "zip"])
+    );
+    assert_eq!(body["detail"][0]["msg"], "must be at least 5 characters");
+}
+
+#[tokio::test]
+async fn test_errors_from_several_levels_are_collected() {
+    let client = TestClient::new(create_app()).await;
+    let mut order = valid_order();
+    order["reference"] = json!("");
+    order["items"][0]["quantity"] = json!(0);
+    order["billing"] = json!({ "zip": "12" });
+
+    let body = post_invalid(&client, order).await;
+    let locs: Vec<serde_json::Value> = body["detail"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["loc"].clone())
+        .collect();
+    assert_eq!(
+        locs,
+        vec![
+            json!(["body", "reference"]),
+            json!(["body", "items", 0, "quantity"]),
+            json!(["body", "billing", "zip"]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_error_inside_boxed_option_and_map() {
+    let client = TestClient::new(create_app()).await;
+    let mut order = valid_order();
+    order["fallback"] = json!({ "zip": "1" });
+    order["warehouses"] = json!({ "tokyo": { "zip": "2" } });
+
+    let body = post_invalid(&client, order).await;
+    assert_eq!(
+        body["details"],
+        json!([
+            "fallback.zip: must be at least 5 characters",
+            "warehouses.tokyo.zip: must be at least 5 characters"
+        ])
+    );
+    assert_eq!(
+        body["detail"][1]["loc"],
+        json!(["body", "warehouses", "tokyo", "zip"])
+    );
+}
+
+#[test]
+fn test_nested_error_is_rerooted_under_parent_loc() {
+    let error = ValidationErrorDetail::new("string_too_short", "too short").at("address.zip");
+    let nested = error.nested_under(&["items".into(), 3usize.into()]);
+    assert_eq!(nested.field_path(), "items[3].address.zip");
+    assert_eq!(nested.to_message(), "items[3].address.zip: too short");
+}
@@ -30,6 +30,7 @@ fn test_extension_on_schema() {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         },
     );
 
@@ -645,6 +646,7 @@ fn test_min_items_constraint() {
             read_only: false,
             write_only: false,
             deprecated: false,
+            ..Default::default()
         })),
         nullable: false,
         example: None,
@@ -652,6 +654,7 @@ fn test_min_items_constraint() {
         read_only: false,
         write_only: false,
         deprecated: false,
+        ..Default::default()
     };
 
     let json = prop.to_json_value();
@@ -679,6 +682,7 @@ fn test_pattern_constraint() {
         read_only: false,
         write_only: false,
         deprecated: false,
+        ..Default::default()
     };
 
     let json = prop.to_json_value();
@@ -707,6 +711,7 @@ fn test_read_only_property() {
         read_only: true,
         write_only: false,
         deprecated: false,
+        ..Default::default()
     };
 
     let json = prop.to_json_value();
@@ -734,6 +739,7 @@ fn test_write_only_property() {
         read_only: false,
         write_only: true,
         deprecated: false,
+        ..Default::default()
     };
 
     let json = prop.to_json_value();
@@ -761,6 +767,7 @@ fn test_read_and_write_only_false_by_default() {
         read_only: false,
         write_only: false,
         deprecated: false,
+        ..Default::default()
     };
 
     let json = prop.to_json_value();
@@ -0,0 +1,237 @@
+//! Parameter Validation Tests
+//!
+//! Path, scalar query, header and cookie arguments accept `#[validate(...)]`
+//! constraints. Extraction failures and constraint violations are reported as
+//! 422 `HTTPValidationError` bodies rooted at the parameter's source, and the
+//! constraints appear in the OpenAPI `Parameter` schema.
+
+use serde_json::json;
+use ultraapi::axum_extra::headers::UserAgent;
+use ultraapi::prelude::*;
+
+// --- Test Models ---
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct ParamEcho {
+    value: String,
+}
+
+// --- Test Routes ---
+
+#[get("/__test_param_items/{item_id}")]
+async fn get_param_item(
+    #[validate(minimum = 1, maximum = 1000)] item_id: i64,
+    #[validate(pattern = "^[a-z]+$")] q: Option<String>,
+) -> ParamEcho {
+    ParamEcho {
+        value: format!("{}:{}", item_id, q.unwrap_or_default()),
+    }
+}
+
+#[get("/__test_param_orders/{region}/{order_id}")]
+async fn get_param_order(
+    #[validate(one_of = ["eu", "us"])] region: String,
+    order_id: u32,
+) -> ParamEcho {
+    ParamEcho {
+        value: format!("{}/{}", region, order_id),
+    }
+}
+
+#[get("/__test_param_headers")]
+async fn get_param_headers(#[validate(min_length = 5)] agent: TypedHeader<UserAgent>) -> ParamEcho {
+    ParamEcho {
+        value: agent.as_str().to_string(),
+    }
+}
+
+#[get("/__test_param_cookies")]
+async fn get_param_cookies(
+    #[validate(required, min_length = 8)] session_id: CookieJar,
+) -> ParamEcho {
+    ParamEcho {
+        value: session_id
+            .get("session_id")
+            .map(|c| c.value().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+// --- Helpers ---
+
+fn create_app() -> UltraApiApp {
+    UltraApiApp::new()
+        .title("Parameter Validation Test API")
+        .version("0.1.0")
+}
+
+async fn get_with_header(
+    client: &TestClient,
+    path: &str,
+    name: &str,
+    value: &str,
+) -> reqwest::Response {
+    client
+        .client()
+        .get(format!("{}{}", client.base_url(), path))
+        .header(name, value)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn first_error(response: reqwest::Response) -> serde_json::Value {
+    assert_eq!(response.status(), 422);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["detail"][0].clone()
+}
+
+// --- Path Tests ---
+
+#[tokio::test]
+async fn test_valid_path_and_query_params_pass() {
+    let client = TestClient::new(create_app()).await;
+    let response = client.get("/__test_param_items/5?q=abc").await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["value"], "5:abc");
+}
+
+#[tokio::test]
+async fn test_unparsable_path_param_is_422() {
+    let client = TestClient::new(create_app()).await;
+
+    let error = first_error(client.get("/__test_param_items/abc").await).await;
+    assert_eq!(error["loc"], json!(["path", "item_id"]));
+    assert_eq!(error["type"], "int_parsing");
+    assert_eq!(error["input"], "abc");
+
+    // Tuple extraction reports the failing segment by name
+    let error = first_error(client.get("/__test_param_orders/eu/first").await).await;
+    assert_eq!(error["loc"], json!(["path", "order_id"]));
+    assert_eq!(error["type"], "int_parsing");
+}
+
+#[tokio::test]
+async fn test_path_constraints_are_enforced() {
+    let client = TestClient::new(create_app()).await;
+
+    let error = first_error(client.get("/__test_param_items/0").await).await;
+    assert_eq!(
+        error,
+        json!({
+            "loc": ["path", "item_id"],
+            "msg": "must be at least 1",
+            "type": "greater_than_equal",
+            "input": 0,
+            "ctx": { "ge": 1 }
+        })
+    );
+
+    let error = first_error(client.get("/__test_param_orders/asia/1").await).await;
+    assert_eq!(error["loc"], json!(["path", "region"]));
+    assert_eq!(error["type"], "literal_error");
+    assert_eq!(error["msg"], "must be one of eu, us");
+}
+
+#[tokio::test]
+async fn test_scalar_query_constraints_are_enforced() {
+    let client = TestClient::new(create_app()).await;
+    let error = first_error(client.get("/__test_param_items/5?q=ABC").await).await;
+    assert_eq!(error["loc"], json!(["query", "q"]));
+    assert_eq!(error["type"], "string_pattern_mismatch");
+}
+
+// --- Header / Cookie Tests ---
+
+#[tokio::test]
+async fn test_header_missing_and_constraint_errors() {
+    let client = TestClient::new(create_app()).await;
+
+    let error = first_error(client.get("/__test_param_headers").await).await;
+    assert_eq!(
+        error,
+        json!({ "loc": ["header", "user-agent"], "msg": "Field required", "type": "missing" })
+    );
+
+    let response = get_with_header(&client, "/__test_param_headers", "user-agent", "cli").await;
+    let error = first_error(response).await;
+    assert_eq!(error["loc"], json!(["header", "user-agent"]));
+    assert_eq!(error["type"], "string_too_short");
+
+    let response =
+        get_with_header(&client, "/__test_param_headers", "user-agent", "curl/8.0").await;
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_cookie_missing_and_constraint_errors() {
+    let client = TestClient::new(create_app()).await;
+
+    let error = first_error(client.get("/__test_param_cookies").await).await;
+    assert_eq!(error["loc"], json!(["cookie", "session_id"]));
+    assert_eq!(error["type"], "missing");
+
+    let response = get_with_header(
+        &client,
+        "/__test_param_cookies",
+        "cookie",
+        "session_id=short",
+    )
+    .await;
+    let error = first_error(response).await;
+    assert_eq!(error["loc"], json!(["cookie", "session_id"]));
+    assert_eq!(error["type"], "string_too_short");
+
+    let response = get_with_header(
+        &client,
+        "/__test_param_cookies",
+        "cookie",
+        "session_id=abcdefgh12",
+    )
+    .await;
+    assert_eq!(response.status(), 200);
+}
+
+// --- OpenAPI Tests ---
+
+fn find_param<'a>(op: &'a serde_json::Value, name: &str) -> &'a serde_json::Value {
+    op["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == name)
+        .unwrap_or_else(|| panic!("parameter {} not documented", name))
+}
+
+#[tokio::test]
+async fn test_param_constraints_in_openapi() {
+    let client = TestClient::new(create_app()).await;
+    let spec: serde_json::Value = client.get("/openapi.json").await.json().await.unwrap();
+
+    let items = &spec["paths"]["/__test_param_items/{item_id}"]["get"];
+    let item_id = find_param(items, "item_id");
+    assert_eq!(item_id["in"], "path");
+    assert_eq!(item_id["required"], true);
+    assert_eq!(
+        item_id["schema"],
+        json!({ "type": "integer", "minimum": 1.0, "maximum": 1000.0 })
+    );
+    assert_eq!(find_param(items, "q")["schema"]["pattern"], "^[a-z]+$");
+
+    let orders = &spec["paths"]["/__test_param_orders/{region}/{order_id}"]["get"];
+    assert_eq!(
+        find_param(orders, "region")["schema"]["enum"],
+        json!(["eu", "us"])
+    );
+    assert_eq!(find_param(orders, "order_id")["schema"]["type"], "integer");
+
+    let headers = &spec["paths"]["/__test_param_headers"]["get"];
+    assert_eq!(find_param(headers, "user-agent")["schema"]["minLength"], 5);
+
+    let cookies = &spec["paths"]["/__test_param_cookies"]["get"];
+    let session = find_param(cookies, "session_id");
+    assert_eq!(session["required"], true);
+    assert_eq!(session["schema"]["minLength"], 8);
+}
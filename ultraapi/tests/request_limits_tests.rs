@@ -0,0 +1,308 @@
+// Tests for request body size limits and handler timeouts
+
+use std::time::Duration;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use serde_json::Value;
+use tower::ServiceExt;
+use ultraapi::axum;
+use ultraapi::prelude::*;
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct Note {
+    text: String,
+}
+
+#[post("/limits/small")]
+#[body_limit("1KB")]
+async fn small_note(note: Note) -> Note {
+    note
+}
+
+#[post("/limits/large")]
+#[body_limit("4MB")]
+async fn large_note(note: Note) -> Note {
+    note
+}
+
+#[post("/limits/default")]
+async fn default_note(note: Note) -> Note {
+    note
+}
+
+#[post("/limits/items/{id}")]
+#[body_limit("1KB")]
+async fn update_item(id: i64, note: Note) -> Note {
+    Note {
+        text: format!("{}:{}", id, note.text.len()),
+    }
+}
+
+#[post("/limits/items/bulk")]
+#[body_limit("4KB")]
+async fn bulk_items(note: Note) -> Note {
+    note
+}
+
+#[post("/limits/things/{id}")]
+#[body_limit("1KB")]
+async fn update_thing(id: i64, note: Note) -> Note {
+    Note {
+        text: format!("{}:{}", id, note.text.len()),
+    }
+}
+
+#[post("/limits/things/bulk")]
+async fn bulk_things(note: Note) -> Note {
+    note
+}
+
+#[get("/limits/slow")]
+#[timeout("50ms")]
+async fn slow() -> String {
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    "done".to_string()
+}
+
+#[get("/limits/fast")]
+#[timeout("2s")]
+async fn fast() -> String {
+    "done".to_string()
+}
+
+fn note_body(len: usize) -> String {
+    serde_json::json!({ "text": "x".repeat(len) }).to_string()
+}
+
+async fn post(app: UltraApiApp, path: &str, body: Body) -> (StatusCode, Value) {
+    let response = app
+        .into_router()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(path)
+                .header("content-type", "application/json")
+                .body(body)
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn get(app: UltraApiApp, path: &str) -> (StatusCode, Value) {
+    let response = app
+        .into_router()
+        .oneshot(Request::builder().uri(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// A body without Content-Length, sent in 256 byte chunks
+fn chunked(body: String) -> Body {
+    let chunks: Vec<Result<Vec<u8>, std::io::Error>> = body
+        .into_bytes()
+        .chunks(256)
+        .map(|chunk| Ok(chunk.to_vec()))
+        .collect();
+    Body::from_stream(futures_util::stream::iter(chunks))
+}
+
+#[tokio::test]
+async fn test_route_body_limit_rejects_by_content_length() {
+    let (status, _) = post(UltraApiApp::new(), "/limits/small", note_body(100).into()).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = post(UltraApiApp::new(), "/limits/small", note_body(2000).into()).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+        body["error"],
+        "Request body exceeds the limit of 1024 bytes"
+    );
+}
+
+#[tokio::test]
+async fn test_route_body_limit_rejects_chunked_bodies() {
+    let (status, _) = post(UltraApiApp::new(), "/limits/small", chunked(note_body(100))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = post(
+        UltraApiApp::new(),
+        "/limits/small",
+        chunked(note_body(2000)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+        body["error"],
+        "Request body exceeds the limit of 1024 bytes"
+    );
+}
+
+#[tokio::test]
+async fn test_route_body_limit_can_exceed_extractor_default() {
+    // axum's extractors stop at 2MB unless the route raises the limit
+    let body = note_body(3 * 1024 * 1024);
+    let (status, _) = post(UltraApiApp::new(), "/limits/large", body.into()).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _) = post(
+        UltraApiApp::new(),
+        "/limits/large",
+        chunked(note_body(5 << 20)),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_global_body_limit_and_route_override() {
+    let app = || UltraApiApp::new().middleware(|m| m.body_limit(512));
+
+    let (status, _) = post(app(), "/limits/default", note_body(100).into()).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, body) = post(app(), "/limits/default", note_body(600).into()).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(body["error"], "Request body exceeds the limit of 512 bytes");
+
+    // #[body_limit("1KB")] takes precedence over the global limit
+    let (status, _) = post(app(), "/limits/small", note_body(600).into()).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_static_segments_take_precedence() {
+    // /limits/items/bulk also matches /limits/items/{id}, which is registered first
+    let app = || {
+        UltraApiApp::new().include(
+            UltraApiRouter::new("")
+                .route(__HAYAI_ROUTE_UPDATE_ITEM)
+                .route(__HAYAI_ROUTE_BULK_ITEMS),
+        )
+    };
+    let (status, _) = post(app(), "/limits/items/bulk", note_body(2000).into()).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = post(app(), "/limits/items/7", note_body(2000).into()).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+        body["error"],
+        "Request body exceeds the limit of 1024 bytes"
+    );
+}
+
+#[tokio::test]
+async fn test_static_route_without_limit_ignores_parameterized_sibling() {
+    // /limits/things/bulk sets no limit and must not pick up the 1KB of /limits/things/{id}
+    let app = || {
+        UltraApiApp::new().include(
+            UltraApiRouter::new("")
+                .route(__HAYAI_ROUTE_UPDATE_THING)
+                .route(__HAYAI_ROUTE_BULK_THINGS),
+        )
+    };
+    let (status, _) = post(app(), "/limits/things/bulk", note_body(2048).into()).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _) = post(app(), "/limits/things/7", note_body(2048).into()).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_mounted_app_routes_keep_their_limits() {
+    let app = || {
+        let sub_app = UltraApiApp::new().include(
+            UltraApiRouter::new("/v1")
+                .route(__HAYAI_ROUTE_SMALL_NOTE)
+                .route(__HAYAI_ROUTE_SLOW),
+        );
+        UltraApiApp::new().mount("/sub", sub_app)
+    };
+
+    let (status, _) = post(app(), "/sub/v1/limits/small", note_body(100).into()).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, body) = post(app(), "/sub/v1/limits/small", note_body(2000).into()).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+        body["error"],
+        "Request body exceeds the limit of 1024 bytes"
+    );
+
+    let (status, _) = get(app(), "/sub/v1/limits/slow").await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[tokio::test]
+async fn test_route_timeout() {
+    let (status, body) = get(UltraApiApp::new(), "/limits/slow").await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(body["error"], "Request timed out after 50ms");
+
+    let (status, _) = get(UltraApiApp::new(), "/limits/fast").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_global_timeout_and_route_override() {
+    let app = || UltraApiApp::new().middleware(|m| m.timeout(Duration::from_millis(20)));
+
+    let (status, body) = get(app(), "/limits/slow").await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(body["error"], "Request timed out after 50ms");
+
+    let app = || UltraApiApp::new().middleware(|m| m.timeout(Duration::from_secs(1)));
+    let (status, _) = get(app(), "/limits/slow").await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+    let (status, _) = get(app(), "/limits/fast").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_openapi_documents_limits() {
+    let (_, spec) = get(UltraApiApp::new(), "/openapi.json").await;
+    let paths = &spec["paths"];
+
+    let small = &paths["/limits/small"]["post"]["responses"];
+    assert_eq!(
+        small["413"]["description"],
+        "Payload Too Large (request body over 1024 bytes)"
+    );
+    assert_eq!(
+        small["413"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/ApiError"
+    );
+    assert!(small.get("504").is_none());
+    assert!(paths["/limits/default"]["post"]["responses"]
+        .get("413")
+        .is_none());
+
+    let slow = &paths["/limits/slow"]["get"]["responses"];
+    assert_eq!(
+        slow["504"]["description"],
+        "Gateway Timeout (no response within 50 ms)"
+    );
+    assert!(slow.get("413").is_none());
+
+    // Global defaults are documented on every route they apply to
+    let app = UltraApiApp::new().middleware(|m| m.body_limit(512).timeout(Duration::from_secs(3)));
+    let (_, spec) = get(app, "/openapi.json").await;
+    let default = &spec["paths"]["/limits/default"]["post"]["responses"];
+    assert_eq!(
+        default["413"]["description"],
+        "Payload Too Large (request body over 512 bytes)"
+    );
+    assert_eq!(
+        default["504"]["description"],
+        "Gateway Timeout (no response within 3000 ms)"
+    );
+    assert!(spec["paths"]["/limits/fast"]["get"]["responses"]
+        .get("413")
+        .is_none());
+}
@@ -0,0 +1,162 @@
+// Tests for SSE resumption: LastEventId extractor and the EventLog replay buffer
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use serde_json::json;
+use ultraapi::axum::response::sse::Event;
+use ultraapi::prelude::*;
+
+#[sse("/sse/notifications")]
+async fn notifications(
+    log: Dep<EventLog>,
+    last_event_id: LastEventId,
+) -> impl ultraapi::tokio_stream::Stream<Item = Result<Event, Infallible>> {
+    log.subscribe("notifications", &last_event_id)
+}
+
+fn app(log: EventLog) -> UltraApiApp {
+    UltraApiApp::new()
+        .dep(log)
+        .include(UltraApiRouter::new("").route(__ULTRAAPI_SSE_NOTIFICATIONS))
+}
+
+async fn open(client: &TestClient, last_event_id: Option<&str>) -> reqwest::Response {
+    let mut request =
+        reqwest::Client::new().get(format!("{}/sse/notifications", client.base_url()));
+    if let Some(id) = last_event_id {
+        request = request.header("Last-Event-ID", id);
+    }
+    let resp = request.send().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    resp
+}
+
+/// Read until `expected` complete events (blank-line separated) have arrived
+async fn read_events(resp: &mut reqwest::Response, expected: usize) -> Vec<String> {
+    let mut buffer = String::new();
+    while buffer.matches("\n\n").count() < expected {
+        let chunk = tokio::time::timeout(Duration::from_secs(5), resp.chunk())
+            .await
+            .expect("timed out")
+            .unwrap()
+            .expect("stream ended");
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+    }
+    buffer
+        .split("\n\n")
+        .filter(|event| !event.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn ids(log: &EventLog, stream: &str, last: Option<&str>) -> Vec<u64> {
+    log.since(stream, &LastEventId(last.map(str::to_string)))
+        .iter()
+        .map(|event| event.id)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_since_replays_events_after_the_last_id() {
+    let log = EventLog::new();
+    let first = log.publish("a", None, "one");
+    log.publish("b", None, "other stream");
+    let third = log.publish("a", Some("update"), "three");
+
+    assert_eq!(ids(&log, "a", None), Vec::<u64>::new());
+    assert_eq!(ids(&log, "a", Some(&first.to_string())), vec![third]);
+    assert_eq!(ids(&log, "a", Some(&third.to_string())), Vec::<u64>::new());
+    // Ids that cannot be parsed replay everything still retained
+    assert_eq!(ids(&log, "a", Some("not-a-number")), vec![first, third]);
+    // Numeric ids always filter, even when not retained in this stream
+    assert_eq!(ids(&log, "a", Some("999")), Vec::<u64>::new());
+    let second = first + 1;
+    assert_eq!(ids(&log, "a", Some(&second.to_string())), vec![third]);
+
+    let events = log.since("a", &LastEventId(Some(first.to_string())));
+    assert_eq!(events[0].event.as_deref(), Some("update"));
+    assert_eq!(events[0].data, "three");
+    assert_eq!(log.streams(), vec!["a", "b"]);
+}
+
+#[tokio::test]
+async fn test_retention_by_count_and_age() {
+    let log = EventLog::new().max_events(2);
+    let ids_published: Vec<u64> = (0..4)
+        .map(|n| log.publish("s", None, n.to_string()))
+        .collect();
+    assert_eq!(ids(&log, "s", Some("0")), ids_published[2..].to_vec());
+
+    let log = EventLog::new().max_age(Duration::from_millis(50));
+    log.publish("s", None, "old");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let fresh = log.publish("s", None, "fresh");
+    assert_eq!(ids(&log, "s", Some("0")), vec![fresh]);
+}
+
+#[tokio::test]
+async fn test_resumed_client_receives_missed_events_before_live_ones() {
+    let log = EventLog::new().retry(Duration::from_secs(3));
+    let client = TestClient::new(app(log.clone())).await;
+
+    let mut resp = open(&client, None).await;
+    assert_eq!(
+        resp.headers()["content-type"].to_str().unwrap(),
+        "text/event-stream"
+    );
+    assert_eq!(read_events(&mut resp, 1).await, vec!["retry: 3000"]);
+
+    let seen = log
+        .publish_json("notifications", Some("created"), &json!({"n": 1}))
+        .unwrap();
+    assert_eq!(
+        read_events(&mut resp, 1).await,
+        vec![format!("event: created\ndata: {{\"n\":1}}\nid: {seen}")]
+    );
+    drop(resp);
+
+    // Published while the client was disconnected
+    let missed = log.publish("notifications", None, "missed");
+
+    let mut resp = open(&client, Some(&seen.to_string())).await;
+    let events = read_events(&mut resp, 2).await;
+    assert_eq!(events[0], "retry: 3000");
+    assert_eq!(events[1], format!("data: missed\nid: {missed}"));
+
+    let live = log.publish("notifications", None, "live");
+    assert_eq!(
+        read_events(&mut resp, 1).await,
+        vec![format!("data: live\nid: {live}")]
+    );
+}
+
+#[tokio::test]
+async fn test_last_event_id_header_in_openapi() {
+    let client = TestClient::new(app(EventLog::new())).await;
+    let spec: serde_json::Value = client.get("/openapi.json").await.json().await.unwrap();
+    let parameters = &spec["paths"]["/sse/notifications"]["get"]["parameters"];
+    assert_eq!(parameters[0]["name"], "Last-Event-ID");
+    assert_eq!(parameters[0]["in"], "header");
+    assert_eq!(parameters[0]["required"], false);
+}
+
+#[tokio::test]
+async fn test_lagging_subscriber_catches_up_from_the_buffer() {
+    use futures_util::StreamExt;
+
+    let log = EventLog::new();
+    let mut stream = Box::pin(log.subscribe("s", &LastEventId::default()));
+    // More than the live channel holds while the subscriber is not reading
+    for n in 0..400 {
+        log.publish("s", None, n.to_string());
+    }
+    let mut received = 0;
+    while tokio::time::timeout(Duration::from_millis(200), stream.next())
+        .await
+        .is_ok()
+    {
+        received += 1;
+    }
+    assert_eq!(received, 400);
+}
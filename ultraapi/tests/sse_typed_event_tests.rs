@@ -0,0 +1,145 @@
+// Tests for typed SSE events (SseEvent<T>), their OpenAPI / AsyncAPI schemas and keep-alive frames
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use serde_json::Value;
+use ultraapi::axum::response::sse::Event;
+use ultraapi::prelude::*;
+use ultraapi::tokio_stream::{self, Stream};
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct Order {
+    id: i64,
+    status: String,
+}
+
+/// Order updates
+#[sse("/sse/orders", events = ["created", "shipped"])]
+async fn order_events() -> impl Stream<Item = SseEvent<Order>> {
+    tokio_stream::iter(vec![
+        SseEvent::new(Order {
+            id: 1,
+            status: "new".into(),
+        })
+        .event("created")
+        .id(1),
+        SseEvent::new(Order {
+            id: 1,
+            status: "sent".into(),
+        })
+        .event("shipped")
+        .id(2)
+        .retry(Duration::from_secs(5)),
+    ])
+}
+
+#[sse("/sse/ticks", keep_alive_secs = 1, keep_alive_text = "ping")]
+async fn ticks() -> impl Stream<Item = Result<Event, Infallible>> {
+    tokio_stream::pending()
+}
+
+#[sse("/sse/plain")]
+async fn plain() -> impl Stream<Item = Result<Event, Infallible>> {
+    tokio_stream::iter(vec![Ok(Event::default().data("hi"))])
+}
+
+fn app() -> UltraApiApp {
+    UltraApiApp::new().include(
+        UltraApiRouter::new("")
+            .route(__ULTRAAPI_SSE_ORDER_EVENTS)
+            .route(__ULTRAAPI_SSE_TICKS)
+            .route(__ULTRAAPI_SSE_PLAIN),
+    )
+}
+
+async fn next_chunk(resp: &mut reqwest::Response) -> String {
+    let chunk = tokio::time::timeout(Duration::from_secs(5), resp.chunk())
+        .await
+        .expect("timed out")
+        .unwrap()
+        .expect("stream ended");
+    String::from_utf8_lossy(&chunk).into_owned()
+}
+
+#[tokio::test]
+async fn test_typed_events_are_sent_as_json() {
+    let client = TestClient::new(app()).await;
+    let resp = client.get("/sse/orders").await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["content-type"].to_str().unwrap(),
+        "text/event-stream"
+    );
+    assert_eq!(
+        resp.text().await.unwrap(),
+        "event: created\ndata: {\"id\":1,\"status\":\"new\"}\nid: 1\n\n\
+         event: shipped\ndata: {\"id\":1,\"status\":\"sent\"}\nid: 2\nretry: 5000\n\n"
+    );
+}
+
+#[tokio::test]
+async fn test_sse_event_accessors() {
+    let event = SseEvent::new(Order {
+        id: 7,
+        status: "new".into(),
+    })
+    .event("created");
+    assert_eq!(event.event_name(), Some("created"));
+    assert_eq!(event.data().id, 7);
+    assert!(event.into_event().is_ok());
+}
+
+#[tokio::test]
+async fn test_openapi_documents_the_event_schema() {
+    let client = TestClient::new(app()).await;
+    let spec: Value = client.get("/openapi.json").await.json().await.unwrap();
+
+    let content = &spec["paths"]["/sse/orders"]["get"]["responses"]["200"]["content"];
+    let schema = &content["text/event-stream"]["schema"];
+    assert_eq!(
+        schema["properties"]["data"]["$ref"],
+        "#/components/schemas/Order"
+    );
+    assert_eq!(
+        schema["properties"]["event"]["enum"],
+        serde_json::json!(["created", "shipped"])
+    );
+    assert_eq!(schema["required"], serde_json::json!(["data"]));
+    assert_eq!(
+        schema["x-sse-events"],
+        serde_json::json!(["created", "shipped"])
+    );
+    assert!(spec["components"]["schemas"]["Order"].is_object());
+
+    // Untyped streams keep an opaque body
+    let plain = &spec["paths"]["/sse/plain"]["get"]["responses"]["200"]["content"];
+    assert_eq!(plain["text/event-stream"], serde_json::json!({}));
+}
+
+#[tokio::test]
+async fn test_asyncapi_references_the_event_payload() {
+    let client = TestClient::new(app()).await;
+    let doc: Value = client.get("/asyncapi.json").await.json().await.unwrap();
+
+    assert_eq!(
+        doc["channels"]["order_events"]["messages"]["Order"]["$ref"],
+        "#/components/messages/Order"
+    );
+    assert_eq!(
+        doc["operations"]["order_events_send"]["action"],
+        serde_json::json!("send")
+    );
+    assert!(doc["components"]["schemas"]["Order"].is_object());
+}
+
+#[tokio::test]
+async fn test_keep_alive_comment_frames() {
+    let client = TestClient::new(app()).await;
+    let mut resp = client.get("/sse/ticks").await;
+    assert_eq!(resp.status(), 200);
+
+    assert_eq!(next_chunk(&mut resp).await, ": ping\n\n");
+    assert_eq!(next_chunk(&mut resp).await, ": ping\n\n");
+}
@@ -0,0 +1,194 @@
+//! Structured Validation Error Tests
+//!
+//! Validation failures are reported as FastAPI/pydantic-style entries with a
+//! source-rooted `loc`, a machine-readable `type`, the offending `input` and
+//! a `ctx` object describing the violated constraint.
+
+use axum::extract::Query;
+use serde_json::json;
+use ultraapi::prelude::*;
+
+// --- Test Models ---
+
+fn reject_reserved_names(signup: &StructuredSignup) -> Result<(), Vec<String>> {
+    if signup.username == "root" {
+        return Err(vec!["username: is reserved".to_string()]);
+    }
+    Ok(())
+}
+
+#[api_model(validate(custom = "reject_reserved_names"))]
+#[derive(Debug, Clone)]
+struct StructuredSignup {
+    #[validate(min_length = 3)]
+    username: String,
+
+    #[validate(minimum = 18, maximum = 130)]
+    age: i64,
+
+    #[validate(pattern = "^[a-z]+$")]
+    nickname: String,
+
+    #[validate(min_items = 1)]
+    interests: Vec<String>,
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct StructuredPaging {
+    #[validate(minimum = 1)]
+    limit: i64,
+}
+
+// --- Test Routes ---
+
+#[post("/__test_structured_signup")]
+async fn structured_signup(body: StructuredSignup) -> StructuredSignup {
+    body
+}
+
+#[get("/__test_structured_paging")]
+async fn structured_paging(query: Query<StructuredPaging>) -> StructuredPaging {
+    query.0
+}
+
+// --- Helpers ---
+
+fn create_app() -> UltraApiApp {
+    UltraApiApp::new()
+        .title("Structured Validation Test API")
+        .version("0.1.0")
+}
+
+fn valid_signup() -> serde_json::Value {
+    json!({
+        "username": "alice",
+        "age": 30,
+        "nickname": "ally",
+        "interests": ["rust"]
+    })
+}
+
+// --- Tests ---
+
+#[tokio::test]
+async fn test_body_errors_carry_type_input_and_ctx() {
+    let client = TestClient::new(create_app()).await;
+    let mut signup = valid_signup();
+    signup["username"] = json!("al");
+    signup["age"] = json!(12);
+    signup["nickname"] = json!("Ally1");
+    signup["interests"] = json!([]);
+
+    let response = client.post("/__test_structured_signup", &signup).await;
+    assert_eq!(response.status(), 422);
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    assert_eq!(
+        body["detail"],
+        json!([
+            {
+                "loc": ["body", "username"],
+                "msg": "must be at least 3 characters",
+                "type": "string_too_short",
+                "input": "al",
+                "ctx": { "min_length": 3 }
+            },
+            {
+                "loc": ["body", "age"],
+                "msg": "must be at least 18",
+                "type": "greater_than_equal",
+                "input": 12,
+                "ctx": { "ge": 18 }
+            },
+            {
+                "loc": ["body", "nickname"],
+                "msg": "must match pattern ^[a-z]+$",
+                "type": "string_pattern_mismatch",
+                "input": "Ally1",
+                "ctx": { "pattern": "^[a-z]+$" }
+            },
+            {
+                "loc": ["body", "interests"],
+                "msg": "must have at least 1 items",
+                "type": "too_short",
+                "input": [],
+                "ctx": { "field_type": "List", "min_length": 1, "actual_length": 0 }
+            }
+        ])
+    );
+    // Legacy string details are kept alongside the structured entries
+    assert_eq!(
+        body["details"][0],
+        "username: must be at least 3 characters"
+    );
+}
+
+#[tokio::test]
+async fn test_custom_validator_messages_become_value_errors() {
+    let client = TestClient::new(create_app()).await;
+    let mut signup = valid_signup();
+    signup["username"] = json!("root");
+
+    let response = client.post("/__test_structured_signup", &signup).await;
+    assert_eq!(response.status(), 422);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        body["detail"],
+        json!([{ "loc": ["body", "username"], "msg": "is reserved", "type": "value_error" }])
+    );
+}
+
+#[tokio::test]
+async fn test_malformed_json_body_is_json_invalid() {
+    let client = TestClient::new(create_app()).await;
+    let response = client
+        .client()
+        .post(format!("{}/__test_structured_signup", client.base_url()))
+        .header("content-type", "application/json")
+        .body("{\"username\": \"alice\",")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 422);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let detail = &body["detail"][0];
+    assert_eq!(body["detail"].as_array().unwrap().len(), 1);
+    assert_eq!(detail["loc"], json!(["body"]));
+    assert_eq!(detail["type"], "json_invalid");
+    assert_eq!(detail["msg"], "JSON decode error");
+    assert!(detail["ctx"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("EOF while parsing"));
+}
+
+#[tokio::test]
+async fn test_query_errors_are_rooted_at_query() {
+    let client = TestClient::new(create_app()).await;
+
+    let response = client.get("/__test_structured_paging?limit=0").await;
+    assert_eq!(response.status(), 422);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["detail"][0]["loc"], json!(["query", "limit"]));
+    assert_eq!(body["detail"][0]["type"], "greater_than_equal");
+    assert_eq!(body["detail"][0]["input"], 0);
+}
+
+#[test]
+fn test_validation_errors_prefix_source() {
+    let error = ValidationErrorDetail::new("missing", "Field required").at("X-Token");
+    let api_error = ApiError::validation_errors(ValidationSource::Header, vec![error]);
+    let json = serde_json::to_value(&api_error).unwrap();
+
+    assert_eq!(
+        api_error.status,
+        axum::http::StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert_eq!(json["details"], json!(["X-Token: Field required"]));
+    assert_eq!(
+        json["detail"],
+        json!([{ "loc": ["header", "X-Token"], "msg": "Field required", "type": "missing" }])
+    );
+}
@@ -0,0 +1,285 @@
+// Tests for typed #[ws] handlers (TypedSocket) and the AsyncAPI document
+
+use std::convert::Infallible;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use ultraapi::prelude::*;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Messages sent by chat clients
+#[api_model]
+#[derive(Debug, Clone)]
+#[serde(tag = "type")]
+enum ClientMsg {
+    Join { room: String },
+    Say { text: String },
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+#[serde(tag = "type")]
+enum ServerMsg {
+    Joined { room: String },
+    Said { room: String, text: String },
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct Ping {
+    #[validate(min_length = 1)]
+    token: String,
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct Pong {
+    token: String,
+}
+
+/// Chat with typed messages
+#[ws("/typed/chat", incoming = ClientMsg, outgoing = ServerMsg)]
+#[tag("chat")]
+async fn typed_chat(mut socket: TypedSocket<ClientMsg, ServerMsg>) {
+    let mut room = String::new();
+    while let Some(message) = socket.recv().await {
+        let reply = match message {
+            ClientMsg::Join { room: joined } => {
+                room = joined;
+                ServerMsg::Joined { room: room.clone() }
+            }
+            ClientMsg::Say { text } => ServerMsg::Said {
+                room: room.clone(),
+                text,
+            },
+        };
+        if socket.send(&reply).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[ws("/typed/strict")]
+async fn typed_strict(socket: TypedSocket<Ping, Pong>) {
+    let mut socket = socket.on_invalid(InvalidMessage::Close);
+    while let Some(ping) = socket.recv().await {
+        let _ = socket.send(&Pong { token: ping.token }).await;
+    }
+}
+
+#[sse("/typed/events")]
+async fn typed_events(
+) -> impl ultraapi::tokio_stream::Stream<Item = Result<ultraapi::axum::response::sse::Event, Infallible>>
+{
+    ultraapi::tokio_stream::iter(vec![Ok(
+        ultraapi::axum::response::sse::Event::default().data("tick")
+    )])
+}
+
+fn app() -> UltraApiApp {
+    UltraApiApp::new().title("Chat").version("1.0.0").include(
+        UltraApiRouter::new("")
+            .route(__ULTRAAPI_WS_TYPED_CHAT)
+            .route(__ULTRAAPI_WS_TYPED_STRICT)
+            .route(__ULTRAAPI_SSE_TYPED_EVENTS),
+    )
+}
+
+async fn connect(client: &TestClient, path: &str) -> Socket {
+    let url = format!("{}{}", client.base_url().replace("http://", "ws://"), path);
+    let (socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    socket
+}
+
+async fn recv(socket: &mut Socket) -> Message {
+    tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+        .await
+        .expect("timed out")
+        .expect("socket closed")
+        .unwrap()
+}
+
+async fn recv_json(socket: &mut Socket) -> Value {
+    match recv(socket).await {
+        Message::Text(text) => serde_json::from_str(&text).unwrap(),
+        other => panic!("unexpected message: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_typed_socket_round_trips_json_messages() {
+    let client = TestClient::new(app()).await;
+    let mut socket = connect(&client, "/typed/chat").await;
+
+    socket
+        .send(Message::text(
+            json!({"type": "Join", "room": "rust"}).to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        recv_json(&mut socket).await,
+        json!({"type": "Joined", "room": "rust"})
+    );
+
+    socket
+        .send(Message::binary(
+            json!({"type": "Say", "text": "hi"})
+                .to_string()
+                .into_bytes(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        recv_json(&mut socket).await,
+        json!({"type": "Said", "room": "rust", "text": "hi"})
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_message_is_answered_with_validation_errors() {
+    let client = TestClient::new(app()).await;
+    let mut socket = connect(&client, "/typed/chat").await;
+
+    socket
+        .send(Message::text(json!({"type": "Join"}).to_string()))
+        .await
+        .unwrap();
+    let error = recv_json(&mut socket).await;
+    assert_eq!(error["detail"][0]["type"], "missing");
+    assert_eq!(error["detail"][0]["loc"], json!(["room"]));
+
+    socket.send(Message::text("{not json")).await.unwrap();
+    let error = recv_json(&mut socket).await;
+    assert_eq!(error["detail"][0]["type"], "json_invalid");
+
+    // The connection stays usable after an invalid message
+    socket
+        .send(Message::text(
+            json!({"type": "Join", "room": "a"}).to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(recv_json(&mut socket).await["type"], "Joined");
+}
+
+#[tokio::test]
+async fn test_close_policy_closes_with_invalid_payload_code() {
+    let client = TestClient::new(app()).await;
+    let mut socket = connect(&client, "/typed/strict").await;
+
+    socket
+        .send(Message::text(json!({"token": "abc"}).to_string()))
+        .await
+        .unwrap();
+    assert_eq!(recv_json(&mut socket).await, json!({"token": "abc"}));
+
+    // Fails #[validate(min_length = 1)]
+    socket
+        .send(Message::text(json!({"token": ""}).to_string()))
+        .await
+        .unwrap();
+    match recv(&mut socket).await {
+        Message::Close(Some(frame)) => {
+            assert_eq!(frame.code, CloseCode::Invalid);
+            assert!(frame.reason.starts_with("token:"), "{}", frame.reason);
+        }
+        other => panic!("expected close frame, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_asyncapi_document_describes_ws_and_sse_routes() {
+    let client = TestClient::new(app()).await;
+    let resp = client.get("/asyncapi.json").await;
+    assert_eq!(resp.status(), 200);
+    let doc: Value = resp.json().await.unwrap();
+
+    assert_eq!(doc["asyncapi"], "3.0.0");
+    assert_eq!(doc["info"]["title"], "Chat");
+
+    let chat = &doc["channels"]["typed_chat"];
+    assert_eq!(chat["address"], "/typed/chat");
+    assert_eq!(chat["description"], "Chat with typed messages");
+    assert_eq!(chat["bindings"]["ws"]["method"], "GET");
+    assert_eq!(
+        chat["messages"]["ClientMsg"]["$ref"],
+        "#/components/messages/ClientMsg"
+    );
+
+    let receive = &doc["operations"]["typed_chat_receive"];
+    assert_eq!(receive["action"], "receive");
+    assert_eq!(receive["channel"]["$ref"], "#/channels/typed_chat");
+    assert_eq!(
+        receive["messages"][0]["$ref"],
+        "#/channels/typed_chat/messages/ClientMsg"
+    );
+    assert_eq!(receive["tags"][0]["name"], "chat");
+    assert_eq!(
+        doc["operations"]["typed_chat_send"]["messages"][0]["$ref"],
+        "#/channels/typed_chat/messages/ServerMsg"
+    );
+
+    // Message types inferred from TypedSocket<Ping, Pong>
+    assert!(doc["channels"]["typed_strict"]["messages"]["Ping"].is_object());
+    assert!(doc["channels"]["typed_strict"]["messages"]["Pong"].is_object());
+
+    assert_eq!(
+        doc["components"]["messages"]["ClientMsg"]["payload"]["$ref"],
+        "#/components/schemas/ClientMsg"
+    );
+    let client_schema = &doc["components"]["schemas"]["ClientMsg"];
+    assert_eq!(client_schema["oneOf"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        doc["components"]["schemas"]["Ping"]["properties"]["token"]["type"],
+        "string"
+    );
+
+    // SSE routes are send-only channels
+    assert_eq!(doc["channels"]["typed_events"]["address"], "/typed/events");
+    assert_eq!(doc["operations"]["typed_events_send"]["action"], "send");
+    assert!(doc["operations"].get("typed_events_receive").is_none());
+}
+
+#[tokio::test]
+async fn test_asyncapi_url_is_configurable() {
+    let client = TestClient::new(app().asyncapi_url("/docs/asyncapi.json")).await;
+    assert_eq!(client.get("/docs/asyncapi.json").await.status(), 200);
+    assert_eq!(client.get("/asyncapi.json").await.status(), 404);
+}
+
+#[tokio::test]
+async fn test_message_enums_are_oneof_unions_in_openapi() {
+    let client = TestClient::new(app()).await;
+    let spec: Value = client.get("/openapi.json").await.json().await.unwrap();
+    let schemas = &spec["components"]["schemas"];
+
+    assert_eq!(
+        schemas["ClientMsg"]["description"],
+        "Messages sent by chat clients"
+    );
+    assert_eq!(
+        schemas["ClientMsg"]["discriminator"]["propertyName"],
+        "type"
+    );
+    assert_eq!(
+        schemas["ClientMsg"]["discriminator"]["mapping"]["Join"],
+        "#/components/schemas/ClientMsg_Join"
+    );
+    assert_eq!(
+        schemas["ClientMsg_Join"]["properties"]["type"]["enum"],
+        json!(["Join"])
+    );
+    assert_eq!(
+        schemas["ClientMsg_Join"]["properties"]["room"]["type"],
+        "string"
+    );
+
+    // WebSocket upgrades stay out of the OpenAPI paths
+    assert!(spec["paths"].get("/typed/chat").is_none());
+}
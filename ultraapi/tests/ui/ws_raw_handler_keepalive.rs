@@ -0,0 +1,12 @@
+use ultraapi::prelude::*;
+
+// Keepalive needs a TypedSocket; a raw WebSocketUpgrade handler upgrades the
+// connection itself, so the macro rejects the option
+#[ws("/raw", ping_interval_secs = 30)]
+async fn raw(
+    ws: ultraapi::axum::extract::ws::WebSocketUpgrade,
+) -> ultraapi::axum::response::Response {
+    ws.on_upgrade(|_socket| async {})
+}
+
+fn main() {}
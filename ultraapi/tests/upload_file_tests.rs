@@ -0,0 +1,378 @@
+// Tests for UploadFile, #[form_model] / MultipartForm<T> and UploadLimits
+
+use std::path::PathBuf;
+
+use reqwest::multipart::{Form, Part};
+use serde_json::Value;
+use tempfile::TempDir;
+use ultraapi::prelude::*;
+
+/// Avatar upload
+#[form_model]
+struct AvatarForm {
+    /// Display name
+    name: String,
+    age: Option<u32>,
+    #[upload(max_size = "1KB", content_types = ["image/*"])]
+    image: UploadFile,
+    attachments: Option<Vec<UploadFile>>,
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct AvatarResult {
+    name: String,
+    age: Option<u32>,
+    image_name: String,
+    image_type: String,
+    image_size: u64,
+    attachments: Vec<String>,
+}
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct FileSummary {
+    filename: String,
+    size: u64,
+    in_memory: bool,
+    content: String,
+}
+
+#[derive(Clone)]
+struct SaveDir(PathBuf);
+
+#[post("/uploads/avatar")]
+async fn upload_avatar(form: MultipartForm<AvatarForm>) -> Result<AvatarResult, ApiError> {
+    let form = form.into_inner();
+    Ok(AvatarResult {
+        name: form.name,
+        age: form.age,
+        image_name: form.image.filename().unwrap_or_default().to_string(),
+        image_type: form.image.content_type().unwrap_or_default().to_string(),
+        image_size: form.image.size(),
+        attachments: form
+            .attachments
+            .unwrap_or_default()
+            .iter()
+            .map(|file| file.filename().unwrap_or_default().to_string())
+            .collect(),
+    })
+}
+
+#[post("/uploads/single")]
+async fn upload_single(file: UploadFile) -> Result<FileSummary, ApiError> {
+    let content = file
+        .text()
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(FileSummary {
+        filename: file.filename().unwrap_or_default().to_string(),
+        size: file.size(),
+        in_memory: file.is_in_memory(),
+        content,
+    })
+}
+
+#[post("/uploads/large")]
+#[body_limit("16MB")]
+async fn upload_large(file: UploadFile) -> String {
+    file.size().to_string()
+}
+
+#[post("/uploads/save")]
+async fn upload_save(
+    #[upload(max_size = "64KB")] file: UploadFile,
+    dir: Dep<SaveDir>,
+) -> Result<String, ApiError> {
+    let filename = file.filename().unwrap_or_default().to_string();
+    file.persist(dir.0.join(&filename))
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(filename)
+}
+
+fn app(limits: UploadLimits, save_dir: &TempDir) -> UltraApiApp {
+    UltraApiApp::new()
+        .dep(limits)
+        .dep(SaveDir(save_dir.path().to_path_buf()))
+}
+
+fn png(name: &str, size: usize) -> Part {
+    Part::bytes(vec![0u8; size])
+        .file_name(name.to_string())
+        .mime_str("image/png")
+        .unwrap()
+}
+
+fn text_file(name: &str, content: &str) -> Part {
+    Part::text(content.to_string())
+        .file_name(name.to_string())
+        .mime_str("text/plain")
+        .unwrap()
+}
+
+async fn send(client: &TestClient, path: &str, form: Form) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("{}{}", client.base_url(), path))
+        .multipart(form)
+        .send()
+        .await
+        .unwrap()
+}
+
+/// A multipart body with a hand-written Content-Disposition filename
+async fn send_raw_filename(client: &TestClient, path: &str, filename: &str) -> reqwest::Response {
+    let body = format!(
+        "--b\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n\
+         Content-Type: text/plain\r\n\r\nhello\r\n--b--\r\n",
+        filename
+    );
+    reqwest::Client::new()
+        .post(format!("{}{}", client.base_url(), path))
+        .header("content-type", "multipart/form-data; boundary=b")
+        .body(body)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_form_model_binds_fields_and_files() {
+    let dir = TempDir::new().unwrap();
+    let client = TestClient::new(app(UploadLimits::new(), &dir)).await;
+
+    let form = Form::new()
+        .text("name", "alice")
+        .text("age", "30")
+        .part("image", png("me.png", 100))
+        .part("attachments", text_file("a.txt", "a"))
+        .part("attachments", text_file("b.txt", "b"))
+        .text("ignored", "x");
+    let resp = send(&client, "/uploads/avatar", form).await;
+    assert_eq!(resp.status(), 201);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["name"], "alice");
+    assert_eq!(body["age"], 30);
+    assert_eq!(body["image_name"], "me.png");
+    assert_eq!(body["image_type"], "image/png");
+    assert_eq!(body["image_size"], 100);
+    assert_eq!(body["attachments"], serde_json::json!(["a.txt", "b.txt"]));
+
+    // Optional fields may be left out
+    let form = Form::new()
+        .text("name", "bob")
+        .part("image", png("me.png", 1));
+    let body: Value = send(&client, "/uploads/avatar", form)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["age"], Value::Null);
+    assert_eq!(body["attachments"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_missing_and_invalid_fields_are_422() {
+    let dir = TempDir::new().unwrap();
+    let client = TestClient::new(app(UploadLimits::new(), &dir)).await;
+
+    let resp = send(&client, "/uploads/avatar", Form::new().text("age", "old")).await;
+    assert_eq!(resp.status(), 422);
+    let body: Value = resp.json().await.unwrap();
+    let detail = body["detail"].as_array().unwrap();
+    let find = |field: &str| {
+        detail
+            .iter()
+            .find(|d| d["loc"] == serde_json::json!(["body", field]))
+            .unwrap_or_else(|| panic!("no error for {}", field))
+    };
+    assert_eq!(find("name")["type"], "missing");
+    assert_eq!(find("image")["type"], "missing");
+    assert_eq!(find("age")["type"], "int_parsing");
+
+    // A text value where a file is expected
+    let form = Form::new().text("name", "x").text("image", "not a file");
+    let body: Value = send(&client, "/uploads/avatar", form)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["detail"][0]["msg"], "Expected a file upload");
+}
+
+#[tokio::test]
+async fn test_field_size_and_content_type_rules() {
+    let dir = TempDir::new().unwrap();
+    let client = TestClient::new(app(UploadLimits::new(), &dir)).await;
+
+    let form = Form::new()
+        .text("name", "x")
+        .part("image", png("big.png", 2048));
+    let resp = send(&client, "/uploads/avatar", form).await;
+    assert_eq!(resp.status(), 413);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(
+        body["error"],
+        "File \"image\" exceeds the maximum size of 1024 bytes"
+    );
+
+    let form = Form::new()
+        .text("name", "x")
+        .part("image", text_file("me.txt", "hi"));
+    let resp = send(&client, "/uploads/avatar", form).await;
+    assert_eq!(resp.status(), 415);
+}
+
+#[tokio::test]
+async fn test_global_upload_limits() {
+    let dir = TempDir::new().unwrap();
+    let limits = UploadLimits::new()
+        .max_files(1)
+        .max_total_size(4096)
+        .allowed_content_types(["text/plain"]);
+    let client = TestClient::new(app(limits, &dir)).await;
+
+    let form = Form::new().part("file", text_file("a.txt", "hello"));
+    assert_eq!(send(&client, "/uploads/single", form).await.status(), 201);
+
+    let form = Form::new()
+        .part("file", text_file("a.txt", "a"))
+        .part("file", text_file("b.txt", "b"));
+    assert_eq!(send(&client, "/uploads/single", form).await.status(), 413);
+
+    let form = Form::new().part("file", text_file("a.txt", &"x".repeat(5000)));
+    assert_eq!(send(&client, "/uploads/single", form).await.status(), 413);
+
+    let form = Form::new().part("file", png("a.png", 10));
+    assert_eq!(send(&client, "/uploads/single", form).await.status(), 415);
+}
+
+#[tokio::test]
+async fn test_default_upload_size_follows_the_body_limit() {
+    let dir = TempDir::new().unwrap();
+    let client = TestClient::new(app(UploadLimits::new(), &dir)).await;
+    let size = ultraapi::upload::DEFAULT_MAX_UPLOAD_SIZE as usize + 1024;
+
+    // Without any configured limit a request is capped at DEFAULT_MAX_UPLOAD_SIZE
+    let form = Form::new().part("file", png("big.png", size));
+    assert_eq!(send(&client, "/uploads/single", form).await.status(), 413);
+
+    // #[body_limit] raises it for the route
+    let form = Form::new().part("file", png("big.png", size));
+    let resp = send(&client, "/uploads/large", form).await;
+    assert_eq!(resp.status(), 201);
+    assert_eq!(resp.json::<Value>().await.unwrap(), size.to_string());
+}
+
+#[tokio::test]
+async fn test_large_files_are_spooled_to_disk_and_removed() {
+    let dir = TempDir::new().unwrap();
+    let spool = TempDir::new().unwrap();
+    let limits = UploadLimits::new()
+        .memory_threshold(16)
+        .spool_dir(spool.path());
+    let client = TestClient::new(app(limits, &dir)).await;
+
+    let small = Form::new().part("file", text_file("small.txt", "tiny"));
+    let body: Value = send(&client, "/uploads/single", small)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["in_memory"], true);
+    assert_eq!(body["content"], "tiny");
+
+    let content = "0123456789".repeat(10);
+    let large = Form::new().part("file", text_file("large.txt", &content));
+    let body: Value = send(&client, "/uploads/single", large)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["in_memory"], false);
+    assert_eq!(body["size"], 100);
+    assert_eq!(body["content"], content);
+    assert_eq!(std::fs::read_dir(spool.path()).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn test_persist_and_filename_validation() {
+    let dir = TempDir::new().unwrap();
+    let client = TestClient::new(app(UploadLimits::new().memory_threshold(4), &dir)).await;
+
+    // Directory parts of the client's file name are dropped
+    let resp = send_raw_filename(&client, "/uploads/save", "../../evil.txt").await;
+    assert_eq!(resp.status(), 201);
+    assert_eq!(resp.json::<String>().await.unwrap(), "evil.txt");
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("evil.txt")).unwrap(),
+        "hello"
+    );
+
+    let resp = send_raw_filename(&client, "/uploads/save", "bad\tname.txt").await;
+    assert_eq!(resp.status(), 422);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(
+        body["detail"][0]["loc"],
+        serde_json::json!(["body", "file"])
+    );
+    assert_eq!(body["detail"][0]["msg"], "Invalid filename");
+
+    let resp = send_raw_filename(&client, "/uploads/save", "..").await;
+    assert_eq!(resp.status(), 422);
+
+    // #[upload(...)] on a handler parameter
+    let form = Form::new().part("file", text_file("big.txt", &"x".repeat(70 * 1024)));
+    assert_eq!(send(&client, "/uploads/save", form).await.status(), 413);
+}
+
+#[tokio::test]
+async fn test_non_multipart_body_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let client = TestClient::new(app(UploadLimits::new(), &dir)).await;
+    let resp = reqwest::Client::new()
+        .post(format!("{}/uploads/single", client.base_url()))
+        .json(&serde_json::json!({"file": "x"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_openapi_multipart_schemas() {
+    let dir = TempDir::new().unwrap();
+    let client = TestClient::new(app(UploadLimits::new(), &dir)).await;
+    let spec: Value = client.get("/openapi.json").await.json().await.unwrap();
+
+    let content = &spec["paths"]["/uploads/avatar"]["post"]["requestBody"]["content"];
+    assert_eq!(
+        content["multipart/form-data"]["schema"]["$ref"],
+        "#/components/schemas/AvatarForm"
+    );
+    let avatar = &spec["components"]["schemas"]["AvatarForm"];
+    assert_eq!(avatar["description"], "Avatar upload");
+    assert_eq!(avatar["properties"]["image"]["type"], "string");
+    assert_eq!(avatar["properties"]["image"]["format"], "binary");
+    // Option<Vec<UploadFile>>: a nullable array of files
+    let attachments = &avatar["properties"]["attachments"]["anyOf"][0];
+    assert_eq!(attachments["type"], "array");
+    assert_eq!(attachments["items"]["format"], "binary");
+    assert_eq!(avatar["properties"]["name"]["description"], "Display name");
+    let required = avatar["required"].as_array().unwrap();
+    assert!(required.contains(&Value::from("name")));
+    assert!(required.contains(&Value::from("image")));
+    assert!(!required.contains(&Value::from("age")));
+
+    let content = &spec["paths"]["/uploads/single"]["post"]["requestBody"]["content"];
+    assert_eq!(
+        content["multipart/form-data"]["schema"]["$ref"],
+        "#/components/schemas/Body_upload_single"
+    );
+    let body = &spec["components"]["schemas"]["Body_upload_single"];
+    assert_eq!(body["properties"]["file"]["format"], "binary");
+    assert_eq!(body["required"], serde_json::json!(["file"]));
+
+    // No raw Multipart routes here, so no placeholder schema
+    assert!(spec["components"]["schemas"].get("Multipart").is_none());
+}
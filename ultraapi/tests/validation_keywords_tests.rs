@@ -0,0 +1,199 @@
+//! Validation Keyword Tests
+//!
+//! Tests for the JSON Schema keywords supported by `#[validate(...)]`:
+//! each keyword must reject invalid input at runtime and appear in the
+//! generated OpenAPI schema.
+
+use serde_json::json;
+use ultraapi::prelude::*;
+
+// --- Test Models ---
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct KeywordItem {
+    #[validate(max_items = 3, unique_items)]
+    tags: Vec<String>,
+
+    #[validate(multiple_of = 5)]
+    quantity: i64,
+
+    #[validate(exclusive_minimum = 0, exclusive_maximum = 1.5)]
+    ratio: f64,
+
+    #[validate(url)]
+    homepage: String,
+
+    #[validate(uuid)]
+    external_id: String,
+
+    #[validate(ip)]
+    address: String,
+
+    #[validate(ipv4)]
+    gateway: String,
+
+    #[validate(one_of = ["draft", "published"])]
+    status: String,
+
+    #[validate(one_of = [1, 2, 3])]
+    priority: i64,
+
+    #[validate(length(equal = 2))]
+    country: String,
+
+    #[validate(length(min = 1, max = 4))]
+    scores: Vec<i64>,
+
+    #[validate(contains = "admin")]
+    roles: Vec<String>,
+}
+
+// --- Test Routes ---
+
+#[post("/__test_validation_keywords")]
+async fn create_keyword_item(body: KeywordItem) -> KeywordItem {
+    body
+}
+
+// --- Helpers ---
+
+fn valid_item() -> serde_json::Value {
+    json!({
+        "tags": ["a", "b"],
+        "quantity": 15,
+        "ratio": 0.5,
+        "homepage": "https://example.com/docs?page=1",
+        "external_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+        "address": "::1",
+        "gateway": "192.168.0.1",
+        "status": "draft",
+        "priority": 2,
+        "country": "JP",
+        "scores": [1, 2],
+        "roles": ["user", "admin"]
+    })
+}
+
+fn with_field(field: &str, value: serde_json::Value) -> serde_json::Value {
+    let mut item = valid_item();
+    item[field] = value;
+    item
+}
+
+async fn error_details(client: &TestClient, body: serde_json::Value) -> Vec<String> {
+    let response = client.post("/__test_validation_keywords", &body).await;
+    assert_eq!(response.status(), 422, "expected 422 for {}", body);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["details"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d.as_str().unwrap().to_string())
+        .collect()
+}
+
+fn create_app() -> UltraApiApp {
+    UltraApiApp::new()
+        .title("Validation Keyword Test API")
+        .version("0.1.0")
+}
+
+// --- Runtime Tests ---
+
+#[tokio::test]
+async fn test_valid_payload_passes_all_keywords() {
+    let client = TestClient::new(create_app()).await;
+    let response = client
+        .post("/__test_validation_keywords", &valid_item())
+        .await;
+    assert_eq!(response.status(), 201);
+}
+
+#[tokio::test]
+async fn test_array_keywords_rejected() {
+    let client = TestClient::new(create_app()).await;
+
+    let details = error_details(&client, with_field("tags", json!(["a", "b", "c", "d"]))).await;
+    assert_eq!(details, vec!["tags: must have at most 3 items"]);
+
+    let details = error_details(&client, with_field("tags", json!(["a", "a"]))).await;
+    assert_eq!(details, vec!["tags: must contain unique items"]);
+
+    let details = error_details(&client, with_field("roles", json!(["user"]))).await;
+    assert_eq!(details, vec!["roles: must contain admin"]);
+
+    let details = error_details(&client, with_field("scores", json!([]))).await;
+    assert_eq!(details, vec!["scores: must have at least 1 items"]);
+}
+
+#[tokio::test]
+async fn test_numeric_keywords_rejected() {
+    let client = TestClient::new(create_app()).await;
+
+    let details = error_details(&client, with_field("quantity", json!(12))).await;
+    assert_eq!(details, vec!["quantity: must be a multiple of 5"]);
+
+    let details = error_details(&client, with_field("ratio", json!(0.0))).await;
+    assert_eq!(details, vec!["ratio: must be greater than 0"]);
+
+    let details = error_details(&client, with_field("ratio", json!(1.5))).await;
+    assert_eq!(details, vec!["ratio: must be less than 1.5"]);
+}
+
+#[tokio::test]
+async fn test_format_keywords_rejected() {
+    let client = TestClient::new(create_app()).await;
+
+    let details = error_details(&client, with_field("homepage", json!("not a url"))).await;
+    assert_eq!(details, vec!["homepage: must be a valid URL"]);
+
+    let details = error_details(&client, with_field("external_id", json!("1234"))).await;
+    assert_eq!(details, vec!["external_id: must be a valid UUID"]);
+
+    let details = error_details(&client, with_field("address", json!("999.1.1.1"))).await;
+    assert_eq!(details, vec!["address: must be a valid IP address"]);
+
+    let details = error_details(&client, with_field("gateway", json!("::1"))).await;
+    assert_eq!(details, vec!["gateway: must be a valid IPv4 address"]);
+}
+
+#[tokio::test]
+async fn test_enum_and_length_keywords_rejected() {
+    let client = TestClient::new(create_app()).await;
+
+    let details = error_details(&client, with_field("status", json!("archived"))).await;
+    assert_eq!(details, vec!["status: must be one of draft, published"]);
+
+    let details = error_details(&client, with_field("priority", json!(7))).await;
+    assert_eq!(details, vec!["priority: must be one of 1, 2, 3"]);
+
+    let details = error_details(&client, with_field("country", json!("JPN"))).await;
+    assert_eq!(details, vec!["country: must have exactly 2 characters"]);
+}
+
+// --- OpenAPI Tests ---
+
+#[tokio::test]
+async fn test_keywords_emitted_in_openapi_schema() {
+    let client = TestClient::new(create_app()).await;
+    let spec: serde_json::Value = client.get("/openapi.json").await.json().await.unwrap();
+    let props = &spec["components"]["schemas"]["KeywordItem"]["properties"];
+
+    assert_eq!(props["tags"]["maxItems"], 3);
+    assert_eq!(props["tags"]["uniqueItems"], true);
+    assert_eq!(props["quantity"]["multipleOf"], 5.0);
+    assert_eq!(props["ratio"]["exclusiveMinimum"], 0.0);
+    assert_eq!(props["ratio"]["exclusiveMaximum"], 1.5);
+    assert_eq!(props["homepage"]["format"], "uri");
+    assert_eq!(props["external_id"]["format"], "uuid");
+    assert_eq!(props["address"]["format"], "ipvanyaddress");
+    assert_eq!(props["gateway"]["format"], "ipv4");
+    assert_eq!(props["status"]["enum"], json!(["draft", "published"]));
+    assert_eq!(props["priority"]["enum"], json!([1, 2, 3]));
+    assert_eq!(props["country"]["minLength"], 2);
+    assert_eq!(props["country"]["maxLength"], 2);
+    assert_eq!(props["scores"]["minItems"], 1);
+    assert_eq!(props["scores"]["maxItems"], 4);
+    assert_eq!(props["roles"]["contains"], json!({ "const": "admin" }));
+}
@@ -0,0 +1,401 @@
+// Tests for #[ws] routes: dependencies, authentication, origins, keepalive and size limits
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use ultraapi::prelude::*;
+use ultraapi::{AppState, DependencyError, Scope};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[api_model]
+#[derive(Debug, Clone)]
+struct Echo {
+    text: String,
+}
+
+#[derive(Clone)]
+struct Greeting {
+    prefix: String,
+}
+
+#[derive(Clone)]
+struct Audit {
+    connections: Arc<AtomicUsize>,
+}
+
+/// Yield dependency released when the connection ends
+#[derive(Clone)]
+struct Session {
+    cleaned_up: Arc<AtomicBool>,
+}
+
+#[async_trait::async_trait]
+impl Generator for Session {
+    type Output = Self;
+    type Error = DependencyError;
+
+    async fn generate(self: Arc<Self>, _scope: Scope) -> Result<Self::Output, Self::Error> {
+        Ok((*self).clone())
+    }
+
+    async fn cleanup(self: Arc<Self>) -> Result<(), Self::Error> {
+        self.cleaned_up.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[ws("/ws/deps")]
+#[dependencies(Depends<Audit>)]
+async fn ws_deps(
+    mut socket: TypedSocket<Echo, Echo>,
+    greeting: Depends<Greeting>,
+    _session: Depends<Session>,
+) {
+    while let Some(message) = socket.recv().await {
+        let reply = Echo {
+            text: format!("{} {}", greeting.prefix, message.text),
+        };
+        if socket.send(&reply).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[ws("/ws/raw")]
+async fn ws_raw(
+    ws: ultraapi::axum::extract::ws::WebSocketUpgrade,
+    _session: Depends<Session>,
+) -> ultraapi::axum::response::Response {
+    ws.on_upgrade(|mut socket| async move {
+        while let Some(Ok(message)) = socket.recv().await {
+            if socket.send(message).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[ws("/ws/oauth2")]
+#[dependencies(OAuth2PasswordBearer)]
+async fn ws_oauth2(mut socket: TypedSocket<Echo, Echo>) {
+    while let Some(message) = socket.recv().await {
+        let _ = socket.send(&message).await;
+    }
+}
+
+#[ws("/ws/secure")]
+#[security("bearer")]
+async fn ws_secure(mut socket: TypedSocket<Echo, Echo>) {
+    while let Some(message) = socket.recv().await {
+        let _ = socket.send(&message).await;
+    }
+}
+
+#[ws("/ws/origin", allowed_origins = ["https://app.example.com"])]
+async fn ws_origin(mut socket: TypedSocket<Echo, Echo>) {
+    while let Some(message) = socket.recv().await {
+        let _ = socket.send(&message).await;
+    }
+}
+
+#[ws("/ws/keepalive", ping_interval_secs = 1, idle_timeout_secs = 3)]
+async fn ws_keepalive(mut socket: TypedSocket<Echo, Echo>) {
+    while let Some(message) = socket.recv().await {
+        let _ = socket.send(&message).await;
+    }
+}
+
+#[ws("/ws/idle", idle_timeout_secs = 1)]
+async fn ws_idle(mut socket: TypedSocket<Echo, Echo>) {
+    while let Some(message) = socket.recv().await {
+        let _ = socket.send(&message).await;
+    }
+}
+
+#[ws("/ws/limited", max_message_size = 64)]
+async fn ws_limited(mut socket: TypedSocket<Echo, Echo>) {
+    while let Some(message) = socket.recv().await {
+        let _ = socket.send(&message).await;
+    }
+}
+
+async fn connect(client: &TestClient, path: &str) -> Result<Socket, WsError> {
+    connect_with(client, path, &[]).await
+}
+
+async fn connect_with(
+    client: &TestClient,
+    path: &str,
+    headers: &[(&'static str, &str)],
+) -> Result<Socket, WsError> {
+    let url = format!("{}{}", client.base_url().replace("http://", "ws://"), path);
+    let mut request = url.into_client_request().unwrap();
+    for (name, value) in headers {
+        request.headers_mut().insert(*name, value.parse().unwrap());
+    }
+    let (socket, response) = tokio_tungstenite::connect_async(request).await?;
+    if let Some(protocol) = response.headers().get("sec-websocket-protocol") {
+        assert_eq!(protocol, "bearer");
+    }
+    Ok(socket)
+}
+
+fn rejected_status(result: Result<Socket, WsError>) -> u16 {
+    match result {
+        Err(WsError::Http(response)) => response.status().as_u16(),
+        Err(other) => panic!("unexpected error: {other}"),
+        Ok(_) => panic!("handshake was accepted"),
+    }
+}
+
+async fn next(socket: &mut Socket) -> Option<Message> {
+    tokio::time::timeout(Duration::from_secs(5), socket.next())
+        .await
+        .expect("timed out")
+        .and_then(Result::ok)
+}
+
+async fn echo(socket: &mut Socket, text: &str) -> String {
+    socket
+        .send(Message::text(format!(r#"{{"text":"{text}"}}"#)))
+        .await
+        .unwrap();
+    match next(socket).await {
+        Some(Message::Text(reply)) => serde_json::from_str::<serde_json::Value>(&reply).unwrap()
+            ["text"]
+            .as_str()
+            .unwrap()
+            .to_string(),
+        other => panic!("unexpected message: {:?}", other),
+    }
+}
+
+fn router() -> UltraApiRouter {
+    UltraApiRouter::new("")
+        .route(__ULTRAAPI_WS_WS_DEPS)
+        .route(__ULTRAAPI_WS_WS_RAW)
+        .route(__ULTRAAPI_WS_WS_OAUTH2)
+        .route(__ULTRAAPI_WS_WS_SECURE)
+        .route(__ULTRAAPI_WS_WS_ORIGIN)
+        .route(__ULTRAAPI_WS_WS_KEEPALIVE)
+        .route(__ULTRAAPI_WS_WS_IDLE)
+        .route(__ULTRAAPI_WS_WS_LIMITED)
+}
+
+#[tokio::test]
+async fn test_depends_and_yield_dependencies_live_for_the_connection() {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let cleaned_up = Arc::new(AtomicBool::new(false));
+    let audit = Audit {
+        connections: connections.clone(),
+    };
+    let app = UltraApiApp::new()
+        .depends(|_state: AppState| async {
+            Ok(Greeting {
+                prefix: "hello".to_string(),
+            })
+        })
+        .depends(move |_state: AppState| {
+            let audit = audit.clone();
+            async move {
+                audit.connections.fetch_add(1, Ordering::SeqCst);
+                Ok(audit)
+            }
+        })
+        .yield_depends(
+            Session {
+                cleaned_up: cleaned_up.clone(),
+            },
+            Scope::Function,
+        )
+        .include(router());
+    let client = TestClient::new(app).await;
+
+    let mut socket = connect(&client, "/ws/deps").await.unwrap();
+    assert_eq!(echo(&mut socket, "world").await, "hello world");
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+    assert!(!cleaned_up.load(Ordering::SeqCst));
+
+    socket.close(None).await.unwrap();
+    for _ in 0..100 {
+        if cleaned_up.load(Ordering::SeqCst) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(cleaned_up.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_raw_handler_yield_dependencies_end_with_the_handshake() {
+    let cleaned_up = Arc::new(AtomicBool::new(false));
+    let app = UltraApiApp::new()
+        .yield_depends(
+            Session {
+                cleaned_up: cleaned_up.clone(),
+            },
+            Scope::Function,
+        )
+        .include(router());
+    let client = TestClient::new(app).await;
+
+    // The macro cannot see the upgraded connection of a WebSocketUpgrade handler
+    let mut socket = connect(&client, "/ws/raw").await.unwrap();
+    assert!(cleaned_up.load(Ordering::SeqCst));
+    assert_eq!(echo(&mut socket, "still open").await, "still open");
+}
+
+#[tokio::test]
+async fn test_route_dependencies_reject_the_handshake() {
+    // Only the OAuth2PasswordBearer dependency checks the token here
+    let app = UltraApiApp::new()
+        .middleware(|builder| builder.auth_default_policy(AuthDefaultPolicy::ExplicitOnly))
+        .include(router());
+    let client = TestClient::new(app).await;
+
+    assert_eq!(rejected_status(connect(&client, "/ws/oauth2").await), 401);
+
+    let mut socket = connect(&client, "/ws/oauth2?access_token=abc")
+        .await
+        .unwrap();
+    assert_eq!(echo(&mut socket, "query").await, "query");
+
+    let mut socket = connect_with(
+        &client,
+        "/ws/oauth2",
+        &[("sec-websocket-protocol", "bearer, abc")],
+    )
+    .await
+    .unwrap();
+    assert_eq!(echo(&mut socket, "protocol").await, "protocol");
+}
+
+#[tokio::test]
+async fn test_auth_layer_accepts_websocket_credentials() {
+    let app = UltraApiApp::new()
+        .bearer_auth()
+        .middleware(|builder| builder.enable_auth())
+        .include(router());
+    let client = TestClient::new(app).await;
+
+    assert_eq!(rejected_status(connect(&client, "/ws/secure").await), 401);
+    assert_eq!(
+        rejected_status(connect(&client, "/ws/secure?access_token=wrong").await),
+        401
+    );
+
+    let mut socket = connect_with(
+        &client,
+        "/ws/secure",
+        &[("authorization", "Bearer valid-header")],
+    )
+    .await
+    .unwrap();
+    assert_eq!(echo(&mut socket, "header").await, "header");
+
+    let mut socket = connect_with(
+        &client,
+        "/ws/secure",
+        &[("sec-websocket-protocol", "bearer, valid-protocol")],
+    )
+    .await
+    .unwrap();
+    assert_eq!(echo(&mut socket, "protocol").await, "protocol");
+
+    let mut socket = connect(&client, "/ws/secure?access_token=valid-query")
+        .await
+        .unwrap();
+    assert_eq!(echo(&mut socket, "query").await, "query");
+}
+
+#[tokio::test]
+async fn test_query_token_is_ignored_for_plain_http_requests() {
+    let app = UltraApiApp::new()
+        .bearer_auth()
+        .middleware(|builder| builder.enable_auth())
+        .include(router());
+    let client = TestClient::new(app).await;
+
+    let resp = client.get("/ws/secure?access_token=valid-query").await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[tokio::test]
+async fn test_allowed_origins() {
+    let client = TestClient::new(UltraApiApp::new().include(router())).await;
+
+    assert_eq!(
+        rejected_status(
+            connect_with(&client, "/ws/origin", &[("origin", "https://evil.example")]).await
+        ),
+        403
+    );
+
+    let mut socket = connect_with(
+        &client,
+        "/ws/origin",
+        &[("origin", "https://app.example.com")],
+    )
+    .await
+    .unwrap();
+    assert_eq!(echo(&mut socket, "allowed").await, "allowed");
+
+    // Non-browser clients send no Origin header
+    let mut socket = connect(&client, "/ws/origin").await.unwrap();
+    assert_eq!(echo(&mut socket, "native").await, "native");
+}
+
+#[tokio::test]
+async fn test_ping_interval_keeps_the_connection_alive() {
+    let client = TestClient::new(UltraApiApp::new().include(router())).await;
+    let mut socket = connect(&client, "/ws/keepalive").await.unwrap();
+
+    // Reading answers the pings with pongs, which count as activity
+    let mut pings = 0;
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(4500);
+    while tokio::time::Instant::now() < deadline {
+        match tokio::time::timeout_at(deadline, socket.next()).await {
+            Ok(Some(Ok(Message::Ping(_)))) => pings += 1,
+            Ok(other) => panic!("unexpected message: {:?}", other),
+            Err(_) => break,
+        }
+    }
+    assert!(pings >= 3, "received {pings} pings");
+    assert_eq!(echo(&mut socket, "still here").await, "still here");
+}
+
+#[tokio::test]
+async fn test_idle_timeout_closes_silent_connections() {
+    let client = TestClient::new(UltraApiApp::new().include(router())).await;
+    let mut socket = connect(&client, "/ws/idle").await.unwrap();
+
+    match next(&mut socket).await {
+        Some(Message::Close(Some(frame))) => {
+            assert_eq!(frame.code, CloseCode::Away);
+            assert_eq!(frame.reason, "idle timeout");
+        }
+        other => panic!("expected close frame, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_max_message_size_ends_the_connection() {
+    let client = TestClient::new(UltraApiApp::new().include(router())).await;
+    let mut socket = connect(&client, "/ws/limited").await.unwrap();
+    assert_eq!(echo(&mut socket, "small").await, "small");
+
+    let big = "x".repeat(256);
+    socket
+        .send(Message::text(format!(r#"{{"text":"{big}"}}"#)))
+        .await
+        .unwrap();
+    assert!(!matches!(next(&mut socket).await, Some(Message::Text(_))));
+}